@@ -7,6 +7,9 @@ use std::{env, path::PathBuf};
 use prost_build::Config;
 use prost_reflect::{prost::Message as ProstMessage, prost_types::FileDescriptorSet};
 
+mod rust_schemas;
+pub use rust_schemas::*;
+
 #[derive(Default)]
 pub struct DescriptorDataConfig {
   collect_oneofs_data: bool,
@@ -166,26 +169,12 @@ fn set_up_validators_inner(
   include_paths: &[impl AsRef<Path>],
   packages: &[&str],
 ) -> Result<DescriptorData, Box<dyn std::error::Error>> {
-  let out_dir = env::var("OUT_DIR")
-    .map(PathBuf::from)
-    .unwrap_or(env::temp_dir());
-
   config
     .extern_path(".google.protobuf", "::prelude::proto_types")
     .extern_path(".buf.validate", "::prelude::proto_types::protovalidate")
     .compile_well_known_types();
 
-  let temp_descriptor_path = out_dir.join("__temp_file_descriptor_set.bin");
-  {
-    let mut temp_config = prost_build::Config::new();
-    temp_config.file_descriptor_set_path(&temp_descriptor_path);
-    temp_config.out_dir(&out_dir);
-    temp_config.compile_protos(files, include_paths)?;
-  }
-
-  let mut fds_file = std::fs::File::open(&temp_descriptor_path)?;
-  let mut fds_bytes = Vec::new();
-  fds_file.read_to_end(&mut fds_bytes)?;
+  let fds_bytes = compile_descriptor_set(files, include_paths)?;
   let fds = FileDescriptorSet::decode(fds_bytes.as_slice())?;
   let pool = prost_reflect::DescriptorPool::from_file_descriptor_set(fds)?;
 
@@ -268,6 +257,30 @@ fn set_up_validators_inner(
   Ok(desc_data)
 }
 
+// Compiles the given files with protoc and returns the encoded `FileDescriptorSet` (including the imported files)
+fn compile_descriptor_set(
+  files: &[impl AsRef<Path>],
+  include_paths: &[impl AsRef<Path>],
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+  let out_dir = env::var("OUT_DIR")
+    .map(PathBuf::from)
+    .unwrap_or(env::temp_dir());
+
+  let temp_descriptor_path = out_dir.join("__temp_file_descriptor_set.bin");
+  {
+    let mut temp_config = prost_build::Config::new();
+    temp_config.file_descriptor_set_path(&temp_descriptor_path);
+    temp_config.out_dir(&out_dir);
+    temp_config.compile_protos(files, include_paths)?;
+  }
+
+  let mut fds_file = std::fs::File::open(&temp_descriptor_path)?;
+  let mut fds_bytes = Vec::new();
+  fds_file.read_to_end(&mut fds_bytes)?;
+
+  Ok(fds_bytes)
+}
+
 /// A helper to use when gathering the names of proto files to pass to [`prost_build::Config::compile_protos`].
 /// Recursively collects all .proto files in a given directory and its subdirectories.
 pub fn get_proto_files_recursive(base_dir: impl Into<PathBuf>) -> io::Result<Vec<String>> {
@@ -0,0 +1,1576 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Debug, Write};
+use std::path::Path;
+
+use convert_case::{Boundary, Case, Casing};
+use prost_reflect::{
+  Cardinality, DescriptorPool, DynamicMessage, EnumDescriptor, ExtensionDescriptor,
+  FieldDescriptor, FileDescriptor, Kind, MessageDescriptor, OneofDescriptor, ServiceDescriptor,
+  Value,
+};
+
+const VALIDATE_PROTO: &str = "buf/validate/validate.proto";
+// The partial version of `buf/validate/validate.proto` served by the runtime descriptors
+const KNOWN_RULES_PROTO: &str = "buf/validate/validate_known_rules.proto";
+const MAX_TAG: u32 = 536_870_911;
+
+const RUST_KEYWORDS: &[&str] = &[
+  "as", "async", "await", "break", "const", "continue", "dyn", "else", "enum", "extern", "false",
+  "fn", "for", "gen", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+  "ref", "return", "static", "struct", "trait", "true", "type", "unsafe", "use", "where", "while",
+  "abstract", "become", "box", "do", "final", "macro", "override", "priv", "try", "typeof",
+  "unsized", "virtual", "yield",
+];
+
+// Keywords that cannot be used as raw identifiers
+const RESERVED_IDENTS: &[&str] = &["self", "Self", "super", "crate", "_"];
+
+/// Generates the rust definitions (using `proto_package!`, `define_proto_file!` and the item macros) for the
+/// files of the given package, from an encoded `FileDescriptorSet`.
+///
+/// The descriptor set must include the imported files as well (such as `buf/validate/validate.proto`),
+/// so that custom options and `buf.validate` rules can be interpreted.
+///
+/// Each file is placed in its own module, whose items are re-exported at the top level.
+/// The `buf.validate` rules are translated into `validate = |v| ...` closures. If some of the rules do not have
+/// an equivalent in the validator builders, an error listing all of them (along with the items that use them) is returned.
+///
+/// Messages and enums from other packages (except for the well known types) are referred to by their rust name, so they must
+/// be in scope for the generated code to compile.
+pub fn generate_rust_schemas(
+  descriptor_set: &[u8],
+  package: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+  let pool = DescriptorPool::decode(descriptor_set)?;
+
+  RustSchemasGenerator::new(&pool, package).generate()
+}
+
+/// Compiles the given proto files with `protoc` and generates the rust definitions for the selected package.
+///
+/// See [`generate_rust_schemas`] for more information.
+pub fn generate_rust_schemas_from_protos(
+  files: &[impl AsRef<Path>],
+  include_paths: &[impl AsRef<Path>],
+  package: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+  let descriptor_set = crate::compile_descriptor_set(files, include_paths)?;
+
+  generate_rust_schemas(&descriptor_set, package)
+}
+
+struct RustSchemasGenerator<'a> {
+  pool: &'a DescriptorPool,
+  package: &'a str,
+  // Maps the full name of each message and enum in the package to its rust ident
+  idents: HashMap<String, String>,
+  output: String,
+}
+
+struct FieldTypeInfo {
+  // The name of the type as used inside the `proto` attribute (e.g. `sint32`, `enum_(MyEnum)`)
+  meta: String,
+  // Whether the type must be set manually in the attribute
+  is_explicit: bool,
+  rust_type: String,
+}
+
+impl<'a> RustSchemasGenerator<'a> {
+  fn new(pool: &'a DescriptorPool, package: &'a str) -> Self {
+    let mut idents = HashMap::new();
+    let mut used_idents: HashSet<String> = HashSet::new();
+
+    let messages = pool
+      .all_messages()
+      .filter(|m| m.package_name() == package && !m.is_map_entry())
+      .map(|m| (m.full_name().to_string(), m.name().to_string()));
+    let enums = pool
+      .all_enums()
+      .filter(|e| e.package_name() == package)
+      .map(|e| (e.full_name().to_string(), e.name().to_string()));
+
+    let mut items: Vec<(String, String)> = messages.chain(enums).collect();
+    // Top level items get the first pick for their names
+    items.sort_by_key(|(full_name, _)| full_name.matches('.').count());
+
+    for (full_name, short_name) in items {
+      let mut ident = to_pascal_case(&short_name);
+
+      if used_idents.contains(&ident) {
+        let full_ish_name = full_ish_name(&full_name, package);
+        ident = to_pascal_case(&full_ish_name.replace('.', "_"));
+      }
+
+      used_idents.insert(ident.clone());
+      idents.insert(full_name, ident);
+    }
+
+    Self {
+      pool,
+      package,
+      idents,
+      output: String::new(),
+    }
+  }
+
+  fn generate(mut self) -> Result<String, Box<dyn std::error::Error>> {
+    let package_ident = format!(
+      "{}_PKG",
+      to_upper_snake_case(&self.package.replace('.', "_"))
+    );
+
+    let _ = writeln!(
+      self.output,
+      "// Generated from the descriptors of the `{}` package",
+      self.package
+    );
+    let _ = writeln!(self.output, "use prelude::*;");
+    let _ = writeln!(self.output, "use std::collections::HashMap;\n");
+    let _ = writeln!(
+      self.output,
+      "proto_package!({package_ident}, name = \"{}\");",
+      self.package
+    );
+
+    let files: Vec<FileDescriptor> = self
+      .pool
+      .files()
+      .filter(|f| f.package_name() == self.package)
+      .collect();
+
+    for file in &files {
+      let _ = writeln!(self.output, "\npub use {}::*;", file_module_name(file));
+    }
+
+    let mut unsupported = Vec::new();
+
+    for file in &files {
+      let (code, file_unsupported) = FileGenerator::new(&self, file, &package_ident).generate();
+
+      self.output.push_str(&code);
+      unsupported.extend(file_unsupported);
+    }
+
+    if !unsupported.is_empty() {
+      return Err(
+        format!(
+          "The following `buf.validate` rules cannot be converted into validators:\n- {}",
+          unsupported.join("\n- ")
+        )
+        .into(),
+      );
+    }
+
+    Ok(self.output)
+  }
+
+  fn item_ident(&self, full_name: &str, package: &str) -> String {
+    if let Some(ident) = self.idents.get(full_name) {
+      return ident.clone();
+    }
+
+    // Items from other packages are expected to be in scope
+    to_pascal_case(&full_ish_name(full_name, package).replace('.', "_"))
+  }
+
+  fn message_ident(&self, message: &MessageDescriptor) -> String {
+    self.item_ident(message.full_name(), message.package_name())
+  }
+
+  fn enum_ident(&self, enum_: &EnumDescriptor) -> String {
+    self.item_ident(enum_.full_name(), enum_.package_name())
+  }
+
+  fn value_type(&self, kind: &Kind, boxed: bool) -> FieldTypeInfo {
+    let (meta, is_explicit, rust_type) = match kind {
+      Kind::Double => ("double".to_string(), false, "f64".to_string()),
+      Kind::Float => ("float".to_string(), false, "f32".to_string()),
+      Kind::Int32 => ("int32".to_string(), false, "i32".to_string()),
+      Kind::Int64 => ("int64".to_string(), false, "i64".to_string()),
+      Kind::Uint32 => ("uint32".to_string(), false, "u32".to_string()),
+      Kind::Uint64 => ("uint64".to_string(), false, "u64".to_string()),
+      Kind::Sint32 => ("sint32".to_string(), true, "i32".to_string()),
+      Kind::Sint64 => ("sint64".to_string(), true, "i64".to_string()),
+      Kind::Fixed32 => ("fixed32".to_string(), true, "u32".to_string()),
+      Kind::Fixed64 => ("fixed64".to_string(), true, "u64".to_string()),
+      Kind::Sfixed32 => ("sfixed32".to_string(), true, "i32".to_string()),
+      Kind::Sfixed64 => ("sfixed64".to_string(), true, "i64".to_string()),
+      Kind::Bool => ("bool".to_string(), false, "bool".to_string()),
+      Kind::String => ("string".to_string(), false, "String".to_string()),
+      Kind::Bytes => ("bytes".to_string(), false, "::bytes::Bytes".to_string()),
+      Kind::Enum(enum_) => (
+        format!("enum_({})", self.enum_ident(enum_)),
+        true,
+        "i32".to_string(),
+      ),
+      Kind::Message(message) => {
+        let well_known = match message.full_name() {
+          "google.protobuf.Duration" => Some(("duration", "Duration")),
+          "google.protobuf.Timestamp" => Some(("timestamp", "Timestamp")),
+          "google.protobuf.Any" => Some(("any", "Any")),
+          "google.protobuf.FieldMask" => Some(("field_mask", "FieldMask")),
+          _ => None,
+        };
+
+        if let Some((meta, name)) = well_known {
+          (
+            meta.to_string(),
+            true,
+            format!("::prelude::proto_types::{name}"),
+          )
+        } else {
+          let ident = if message.package_name() == "google.protobuf" {
+            format!("::prelude::proto_types::{}", message.name())
+          } else {
+            self.message_ident(message)
+          };
+
+          let rust_type = if boxed {
+            format!("Box<{ident}>")
+          } else {
+            ident
+          };
+
+          ("message".to_string(), true, rust_type)
+        }
+      }
+    };
+
+    FieldTypeInfo {
+      meta,
+      is_explicit,
+      rust_type,
+    }
+  }
+}
+
+struct FileGenerator<'a, 'b> {
+  generator: &'b RustSchemasGenerator<'a>,
+  file: &'b FileDescriptor,
+  package_ident: &'b str,
+  comments: HashMap<Vec<i32>, String>,
+  buf: String,
+  // The rules that cannot be converted, along with the full name of the item that uses them
+  unsupported: Vec<String>,
+}
+
+impl<'a, 'b> FileGenerator<'a, 'b> {
+  fn new(
+    generator: &'b RustSchemasGenerator<'a>,
+    file: &'b FileDescriptor,
+    package_ident: &'b str,
+  ) -> Self {
+    let comments = file
+      .file_descriptor_proto()
+      .source_code_info
+      .iter()
+      .flat_map(|info| &info.location)
+      .filter_map(|location| {
+        location
+          .leading_comments
+          .clone()
+          .map(|comments| (location.path.clone(), comments))
+      })
+      .collect();
+
+    Self {
+      generator,
+      file,
+      package_ident,
+      comments,
+      buf: String::new(),
+      unsupported: Vec::new(),
+    }
+  }
+
+  fn generate(mut self) -> (String, Vec<String>) {
+    let file = self.file;
+    let module_name = file_module_name(file);
+    let file_ident = format!("{}_FILE", to_upper_snake_case(&module_name));
+
+    let mut file_args = vec![
+      format!("name = \"{}\"", file.name()),
+      format!("package = {}", self.package_ident),
+    ];
+
+    match file.file_descriptor_proto().syntax() {
+      "proto2" | "" => file_args.push("edition = Proto2".to_string()),
+      "editions" => file_args.push("edition = E2023".to_string()),
+      _ => {}
+    }
+
+    let ConvertedOptions {
+      options, features, ..
+    } = convert_options(&file.options());
+
+    if let Some(options) = options {
+      file_args.push(format!("options = {options}"));
+    }
+
+    if let Some(features) = features {
+      file_args.push(format!("features = {features}"));
+    }
+
+    let imports: Vec<String> = file
+      .dependencies()
+      .filter(|dep| dep.name() != VALIDATE_PROTO && dep.name() != KNOWN_RULES_PROTO)
+      .map(|dep| format!("\"{}\"", dep.name()))
+      .collect();
+
+    if !imports.is_empty() {
+      file_args.push(format!("imports = [{}]", imports.join(", ")));
+    }
+
+    let extensions = self.extensions(&module_name);
+
+    if !extensions.is_empty() {
+      let idents: Vec<&str> = extensions
+        .iter()
+        .map(|(ident, _)| ident.as_str())
+        .collect();
+
+      file_args.push(format!("extensions = [{}]", idents.join(", ")));
+    }
+
+    let _ = writeln!(
+      self.buf,
+      "  define_proto_file!(\n    {file_ident},\n    {}\n  );",
+      file_args.join(",\n    ")
+    );
+
+    for message in file.messages() {
+      self.message(&message, None);
+    }
+
+    for enum_ in file.enums() {
+      self.enum_(&enum_, None);
+    }
+
+    for service in file.services() {
+      self.service(&service);
+    }
+
+    for (_, code) in extensions {
+      self.buf.push('\n');
+      self.buf.push_str(&code);
+    }
+
+    let code = format!(
+      "\npub mod {module_name} {{\n  use super::*;\n\n{}}}\n",
+      self.buf
+    );
+
+    (code, self.unsupported)
+  }
+
+  fn push_unsupported(&mut self, item: &str, rules: Vec<String>) {
+    self.unsupported.extend(
+      rules
+        .into_iter()
+        .map(|rule| format!("`{item}`: {rule}")),
+    );
+  }
+
+  fn write_comments(&mut self, path: &[i32]) {
+    let comments = self.field_comments(path, 1);
+
+    self.buf.push_str(&comments);
+  }
+
+  fn write_attrs(&mut self, attrs: &[String], indent: usize) {
+    if !attrs.is_empty() {
+      let _ = writeln!(
+        self.buf,
+        "{}#[proto({})]",
+        "  ".repeat(indent),
+        attrs.join(", ")
+      );
+    }
+  }
+
+  fn message(&mut self, message: &MessageDescriptor, parent: Option<&str>) {
+    let ident = self.generator.message_ident(message);
+    let mut attrs = Vec::new();
+
+    if let Some(parent) = parent {
+      attrs.push(format!("parent_message = {parent}"));
+    }
+
+    if to_pascal_case(&ident) != message.name() {
+      attrs.push(format!("name = \"{}\"", message.name()));
+    }
+
+    let proto = message.descriptor_proto();
+
+    if !proto.reserved_range.is_empty() {
+      let ranges: Vec<String> = proto
+        .reserved_range
+        .iter()
+        .map(|range| {
+          let (start, end) = (range.start(), range.end());
+
+          if end == start + 1 {
+            start.to_string()
+          } else if u32::try_from(end).is_ok_and(|end| end > MAX_TAG) {
+            format!("{start}..MAX")
+          } else {
+            format!("{start}..{end}")
+          }
+        })
+        .collect();
+
+      attrs.push(format!("reserved_numbers({})", ranges.join(", ")));
+    }
+
+    if !proto.reserved_name.is_empty() {
+      let names: Vec<String> = proto
+        .reserved_name
+        .iter()
+        .map(|name| format!("{name:?}"))
+        .collect();
+
+      attrs.push(format!("reserved_names({})", names.join(", ")));
+    }
+
+    let ConvertedOptions {
+      options,
+      features,
+      validators,
+      deprecated,
+    } = convert_options(&message.options());
+
+    let mut unsupported = Vec::new();
+
+    if deprecated {
+      attrs.push("deprecated = true".to_string());
+    }
+
+    if let Some(options) = options {
+      attrs.push(format!("options = {options}"));
+    }
+
+    if let Some(features) = features {
+      attrs.push(format!("features = {features}"));
+    }
+
+    if let Some(rules) = validators.get("message") {
+      let mut calls = Vec::new();
+
+      for (field, value) in rules.fields() {
+        match (field.name(), value) {
+          ("cel", Value::List(items)) => {
+            calls.extend(items.iter().filter_map(cel_call));
+          }
+          (name, _) => unsupported.push(format!("(buf.validate.message).{name}")),
+        }
+      }
+
+      if !calls.is_empty() {
+        attrs.push(format!("validate = |v| v{}", calls.concat()));
+      }
+    }
+
+    let mut fields_code = String::new();
+    let mut oneofs: Vec<OneofDescriptor> = Vec::new();
+
+    for field in message.fields() {
+      match field
+        .containing_oneof()
+        .filter(|oneof| !oneof.is_synthetic())
+      {
+        Some(oneof) => {
+          if oneofs.iter().any(|o| o.name() == oneof.name()) {
+            continue;
+          }
+
+          self.oneof_field(&mut fields_code, &oneof, &ident);
+          oneofs.push(oneof);
+        }
+        None => {
+          let code = self.field(&field, message);
+          fields_code.push_str(&code);
+        }
+      }
+    }
+
+    self.push_unsupported(message.full_name(), unsupported);
+
+    self.buf.push('\n');
+    self.write_comments(message.path());
+    let _ = writeln!(self.buf, "  #[proto_message]");
+    self.write_attrs(&attrs, 1);
+    let _ = writeln!(self.buf, "  pub struct {ident} {{");
+    self.buf.push_str(&fields_code);
+    let _ = writeln!(self.buf, "  }}");
+
+    for oneof in &oneofs {
+      self.oneof(oneof, message, &ident);
+    }
+
+    for nested in message.child_messages() {
+      if !nested.is_map_entry() {
+        self.message(&nested, Some(&ident));
+      }
+    }
+
+    for enum_ in message.child_enums() {
+      self.enum_(&enum_, Some(&ident));
+    }
+  }
+
+  fn field_comments(&self, path: &[i32], indent: usize) -> String {
+    let mut output = String::new();
+
+    if let Some(comments) = self.comments.get(path) {
+      for line in comments.trim_end().lines() {
+        let line = line.strip_prefix(' ').unwrap_or(line).trim_end();
+
+        let _ = if line.is_empty() {
+          writeln!(output, "{}///", "  ".repeat(indent))
+        } else {
+          writeln!(output, "{}/// {line}", "  ".repeat(indent))
+        };
+      }
+    }
+
+    output
+  }
+
+  fn field(&mut self, field: &FieldDescriptor, message: &MessageDescriptor) -> String {
+    let mut output = String::new();
+    let mut attrs = vec![format!("tag = {}", field.number())];
+
+    let (ident, needs_name) = field_ident(field.name());
+
+    if needs_name {
+      attrs.push(format!("name = \"{}\"", field.name()));
+    }
+
+    let boxed = matches!(field.kind(), Kind::Message(target) if !field.is_list() && !field.is_map() && reaches(&target, message, &mut HashSet::new()));
+
+    let rust_type = self.field_type(field, boxed, &mut attrs);
+
+    let ConvertedOptions {
+      options,
+      features,
+      validators,
+      deprecated,
+    } = convert_options(&field.options());
+
+    let mut unsupported = Vec::new();
+
+    if deprecated {
+      attrs.push("deprecated = true".to_string());
+    }
+
+    if let Some(options) = options {
+      attrs.push(format!("options = {options}"));
+    }
+
+    if let Some(features) = features {
+      attrs.push(format!("features = {features}"));
+    }
+
+    if let Some(rules) = validators.get("field")
+      && let Some(closure) = field_rules_closure(rules, field, &mut unsupported)
+    {
+      attrs.push(format!("validate = {closure}"));
+    }
+
+    self.push_unsupported(field.full_name(), unsupported);
+
+    output.push_str(&self.field_comments(field.path(), 2));
+    let _ = writeln!(output, "    #[proto({})]", attrs.join(", "));
+    let _ = writeln!(output, "    pub {ident}: {rust_type},");
+
+    output
+  }
+
+  fn field_type(&self, field: &FieldDescriptor, boxed: bool, attrs: &mut Vec<String>) -> String {
+    let kind = field.kind();
+
+    if field.is_map()
+      && let Kind::Message(entry) = &kind
+    {
+      let keys = self
+        .generator
+        .value_type(&entry.map_entry_key_field().kind(), false);
+      let values = self
+        .generator
+        .value_type(&entry.map_entry_value_field().kind(), false);
+
+      attrs.push(format!("map({}, {})", keys.meta, values.meta));
+
+      return format!("HashMap<{}, {}>", keys.rust_type, values.rust_type);
+    }
+
+    let FieldTypeInfo {
+      meta,
+      is_explicit,
+      rust_type,
+    } = self.generator.value_type(&kind, boxed);
+
+    let is_message = matches!(kind, Kind::Message(_));
+
+    let meta = if is_message && is_delimited(field) {
+      "message(delimited)".to_string()
+    } else {
+      meta
+    };
+
+    if is_explicit {
+      attrs.push(meta);
+    }
+
+    if field.is_list() {
+      format!("Vec<{rust_type}>")
+    } else if field.cardinality() == Cardinality::Required || is_legacy_required(field) {
+      attrs.push("required".to_string());
+      rust_type
+    } else if is_message || field.supports_presence() {
+      format!("Option<{rust_type}>")
+    } else {
+      rust_type
+    }
+  }
+
+  fn oneof_field(&self, output: &mut String, oneof: &OneofDescriptor, message_ident: &str) {
+    let enum_ident = oneof_ident(message_ident, oneof);
+    let tags: Vec<String> = oneof
+      .fields()
+      .map(|f| f.number().to_string())
+      .collect();
+
+    let mut oneof_args = vec![format!("tags({})", tags.join(", "))];
+
+    let validators = convert_options(&oneof.options()).validators;
+
+    if let Some(rules) = validators.get("oneof")
+      && rules
+        .get_field_by_name("required")
+        .is_some_and(|v| v.as_bool() == Some(true))
+    {
+      oneof_args.push("required".to_string());
+    }
+
+    let (ident, _) = field_ident(oneof.name());
+
+    output.push_str(&self.field_comments(oneof.path(), 2));
+    let _ = writeln!(output, "    #[proto(oneof({}))]", oneof_args.join(", "));
+    let _ = writeln!(output, "    pub {ident}: Option<{enum_ident}>,");
+  }
+
+  fn oneof(&mut self, oneof: &OneofDescriptor, message: &MessageDescriptor, message_ident: &str) {
+    let enum_ident = oneof_ident(message_ident, oneof);
+
+    let options = convert_options(&oneof.options()).options;
+
+    let mut attrs = Vec::new();
+
+    if let Some(options) = options {
+      attrs.push(format!("options = {options}"));
+    }
+
+    let mut variants = String::new();
+
+    for field in oneof.fields() {
+      let variant_ident = to_pascal_case(field.name());
+      let mut variant_attrs = vec![format!("tag = {}", field.number())];
+
+      if to_snake_case(&variant_ident) != field.name() {
+        variant_attrs.push(format!("name = \"{}\"", field.name()));
+      }
+
+      let boxed = matches!(field.kind(), Kind::Message(target) if reaches(&target, message, &mut HashSet::new()));
+
+      let FieldTypeInfo {
+        meta,
+        is_explicit,
+        rust_type,
+      } = self.generator.value_type(&field.kind(), boxed);
+
+      if is_explicit {
+        variant_attrs.push(meta);
+      }
+
+      let field_options = convert_options(&field.options());
+
+      if field_options.deprecated {
+        variant_attrs.push("deprecated = true".to_string());
+      }
+
+      if let Some(options) = field_options.options {
+        variant_attrs.push(format!("options = {options}"));
+      }
+
+      let mut unsupported = Vec::new();
+
+      if let Some(rules) = field_options.validators.get("field")
+        && let Some(closure) = field_rules_closure(rules, &field, &mut unsupported)
+      {
+        variant_attrs.push(format!("validate = {closure}"));
+      }
+
+      self.push_unsupported(field.full_name(), unsupported);
+
+      variants.push_str(&self.field_comments(field.path(), 2));
+      let _ = writeln!(variants, "    #[proto({})]", variant_attrs.join(", "));
+      let _ = writeln!(variants, "    {variant_ident}({rust_type}),");
+    }
+
+    self.buf.push('\n');
+    let _ = writeln!(self.buf, "  #[proto_oneof]");
+    self.write_attrs(&attrs, 1);
+    let _ = writeln!(self.buf, "  pub enum {enum_ident} {{");
+    self.buf.push_str(&variants);
+    let _ = writeln!(self.buf, "  }}");
+  }
+
+  fn enum_(&mut self, enum_: &EnumDescriptor, parent: Option<&str>) {
+    let ident = self.generator.enum_ident(enum_);
+    let mut attrs = Vec::new();
+
+    if let Some(parent) = parent {
+      attrs.push(format!("parent_message = {parent}"));
+    }
+
+    if to_pascal_case(&ident) != enum_.name() {
+      attrs.push(format!("name = \"{}\"", enum_.name()));
+    }
+
+    let ranges: Vec<String> = enum_
+      .reserved_ranges()
+      .map(|range| {
+        let (start, end) = (*range.start(), *range.end());
+
+        if start == end {
+          start.to_string()
+        } else if end == i32::MAX {
+          format!("{start}..MAX")
+        } else {
+          format!("{start}..={end}")
+        }
+      })
+      .collect();
+
+    if !ranges.is_empty() {
+      attrs.push(format!("reserved_numbers({})", ranges.join(", ")));
+    }
+
+    let names: Vec<String> = enum_
+      .reserved_names()
+      .map(|name| format!("{name:?}"))
+      .collect();
+
+    if !names.is_empty() {
+      attrs.push(format!("reserved_names({})", names.join(", ")));
+    }
+
+    let ConvertedOptions {
+      options,
+      features,
+      deprecated,
+      ..
+    } = convert_options(&enum_.options());
+
+    if deprecated {
+      attrs.push("deprecated = true".to_string());
+    }
+
+    if let Some(options) = options {
+      attrs.push(format!("options = {options}"));
+    }
+
+    if let Some(features) = features {
+      attrs.push(format!("features = {features}"));
+    }
+
+    let enum_name_prefix = to_upper_snake_case(enum_.name());
+    let mut variants = String::new();
+
+    for value in enum_.values() {
+      let plain_name = value
+        .name()
+        .strip_prefix(&format!("{enum_name_prefix}_"))
+        .unwrap_or(value.name());
+      let variant_ident = to_pascal_case(&plain_name.to_lowercase());
+
+      let mut variant_attrs = vec![format!("tag = {}", value.number())];
+
+      if format!("{enum_name_prefix}_{}", to_upper_snake_case(&variant_ident)) != value.name() {
+        variant_attrs.push(format!("name = \"{}\"", value.name()));
+      }
+
+      let value_options = convert_options(&value.options());
+
+      if value_options.deprecated {
+        variant_attrs.push("deprecated = true".to_string());
+      }
+
+      if let Some(options) = value_options.options {
+        variant_attrs.push(format!("options = {options}"));
+      }
+
+      variants.push_str(&self.field_comments(value.path(), 2));
+      let _ = writeln!(variants, "    #[proto({})]", variant_attrs.join(", "));
+      let _ = writeln!(variants, "    {variant_ident},");
+    }
+
+    self.buf.push('\n');
+    self.write_comments(enum_.path());
+    let _ = writeln!(self.buf, "  #[proto_enum]");
+    self.write_attrs(&attrs, 1);
+    let _ = writeln!(self.buf, "  pub enum {ident} {{");
+    self.buf.push_str(&variants);
+    let _ = writeln!(self.buf, "  }}");
+  }
+
+  fn service(&mut self, service: &ServiceDescriptor) {
+    let ident = to_pascal_case(service.name());
+
+    let ConvertedOptions {
+      options,
+      deprecated,
+      ..
+    } = convert_options(&service.options());
+
+    let mut attrs = Vec::new();
+
+    if deprecated {
+      attrs.push("deprecated = true".to_string());
+    }
+
+    if let Some(options) = options {
+      attrs.push(format!("options = {options}"));
+    }
+
+    let mut handlers = String::new();
+
+    for method in service.methods() {
+      let message_type = |message: &MessageDescriptor, is_stream: bool| {
+        let rust_type = self
+          .generator
+          .value_type(&Kind::Message(message.clone()), false)
+          .rust_type;
+
+        if is_stream {
+          format!("Stream<{rust_type}>")
+        } else {
+          rust_type
+        }
+      };
+
+      let request = message_type(&method.input(), method.is_client_streaming());
+      let response = message_type(&method.output(), method.is_server_streaming());
+
+      let method_options = convert_options(&method.options());
+      let mut method_attrs = Vec::new();
+
+      if method_options.deprecated {
+        method_attrs.push("deprecated = true".to_string());
+      }
+
+      if let Some(options) = method_options.options {
+        method_attrs.push(format!("options = {options}"));
+      }
+
+      handlers.push_str(&self.field_comments(method.path(), 2));
+
+      if !method_attrs.is_empty() {
+        let _ = writeln!(handlers, "    #[proto({})]", method_attrs.join(", "));
+      }
+
+      let _ = writeln!(
+        handlers,
+        "    {} {{\n      request: {request},\n      response: {response},\n    }},",
+        method.name()
+      );
+    }
+
+    self.buf.push('\n');
+    self.write_comments(service.path());
+    let _ = writeln!(self.buf, "  #[proto_service]");
+    self.write_attrs(&attrs, 1);
+    let _ = writeln!(self.buf, "  pub enum {ident} {{");
+    self.buf.push_str(&handlers);
+    let _ = writeln!(self.buf, "  }}");
+  }
+
+  // Returns the ident and the code for each extension block
+  fn extensions(&self, module_name: &str) -> Vec<(String, String)> {
+    let mut targets: Vec<(&str, Vec<ExtensionDescriptor>)> = Vec::new();
+
+    for extension in self.file.extensions() {
+      let Some(target) = extension_target(extension.containing_message().full_name()) else {
+        continue;
+      };
+
+      if let Some((_, fields)) = targets.iter_mut().find(|(t, _)| *t == target) {
+        fields.push(extension);
+      } else {
+        targets.push((target, vec![extension]));
+      }
+    }
+
+    targets
+      .into_iter()
+      .map(|(target, extensions)| {
+        let ident = format!("{}{target}Extensions", to_pascal_case(module_name));
+        let mut code = String::new();
+
+        let _ = writeln!(code, "  #[proto_extension(target = {target})]");
+        let _ = writeln!(code, "  pub struct {ident} {{");
+
+        for extension in extensions {
+          let mut attrs = vec![format!("tag = {}", extension.number())];
+
+          let (field_ident, needs_name) = field_ident(extension.name());
+
+          if needs_name {
+            attrs.push(format!("name = \"{}\"", extension.name()));
+          }
+
+          let FieldTypeInfo {
+            meta,
+            is_explicit,
+            rust_type,
+          } = self
+            .generator
+            .value_type(&extension.kind(), false);
+
+          if is_explicit {
+            attrs.push(meta);
+          }
+
+          let rust_type = if extension.is_list() {
+            format!("Vec<{rust_type}>")
+          } else {
+            rust_type
+          };
+
+          if let Some(options) = convert_options(&extension.options()).options {
+            attrs.push(format!("options = {options}"));
+          }
+
+          code.push_str(&self.field_comments(extension.path(), 2));
+          let _ = writeln!(code, "    #[proto({})]", attrs.join(", "));
+          let _ = writeln!(code, "    pub {field_ident}: {rust_type},");
+        }
+
+        let _ = writeln!(code, "  }}");
+
+        (ident, code)
+      })
+      .collect()
+  }
+}
+
+fn extension_target(extendee: &str) -> Option<&'static str> {
+  let target = match extendee {
+    "google.protobuf.FileOptions" => "FileOptions",
+    "google.protobuf.MessageOptions" => "MessageOptions",
+    "google.protobuf.FieldOptions" => "FieldOptions",
+    "google.protobuf.OneofOptions" => "OneofOptions",
+    "google.protobuf.EnumOptions" => "EnumOptions",
+    "google.protobuf.EnumValueOptions" => "EnumValueOptions",
+    "google.protobuf.ServiceOptions" => "ServiceOptions",
+    "google.protobuf.MethodOptions" => "MethodOptions",
+    _ => return None,
+  };
+
+  Some(target)
+}
+
+fn to_snake_case(str: &str) -> String {
+  str
+    .remove_boundaries(&[Boundary::UpperDigit, Boundary::LowerDigit])
+    .to_case(Case::Snake)
+}
+
+fn to_upper_snake_case(str: &str) -> String {
+  str
+    .remove_boundaries(&[Boundary::UpperDigit, Boundary::LowerDigit])
+    .to_case(Case::UpperSnake)
+}
+
+fn to_pascal_case(str: &str) -> String {
+  str
+    .remove_boundaries(&[Boundary::UpperDigit, Boundary::LowerDigit])
+    .to_case(Case::Pascal)
+}
+
+fn full_ish_name<'a>(full_name: &'a str, package: &str) -> &'a str {
+  full_name
+    .strip_prefix(package)
+    .and_then(|name| name.strip_prefix('.'))
+    .unwrap_or(full_name)
+}
+
+fn file_module_name(file: &FileDescriptor) -> String {
+  let name = file
+    .name()
+    .strip_suffix(".proto")
+    .unwrap_or(file.name());
+
+  let sanitized: String = name
+    .chars()
+    .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+    .collect();
+
+  to_snake_case(&sanitized)
+}
+
+fn oneof_ident(message_ident: &str, oneof: &OneofDescriptor) -> String {
+  format!("{message_ident}{}", to_pascal_case(oneof.name()))
+}
+
+// Returns the rust ident for a field, and whether the name must be set manually.
+// The macros use the name of the field (stripped of `r#` or a trailing underscore) as the proto name
+fn field_ident(name: &str) -> (String, bool) {
+  if RESERVED_IDENTS.contains(&name) {
+    return (format!("{name}_field"), true);
+  }
+
+  if RUST_KEYWORDS.contains(&name) {
+    return (format!("r#{name}"), false);
+  }
+
+  let is_valid_ident = name
+    .chars()
+    .next()
+    .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+    && name
+      .chars()
+      .all(|c| c.is_ascii_alphanumeric() || c == '_');
+
+  if !is_valid_ident {
+    let sanitized: String = name
+      .chars()
+      .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+      .collect();
+
+    return (format!("field_{sanitized}"), true);
+  }
+
+  (name.to_string(), name.ends_with('_'))
+}
+
+// Checks if a message can reach the target message through singular message fields,
+// in which case the field must be boxed
+fn reaches(
+  message: &MessageDescriptor,
+  target: &MessageDescriptor,
+  visited: &mut HashSet<String>,
+) -> bool {
+  if message.full_name() == target.full_name() {
+    return true;
+  }
+
+  if !visited.insert(message.full_name().to_string()) {
+    return false;
+  }
+
+  message.fields().any(|field| {
+    !field.is_list()
+      && !field.is_map()
+      && matches!(field.kind(), Kind::Message(next) if reaches(&next, target, visited))
+  })
+}
+
+fn is_delimited(field: &FieldDescriptor) -> bool {
+  field.is_group()
+    || feature_value(&field.options(), "message_encoding").as_deref() == Some("DELIMITED")
+}
+
+fn is_legacy_required(field: &FieldDescriptor) -> bool {
+  feature_value(&field.options(), "field_presence").as_deref() == Some("LEGACY_REQUIRED")
+}
+
+fn feature_value(options: &DynamicMessage, name: &str) -> Option<String> {
+  let features = options.get_field_by_name("features")?;
+  let features = features.as_message()?;
+
+  let (field, value) = features
+    .fields()
+    .find(|(field, _)| field.name() == name)?;
+
+  enum_value_name(&field.kind(), value)
+}
+
+fn enum_value_name(kind: &Kind, value: &Value) -> Option<String> {
+  let (Kind::Enum(enum_), Value::EnumNumber(number)) = (kind, value) else {
+    return None;
+  };
+
+  enum_
+    .get_value(*number)
+    .map(|value| value.name().to_string())
+}
+
+#[derive(Default)]
+struct ConvertedOptions {
+  // The rust expression for the options
+  options: Option<String>,
+  // The rust expression for the features
+  features: Option<String>,
+  // The `buf.validate` rules, by their extension name (field, message, oneof)
+  validators: HashMap<String, DynamicMessage>,
+  deprecated: bool,
+}
+
+fn convert_options(options: &DynamicMessage) -> ConvertedOptions {
+  let mut converted = ConvertedOptions::default();
+  let mut items = Vec::new();
+
+  for (field, value) in options.fields() {
+    match field.name() {
+      "deprecated" => converted.deprecated = value.as_bool() == Some(true),
+      "features" => {
+        if let Value::Message(features) = value {
+          converted.features = features_expr(features);
+        }
+      }
+      // These are set by the field types themselves
+      "uninterpreted_option" | "map_entry" => {}
+      name => items.push(proto_option_expr(name, value, &field.kind())),
+    }
+  }
+
+  for (extension, value) in options.extensions() {
+    if let Some(rule_type) = extension
+      .full_name()
+      .strip_prefix("buf.validate.")
+    {
+      if let Value::Message(rules) = value {
+        converted
+          .validators
+          .insert(rule_type.to_string(), rules.clone());
+      }
+    } else {
+      items.push(proto_option_expr(
+        &format!("({})", extension.full_name()),
+        value,
+        &extension.kind(),
+      ));
+    }
+  }
+
+  if !items.is_empty() {
+    converted.options = Some(format!("vec![{}]", items.join(", ")));
+  }
+
+  converted
+}
+
+fn features_expr(features: &DynamicMessage) -> Option<String> {
+  let mut expr = String::from("FeatureSet::new()");
+  let mut is_empty = true;
+
+  for (field, value) in features.fields() {
+    // Presence and message encoding are inferred from the field types
+    if matches!(field.name(), "field_presence" | "message_encoding") {
+      continue;
+    }
+
+    if let Some(value_name) = enum_value_name(&field.kind(), value) {
+      is_empty = false;
+
+      let _ = write!(
+        expr,
+        ".with_{}({}::{})",
+        field.name(),
+        to_pascal_case(field.name()),
+        to_pascal_case(&value_name.to_lowercase())
+      );
+    }
+  }
+
+  (!is_empty).then_some(expr)
+}
+
+fn proto_option_expr(name: &str, value: &Value, kind: &Kind) -> String {
+  format!(
+    "ProtoOption {{ name: {name:?}.into(), value: {} }}",
+    option_value_expr(value, kind)
+  )
+}
+
+fn option_value_expr(value: &Value, kind: &Kind) -> String {
+  match value {
+    Value::Bool(boolean) => format!("OptionValue::Bool({boolean})"),
+    Value::I32(int) => format!("OptionValue::Int({int})"),
+    Value::I64(int) => format!("OptionValue::Int({int})"),
+    Value::U32(int) => format!("OptionValue::Uint({int})"),
+    Value::U64(int) => format!("OptionValue::Uint({int})"),
+    Value::F32(float) => format!(
+      "OptionValue::Float({})",
+      float_literal(f64::from(*float), "f64")
+    ),
+    Value::F64(float) => format!("OptionValue::Float({})", float_literal(*float, "f64")),
+    Value::String(string) => format!("OptionValue::String({string:?}.into())"),
+    Value::Bytes(bytes) => format!("OptionValue::new_bytes({})", bytes_literal(bytes)),
+    Value::EnumNumber(number) => enum_value_name(kind, value).map_or_else(
+      || format!("OptionValue::Int({number})"),
+      |name| format!("OptionValue::Enum({name:?}.into())"),
+    ),
+    Value::Message(message) => match message.descriptor().full_name() {
+      "google.protobuf.Duration" => format!("OptionValue::Duration({})", duration_expr(message)),
+      "google.protobuf.Timestamp" => {
+        format!("OptionValue::Timestamp({})", timestamp_expr(message))
+      }
+      _ => {
+        let fields: Vec<String> = message
+          .fields()
+          .map(|(field, value)| proto_option_expr(field.name(), value, &field.kind()))
+          .chain(message.extensions().map(|(extension, value)| {
+            proto_option_expr(
+              &format!("({})", extension.full_name()),
+              value,
+              &extension.kind(),
+            )
+          }))
+          .collect();
+
+        format!("OptionValue::new_message(vec![{}])", fields.join(", "))
+      }
+    },
+    Value::List(items) => {
+      let items: Vec<String> = items
+        .iter()
+        .map(|item| option_value_expr(item, kind))
+        .collect();
+
+      format!("OptionValue::new_list(vec![{}])", items.join(", "))
+    }
+    Value::Map(map) => {
+      let (key_kind, value_kind) = match kind {
+        Kind::Message(entry) => (
+          entry.map_entry_key_field().kind(),
+          entry.map_entry_value_field().kind(),
+        ),
+        _ => (Kind::String, Kind::String),
+      };
+
+      let mut entries: Vec<_> = map.iter().collect();
+      entries.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+
+      let items: Vec<String> = entries
+        .into_iter()
+        .map(|(key, value)| {
+          let key: Value = key.clone().into();
+
+          format!(
+            "OptionValue::new_message(vec![{}, {}])",
+            proto_option_expr("key", &key, &key_kind),
+            proto_option_expr("value", value, &value_kind)
+          )
+        })
+        .collect();
+
+      format!("OptionValue::new_list(vec![{}])", items.join(", "))
+    }
+  }
+}
+
+fn float_literal<F: Into<f64> + Debug + Copy>(float: F, type_name: &str) -> String {
+  let as_f64: f64 = float.into();
+
+  if as_f64.is_nan() {
+    format!("{type_name}::NAN")
+  } else if as_f64.is_infinite() {
+    if as_f64.is_sign_positive() {
+      format!("{type_name}::INFINITY")
+    } else {
+      format!("{type_name}::NEG_INFINITY")
+    }
+  } else {
+    format!("{float:?}")
+  }
+}
+
+fn bytes_literal(bytes: &[u8]) -> String {
+  let mut literal = String::from("b\"");
+
+  for byte in bytes {
+    match byte {
+      b'"' => literal.push_str("\\\""),
+      b'\\' => literal.push_str("\\\\"),
+      0x20..=0x7e => literal.push(char::from(*byte)),
+      _ => {
+        let _ = write!(literal, "\\x{byte:02x}");
+      }
+    }
+  }
+
+  literal.push('"');
+  literal
+}
+
+fn int_field(message: &DynamicMessage, name: &str) -> i64 {
+  message
+    .get_field_by_name(name)
+    .and_then(|value| match value.as_ref() {
+      Value::I64(int) => Some(*int),
+      Value::I32(int) => Some(i64::from(*int)),
+      _ => None,
+    })
+    .unwrap_or_default()
+}
+
+fn duration_expr(message: &DynamicMessage) -> String {
+  format!(
+    "::prelude::proto_types::Duration {{ seconds: {}, nanos: {} }}",
+    int_field(message, "seconds"),
+    int_field(message, "nanos")
+  )
+}
+
+fn timestamp_expr(message: &DynamicMessage) -> String {
+  format!(
+    "::prelude::proto_types::Timestamp {{ seconds: {}, nanos: {} }}",
+    int_field(message, "seconds"),
+    int_field(message, "nanos")
+  )
+}
+
+fn cel_call(rule: &Value) -> Option<String> {
+  let rule = rule.as_message()?;
+
+  let get_str = |name: &str| {
+    rule
+      .get_field_by_name(name)
+      .and_then(|value| value.as_str().map(str::to_string))
+      .unwrap_or_default()
+  };
+
+  Some(format!(
+    ".cel(cel_program!(id = {:?}, msg = {:?}, expr = {:?}))",
+    get_str("id"),
+    get_str("message"),
+    get_str("expression")
+  ))
+}
+
+// Generates the validator closure for the `(buf.validate.field)` rules of a field
+fn field_rules_closure(
+  rules: &DynamicMessage,
+  field: &FieldDescriptor,
+  unsupported: &mut Vec<String>,
+) -> Option<String> {
+  let calls = field_rules_calls(rules, field.is_list(), field.is_map(), unsupported, "");
+
+  (!calls.is_empty()).then(|| format!("|v| v{calls}"))
+}
+
+fn field_rules_calls(
+  rules: &DynamicMessage,
+  is_list: bool,
+  is_map: bool,
+  unsupported: &mut Vec<String>,
+  path_prefix: &str,
+) -> String {
+  let mut calls = String::new();
+
+  for (field, value) in rules.fields() {
+    let name = field.name();
+
+    match (name, value) {
+      ("required", Value::Bool(true)) if !is_list && !is_map => calls.push_str(".required()"),
+      ("ignore", value) => match enum_value_name(&field.kind(), value).as_deref() {
+        Some("IGNORE_IF_ZERO_VALUE" | "IGNORE_IF_UNPOPULATED") => {
+          calls.push_str(".ignore_if_zero_value()");
+        }
+        Some("IGNORE_ALWAYS") => calls.push_str(".ignore_always()"),
+        _ => {}
+      },
+      ("cel", Value::List(items)) => {
+        for call in items.iter().filter_map(cel_call) {
+          calls.push_str(&call);
+        }
+      }
+      (_, Value::Message(type_rules)) if field.containing_oneof().is_some() => {
+        calls.push_str(&type_rules_calls(
+          type_rules,
+          name,
+          unsupported,
+          &format!("{path_prefix}{name}."),
+        ));
+      }
+      _ => unsupported.push(format!("(buf.validate.field).{path_prefix}{name}")),
+    }
+  }
+
+  calls
+}
+
+// The rules that have a matching method (with the same name, or with a trailing underscore
+// for `const_` and `in_`) in the validator builder for each type of rules
+fn builder_rules(rules_type: &str) -> &'static [&'static str] {
+  match rules_type {
+    "string" => &[
+      "const",
+      "len",
+      "min_len",
+      "max_len",
+      "len_bytes",
+      "min_bytes",
+      "max_bytes",
+      "pattern",
+      "prefix",
+      "suffix",
+      "contains",
+      "not_contains",
+      "in",
+      "not_in",
+      "email",
+      "hostname",
+      "ip",
+      "ipv4",
+      "ipv6",
+      "uri",
+      "uri_ref",
+      "address",
+      "uuid",
+      "tuuid",
+      "ulid",
+      "ip_with_prefixlen",
+      "ipv4_with_prefixlen",
+      "ipv6_with_prefixlen",
+      "ip_prefix",
+      "ipv4_prefix",
+      "ipv6_prefix",
+      "host_and_port",
+      "well_known_regex",
+      "strict",
+    ],
+    "bytes" => &[
+      "const", "len", "min_len", "max_len", "pattern", "prefix", "suffix", "contains", "in",
+      "not_in", "ip", "ipv4", "ipv6", "uuid",
+    ],
+    "float" | "double" => &["const", "lt", "lte", "gt", "gte", "in", "not_in", "finite"],
+    "int32" | "int64" | "uint32" | "uint64" | "sint32" | "sint64" | "fixed32" | "fixed64"
+    | "sfixed32" | "sfixed64" | "duration" => &["const", "lt", "lte", "gt", "gte", "in", "not_in"],
+    "bool" => &["const"],
+    "enum" => &["const", "defined_only", "in", "not_in"],
+    "repeated" => &["min_items", "max_items", "unique", "items"],
+    "map" => &["min_pairs", "max_pairs", "keys", "values"],
+    "any" => &["in", "not_in"],
+    "timestamp" => &[
+      "const", "lt", "lte", "gt", "gte", "lt_now", "gt_now", "within",
+    ],
+    "field_mask" => &["const", "in", "not_in"],
+    _ => &[],
+  }
+}
+
+fn type_rules_calls(
+  rules: &DynamicMessage,
+  rules_type: &str,
+  unsupported: &mut Vec<String>,
+  path: &str,
+) -> String {
+  let mut calls = String::new();
+
+  // `strict` defaults to true when it's not set
+  let is_strict = !rules.has_field_by_name("strict")
+    || rules
+      .get_field_by_name("strict")
+      .is_none_or(|value| value.as_bool() != Some(false));
+
+  for (field, value) in rules.fields() {
+    let name = field.name();
+
+    if !builder_rules(rules_type).contains(&name) {
+      unsupported.push(format!("(buf.validate.field).{path}{name}"));
+      continue;
+    }
+
+    match (name, value) {
+      ("items" | "keys" | "values", Value::Message(nested)) => {
+        let nested_calls =
+          field_rules_calls(nested, false, false, unsupported, &format!("{path}{name}."));
+
+        if !nested_calls.is_empty() {
+          let _ = write!(calls, ".{name}(|v| v{nested_calls})");
+        }
+      }
+      ("const", value) => {
+        if let Some(literal) = rule_literal(value) {
+          let _ = write!(calls, ".const_({literal})");
+        } else {
+          unsupported.push(format!("(buf.validate.field).{path}{name}"));
+        }
+      }
+      ("in" | "not_in", Value::List(items)) => {
+        let literals: Option<Vec<String>> = items.iter().map(rule_literal).collect();
+
+        if let Some(literals) = literals {
+          let method = if name == "in" { "in_" } else { "not_in" };
+
+          if matches!(items.first(), Some(Value::Bytes(_))) {
+            let slices: Vec<String> = literals
+              .iter()
+              .map(|literal| format!("{literal}.as_slice()"))
+              .collect();
+
+            let _ = write!(calls, ".{method}([{}].as_slice())", slices.join(", "));
+          } else {
+            let _ = write!(calls, ".{method}([{}])", literals.join(", "));
+          }
+        } else {
+          unsupported.push(format!("(buf.validate.field).{path}{name}"));
+        }
+      }
+      ("well_known_regex", value) => {
+        let suffix = if is_strict { "strict" } else { "loose" };
+
+        match enum_value_name(&field.kind(), value).as_deref() {
+          Some("KNOWN_REGEX_HTTP_HEADER_NAME") => {
+            let _ = write!(calls, ".header_name_{suffix}()");
+          }
+          Some("KNOWN_REGEX_HTTP_HEADER_VALUE") => {
+            let _ = write!(calls, ".header_value_{suffix}()");
+          }
+          _ => unsupported.push(format!("(buf.validate.field).{path}{name}")),
+        }
+      }
+      ("strict", _) => {}
+      (_, Value::Bool(true)) => {
+        let _ = write!(calls, ".{name}()");
+      }
+      (_, Value::Bool(false)) => {}
+      (_, value) => {
+        if let Some(literal) = rule_literal(value) {
+          let _ = write!(calls, ".{name}({literal})");
+        } else {
+          unsupported.push(format!("(buf.validate.field).{path}{name}"));
+        }
+      }
+    }
+  }
+
+  calls
+}
+
+fn rule_literal(value: &Value) -> Option<String> {
+  let literal = match value {
+    Value::Bool(boolean) => boolean.to_string(),
+    Value::I32(int) => int.to_string(),
+    Value::I64(int) => int.to_string(),
+    Value::U32(int) => int.to_string(),
+    Value::U64(int) => int.to_string(),
+    Value::EnumNumber(int) => int.to_string(),
+    Value::F32(float) => float_literal(*float, "f32"),
+    Value::F64(float) => float_literal(*float, "f64"),
+    Value::String(string) => format!("{string:?}"),
+    Value::Bytes(bytes) => bytes_literal(bytes),
+    Value::Message(message) => match message.descriptor().full_name() {
+      "google.protobuf.Duration" => duration_expr(message),
+      "google.protobuf.Timestamp" => timestamp_expr(message),
+      _ => return None,
+    },
+    Value::List(_) | Value::Map(_) => return None,
+  };
+
+  Some(literal)
+}
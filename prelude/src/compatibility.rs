@@ -0,0 +1,381 @@
+use crate::*;
+
+/// A change between two versions of a [`Package`] that breaks the compatibility of the wire format
+/// (or of the generated APIs) with the previous version.
+#[non_exhaustive]
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+pub enum BreakingChange {
+  #[error("The package `{old}` has been renamed to `{new}`")]
+  PackageRenamed { old: FixedStr, new: FixedStr },
+  #[error("The tag of the field `{message}.{field}` has changed from {old_tag} to {new_tag}")]
+  FieldTagChanged {
+    message: FixedStr,
+    field: FixedStr,
+    old_tag: i32,
+    new_tag: i32,
+  },
+  #[error(
+    "The tag {tag} in message `{message}` was used by the field `{old_field}` and is now used by the field `{new_field}`"
+  )]
+  FieldTagReused {
+    message: FixedStr,
+    tag: i32,
+    old_field: FixedStr,
+    new_field: FixedStr,
+  },
+  #[error(
+    "The type of the field `{message}.{field}` has changed from `{old_type}` to `{new_type}`"
+  )]
+  FieldTypeChanged {
+    message: FixedStr,
+    field: FixedStr,
+    old_type: String,
+    new_type: String,
+  },
+  #[error(
+    "The field `{message}.{field}` (tag {tag}) has been removed, but neither its name nor its number has been reserved"
+  )]
+  FieldNotReserved {
+    message: FixedStr,
+    field: FixedStr,
+    tag: i32,
+  },
+  #[error("The number of the variant `{enum_}.{variant}` has changed from {old_tag} to {new_tag}")]
+  VariantTagChanged {
+    enum_: FixedStr,
+    variant: FixedStr,
+    old_tag: i32,
+    new_tag: i32,
+  },
+  #[error(
+    "The number {tag} in enum `{enum_}` was used by the variant `{old_variant}` and is now used by the variant `{new_variant}`"
+  )]
+  VariantTagReused {
+    enum_: FixedStr,
+    tag: i32,
+    old_variant: FixedStr,
+    new_variant: FixedStr,
+  },
+  #[error(
+    "The variant `{enum_}.{variant}` (number {tag}) has been removed, but neither its name nor its number has been reserved"
+  )]
+  VariantNotReserved {
+    enum_: FixedStr,
+    variant: FixedStr,
+    tag: i32,
+  },
+  #[error("The message `{message}` has been removed")]
+  MessageRemoved { message: FixedStr },
+  #[error("The enum `{enum_}` has been removed")]
+  EnumRemoved { enum_: FixedStr },
+  #[error("The service `{service}` has been removed")]
+  ServiceRemoved { service: FixedStr },
+  #[error("The method `{service}.{method}` has been removed")]
+  MethodRemoved { service: FixedStr, method: FixedStr },
+  #[error("The signature of the method `{service}.{method}` has changed from `{old}` to `{new}`")]
+  MethodSignatureChanged {
+    service: FixedStr,
+    method: FixedStr,
+    old: String,
+    new: String,
+  },
+}
+
+impl Package {
+  /// Compares this package with a previous version of it, and returns the list of changes that
+  /// break the compatibility with the previous version.
+  ///
+  /// Since tags can be assigned automatically, reordering or removing a field can silently change the tags
+  /// of other fields, so this can be used (for example with a snapshot of the package serialized with the `serde` feature)
+  /// to catch these changes in a test.
+  ///
+  /// The checks are performed on the items that are present in both versions, by matching them by name.
+  /// Removed messages and enums are reported as well (only once, if their parent message has also been removed).
+  /// Since a renamed field cannot be told apart from a removed field whose tag has been reused by a new field,
+  /// changing the name of a field (or enum variant) is also reported as a breaking change.
+  #[must_use]
+  pub fn breaking_changes(&self, previous: &Self) -> Vec<BreakingChange> {
+    let mut changes = Vec::new();
+
+    if self.name != previous.name {
+      changes.push(BreakingChange::PackageRenamed {
+        old: previous.name.clone(),
+        new: self.name.clone(),
+      });
+    }
+
+    let mut new_messages = HashMap::default();
+    let mut new_enums = HashMap::default();
+    let mut old_messages = HashMap::default();
+    let mut old_enums = HashMap::default();
+
+    collect_items(self, &mut new_messages, &mut new_enums);
+    collect_items(previous, &mut old_messages, &mut old_enums);
+
+    let mut old_message_names: Vec<&str> = old_messages.keys().copied().collect();
+    old_message_names.sort_unstable();
+
+    let parent_removed = |name: &str| {
+      name
+        .rsplit_once('.')
+        .is_some_and(|(parent, _)| !new_messages.contains_key(parent))
+    };
+
+    for name in old_message_names {
+      if let Some(new_message) = new_messages.get(name) {
+        compare_messages(old_messages[name], new_message, &mut changes);
+      } else if !parent_removed(name) {
+        changes.push(BreakingChange::MessageRemoved {
+          message: old_messages[name].name.clone(),
+        });
+      }
+    }
+
+    let mut old_enum_names: Vec<&str> = old_enums.keys().copied().collect();
+    old_enum_names.sort_unstable();
+
+    for name in old_enum_names {
+      if let Some(new_enum) = new_enums.get(name) {
+        compare_enums(old_enums[name], new_enum, &mut changes);
+      } else if !parent_removed(name) {
+        changes.push(BreakingChange::EnumRemoved {
+          enum_: old_enums[name].name.clone(),
+        });
+      }
+    }
+
+    let new_services: Vec<&Service> = self
+      .files
+      .iter()
+      .flat_map(|f| &f.services)
+      .collect();
+
+    for old_service in previous.files.iter().flat_map(|f| &f.services) {
+      if let Some(new_service) = new_services
+        .iter()
+        .find(|s| s.name == old_service.name)
+      {
+        compare_services(old_service, new_service, &mut changes);
+      } else {
+        changes.push(BreakingChange::ServiceRemoved {
+          service: old_service.name.clone(),
+        });
+      }
+    }
+
+    changes
+  }
+
+  /// Checks that this package is compatible with a previous version of it.
+  ///
+  /// See [`Package::breaking_changes`] for more information.
+  pub fn check_wire_compatibility(&self, previous: &Self) -> Result<(), String> {
+    let changes = self.breaking_changes(previous);
+
+    if changes.is_empty() {
+      return Ok(());
+    }
+
+    let mut error = String::new();
+
+    let _ = writeln!(
+      error,
+      "❌ Found breaking changes in package `{}`:",
+      self.name.bright_yellow()
+    );
+
+    for change in changes {
+      let _ = writeln!(error, "  - {change}");
+    }
+
+    Err(error)
+  }
+
+  /// Checks that this package is compatible with a previous version of it, serialized as JSON.
+  ///
+  /// See [`Package::breaking_changes`] for more information.
+  #[cfg(feature = "serde")]
+  pub fn check_wire_compatibility_with_json(&self, previous: &str) -> Result<(), String> {
+    let previous: Self = serde_json::from_str(previous)
+      .map_err(|e| format!("Failed to deserialize the previous version of the package: {e}"))?;
+
+    self.check_wire_compatibility(&previous)
+  }
+}
+
+fn collect_items<'a>(
+  package: &'a Package,
+  messages: &mut HashMap<&'a str, &'a Message>,
+  enums: &mut HashMap<&'a str, &'a Enum>,
+) {
+  fn collect_message<'a>(
+    message: &'a Message,
+    messages: &mut HashMap<&'a str, &'a Message>,
+    enums: &mut HashMap<&'a str, &'a Enum>,
+  ) {
+    messages.insert(&message.name, message);
+
+    for enum_ in &message.enums {
+      enums.insert(&enum_.name, enum_);
+    }
+
+    for nested in &message.messages {
+      collect_message(nested, messages, enums);
+    }
+  }
+
+  for file in &package.files {
+    for message in &file.messages {
+      collect_message(message, messages, enums);
+    }
+
+    for enum_ in &file.enums {
+      enums.insert(&enum_.name, enum_);
+    }
+  }
+}
+
+// Optional and required fields share the same encoding as normal fields,
+// so only the cardinality and the type of the values are relevant here
+fn wire_type(field_type: &FieldType) -> String {
+  match field_type {
+    FieldType::Normal(type_) | FieldType::Optional(type_) | FieldType::Required(type_) => {
+      type_.to_string()
+    }
+    FieldType::Repeated(type_) => format!("repeated {type_}"),
+    FieldType::Map { keys, values } => format!("map<{keys}, {values}>"),
+  }
+}
+
+// Reserving either the number or the name is enough to prevent accidental reuses
+fn is_reserved(tag: i32, name: &str, numbers: &[Range<i32>], names: &[FixedStr]) -> bool {
+  numbers.iter().any(|range| range.contains(&tag)) || names.iter().any(|n| *n == name)
+}
+
+fn compare_messages(old: &Message, new: &Message, changes: &mut Vec<BreakingChange>) {
+  for old_field in old.fields() {
+    if let Some(new_field) = new.fields().find(|f| f.name == old_field.name) {
+      if new_field.tag != old_field.tag {
+        changes.push(BreakingChange::FieldTagChanged {
+          message: old.name.clone(),
+          field: old_field.name.clone(),
+          old_tag: old_field.tag,
+          new_tag: new_field.tag,
+        });
+      }
+
+      let old_type = wire_type(&old_field.type_);
+      let new_type = wire_type(&new_field.type_);
+
+      if old_type != new_type {
+        changes.push(BreakingChange::FieldTypeChanged {
+          message: old.name.clone(),
+          field: old_field.name.clone(),
+          old_type,
+          new_type,
+        });
+      }
+    } else if let Some(new_field) = new.fields().find(|f| f.tag == old_field.tag) {
+      // Only checked for removed fields, since swapping the tags of two fields is already reported above
+      changes.push(BreakingChange::FieldTagReused {
+        message: old.name.clone(),
+        tag: old_field.tag,
+        old_field: old_field.name.clone(),
+        new_field: new_field.name.clone(),
+      });
+    } else if !is_reserved(
+      old_field.tag,
+      &old_field.name,
+      &new.reserved_numbers,
+      &new.reserved_names,
+    ) {
+      changes.push(BreakingChange::FieldNotReserved {
+        message: old.name.clone(),
+        field: old_field.name.clone(),
+        tag: old_field.tag,
+      });
+    }
+  }
+}
+
+fn compare_enums(old: &Enum, new: &Enum, changes: &mut Vec<BreakingChange>) {
+  for old_variant in &old.variants {
+    if let Some(new_variant) = new
+      .variants
+      .iter()
+      .find(|v| v.name == old_variant.name)
+    {
+      if new_variant.tag != old_variant.tag {
+        changes.push(BreakingChange::VariantTagChanged {
+          enum_: old.name.clone(),
+          variant: old_variant.name.clone(),
+          old_tag: old_variant.tag,
+          new_tag: new_variant.tag,
+        });
+      }
+    } else if let Some(new_variant) = new
+      .variants
+      .iter()
+      .find(|v| v.tag == old_variant.tag)
+    {
+      // Aliases are allowed to share the same number, so this is only checked for removed variants
+      changes.push(BreakingChange::VariantTagReused {
+        enum_: old.name.clone(),
+        tag: old_variant.tag,
+        old_variant: old_variant.name.clone(),
+        new_variant: new_variant.name.clone(),
+      });
+    } else if !is_reserved(
+      old_variant.tag,
+      &old_variant.name,
+      &new.reserved_numbers,
+      &new.reserved_names,
+    ) {
+      changes.push(BreakingChange::VariantNotReserved {
+        enum_: old.name.clone(),
+        variant: old_variant.name.clone(),
+        tag: old_variant.tag,
+      });
+    }
+  }
+}
+
+fn method_signature(handler: &ServiceHandler) -> String {
+  let stream_prefix = |is_stream: bool| if is_stream { "stream " } else { "" };
+
+  format!(
+    "({}{}) returns ({}{})",
+    stream_prefix(handler.client_streaming),
+    handler.request,
+    stream_prefix(handler.server_streaming),
+    handler.response
+  )
+}
+
+fn compare_services(old: &Service, new: &Service, changes: &mut Vec<BreakingChange>) {
+  for old_handler in &old.handlers {
+    let Some(new_handler) = new
+      .handlers
+      .iter()
+      .find(|h| h.name == old_handler.name)
+    else {
+      changes.push(BreakingChange::MethodRemoved {
+        service: old.name.clone(),
+        method: old_handler.name.clone(),
+      });
+      continue;
+    };
+
+    let old_signature = method_signature(old_handler);
+    let new_signature = method_signature(new_handler);
+
+    if old_signature != new_signature {
+      changes.push(BreakingChange::MethodSignatureChanged {
+        service: old.name.clone(),
+        method: old_handler.name.clone(),
+        old: old_signature,
+        new: new_signature,
+      });
+    }
+  }
+}
@@ -51,6 +51,54 @@ macro_rules! register_proto_data {
   ($($tokens:tt)*) => {};
 }
 
+/// Registers a function that adds custom functions to every CEL context, so that they can be used in CEL rules.
+///
+/// The function receives the [`cel::Context`](::cel::Context) and can add functions to it with `add_function`.
+/// Requires the `inventory` feature. Otherwise, [`register_runtime_cel_functions`](crate::register_runtime_cel_functions) can be used.
+///
+/// ```rust,ignore
+/// use prelude::*;
+///
+/// fn sku_functions(ctx: &mut cel::Context) {
+///   ctx.add_function("isValidSku", |sku: Arc<String>| sku.starts_with("SKU-"));
+/// }
+///
+/// register_cel_functions!(sku_functions);
+/// ```
+#[cfg(feature = "cel")]
+#[macro_export]
+macro_rules! register_cel_functions {
+  ($install:path) => {
+    $crate::register_proto_data! {
+      $crate::RegistryCelFunctions {
+        install: $install,
+      }
+    }
+  };
+}
+
+/// Declares the names of the CEL variables that are provided by a [`ValidationData`](crate::ValidationData)
+/// at validation time, so that the automatically generated consistency checks do not report them as undeclared.
+///
+/// Requires the `inventory` feature. Otherwise, [`register_runtime_cel_variables`](crate::register_runtime_cel_variables) can be used.
+///
+/// ```rust,ignore
+/// use prelude::*;
+///
+/// register_cel_variables!("tenant", "caller_role");
+/// ```
+#[cfg(feature = "cel")]
+#[macro_export]
+macro_rules! register_cel_variables {
+  ($($name:literal),* $(,)?) => {
+    $crate::register_proto_data! {
+      $crate::RegistryCelVariables {
+        names: &[$($name),*],
+      }
+    }
+  };
+}
+
 /// This macro can be used to manually create a package schema if the inventory feature is not available.
 ///
 /// The first argument is the name of the package, and the second argument is a collection of the files to include.
@@ -255,6 +303,7 @@ macro_rules! use_proto_file {
       name: $file.name,
       package: $file.package,
       extern_path: ::core::module_path!(),
+      edition: $file.edition,
     };
   };
 }
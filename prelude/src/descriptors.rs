@@ -0,0 +1,494 @@
+mod known_options;
+mod option_encoding;
+#[cfg(feature = "descriptor-pool")]
+mod pool;
+#[cfg(feature = "descriptor-pool")]
+pub use pool::DescriptorConversionError;
+#[cfg(feature = "runtime-descriptors")]
+mod runtime;
+#[cfg(feature = "runtime-descriptors")]
+pub use runtime::{
+  DescriptorPoolError, MessageDescriptorError, message_descriptor, try_message_descriptor,
+};
+
+pub use option_encoding::{OptionEncodingError, OptionEncodingErrorKind};
+
+use crate::*;
+use option_encoding::*;
+use proto_types::{
+  DescriptorProto, Edition as DescriptorEdition, EnumDescriptorProto, EnumOptions,
+  EnumValueDescriptorProto, EnumValueOptions, FieldDescriptorProto, FieldOptions,
+  FileDescriptorProto, FileDescriptorSet, FileOptions, MessageOptions, MethodDescriptorProto,
+  MethodOptions, OneofDescriptorProto, OneofOptions, ServiceDescriptorProto, ServiceOptions,
+  descriptor_proto::ReservedRange,
+  enum_descriptor_proto::EnumReservedRange,
+  field_descriptor_proto::{Label, Type as DescriptorType},
+};
+
+impl Package {
+  /// Converts all the files in this package into a [`FileDescriptorSet`].
+  ///
+  /// The `prost_types` options messages have no fields for extensions, so the options other than `deprecated`
+  /// (such as `features`, custom options and the `buf.validate` rules) are stored as uninterpreted options.
+  /// Use [`Package::encode_file_descriptor_set`] to get the encoded set where those options are encoded as actual fields and extensions.
+  ///
+  /// The files imported from other packages (such as `buf/validate/validate.proto`) are not included in the set.
+  #[must_use]
+  pub fn to_file_descriptor_set(&self) -> FileDescriptorSet {
+    FileDescriptorSet {
+      file: self
+        .files
+        .iter()
+        .map(|f| f.to_file_descriptor_proto())
+        .collect(),
+    }
+  }
+
+  /// Encodes the [`FileDescriptorSet`] for this package.
+  ///
+  /// Unlike the descriptors returned by [`Package::to_file_descriptor_set`], the `features` and the `buf.validate` rules
+  /// are encoded as actual fields and extensions of the options messages, so that the output can be used directly
+  /// by tools such as `protovalidate` or `prost-reflect`.
+  ///
+  /// Returns an error if one of these options does not match its schema, such as a rule with a value of the wrong type.
+  /// Other custom options are kept as uninterpreted options.
+  pub fn encode_file_descriptor_set(&self) -> Result<Vec<u8>, OptionEncodingError> {
+    let mut buf = Vec::new();
+
+    for file in &self.files {
+      encode_len_delimited(1, &file.encode_file_descriptor_proto()?, &mut buf);
+    }
+
+    Ok(buf)
+  }
+}
+
+impl ProtoFile {
+  /// Converts this file into a [`FileDescriptorProto`].
+  ///
+  /// See [`Package::to_file_descriptor_set`] for more information about how options are converted.
+  #[must_use]
+  pub fn to_file_descriptor_proto(&self) -> FileDescriptorProto {
+    DescriptorBuilder::new(self).build_file(self)
+  }
+
+  /// Encodes the [`FileDescriptorProto`] for this file.
+  ///
+  /// See [`Package::encode_file_descriptor_set`] for more information about how options are encoded.
+  pub fn encode_file_descriptor_proto(&self) -> Result<Vec<u8>, OptionEncodingError> {
+    let mut builder = DescriptorBuilder::new(self);
+
+    let descriptor = builder.build_file(self);
+
+    encode_file(&descriptor, &builder.option_sites)
+  }
+}
+
+struct DescriptorBuilder {
+  edition: Edition,
+  option_sites: OptionSites,
+}
+
+fn child_path(path: &[i32], field_number: i32, index: usize) -> Vec<i32> {
+  let mut child = path.to_vec();
+  child.push(field_number);
+  child.push(index_to_i32(index));
+  child
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+const fn index_to_i32(index: usize) -> i32 {
+  index as i32
+}
+
+fn type_name(package: &FixedStr, name: &FixedStr) -> String {
+  format!(".{package}.{name}")
+}
+
+/// Returns the name of the synthetic message that `protoc` generates for a map field.
+fn map_entry_name(field_name: &str) -> String {
+  let mut output = String::with_capacity(field_name.len() + 5);
+  let mut capitalize_next = true;
+
+  for ch in field_name.chars() {
+    if ch == '_' {
+      capitalize_next = true;
+    } else if capitalize_next {
+      output.push(ch.to_ascii_uppercase());
+      capitalize_next = false;
+    } else {
+      output.push(ch);
+    }
+  }
+
+  output.push_str("Entry");
+
+  output
+}
+
+fn map_entry(name: String, keys: ProtoMapKey, values: &ProtoType) -> DescriptorProto {
+  let key_field = FieldDescriptorProto {
+    name: Some("key".into()),
+    number: Some(1),
+    label: Some(Label::Optional as i32),
+    r#type: Some(DescriptorType::from(keys.into_type()) as i32),
+    json_name: Some("key".into()),
+    ..Default::default()
+  };
+
+  let value_field = FieldDescriptorProto {
+    name: Some("value".into()),
+    number: Some(2),
+    label: Some(Label::Optional as i32),
+    r#type: Some(DescriptorType::from(values.clone()) as i32),
+    type_name: proto_type_name(values),
+    json_name: Some("value".into()),
+    ..Default::default()
+  };
+
+  DescriptorProto {
+    name: Some(name),
+    field: vec![key_field, value_field],
+    options: Some(MessageOptions {
+      map_entry: Some(true),
+      ..Default::default()
+    }),
+    ..Default::default()
+  }
+}
+
+fn proto_type_name(proto_type: &ProtoType) -> Option<String> {
+  match proto_type {
+    ProtoType::Scalar(_) => None,
+    ProtoType::Message(path) | ProtoType::Enum(path) => Some(type_name(&path.package, &path.name)),
+  }
+}
+
+const fn descriptor_syntax(edition: Edition) -> &'static str {
+  match edition {
+    Edition::Proto2 => "proto2",
+    Edition::Proto3 => "proto3",
+    Edition::E2023 => "editions",
+  }
+}
+
+impl DescriptorBuilder {
+  fn new(file: &ProtoFile) -> Self {
+    Self {
+      edition: file.edition,
+      option_sites: OptionSites::default(),
+    }
+  }
+
+  fn options<O: DescriptorOptions>(&mut self, options: &[ProtoOption], path: &[i32]) -> Option<O> {
+    if options.is_empty() {
+      return None;
+    }
+
+    let mut descriptor_options = O::default();
+    let mut uninterpreted = Vec::new();
+
+    for option in options {
+      if !descriptor_options.set_standard_option(option) {
+        descriptor_options
+          .uninterpreted_options()
+          .extend(uninterpreted_options(option));
+        uninterpreted.push(option.clone());
+      }
+    }
+
+    if !uninterpreted.is_empty() {
+      self
+        .option_sites
+        .insert(path.to_vec(), uninterpreted);
+    }
+
+    Some(descriptor_options)
+  }
+
+  fn build_file(&mut self, file: &ProtoFile) -> FileDescriptorProto {
+    let message_type = file
+      .messages
+      .iter()
+      .enumerate()
+      .map(|(i, msg)| self.message(msg, &child_path(&[], 4, i)))
+      .collect();
+
+    let enum_type = file
+      .enums
+      .iter()
+      .enumerate()
+      .map(|(i, enum_)| self.enum_(enum_, &child_path(&[], 5, i)))
+      .collect();
+
+    let service = file
+      .services
+      .iter()
+      .enumerate()
+      .map(|(i, service)| self.service(service, &child_path(&[], 6, i)))
+      .collect();
+
+    let extension = file
+      .extensions
+      .iter()
+      .flat_map(|ext| {
+        ext
+          .fields
+          .iter()
+          .map(move |field| (ext.target, field))
+      })
+      .enumerate()
+      .map(|(i, (target, field))| {
+        let mut descriptor = self.field(field, FieldScope::Extension, &child_path(&[], 7, i));
+
+        descriptor.extendee = Some(format!(".{target}"));
+
+        descriptor
+      })
+      .collect();
+
+    FileDescriptorProto {
+      name: Some(file.name.to_string()),
+      package: Some(file.package.to_string()),
+      dependency: file
+        .imports
+        .as_sorted_vec()
+        .into_iter()
+        .map(|import| import.to_string())
+        .collect(),
+      message_type,
+      enum_type,
+      service,
+      extension,
+      options: self.options::<FileOptions>(&file.options_with_features(), &[8]),
+      syntax: Some(descriptor_syntax(file.edition).to_string()),
+      edition: (file.edition == Edition::E2023).then_some(DescriptorEdition::Edition2023 as i32),
+      ..Default::default()
+    }
+  }
+
+  fn message(&mut self, message: &Message, path: &[i32]) -> DescriptorProto {
+    let mut fields: Vec<FieldDescriptorProto> = Vec::new();
+    let mut oneofs: Vec<OneofDescriptorProto> = Vec::new();
+    let mut map_entries: Vec<DescriptorProto> = Vec::new();
+    let mut synthetic_oneofs: Vec<usize> = Vec::new();
+
+    for entry in &message.entries {
+      match entry {
+        MessageEntry::Field(field) => {
+          let field_idx = fields.len();
+
+          let mut descriptor =
+            self.field(field, FieldScope::Message, &child_path(path, 2, field_idx));
+
+          if let FieldType::Map { keys, values } = &field.type_ {
+            let entry_name = map_entry_name(&field.name);
+
+            descriptor.type_name = Some(format!(
+              "{}.{entry_name}",
+              type_name(&message.package, &message.name)
+            ));
+
+            map_entries.push(map_entry(entry_name, *keys, values));
+          }
+
+          if descriptor.proto3_optional == Some(true) {
+            synthetic_oneofs.push(field_idx);
+          }
+
+          fields.push(descriptor);
+        }
+        MessageEntry::Oneof(oneof) => {
+          let oneof_idx = index_to_i32(oneofs.len());
+
+          let oneof_options: Vec<ProtoOption> = oneof.options_with_validators().cloned().collect();
+
+          oneofs.push(OneofDescriptorProto {
+            name: Some(oneof.name.to_string()),
+            options: self
+              .options::<OneofOptions>(&oneof_options, &[path, &[8, oneof_idx, 2]].concat()),
+          });
+
+          for field in &oneof.fields {
+            let mut descriptor =
+              self.field(field, FieldScope::Oneof, &child_path(path, 2, fields.len()));
+
+            descriptor.oneof_index = Some(oneof_idx);
+
+            fields.push(descriptor);
+          }
+        }
+      }
+    }
+
+    // Synthetic oneofs must come after all of the real ones
+    for field_idx in synthetic_oneofs {
+      let field = &mut fields[field_idx];
+
+      field.oneof_index = Some(index_to_i32(oneofs.len()));
+
+      oneofs.push(OneofDescriptorProto {
+        name: Some(format!("_{}", field.name())),
+        options: None,
+      });
+    }
+
+    let nested_type = message
+      .messages
+      .iter()
+      .enumerate()
+      .map(|(i, nested)| self.message(nested, &child_path(path, 3, i)))
+      .chain(map_entries)
+      .collect();
+
+    let enum_type = message
+      .enums
+      .iter()
+      .enumerate()
+      .map(|(i, enum_)| self.enum_(enum_, &child_path(path, 4, i)))
+      .collect();
+
+    DescriptorProto {
+      name: Some(message.short_name.to_string()),
+      field: fields,
+      nested_type,
+      enum_type,
+      oneof_decl: oneofs,
+      options: self
+        .options::<MessageOptions>(&message.options_with_validators(), &[path, &[7]].concat()),
+      reserved_range: message
+        .reserved_numbers
+        .iter()
+        .map(|range| ReservedRange {
+          start: Some(range.start),
+          end: Some(range.end),
+        })
+        .collect(),
+      reserved_name: message
+        .reserved_names
+        .iter()
+        .map(|name| name.to_string())
+        .collect(),
+      ..Default::default()
+    }
+  }
+
+  fn field(&mut self, field: &Field, scope: FieldScope, path: &[i32]) -> FieldDescriptorProto {
+    let edition = self.edition;
+
+    let (label, inner) = match &field.type_ {
+      FieldType::Normal(inner) | FieldType::Optional(inner) => (Label::Optional, Some(inner)),
+      FieldType::Repeated(inner) => (Label::Repeated, Some(inner)),
+      FieldType::Required(inner) => {
+        // Editions files use `features.field_presence = LEGACY_REQUIRED` instead
+        let label = if edition.is_edition() {
+          Label::Optional
+        } else {
+          Label::Required
+        };
+
+        (label, Some(inner))
+      }
+      // The type name is set by the caller, which also creates the map entry message
+      FieldType::Map { .. } => (Label::Repeated, None),
+    };
+
+    let (field_type, type_name) = match inner {
+      Some(inner) => (DescriptorType::from(inner.clone()), proto_type_name(inner)),
+      None => (DescriptorType::Message, None),
+    };
+
+    let proto3_optional = (edition == Edition::Proto3
+      && scope == FieldScope::Message
+      && matches!(&field.type_, FieldType::Optional(inner) if !inner.is_message()))
+    .then_some(true);
+
+    FieldDescriptorProto {
+      name: Some(field.name.to_string()),
+      number: Some(field.tag),
+      label: Some(label as i32),
+      r#type: Some(field_type as i32),
+      type_name,
+      json_name: Some(field.json_name()),
+      default_value: (edition != Edition::Proto3)
+        .then(|| {
+          field
+            .default_value()
+            .map(descriptor_default_value)
+        })
+        .flatten(),
+      options: self.options::<FieldOptions>(
+        &field
+          .options_with_validators(edition, scope)
+          .into_iter()
+          // `json_name` and `default` have dedicated fields in the descriptor
+          .filter(|option| !matches!(option.name.as_ref(), "json_name" | "default"))
+          .collect::<Vec<_>>(),
+        &[path, &[8]].concat(),
+      ),
+      proto3_optional,
+      ..Default::default()
+    }
+  }
+
+  fn enum_(&mut self, enum_: &Enum, path: &[i32]) -> EnumDescriptorProto {
+    let value = enum_
+      .variants
+      .iter()
+      .enumerate()
+      .map(|(i, variant)| EnumValueDescriptorProto {
+        name: Some(variant.name.to_string()),
+        number: Some(variant.tag),
+        options: self.options::<EnumValueOptions>(
+          &variant.options,
+          &[child_path(path, 2, i).as_slice(), &[3]].concat(),
+        ),
+      })
+      .collect();
+
+    EnumDescriptorProto {
+      name: Some(enum_.short_name.to_string()),
+      value,
+      options: self.options::<EnumOptions>(&enum_.options_with_features(), &[path, &[3]].concat()),
+      // Unlike the ones for messages, the reserved ranges for enums are inclusive
+      reserved_range: enum_
+        .reserved_numbers
+        .iter()
+        .map(|range| EnumReservedRange {
+          start: Some(range.start),
+          end: Some(range.end - 1),
+        })
+        .collect(),
+      reserved_name: enum_
+        .reserved_names
+        .iter()
+        .map(|name| name.to_string())
+        .collect(),
+    }
+  }
+
+  fn service(&mut self, service: &Service, path: &[i32]) -> ServiceDescriptorProto {
+    let method = service
+      .handlers
+      .iter()
+      .enumerate()
+      .map(|(i, handler)| MethodDescriptorProto {
+        name: Some(handler.name.to_string()),
+        input_type: Some(type_name(&handler.request.package, &handler.request.name)),
+        output_type: Some(type_name(&handler.response.package, &handler.response.name)),
+        options: self.options::<MethodOptions>(
+          &handler.options,
+          &[child_path(path, 2, i).as_slice(), &[4]].concat(),
+        ),
+        client_streaming: handler.client_streaming.then_some(true),
+        server_streaming: handler.server_streaming.then_some(true),
+      })
+      .collect();
+
+    ServiceDescriptorProto {
+      name: Some(service.name.to_string()),
+      method,
+      options: self.options::<ServiceOptions>(&service.options, &[path, &[3]].concat()),
+    }
+  }
+}
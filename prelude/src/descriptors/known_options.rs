@@ -0,0 +1,555 @@
+#[cfg(feature = "runtime-descriptors")]
+use super::index_to_i32;
+use crate::*;
+use proto_types::protovalidate::{Ignore, KnownRegex};
+#[cfg(feature = "runtime-descriptors")]
+use proto_types::{
+  DescriptorProto, EnumDescriptorProto, EnumValueDescriptorProto, FieldDescriptorProto,
+  FileDescriptorProto, OneofDescriptorProto,
+  field_descriptor_proto::{Label, Type as DescriptorType},
+};
+
+#[cfg(feature = "descriptor-pool")]
+pub(crate) const VALIDATE_PROTO: &str = "buf/validate/validate.proto";
+
+/// The name of the file built by [`validate_file_descriptor`], which only covers a part of `buf/validate/validate.proto`.
+#[cfg(feature = "descriptor-pool")]
+pub(crate) const KNOWN_RULES_PROTO: &str = "buf/validate/validate_known_rules.proto";
+
+/// The number of the `buf.validate.message`, `buf.validate.oneof` and `buf.validate.field` extensions.
+pub(crate) const BUF_VALIDATE_EXTENSION: u32 = 1159;
+
+/// The type of a field in one of the options messages whose schema is known, used to encode
+/// the values of [`ProtoOption`]s with the correct wire format.
+#[derive(Clone, Copy)]
+#[cfg_attr(not(feature = "runtime-descriptors"), allow(dead_code))]
+pub(crate) enum KnownType {
+  Int32,
+  Int64,
+  Uint32,
+  Uint64,
+  Sint32,
+  Sint64,
+  Fixed32,
+  Fixed64,
+  Sfixed32,
+  Sfixed64,
+  Float,
+  Double,
+  Bool,
+  String,
+  Bytes,
+  /// The full name of the enum, and the function that resolves the name of a variant into its number.
+  Enum(&'static str, fn(&str) -> Option<i32>),
+  /// Returns the schema of the message.
+  Message(fn() -> &'static KnownMessage),
+}
+
+#[cfg_attr(not(feature = "runtime-descriptors"), allow(dead_code))]
+pub(crate) struct KnownField {
+  pub name: &'static str,
+  pub number: u32,
+  pub repeated: bool,
+  /// The name of the oneof that contains the field, if there is one.
+  pub oneof: Option<&'static str>,
+  pub type_: KnownType,
+}
+
+#[cfg_attr(not(feature = "runtime-descriptors"), allow(dead_code))]
+pub(crate) struct KnownMessage {
+  /// The full name of the message, without the leading dot.
+  pub name: &'static str,
+  pub fields: &'static [KnownField],
+}
+
+/// Returns the number of the `features` field and the schema of `google.protobuf.FeatureSet`
+/// for the given options message.
+pub(crate) const fn features_field(
+  target: ExtensionTarget,
+) -> (u32, fn() -> &'static KnownMessage) {
+  let number = match target {
+    ExtensionTarget::FileOptions => 50,
+    ExtensionTarget::MessageOptions => 12,
+    ExtensionTarget::FieldOptions => 21,
+    ExtensionTarget::OneofOptions => 1,
+    ExtensionTarget::EnumOptions => 7,
+    ExtensionTarget::EnumValueOptions => 2,
+    ExtensionTarget::ServiceOptions => 34,
+    ExtensionTarget::MethodOptions => 35,
+  };
+
+  (number, feature_set)
+}
+
+/// Returns the schema of the `buf.validate` extension for the given options message, if there is one.
+pub(crate) fn buf_validate_extension(
+  name: &str,
+  target: ExtensionTarget,
+) -> Option<fn() -> &'static KnownMessage> {
+  match (name, target) {
+    ("buf.validate.field", ExtensionTarget::FieldOptions) => Some(field_rules),
+    ("buf.validate.message", ExtensionTarget::MessageOptions) => Some(message_rules),
+    ("buf.validate.oneof", ExtensionTarget::OneofOptions) => Some(oneof_rules),
+    _ => None,
+  }
+}
+
+// Repeated fields are marked with `[repeated]` after their number, and the fields of a oneof with `in "oneof_name"`
+macro_rules! known_message {
+  ($fn_name:ident = $full_name:literal { $($name:literal = $number:literal $([$repeated:ident])? $(in $oneof:literal)? => $type_:expr),* $(,)? }) => {
+    fn $fn_name() -> &'static KnownMessage {
+      static MESSAGE: KnownMessage = KnownMessage {
+        name: $full_name,
+        fields: &[
+          $(KnownField {
+            name: $name,
+            number: $number,
+            repeated: known_message!(@repeated $($repeated)?),
+            oneof: known_message!(@oneof $($oneof)?),
+            type_: $type_,
+          }),*
+        ],
+      };
+
+      &MESSAGE
+    }
+  };
+  (@repeated) => { false };
+  (@repeated repeated) => { true };
+  (@oneof) => { None };
+  (@oneof $oneof:literal) => { Some($oneof) };
+}
+
+macro_rules! numeric_rules {
+  ($fn_name:ident = $full_name:literal, $type_:ident) => {
+    known_message!($fn_name = $full_name {
+      "const" = 1 => KnownType::$type_,
+      "lt" = 2 => KnownType::$type_,
+      "lte" = 3 => KnownType::$type_,
+      "gt" = 4 => KnownType::$type_,
+      "gte" = 5 => KnownType::$type_,
+      "in" = 6 [repeated] => KnownType::$type_,
+      "not_in" = 7 [repeated] => KnownType::$type_,
+      "example" = 8 [repeated] => KnownType::$type_,
+    });
+  };
+}
+
+macro_rules! float_rules {
+  ($fn_name:ident = $full_name:literal, $type_:ident) => {
+    known_message!($fn_name = $full_name {
+      "const" = 1 => KnownType::$type_,
+      "lt" = 2 => KnownType::$type_,
+      "lte" = 3 => KnownType::$type_,
+      "gt" = 4 => KnownType::$type_,
+      "gte" = 5 => KnownType::$type_,
+      "in" = 6 [repeated] => KnownType::$type_,
+      "not_in" = 7 [repeated] => KnownType::$type_,
+      "finite" = 8 => KnownType::Bool,
+      "example" = 9 [repeated] => KnownType::$type_,
+    });
+  };
+}
+
+known_message!(feature_set = "google.protobuf.FeatureSet" {
+  "field_presence" = 1 => KnownType::Enum("google.protobuf.FeatureSet.FieldPresence", FieldPresence::number_from_name),
+  "enum_type" = 2 => KnownType::Enum("google.protobuf.FeatureSet.EnumType", EnumType::number_from_name),
+  "repeated_field_encoding" = 3 => KnownType::Enum("google.protobuf.FeatureSet.RepeatedFieldEncoding", RepeatedFieldEncoding::number_from_name),
+  "utf8_validation" = 4 => KnownType::Enum("google.protobuf.FeatureSet.Utf8Validation", Utf8Validation::number_from_name),
+  "message_encoding" = 5 => KnownType::Enum("google.protobuf.FeatureSet.MessageEncoding", MessageEncoding::number_from_name),
+  "json_format" = 6 => KnownType::Enum("google.protobuf.FeatureSet.JsonFormat", JsonFormat::number_from_name),
+});
+
+fn ignore_number(name: &str) -> Option<i32> {
+  Ignore::from_str_name(name).map(|ignore| ignore as i32)
+}
+
+fn known_regex_number(name: &str) -> Option<i32> {
+  KnownRegex::from_str_name(name).map(|regex| regex as i32)
+}
+
+known_message!(duration = "google.protobuf.Duration" {
+  "seconds" = 1 => KnownType::Int64,
+  "nanos" = 2 => KnownType::Int32,
+});
+
+known_message!(timestamp = "google.protobuf.Timestamp" {
+  "seconds" = 1 => KnownType::Int64,
+  "nanos" = 2 => KnownType::Int32,
+});
+
+known_message!(field_mask = "google.protobuf.FieldMask" {
+  "paths" = 1 [repeated] => KnownType::String,
+});
+
+known_message!(rule = "buf.validate.Rule" {
+  "id" = 1 => KnownType::String,
+  "message" = 2 => KnownType::String,
+  "expression" = 3 => KnownType::String,
+});
+
+known_message!(message_oneof_rule = "buf.validate.MessageOneofRule" {
+  "fields" = 1 [repeated] => KnownType::String,
+  "required" = 2 => KnownType::Bool,
+});
+
+known_message!(message_rules = "buf.validate.MessageRules" {
+  "cel_expression" = 5 [repeated] => KnownType::String,
+  "cel" = 3 [repeated] => KnownType::Message(rule),
+  "oneof" = 4 [repeated] => KnownType::Message(message_oneof_rule),
+});
+
+known_message!(oneof_rules = "buf.validate.OneofRules" {
+  "required" = 1 => KnownType::Bool,
+});
+
+known_message!(field_rules = "buf.validate.FieldRules" {
+  "cel_expression" = 29 [repeated] => KnownType::String,
+  "cel" = 23 [repeated] => KnownType::Message(rule),
+  "required" = 25 => KnownType::Bool,
+  "ignore" = 27 => KnownType::Enum("buf.validate.Ignore", ignore_number),
+  "float" = 1 in "type" => KnownType::Message(float_rules),
+  "double" = 2 in "type" => KnownType::Message(double_rules),
+  "int32" = 3 in "type" => KnownType::Message(int32_rules),
+  "int64" = 4 in "type" => KnownType::Message(int64_rules),
+  "uint32" = 5 in "type" => KnownType::Message(uint32_rules),
+  "uint64" = 6 in "type" => KnownType::Message(uint64_rules),
+  "sint32" = 7 in "type" => KnownType::Message(sint32_rules),
+  "sint64" = 8 in "type" => KnownType::Message(sint64_rules),
+  "fixed32" = 9 in "type" => KnownType::Message(fixed32_rules),
+  "fixed64" = 10 in "type" => KnownType::Message(fixed64_rules),
+  "sfixed32" = 11 in "type" => KnownType::Message(sfixed32_rules),
+  "sfixed64" = 12 in "type" => KnownType::Message(sfixed64_rules),
+  "bool" = 13 in "type" => KnownType::Message(bool_rules),
+  "string" = 14 in "type" => KnownType::Message(string_rules),
+  "bytes" = 15 in "type" => KnownType::Message(bytes_rules),
+  "enum" = 16 in "type" => KnownType::Message(enum_rules),
+  "repeated" = 18 in "type" => KnownType::Message(repeated_rules),
+  "map" = 19 in "type" => KnownType::Message(map_rules),
+  "any" = 20 in "type" => KnownType::Message(any_rules),
+  "duration" = 21 in "type" => KnownType::Message(duration_rules),
+  "timestamp" = 22 in "type" => KnownType::Message(timestamp_rules),
+  "field_mask" = 28 in "type" => KnownType::Message(field_mask_rules),
+});
+
+float_rules!(float_rules = "buf.validate.FloatRules", Float);
+float_rules!(double_rules = "buf.validate.DoubleRules", Double);
+numeric_rules!(int32_rules = "buf.validate.Int32Rules", Int32);
+numeric_rules!(int64_rules = "buf.validate.Int64Rules", Int64);
+numeric_rules!(uint32_rules = "buf.validate.UInt32Rules", Uint32);
+numeric_rules!(uint64_rules = "buf.validate.UInt64Rules", Uint64);
+numeric_rules!(sint32_rules = "buf.validate.SInt32Rules", Sint32);
+numeric_rules!(sint64_rules = "buf.validate.SInt64Rules", Sint64);
+numeric_rules!(fixed32_rules = "buf.validate.Fixed32Rules", Fixed32);
+numeric_rules!(fixed64_rules = "buf.validate.Fixed64Rules", Fixed64);
+numeric_rules!(sfixed32_rules = "buf.validate.SFixed32Rules", Sfixed32);
+numeric_rules!(sfixed64_rules = "buf.validate.SFixed64Rules", Sfixed64);
+
+known_message!(bool_rules = "buf.validate.BoolRules" {
+  "const" = 1 => KnownType::Bool,
+  "example" = 2 [repeated] => KnownType::Bool,
+});
+
+known_message!(string_rules = "buf.validate.StringRules" {
+  "const" = 1 => KnownType::String,
+  "len" = 19 => KnownType::Uint64,
+  "min_len" = 2 => KnownType::Uint64,
+  "max_len" = 3 => KnownType::Uint64,
+  "len_bytes" = 20 => KnownType::Uint64,
+  "min_bytes" = 4 => KnownType::Uint64,
+  "max_bytes" = 5 => KnownType::Uint64,
+  "pattern" = 6 => KnownType::String,
+  "prefix" = 7 => KnownType::String,
+  "suffix" = 8 => KnownType::String,
+  "contains" = 9 => KnownType::String,
+  "not_contains" = 23 => KnownType::String,
+  "in" = 10 [repeated] => KnownType::String,
+  "not_in" = 11 [repeated] => KnownType::String,
+  "email" = 12 => KnownType::Bool,
+  "hostname" = 13 => KnownType::Bool,
+  "ip" = 14 => KnownType::Bool,
+  "ipv4" = 15 => KnownType::Bool,
+  "ipv6" = 16 => KnownType::Bool,
+  "uri" = 17 => KnownType::Bool,
+  "uri_ref" = 18 => KnownType::Bool,
+  "address" = 21 => KnownType::Bool,
+  "uuid" = 22 => KnownType::Bool,
+  "tuuid" = 33 => KnownType::Bool,
+  "ip_with_prefixlen" = 26 => KnownType::Bool,
+  "ipv4_with_prefixlen" = 27 => KnownType::Bool,
+  "ipv6_with_prefixlen" = 28 => KnownType::Bool,
+  "ip_prefix" = 29 => KnownType::Bool,
+  "ipv4_prefix" = 30 => KnownType::Bool,
+  "ipv6_prefix" = 31 => KnownType::Bool,
+  "host_and_port" = 32 => KnownType::Bool,
+  "ulid" = 35 => KnownType::Bool,
+  "well_known_regex" = 24 => KnownType::Enum("buf.validate.KnownRegex", known_regex_number),
+  "strict" = 25 => KnownType::Bool,
+  "example" = 34 [repeated] => KnownType::String,
+});
+
+known_message!(bytes_rules = "buf.validate.BytesRules" {
+  "const" = 1 => KnownType::Bytes,
+  "len" = 13 => KnownType::Uint64,
+  "min_len" = 2 => KnownType::Uint64,
+  "max_len" = 3 => KnownType::Uint64,
+  "pattern" = 4 => KnownType::String,
+  "prefix" = 5 => KnownType::Bytes,
+  "suffix" = 6 => KnownType::Bytes,
+  "contains" = 7 => KnownType::Bytes,
+  "in" = 8 [repeated] => KnownType::Bytes,
+  "not_in" = 9 [repeated] => KnownType::Bytes,
+  "ip" = 10 => KnownType::Bool,
+  "ipv4" = 11 => KnownType::Bool,
+  "ipv6" = 12 => KnownType::Bool,
+  "uuid" = 15 => KnownType::Bool,
+  "example" = 14 [repeated] => KnownType::Bytes,
+});
+
+known_message!(enum_rules = "buf.validate.EnumRules" {
+  "const" = 1 => KnownType::Int32,
+  "defined_only" = 2 => KnownType::Bool,
+  "in" = 3 [repeated] => KnownType::Int32,
+  "not_in" = 4 [repeated] => KnownType::Int32,
+  "example" = 5 [repeated] => KnownType::Int32,
+});
+
+known_message!(repeated_rules = "buf.validate.RepeatedRules" {
+  "min_items" = 1 => KnownType::Uint64,
+  "max_items" = 2 => KnownType::Uint64,
+  "unique" = 3 => KnownType::Bool,
+  "items" = 4 => KnownType::Message(field_rules),
+});
+
+known_message!(map_rules = "buf.validate.MapRules" {
+  "min_pairs" = 1 => KnownType::Uint64,
+  "max_pairs" = 2 => KnownType::Uint64,
+  "keys" = 4 => KnownType::Message(field_rules),
+  "values" = 5 => KnownType::Message(field_rules),
+});
+
+known_message!(any_rules = "buf.validate.AnyRules" {
+  "in" = 2 [repeated] => KnownType::String,
+  "not_in" = 3 [repeated] => KnownType::String,
+});
+
+known_message!(duration_rules = "buf.validate.DurationRules" {
+  "const" = 2 => KnownType::Message(duration),
+  "lt" = 3 => KnownType::Message(duration),
+  "lte" = 4 => KnownType::Message(duration),
+  "gt" = 5 => KnownType::Message(duration),
+  "gte" = 6 => KnownType::Message(duration),
+  "in" = 7 [repeated] => KnownType::Message(duration),
+  "not_in" = 8 [repeated] => KnownType::Message(duration),
+  "example" = 9 [repeated] => KnownType::Message(duration),
+});
+
+known_message!(timestamp_rules = "buf.validate.TimestampRules" {
+  "const" = 2 => KnownType::Message(timestamp),
+  "lt" = 3 => KnownType::Message(timestamp),
+  "lte" = 4 => KnownType::Message(timestamp),
+  "lt_now" = 7 => KnownType::Bool,
+  "gt" = 5 => KnownType::Message(timestamp),
+  "gte" = 6 => KnownType::Message(timestamp),
+  "gt_now" = 8 => KnownType::Bool,
+  "within" = 9 => KnownType::Message(timestamp),
+  "example" = 10 [repeated] => KnownType::Message(timestamp),
+});
+
+known_message!(field_mask_rules = "buf.validate.FieldMaskRules" {
+  "const" = 1 => KnownType::Message(field_mask),
+  "in" = 2 [repeated] => KnownType::String,
+  "not_in" = 3 [repeated] => KnownType::String,
+  "example" = 4 [repeated] => KnownType::Message(field_mask),
+});
+
+/// Builds a partial descriptor of `buf/validate/validate.proto` from the schemas of the rules that are known to this crate,
+/// so that the `buf.validate` rules can be resolved when the file is not available in the descriptor pool.
+///
+/// Only the rules that can be encoded by [`Package::encode_file_descriptor_set`] are included, so things like the
+/// predefined rules or the `Violations` message are missing. For this reason, the file is named [`KNOWN_RULES_PROTO`]
+/// rather than pretending to be the real `buf/validate/validate.proto`.
+#[cfg(feature = "runtime-descriptors")]
+pub(crate) fn validate_file_descriptor() -> FileDescriptorProto {
+  let mut messages: Vec<&'static KnownMessage> = Vec::new();
+
+  for rules in [field_rules(), message_rules(), oneof_rules()] {
+    collect_validate_messages(rules, &mut messages);
+  }
+
+  let extension = [
+    ("field", ".google.protobuf.FieldOptions", field_rules()),
+    (
+      "message",
+      ".google.protobuf.MessageOptions",
+      message_rules(),
+    ),
+    ("oneof", ".google.protobuf.OneofOptions", oneof_rules()),
+  ]
+  .into_iter()
+  .map(|(name, extendee, rules)| FieldDescriptorProto {
+    name: Some(name.into()),
+    number: Some(number_to_i32(BUF_VALIDATE_EXTENSION)),
+    label: Some(Label::Optional as i32),
+    r#type: Some(DescriptorType::Message as i32),
+    type_name: Some(format!(".{}", rules.name)),
+    extendee: Some(extendee.into()),
+    ..Default::default()
+  })
+  .collect();
+
+  // The enums are small, so their variants are found by checking the first numbers
+  let ignore_values = (0..16)
+    .filter_map(|number| Ignore::try_from(number).ok())
+    .map(|ignore| (ignore.as_str_name(), ignore as i32));
+  let known_regex_values = (0..16)
+    .filter_map(|number| KnownRegex::try_from(number).ok())
+    .map(|regex| (regex.as_str_name(), regex as i32));
+
+  FileDescriptorProto {
+    name: Some(KNOWN_RULES_PROTO.into()),
+    package: Some("buf.validate".into()),
+    dependency: vec![
+      "google/protobuf/descriptor.proto".into(),
+      "google/protobuf/duration.proto".into(),
+      "google/protobuf/field_mask.proto".into(),
+      "google/protobuf/timestamp.proto".into(),
+    ],
+    message_type: messages
+      .into_iter()
+      .map(known_message_descriptor)
+      .collect(),
+    enum_type: vec![
+      enum_descriptor("Ignore", ignore_values),
+      enum_descriptor("KnownRegex", known_regex_values),
+    ],
+    extension,
+    syntax: Some("proto2".into()),
+    ..Default::default()
+  }
+}
+
+#[cfg(feature = "runtime-descriptors")]
+fn collect_validate_messages(
+  message: &'static KnownMessage,
+  messages: &mut Vec<&'static KnownMessage>,
+) {
+  // The well known types are defined in their own files
+  if !message.name.starts_with("buf.validate.")
+    || messages
+      .iter()
+      .any(|collected| collected.name == message.name)
+  {
+    return;
+  }
+
+  messages.push(message);
+
+  for field in message.fields {
+    if let KnownType::Message(nested) = field.type_ {
+      collect_validate_messages(nested(), messages);
+    }
+  }
+}
+
+#[cfg(feature = "runtime-descriptors")]
+fn known_message_descriptor(message: &'static KnownMessage) -> DescriptorProto {
+  let mut oneofs: Vec<&str> = Vec::new();
+
+  for oneof in message
+    .fields
+    .iter()
+    .filter_map(|field| field.oneof)
+  {
+    if !oneofs.contains(&oneof) {
+      oneofs.push(oneof);
+    }
+  }
+
+  let field = message
+    .fields
+    .iter()
+    .map(|field| {
+      let (type_, type_name) = match field.type_ {
+        KnownType::Int32 => (DescriptorType::Int32, None),
+        KnownType::Int64 => (DescriptorType::Int64, None),
+        KnownType::Uint32 => (DescriptorType::Uint32, None),
+        KnownType::Uint64 => (DescriptorType::Uint64, None),
+        KnownType::Sint32 => (DescriptorType::Sint32, None),
+        KnownType::Sint64 => (DescriptorType::Sint64, None),
+        KnownType::Fixed32 => (DescriptorType::Fixed32, None),
+        KnownType::Fixed64 => (DescriptorType::Fixed64, None),
+        KnownType::Sfixed32 => (DescriptorType::Sfixed32, None),
+        KnownType::Sfixed64 => (DescriptorType::Sfixed64, None),
+        KnownType::Float => (DescriptorType::Float, None),
+        KnownType::Double => (DescriptorType::Double, None),
+        KnownType::Bool => (DescriptorType::Bool, None),
+        KnownType::String => (DescriptorType::String, None),
+        KnownType::Bytes => (DescriptorType::Bytes, None),
+        KnownType::Enum(name, _) => (DescriptorType::Enum, Some(format!(".{name}"))),
+        KnownType::Message(nested) => {
+          (DescriptorType::Message, Some(format!(".{}", nested().name)))
+        }
+      };
+
+      let label = if field.repeated {
+        Label::Repeated
+      } else {
+        Label::Optional
+      };
+
+      FieldDescriptorProto {
+        name: Some(field.name.into()),
+        number: Some(number_to_i32(field.number)),
+        label: Some(label as i32),
+        r#type: Some(type_ as i32),
+        type_name,
+        oneof_index: field
+          .oneof
+          .and_then(|oneof| oneofs.iter().position(|name| *name == oneof))
+          .map(index_to_i32),
+        ..Default::default()
+      }
+    })
+    .collect();
+
+  DescriptorProto {
+    name: message
+      .name
+      .strip_prefix("buf.validate.")
+      .map(String::from),
+    field,
+    oneof_decl: oneofs
+      .into_iter()
+      .map(|name| OneofDescriptorProto {
+        name: Some(name.into()),
+        ..Default::default()
+      })
+      .collect(),
+    ..Default::default()
+  }
+}
+
+#[cfg(feature = "runtime-descriptors")]
+fn enum_descriptor(
+  name: &str,
+  values: impl Iterator<Item = (&'static str, i32)>,
+) -> EnumDescriptorProto {
+  EnumDescriptorProto {
+    name: Some(name.into()),
+    value: values
+      .map(|(name, number)| EnumValueDescriptorProto {
+        name: Some(name.into()),
+        number: Some(number),
+        ..Default::default()
+      })
+      .collect(),
+    ..Default::default()
+  }
+}
+
+#[cfg(feature = "runtime-descriptors")]
+#[allow(clippy::cast_possible_wrap)]
+const fn number_to_i32(number: u32) -> i32 {
+  number as i32
+}
@@ -0,0 +1,799 @@
+use super::{child_path, known_options::*};
+use crate::*;
+use prost::{
+  Message as ProstMessage,
+  encoding::{WireType, encode_key, encode_varint},
+};
+use proto_types::{
+  DescriptorProto, Duration, EnumDescriptorProto, EnumOptions, EnumValueDescriptorProto,
+  EnumValueOptions, FieldDescriptorProto, FieldOptions, FileDescriptorProto, FileOptions,
+  MessageOptions, MethodDescriptorProto, MethodOptions, OneofDescriptorProto, OneofOptions,
+  ServiceDescriptorProto, ServiceOptions, Timestamp, UninterpretedOption,
+  uninterpreted_option::NamePart,
+};
+
+/// The options that are stored as uninterpreted options in the descriptors, mapped to the
+/// path of their options message, using the same format as `SourceCodeInfo.Location.path`.
+pub(crate) type OptionSites = HashMap<Vec<i32>, Vec<ProtoOption>>;
+
+/// Shared behaviour for the options messages of the various descriptors.
+pub(crate) trait DescriptorOptions: ProstMessage + Default + Clone {
+  const TARGET: ExtensionTarget;
+
+  fn uninterpreted_options(&mut self) -> &mut Vec<UninterpretedOption>;
+
+  /// Sets the value of a standard option (such as `deprecated`) in its dedicated field.
+  /// Returns `false` if the option is not a standard option.
+  fn set_standard_option(&mut self, option: &ProtoOption) -> bool;
+}
+
+macro_rules! impl_descriptor_options {
+  ($($options:ident => $target:ident $(, deprecated: $deprecated:ident)?);* $(;)?) => {
+    $(
+      impl DescriptorOptions for $options {
+        const TARGET: ExtensionTarget = ExtensionTarget::$target;
+
+        fn uninterpreted_options(&mut self) -> &mut Vec<UninterpretedOption> {
+          &mut self.uninterpreted_option
+        }
+
+        #[allow(unused_variables)]
+        fn set_standard_option(&mut self, option: &ProtoOption) -> bool {
+          $(
+            if let ("deprecated", OptionValue::Bool($deprecated)) = (option.name.as_ref(), &option.value) {
+              self.deprecated = Some(*$deprecated);
+              return true;
+            }
+          )?
+
+          false
+        }
+      }
+    )*
+  };
+}
+
+impl_descriptor_options!(
+  FileOptions => FileOptions, deprecated: value;
+  MessageOptions => MessageOptions, deprecated: value;
+  FieldOptions => FieldOptions, deprecated: value;
+  OneofOptions => OneofOptions;
+  EnumOptions => EnumOptions, deprecated: value;
+  EnumValueOptions => EnumValueOptions, deprecated: value;
+  ServiceOptions => ServiceOptions, deprecated: value;
+  MethodOptions => MethodOptions, deprecated: value;
+);
+
+/// Splits the name of an option into its parts, such as `(buf.validate.field).string` -> [`buf.validate.field` (extension), `string`].
+fn option_name_parts(name: &str) -> Vec<NamePart> {
+  let mut parts = Vec::new();
+  let mut rest = name;
+
+  while !rest.is_empty() {
+    if let Some(extension) = rest.strip_prefix('(')
+      && let Some((ext_name, after)) = extension.split_once(')')
+    {
+      parts.push(NamePart {
+        name_part: ext_name.to_string(),
+        is_extension: true,
+      });
+
+      rest = after.strip_prefix('.').unwrap_or(after);
+    } else {
+      let (part, after) = rest.split_once('.').unwrap_or((rest, ""));
+
+      parts.push(NamePart {
+        name_part: part.to_string(),
+        is_extension: false,
+      });
+
+      rest = after;
+    }
+  }
+
+  parts
+}
+
+/// Converts a [`ProtoOption`] into the uninterpreted options that `protoc` would create for it.
+///
+/// List values are split into one option for each item, as it happens with repeated options in protobuf files.
+pub(crate) fn uninterpreted_options(option: &ProtoOption) -> Vec<UninterpretedOption> {
+  let name = option_name_parts(&option.name);
+
+  let values: Vec<&OptionValue> = match &option.value {
+    OptionValue::List(list) => list.iter().collect(),
+    other => vec![other],
+  };
+
+  values
+    .into_iter()
+    .map(|value| {
+      let mut uninterpreted = UninterpretedOption {
+        name: name.clone(),
+        ..Default::default()
+      };
+
+      match value {
+        OptionValue::Bool(b) => uninterpreted.identifier_value = Some(b.to_string()),
+        OptionValue::Int(int) => {
+          if *int < 0 {
+            uninterpreted.negative_int_value = Some(*int);
+          } else {
+            uninterpreted.positive_int_value = Some(int.unsigned_abs());
+          }
+        }
+        OptionValue::Uint(uint) => uninterpreted.positive_int_value = Some(*uint),
+        OptionValue::Float(float) => uninterpreted.double_value = Some(*float),
+        OptionValue::String(str) => {
+          uninterpreted.string_value = Some(str.as_bytes().to_vec());
+        }
+        OptionValue::Bytes(bytes) => uninterpreted.string_value = Some(bytes.to_vec()),
+        OptionValue::Enum(ident) => uninterpreted.identifier_value = Some(ident.to_string()),
+        OptionValue::Message(_)
+        | OptionValue::List(_)
+        | OptionValue::Duration(_)
+        | OptionValue::Timestamp(_) => {
+          let mut aggregate = String::new();
+
+          write_aggregate_fields(value, &mut aggregate);
+
+          uninterpreted.aggregate_value = Some(aggregate);
+        }
+      };
+
+      uninterpreted
+    })
+    .collect()
+}
+
+/// Writes the fields of a message value in the text format, without the outer braces.
+fn write_aggregate_fields(value: &OptionValue, output: &mut String) {
+  match value {
+    OptionValue::Message(message) => {
+      for (i, field) in message.iter().enumerate() {
+        if i != 0 {
+          output.push(' ');
+        }
+
+        // Extensions are wrapped in brackets in the text format
+        match field
+          .name
+          .strip_prefix('(')
+          .and_then(|n| n.strip_suffix(')'))
+        {
+          Some(ext_name) => write!(output, "[{ext_name}]: ").unwrap(),
+          None => write!(output, "{}: ", field.name).unwrap(),
+        };
+
+        write_text_value(&field.value, output);
+      }
+    }
+    OptionValue::Duration(Duration { seconds, nanos })
+    | OptionValue::Timestamp(Timestamp { seconds, nanos }) => {
+      write!(output, "seconds: {seconds} nanos: {nanos}").unwrap();
+    }
+    _ => write_text_value(value, output),
+  }
+}
+
+fn write_text_value(value: &OptionValue, output: &mut String) {
+  match value {
+    OptionValue::Bool(b) => write!(output, "{b}").unwrap(),
+    OptionValue::Int(int) => write!(output, "{int}").unwrap(),
+    OptionValue::Uint(uint) => write!(output, "{uint}").unwrap(),
+    OptionValue::Float(float) => {
+      if float.is_nan() {
+        output.push_str("nan");
+      } else if float.is_infinite() {
+        output.push_str(if *float > 0.0 { "inf" } else { "-inf" });
+      } else {
+        write!(output, "{float:?}").unwrap();
+      }
+    }
+    OptionValue::String(str) => write_escaped_bytes(str.as_bytes(), output),
+    OptionValue::Bytes(bytes) => write_escaped_bytes(bytes, output),
+    OptionValue::Enum(ident) => output.push_str(ident),
+    OptionValue::List(list) => {
+      output.push('[');
+
+      for (i, item) in list.iter().enumerate() {
+        if i != 0 {
+          output.push_str(", ");
+        }
+
+        write_text_value(item, output);
+      }
+
+      output.push(']');
+    }
+    OptionValue::Message(_) | OptionValue::Duration(_) | OptionValue::Timestamp(_) => {
+      output.push_str("{ ");
+      write_aggregate_fields(value, output);
+      output.push_str(" }");
+    }
+  }
+}
+
+/// Formats a value for the `default_value` of a field descriptor, which stores strings as they are and bytes in their escaped form.
+pub(crate) fn descriptor_default_value(value: &OptionValue) -> String {
+  let mut output = String::new();
+
+  match value {
+    OptionValue::String(str) => output.push_str(str),
+    OptionValue::Bytes(bytes) => write_escaped_bytes_content(bytes, &mut output),
+    _ => write_text_value(value, &mut output),
+  }
+
+  output
+}
+
+fn write_escaped_bytes(bytes: &[u8], output: &mut String) {
+  output.push('"');
+  write_escaped_bytes_content(bytes, output);
+  output.push('"');
+}
+
+fn write_escaped_bytes_content(bytes: &[u8], output: &mut String) {
+  for byte in bytes {
+    match byte {
+      b'"' => output.push_str("\\\""),
+      b'\\' => output.push_str("\\\\"),
+      b'\n' => output.push_str("\\n"),
+      b'\r' => output.push_str("\\r"),
+      b'\t' => output.push_str("\\t"),
+      0x20..=0x7e => output.push(char::from(*byte)),
+      _ => write!(output, "\\{byte:03o}").unwrap(),
+    }
+  }
+}
+
+pub(crate) fn encode_len_delimited(tag: u32, bytes: &[u8], buf: &mut Vec<u8>) {
+  encode_key(tag, WireType::LengthDelimited, buf);
+  encode_varint(bytes.len() as u64, buf);
+  buf.extend_from_slice(bytes);
+}
+
+/// An error that occurs when an option whose schema is known (such as the `buf.validate` rules or `features`)
+/// cannot be encoded as an actual field or extension of its options message.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("failed to encode the option `{option}` of `{item}`: {kind}")]
+pub struct OptionEncodingError {
+  /// The full name of the element that the option belongs to.
+  pub item: String,
+  /// The path of the value that could not be encoded, such as `(buf.validate.field).string.min_len`.
+  pub option: String,
+  pub kind: OptionEncodingErrorKind,
+}
+
+/// The reason why an option could not be encoded.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum OptionEncodingErrorKind {
+  #[error("`{0}` cannot be used on this element")]
+  UnknownExtension(String),
+  #[error("unknown field `{0}`")]
+  UnknownField(String),
+  #[error("expected a value of type {expected}, found {found}")]
+  TypeMismatch {
+    expected: &'static str,
+    found: &'static str,
+  },
+  #[error("`{value}` is not a variant of `{enum_name}`")]
+  UnknownEnumValue {
+    enum_name: &'static str,
+    value: String,
+  },
+}
+
+// The path of the error is built while unwinding, starting from the value that could not be encoded
+struct PathError {
+  path: Vec<String>,
+  kind: OptionEncodingErrorKind,
+}
+
+impl PathError {
+  const fn new(kind: OptionEncodingErrorKind) -> Self {
+    Self {
+      path: Vec::new(),
+      kind,
+    }
+  }
+
+  fn within(mut self, name: &str) -> Self {
+    self.path.push(name.to_string());
+    self
+  }
+}
+
+const fn known_type_name(type_: KnownType) -> &'static str {
+  match type_ {
+    KnownType::Int32 => "int32",
+    KnownType::Int64 => "int64",
+    KnownType::Uint32 => "uint32",
+    KnownType::Uint64 => "uint64",
+    KnownType::Sint32 => "sint32",
+    KnownType::Sint64 => "sint64",
+    KnownType::Fixed32 => "fixed32",
+    KnownType::Fixed64 => "fixed64",
+    KnownType::Sfixed32 => "sfixed32",
+    KnownType::Sfixed64 => "sfixed64",
+    KnownType::Float => "float",
+    KnownType::Double => "double",
+    KnownType::Bool => "bool",
+    KnownType::String => "string",
+    KnownType::Bytes => "bytes",
+    KnownType::Enum(name, _) => name,
+    KnownType::Message(_) => "message",
+  }
+}
+
+const fn option_value_kind(value: &OptionValue) -> &'static str {
+  match value {
+    OptionValue::Bool(_) => "bool",
+    OptionValue::Int(_) => "int",
+    OptionValue::Uint(_) => "uint",
+    OptionValue::Float(_) => "float",
+    OptionValue::String(_) => "string",
+    OptionValue::Bytes(_) => "bytes",
+    OptionValue::List(_) => "list",
+    OptionValue::Message(_) => "message",
+    OptionValue::Enum(_) => "enum",
+    OptionValue::Duration(_) => "duration",
+    OptionValue::Timestamp(_) => "timestamp",
+  }
+}
+
+/// Encodes an option as an actual field (or extension) of an options message, if its schema is known.
+///
+/// Returns `Ok(false)` (without writing anything) if the schema of the option is not known, so that it can be kept
+/// as an uninterpreted option, and an error if the schema is known but the value does not match it.
+fn encode_known_option(
+  option: &ProtoOption,
+  target: ExtensionTarget,
+  item: &str,
+  buf: &mut Vec<u8>,
+) -> Result<bool, OptionEncodingError> {
+  let name = option_name_parts(&option.name);
+
+  let Some((first, path)) = name.split_first() else {
+    return Ok(false);
+  };
+
+  let to_error = |error: PathError| {
+    let mut option = option_name_part(first);
+
+    for part in error.path.iter().rev() {
+      option.push('.');
+      option.push_str(part);
+    }
+
+    OptionEncodingError {
+      item: item.to_string(),
+      option,
+      kind: error.kind,
+    }
+  };
+
+  let (number, schema) = if first.is_extension {
+    match buf_validate_extension(&first.name_part, target) {
+      Some(schema) => (BUF_VALIDATE_EXTENSION, schema),
+      None if first.name_part.starts_with("buf.validate.") => {
+        return Err(to_error(PathError::new(
+          OptionEncodingErrorKind::UnknownExtension(option_name_part(first)),
+        )));
+      }
+      None => return Ok(false),
+    }
+  } else if first.name_part == "features" {
+    features_field(target)
+  } else {
+    return Ok(false);
+  };
+
+  let mut message_bytes = Vec::new();
+
+  encode_message_path(schema().fields, path, &option.value, &mut message_bytes)
+    .map_err(to_error)?;
+
+  encode_len_delimited(number, &message_bytes, buf);
+
+  Ok(true)
+}
+
+fn option_name_part(part: &NamePart) -> String {
+  if part.is_extension {
+    format!("({})", part.name_part)
+  } else {
+    part.name_part.clone()
+  }
+}
+
+/// Encodes a value located at the given path inside a message with a known schema.
+fn encode_message_path(
+  schema: &[KnownField],
+  path: &[NamePart],
+  value: &OptionValue,
+  buf: &mut Vec<u8>,
+) -> Result<(), PathError> {
+  let Some((first, rest)) = path.split_first() else {
+    let OptionValue::Message(message) = value else {
+      return Err(PathError::new(OptionEncodingErrorKind::TypeMismatch {
+        expected: "message",
+        found: option_value_kind(value),
+      }));
+    };
+
+    return encode_message_fields(schema, message, buf);
+  };
+
+  let name = option_name_part(first);
+
+  let field = known_field(schema, &name).map_err(|e| e.within(&name))?;
+
+  if rest.is_empty() {
+    return encode_known_field(field, value, buf).map_err(|e| e.within(&name));
+  }
+
+  let KnownType::Message(nested_schema) = field.type_ else {
+    return Err(
+      PathError::new(OptionEncodingErrorKind::TypeMismatch {
+        expected: known_type_name(field.type_),
+        found: "message",
+      })
+      .within(&name),
+    );
+  };
+
+  let mut nested_bytes = Vec::new();
+
+  encode_message_path(nested_schema().fields, rest, value, &mut nested_bytes)
+    .map_err(|e| e.within(&name))?;
+
+  encode_len_delimited(field.number, &nested_bytes, buf);
+
+  Ok(())
+}
+
+// Extensions (which are wrapped in parentheses) are never part of a known schema
+fn known_field<'a>(schema: &'a [KnownField], name: &str) -> Result<&'a KnownField, PathError> {
+  schema
+    .iter()
+    .find(|field| field.name == name)
+    .ok_or_else(|| PathError::new(OptionEncodingErrorKind::UnknownField(name.to_string())))
+}
+
+fn encode_message_fields(
+  schema: &[KnownField],
+  message: &OptionMessage,
+  buf: &mut Vec<u8>,
+) -> Result<(), PathError> {
+  for option in message {
+    let field = known_field(schema, &option.name)?;
+
+    encode_known_field(field, &option.value, buf).map_err(|e| e.within(&option.name))?;
+  }
+
+  Ok(())
+}
+
+fn encode_known_field(
+  field: &KnownField,
+  value: &OptionValue,
+  buf: &mut Vec<u8>,
+) -> Result<(), PathError> {
+  // Repeated fields are encoded with one entry for each item
+  if let OptionValue::List(list) = value {
+    for item in list.iter() {
+      encode_single_value(field, item, buf)?;
+    }
+
+    return Ok(());
+  }
+
+  encode_single_value(field, value, buf)
+}
+
+/// Encodes an options message, replacing its uninterpreted options with actual fields and extensions where possible.
+fn encode_options<O: DescriptorOptions>(
+  tag: u32,
+  options: Option<&O>,
+  path: &[i32],
+  item: &str,
+  sites: &OptionSites,
+  buf: &mut Vec<u8>,
+) -> Result<(), OptionEncodingError> {
+  let Some(options) = options else {
+    return Ok(());
+  };
+
+  let Some(site_options) = sites.get(path) else {
+    encode_len_delimited(tag, &options.encode_to_vec(), buf);
+    return Ok(());
+  };
+
+  let mut options = options.clone();
+  let mut known_options = Vec::new();
+
+  options.uninterpreted_options().clear();
+
+  for option in site_options {
+    if !encode_known_option(option, O::TARGET, item, &mut known_options)? {
+      options
+        .uninterpreted_options()
+        .extend(uninterpreted_options(option));
+    }
+  }
+
+  let mut options_bytes = options.encode_to_vec();
+  options_bytes.extend(known_options);
+
+  encode_len_delimited(tag, &options_bytes, buf);
+
+  Ok(())
+}
+
+fn field_path(path: &[i32], field_number: i32) -> Vec<i32> {
+  let mut child = path.to_vec();
+  child.push(field_number);
+  child
+}
+
+fn child_name(scope: &str, name: &str) -> String {
+  if scope.is_empty() {
+    name.to_string()
+  } else {
+    format!("{scope}.{name}")
+  }
+}
+
+// Nested items and options messages are encoded manually (since repeated fields and
+// extensions can be freely appended in the protobuf encoding), while the rest of each
+// descriptor is encoded normally.
+//
+// The full name of each element is tracked so that it can be reported in the errors.
+
+pub(crate) fn encode_file(
+  file: &FileDescriptorProto,
+  sites: &OptionSites,
+) -> Result<Vec<u8>, OptionEncodingError> {
+  let mut shell = file.clone();
+
+  let messages = core::mem::take(&mut shell.message_type);
+  let enums = core::mem::take(&mut shell.enum_type);
+  let services = core::mem::take(&mut shell.service);
+  let extensions = core::mem::take(&mut shell.extension);
+  let options = shell.options.take();
+
+  let scope = file.package();
+
+  let mut buf = shell.encode_to_vec();
+
+  for (i, message) in messages.iter().enumerate() {
+    let bytes = encode_message(message, &child_path(&[], 4, i), scope, sites)?;
+    encode_len_delimited(4, &bytes, &mut buf);
+  }
+
+  for (i, enum_) in enums.iter().enumerate() {
+    let bytes = encode_enum(enum_, &child_path(&[], 5, i), scope, sites)?;
+    encode_len_delimited(5, &bytes, &mut buf);
+  }
+
+  for (i, service) in services.iter().enumerate() {
+    let bytes = encode_service(service, &child_path(&[], 6, i), scope, sites)?;
+    encode_len_delimited(6, &bytes, &mut buf);
+  }
+
+  for (i, extension) in extensions.iter().enumerate() {
+    let bytes = encode_field(extension, &child_path(&[], 7, i), scope, sites)?;
+    encode_len_delimited(7, &bytes, &mut buf);
+  }
+
+  encode_options(8, options.as_ref(), &[8], file.name(), sites, &mut buf)?;
+
+  Ok(buf)
+}
+
+fn encode_message(
+  message: &DescriptorProto,
+  path: &[i32],
+  scope: &str,
+  sites: &OptionSites,
+) -> Result<Vec<u8>, OptionEncodingError> {
+  let mut shell = message.clone();
+
+  let fields = core::mem::take(&mut shell.field);
+  let nested_messages = core::mem::take(&mut shell.nested_type);
+  let enums = core::mem::take(&mut shell.enum_type);
+  let oneofs = core::mem::take(&mut shell.oneof_decl);
+  let options = shell.options.take();
+
+  let name = child_name(scope, message.name());
+
+  let mut buf = shell.encode_to_vec();
+
+  for (i, field) in fields.iter().enumerate() {
+    let bytes = encode_field(field, &child_path(path, 2, i), &name, sites)?;
+    encode_len_delimited(2, &bytes, &mut buf);
+  }
+
+  for (i, nested) in nested_messages.iter().enumerate() {
+    let bytes = encode_message(nested, &child_path(path, 3, i), &name, sites)?;
+    encode_len_delimited(3, &bytes, &mut buf);
+  }
+
+  for (i, enum_) in enums.iter().enumerate() {
+    let bytes = encode_enum(enum_, &child_path(path, 4, i), &name, sites)?;
+    encode_len_delimited(4, &bytes, &mut buf);
+  }
+
+  encode_options(
+    7,
+    options.as_ref(),
+    &field_path(path, 7),
+    &name,
+    sites,
+    &mut buf,
+  )?;
+
+  for (i, oneof) in oneofs.iter().enumerate() {
+    let bytes = encode_oneof(oneof, &child_path(path, 8, i), &name, sites)?;
+    encode_len_delimited(8, &bytes, &mut buf);
+  }
+
+  Ok(buf)
+}
+
+fn encode_field(
+  field: &FieldDescriptorProto,
+  path: &[i32],
+  scope: &str,
+  sites: &OptionSites,
+) -> Result<Vec<u8>, OptionEncodingError> {
+  let mut shell = field.clone();
+  let options = shell.options.take();
+
+  let mut buf = shell.encode_to_vec();
+
+  encode_options(
+    8,
+    options.as_ref(),
+    &field_path(path, 8),
+    &child_name(scope, field.name()),
+    sites,
+    &mut buf,
+  )?;
+
+  Ok(buf)
+}
+
+fn encode_oneof(
+  oneof: &OneofDescriptorProto,
+  path: &[i32],
+  scope: &str,
+  sites: &OptionSites,
+) -> Result<Vec<u8>, OptionEncodingError> {
+  let mut shell = oneof.clone();
+  let options = shell.options.take();
+
+  let mut buf = shell.encode_to_vec();
+
+  encode_options(
+    2,
+    options.as_ref(),
+    &field_path(path, 2),
+    &child_name(scope, oneof.name()),
+    sites,
+    &mut buf,
+  )?;
+
+  Ok(buf)
+}
+
+fn encode_enum(
+  enum_: &EnumDescriptorProto,
+  path: &[i32],
+  scope: &str,
+  sites: &OptionSites,
+) -> Result<Vec<u8>, OptionEncodingError> {
+  let mut shell = enum_.clone();
+
+  let values = core::mem::take(&mut shell.value);
+  let options = shell.options.take();
+
+  let name = child_name(scope, enum_.name());
+
+  let mut buf = shell.encode_to_vec();
+
+  for (i, value) in values.iter().enumerate() {
+    let bytes = encode_enum_value(value, &child_path(path, 2, i), &name, sites)?;
+    encode_len_delimited(2, &bytes, &mut buf);
+  }
+
+  encode_options(
+    3,
+    options.as_ref(),
+    &field_path(path, 3),
+    &name,
+    sites,
+    &mut buf,
+  )?;
+
+  Ok(buf)
+}
+
+fn encode_enum_value(
+  value: &EnumValueDescriptorProto,
+  path: &[i32],
+  scope: &str,
+  sites: &OptionSites,
+) -> Result<Vec<u8>, OptionEncodingError> {
+  let mut shell = value.clone();
+  let options = shell.options.take();
+
+  let mut buf = shell.encode_to_vec();
+
+  encode_options(
+    3,
+    options.as_ref(),
+    &field_path(path, 3),
+    &child_name(scope, value.name()),
+    sites,
+    &mut buf,
+  )?;
+
+  Ok(buf)
+}
+
+fn encode_service(
+  service: &ServiceDescriptorProto,
+  path: &[i32],
+  scope: &str,
+  sites: &OptionSites,
+) -> Result<Vec<u8>, OptionEncodingError> {
+  let mut shell = service.clone();
+
+  let methods = core::mem::take(&mut shell.method);
+  let options = shell.options.take();
+
+  let name = child_name(scope, service.name());
+
+  let mut buf = shell.encode_to_vec();
+
+  for (i, method) in methods.iter().enumerate() {
+    let bytes = encode_method(method, &child_path(path, 2, i), &name, sites)?;
+    encode_len_delimited(2, &bytes, &mut buf);
+  }
+
+  encode_options(
+    3,
+    options.as_ref(),
+    &field_path(path, 3),
+    &name,
+    sites,
+    &mut buf,
+  )?;
+
+  Ok(buf)
+}
+
+fn encode_method(
+  method: &MethodDescriptorProto,
+  path: &[i32],
+  scope: &str,
+  sites: &OptionSites,
+) -> Result<Vec<u8>, OptionEncodingError> {
+  let mut shell = method.clone();
+  let options = shell.options.take();
+
+  let mut buf = shell.encode_to_vec();
+
+  encode_options(
+    4,
+    options.as_ref(),
+    &field_path(path, 4),
+    &child_name(scope, method.name()),
+    sites,
+    &mut buf,
+  )?;
+
+  Ok(buf)
+}
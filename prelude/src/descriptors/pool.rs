@@ -0,0 +1,741 @@
+use crate::{field::default_json_name, *};
+use prost_reflect::{
+  Cardinality, DescriptorPool, DynamicMessage, EnumDescriptor, ExtensionDescriptor,
+  FieldDescriptor, FileDescriptor, Kind, MapKey, MessageDescriptor, MethodDescriptor,
+  OneofDescriptor, ServiceDescriptor, Value,
+};
+use proto_types::{Duration, Edition as DescriptorEdition, Timestamp};
+
+use super::known_options::{KNOWN_RULES_PROTO, VALIDATE_PROTO};
+
+impl Package {
+  /// Converts the files of a [`DescriptorPool`] that belong to the given package into a [`Package`].
+  ///
+  /// The `buf.validate` rules are converted into [`ValidatorSchema`]s, while every other option (including custom options,
+  /// if their extensions are present in the pool) is converted into a [`ProtoOption`].
+  ///
+  /// Since the items in the pool are not linked to any rust type, their `rust_path` is left empty.
+  ///
+  /// Returns an error if one of the files uses a syntax or an edition that is not supported, or if it contains
+  /// an extension that cannot be represented by an [`Extension`], such as one that extends a regular message.
+  pub fn from_descriptor_pool(
+    pool: &DescriptorPool,
+    package: &str,
+  ) -> Result<Self, DescriptorConversionError> {
+    Ok(Self {
+      name: package.to_string().into(),
+      files: pool
+        .files()
+        .filter(|file| file.package_name() == package)
+        .map(|file| FileConverter::new(&file)?.file())
+        .collect::<Result<_, _>>()?,
+    })
+  }
+}
+
+/// An error that occurs when converting a [`DescriptorPool`] into a [`Package`].
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum DescriptorConversionError {
+  /// The file uses a syntax or an edition that is not supported.
+  #[error("file `{file}` uses the unsupported {syntax}")]
+  UnsupportedSyntax { file: String, syntax: String },
+  /// The extension does not target one of the options messages, so it cannot be represented by an [`Extension`].
+  #[error(
+    "extension `{extension}` in file `{file}` extends `{extendee}`, but only the options messages can be extended"
+  )]
+  UnsupportedExtendee {
+    file: String,
+    extension: String,
+    extendee: String,
+  },
+}
+
+struct FileConverter<'a> {
+  file: &'a FileDescriptor,
+  edition: Edition,
+  comments: HashMap<&'a [i32], &'a str>,
+}
+
+impl<'a> FileConverter<'a> {
+  fn new(file: &'a FileDescriptor) -> Result<Self, DescriptorConversionError> {
+    let proto = file.file_descriptor_proto();
+
+    let edition = match proto.syntax() {
+      // An empty syntax stands for proto2
+      "" | "proto2" => Edition::Proto2,
+      "proto3" => Edition::Proto3,
+      "editions" if proto.edition == Some(DescriptorEdition::Edition2023 as i32) => Edition::E2023,
+      "editions" => {
+        return Err(DescriptorConversionError::UnsupportedSyntax {
+          file: file.name().to_string(),
+          syntax: format!(
+            "edition `{}`",
+            DescriptorEdition::try_from(proto.edition.unwrap_or_default())
+              .map_or("UNKNOWN", |edition| edition.as_str_name())
+          ),
+        });
+      }
+      syntax => {
+        return Err(DescriptorConversionError::UnsupportedSyntax {
+          file: file.name().to_string(),
+          syntax: format!("syntax `{syntax}`"),
+        });
+      }
+    };
+
+    let comments = proto
+      .source_code_info
+      .iter()
+      .flat_map(|info| &info.location)
+      .filter_map(|location| {
+        location
+          .leading_comments
+          .as_deref()
+          .map(|comments| (location.path.as_slice(), comments))
+      })
+      .collect();
+
+    Ok(Self {
+      file,
+      edition,
+      comments,
+    })
+  }
+
+  fn comments(&self, path: &[i32]) -> Option<FixedStr> {
+    let comments = self.comments.get(path)?;
+
+    let lines: Vec<&str> = comments
+      .trim_end()
+      .lines()
+      .map(|line| line.strip_prefix(' ').unwrap_or(line).trim_end())
+      .collect();
+
+    Some(lines.join("\n").into())
+  }
+
+  fn file(&self) -> Result<ProtoFile, DescriptorConversionError> {
+    let file = self.file;
+    let name: FixedStr = file.name().to_string().into();
+
+    let mut imports = FileImports::new(name.clone());
+
+    for dependency in file.dependencies() {
+      // The partial file added by `build_descriptor_pool` stands for the real one
+      let name = match dependency.name() {
+        KNOWN_RULES_PROTO => VALIDATE_PROTO,
+        name => name,
+      };
+
+      imports.insert_internal(name.to_string());
+    }
+
+    let ConvertedOptions {
+      options, features, ..
+    } = convert_options(&file.options());
+
+    let mut extensions: Vec<Extension> = Vec::new();
+
+    for extension in file.extensions() {
+      let Some(target) = extension_target(&extension) else {
+        return Err(DescriptorConversionError::UnsupportedExtendee {
+          file: file.name().to_string(),
+          extension: extension.full_name().to_string(),
+          extendee: extension
+            .containing_message()
+            .full_name()
+            .to_string(),
+        });
+      };
+
+      let field = self.extension_field(&extension);
+
+      if let Some(existing) = extensions.iter_mut().find(|e| e.target == target) {
+        existing.fields.push(field);
+      } else {
+        extensions.push(Extension {
+          target,
+          fields: vec![field],
+        });
+      }
+    }
+
+    Ok(ProtoFile {
+      name,
+      package: file.package_name().to_string().into(),
+      imports,
+      messages: file
+        .messages()
+        .map(|message| self.message(&message))
+        .collect(),
+      enums: file
+        .enums()
+        .map(|enum_| self.enum_(&enum_))
+        .collect(),
+      options,
+      edition: self.edition,
+      features,
+      services: file
+        .services()
+        .map(|service| self.service(&service))
+        .collect(),
+      extensions,
+    })
+  }
+
+  fn message(&self, message: &MessageDescriptor) -> Message {
+    let mut entries: Vec<MessageEntry> = Vec::new();
+
+    for field in message.fields() {
+      match field
+        .containing_oneof()
+        .filter(|oneof| !oneof.is_synthetic())
+      {
+        Some(oneof) => {
+          let is_present = entries.iter().any(|entry| {
+            entry
+              .as_oneof()
+              .is_some_and(|o| o.name == oneof.name())
+          });
+
+          if !is_present {
+            entries.push(MessageEntry::Oneof(self.oneof(&oneof)));
+          }
+        }
+        None => entries.push(MessageEntry::Field(self.field(&field, FieldScope::Message))),
+      }
+    }
+
+    let ConvertedOptions {
+      options,
+      features,
+      validators,
+    } = convert_options(&message.options());
+
+    let proto = message.descriptor_proto();
+
+    Message {
+      short_name: message.name().to_string().into(),
+      name: full_ish_name(message.full_name(), message.package_name()),
+      comments: self.comments(message.path()),
+      package: message.package_name().to_string().into(),
+      file: self.file.name().to_string().into(),
+      entries,
+      messages: message
+        .child_messages()
+        .filter(|nested| !nested.is_map_entry())
+        .map(|nested| self.message(&nested))
+        .collect(),
+      enums: message
+        .child_enums()
+        .map(|enum_| self.enum_(&enum_))
+        .collect(),
+      options,
+      features,
+      edition: self.edition,
+      reserved_names: proto
+        .reserved_name
+        .iter()
+        .map(|name| name.clone().into())
+        .collect(),
+      reserved_numbers: proto
+        .reserved_range
+        .iter()
+        .map(|range| range.start()..range.end())
+        .collect(),
+      validators,
+      rust_path: FixedStr::default(),
+    }
+  }
+
+  fn oneof(&self, oneof: &OneofDescriptor) -> Oneof {
+    let ConvertedOptions {
+      options,
+      validators,
+      ..
+    } = convert_options(&oneof.options());
+
+    Oneof {
+      name: oneof.name().to_string().into(),
+      comments: self.comments(oneof.path()),
+      fields: oneof
+        .fields()
+        .map(|field| self.field(&field, FieldScope::Oneof))
+        .collect(),
+      options,
+      validators,
+    }
+  }
+
+  fn field(&self, field: &FieldDescriptor, scope: FieldScope) -> Field {
+    let ConvertedOptions {
+      mut options,
+      features,
+      validators,
+    } = convert_options(&field.options());
+
+    if field.json_name() != default_json_name(field.name()) {
+      options.push(proto_json_name(field.json_name().to_string()));
+    }
+
+    if field
+      .field_descriptor_proto()
+      .default_value
+      .is_some()
+    {
+      options.push(proto_default(option_value(
+        &Value::default_value_for_field(field),
+        &field.kind(),
+      )));
+    }
+
+    let type_ = if scope == FieldScope::Oneof {
+      FieldType::Normal(proto_type(&field.kind()))
+    } else {
+      field_type(field)
+    };
+
+    Field {
+      name: field.name().to_string().into(),
+      comments: self.comments(field.path()),
+      tag: number_to_i32(field.number()),
+      type_,
+      options,
+      features,
+      validators,
+    }
+  }
+
+  fn extension_field(&self, extension: &ExtensionDescriptor) -> Field {
+    let ConvertedOptions {
+      options,
+      features,
+      validators,
+    } = convert_options(&extension.options());
+
+    let type_ = if extension.is_list() {
+      FieldType::Repeated(proto_type(&extension.kind()))
+    } else {
+      FieldType::Normal(proto_type(&extension.kind()))
+    };
+
+    Field {
+      name: extension.name().to_string().into(),
+      comments: self.comments(extension.path()),
+      tag: number_to_i32(extension.number()),
+      type_,
+      options,
+      features,
+      validators,
+    }
+  }
+
+  fn enum_(&self, enum_: &EnumDescriptor) -> Enum {
+    let ConvertedOptions {
+      options, features, ..
+    } = convert_options(&enum_.options());
+
+    Enum {
+      short_name: enum_.name().to_string().into(),
+      name: full_ish_name(enum_.full_name(), enum_.package_name()),
+      comments: self.comments(enum_.path()),
+      package: enum_.package_name().to_string().into(),
+      file: self.file.name().to_string().into(),
+      variants: enum_
+        .values()
+        .map(|value| EnumVariant {
+          name: value.name().to_string().into(),
+          comments: self.comments(value.path()),
+          tag: value.number(),
+          options: convert_options(&value.options()).options,
+        })
+        .collect(),
+      reserved_numbers: enum_
+        .reserved_ranges()
+        .map(|range| *range.start()..range.end().saturating_add(1))
+        .collect(),
+      reserved_names: enum_
+        .reserved_names()
+        .map(|name| name.to_string().into())
+        .collect(),
+      options,
+      features,
+      rust_path: FixedStr::default(),
+    }
+  }
+
+  fn service(&self, service: &ServiceDescriptor) -> Service {
+    Service {
+      name: service.name().to_string().into(),
+      comments: self.comments(service.path()),
+      file: self.file.name().to_string().into(),
+      options: convert_options(&service.options()).options,
+      handlers: service
+        .methods()
+        .map(|method| self.handler(&method))
+        .collect(),
+      package: service.package_name().to_string().into(),
+    }
+  }
+
+  fn handler(&self, method: &MethodDescriptor) -> ServiceHandler {
+    ServiceHandler {
+      name: method.name().to_string().into(),
+      comments: self.comments(method.path()),
+      options: convert_options(&method.options()).options,
+      request: message_path(&method.input()),
+      response: message_path(&method.output()),
+      client_streaming: method.is_client_streaming(),
+      server_streaming: method.is_server_streaming(),
+    }
+  }
+}
+
+#[allow(clippy::cast_possible_wrap)]
+const fn number_to_i32(number: u32) -> i32 {
+  // Field numbers are always within the i32 range
+  number as i32
+}
+
+fn full_ish_name(full_name: &str, package: &str) -> FixedStr {
+  let name = if package.is_empty() {
+    full_name
+  } else {
+    full_name
+      .strip_prefix(package)
+      .and_then(|name| name.strip_prefix('.'))
+      .unwrap_or(full_name)
+  };
+
+  name.to_string().into()
+}
+
+fn message_path(message: &MessageDescriptor) -> ProtoPath {
+  ProtoPath {
+    name: full_ish_name(message.full_name(), message.package_name()),
+    package: message.package_name().to_string().into(),
+    file: message.parent_file().name().to_string().into(),
+  }
+}
+
+fn enum_path(enum_: &EnumDescriptor) -> ProtoPath {
+  ProtoPath {
+    name: full_ish_name(enum_.full_name(), enum_.package_name()),
+    package: enum_.package_name().to_string().into(),
+    file: enum_.parent_file().name().to_string().into(),
+  }
+}
+
+fn extension_target(extension: &ExtensionDescriptor) -> Option<ExtensionTarget> {
+  let target = match extension.containing_message().full_name() {
+    "google.protobuf.FileOptions" => ExtensionTarget::FileOptions,
+    "google.protobuf.MessageOptions" => ExtensionTarget::MessageOptions,
+    "google.protobuf.FieldOptions" => ExtensionTarget::FieldOptions,
+    "google.protobuf.OneofOptions" => ExtensionTarget::OneofOptions,
+    "google.protobuf.EnumOptions" => ExtensionTarget::EnumOptions,
+    "google.protobuf.EnumValueOptions" => ExtensionTarget::EnumValueOptions,
+    "google.protobuf.ServiceOptions" => ExtensionTarget::ServiceOptions,
+    "google.protobuf.MethodOptions" => ExtensionTarget::MethodOptions,
+    _ => return None,
+  };
+
+  Some(target)
+}
+
+fn proto_type(kind: &Kind) -> ProtoType {
+  let scalar = match kind {
+    Kind::Message(message) => return ProtoType::Message(message_path(message)),
+    Kind::Enum(enum_) => return ProtoType::Enum(enum_path(enum_)),
+    Kind::Double => ProtoScalar::Double,
+    Kind::Float => ProtoScalar::Float,
+    Kind::Int32 => ProtoScalar::Int32,
+    Kind::Int64 => ProtoScalar::Int64,
+    Kind::Uint32 => ProtoScalar::Uint32,
+    Kind::Uint64 => ProtoScalar::Uint64,
+    Kind::Sint32 => ProtoScalar::Sint32,
+    Kind::Sint64 => ProtoScalar::Sint64,
+    Kind::Fixed32 => ProtoScalar::Fixed32,
+    Kind::Fixed64 => ProtoScalar::Fixed64,
+    Kind::Sfixed32 => ProtoScalar::Sfixed32,
+    Kind::Sfixed64 => ProtoScalar::Sfixed64,
+    Kind::Bool => ProtoScalar::Bool,
+    Kind::String => ProtoScalar::String,
+    Kind::Bytes => ProtoScalar::Bytes,
+  };
+
+  ProtoType::Scalar(scalar)
+}
+
+fn map_key(kind: &Kind) -> ProtoMapKey {
+  match kind {
+    Kind::Int32 => ProtoMapKey::Int32,
+    Kind::Int64 => ProtoMapKey::Int64,
+    Kind::Uint32 => ProtoMapKey::Uint32,
+    Kind::Uint64 => ProtoMapKey::Uint64,
+    Kind::Sint32 => ProtoMapKey::Sint32,
+    Kind::Sint64 => ProtoMapKey::Sint64,
+    Kind::Fixed32 => ProtoMapKey::Fixed32,
+    Kind::Fixed64 => ProtoMapKey::Fixed64,
+    Kind::Sfixed32 => ProtoMapKey::Sfixed32,
+    Kind::Sfixed64 => ProtoMapKey::Sfixed64,
+    Kind::Bool => ProtoMapKey::Bool,
+    // The validity of map keys is already checked when building the pool
+    _ => ProtoMapKey::String,
+  }
+}
+
+fn field_type(field: &FieldDescriptor) -> FieldType {
+  let kind = field.kind();
+
+  if field.is_map()
+    && let Kind::Message(entry) = &kind
+  {
+    return FieldType::Map {
+      keys: map_key(&entry.map_entry_key_field().kind()),
+      values: proto_type(&entry.map_entry_value_field().kind()),
+    };
+  }
+
+  let type_ = proto_type(&kind);
+
+  if field.is_list() {
+    FieldType::Repeated(type_)
+  } else if field.cardinality() == Cardinality::Required {
+    FieldType::Required(type_)
+  } else if !type_.is_message() && field.supports_presence() {
+    FieldType::Optional(type_)
+  } else {
+    FieldType::Normal(type_)
+  }
+}
+
+#[derive(Default)]
+struct ConvertedOptions {
+  options: Vec<ProtoOption>,
+  features: FeatureSet,
+  validators: Vec<ValidatorSchema>,
+}
+
+fn convert_options(options: &DynamicMessage) -> ConvertedOptions {
+  let mut converted = ConvertedOptions::default();
+
+  for (field, value) in options.fields() {
+    match field.name() {
+      "features" => {
+        if let Value::Message(features) = value {
+          converted.features = convert_features(features);
+        }
+      }
+      "uninterpreted_option" => {}
+      name => converted.options.push(ProtoOption {
+        name: name.to_string().into(),
+        value: option_value(value, &field.kind()),
+      }),
+    }
+  }
+
+  for (extension, value) in options.extensions() {
+    let option = ProtoOption {
+      name: format!("({})", extension.full_name()).into(),
+      value: option_value(value, &extension.kind()),
+    };
+
+    if extension.full_name().starts_with("buf.validate.") {
+      let mut cel_rules = Vec::new();
+
+      if let Value::Message(rules) = value {
+        collect_cel_rules(rules, &mut cel_rules);
+      }
+
+      converted.validators.push(ValidatorSchema {
+        schema: option,
+        cel_rules,
+        imports: vec![VALIDATE_PROTO.into()],
+      });
+    } else {
+      converted.options.push(option);
+    }
+  }
+
+  converted
+}
+
+fn convert_features(features: &DynamicMessage) -> FeatureSet {
+  let mut feature_set = FeatureSet::new();
+
+  for (field, value) in features.fields() {
+    let Value::EnumNumber(number) = value else {
+      continue;
+    };
+
+    let number = *number;
+
+    match field.name() {
+      "field_presence" => feature_set.field_presence = FieldPresence::from_number(number),
+      "enum_type" => feature_set.enum_type = EnumType::from_number(number),
+      "repeated_field_encoding" => {
+        feature_set.repeated_field_encoding = RepeatedFieldEncoding::from_number(number);
+      }
+      "utf8_validation" => feature_set.utf8_validation = Utf8Validation::from_number(number),
+      "message_encoding" => feature_set.message_encoding = MessageEncoding::from_number(number),
+      "json_format" => feature_set.json_format = JsonFormat::from_number(number),
+      _ => {}
+    }
+  }
+
+  feature_set
+}
+
+fn collect_cel_rules(message: &DynamicMessage, rules: &mut Vec<CelRule>) {
+  if message.descriptor().full_name() == "buf.validate.Rule" {
+    let get_str = |name: &str| {
+      message
+        .get_field_by_name(name)
+        .and_then(|value| {
+          value
+            .as_str()
+            .map(|s| FixedStr::from(s.to_string()))
+        })
+        .unwrap_or_default()
+    };
+
+    rules.push(CelRule {
+      id: get_str("id"),
+      message: get_str("message"),
+      expression: get_str("expression"),
+    });
+
+    return;
+  }
+
+  for (_, value) in message.fields() {
+    match value {
+      Value::Message(nested) => collect_cel_rules(nested, rules),
+      Value::List(items) => {
+        for item in items {
+          if let Value::Message(nested) = item {
+            collect_cel_rules(nested, rules);
+          }
+        }
+      }
+      _ => {}
+    }
+  }
+}
+
+fn option_value(value: &Value, kind: &Kind) -> OptionValue {
+  match value {
+    Value::Bool(boolean) => OptionValue::Bool(*boolean),
+    Value::I32(int) => OptionValue::Int(i64::from(*int)),
+    Value::I64(int) => OptionValue::Int(*int),
+    Value::U32(int) => OptionValue::Uint(u64::from(*int)),
+    Value::U64(int) => OptionValue::Uint(*int),
+    Value::F32(float) => OptionValue::Float(f64::from(*float)),
+    Value::F64(float) => OptionValue::Float(*float),
+    Value::String(string) => OptionValue::String(string.clone().into()),
+    Value::Bytes(bytes) => OptionValue::Bytes(bytes.clone()),
+    Value::EnumNumber(number) => {
+      let name = match kind {
+        Kind::Enum(enum_) => enum_
+          .get_value(*number)
+          .map(|value| value.name().to_string()),
+        _ => None,
+      };
+
+      name.map_or(OptionValue::Int(i64::from(*number)), |name| {
+        OptionValue::Enum(name.into())
+      })
+    }
+    Value::Message(message) => message_option_value(message),
+    Value::List(items) => OptionValue::List(
+      items
+        .iter()
+        .map(|item| option_value(item, kind))
+        .collect(),
+    ),
+    Value::Map(map) => {
+      let (key_kind, value_kind) = match kind {
+        Kind::Message(entry) => (
+          entry.map_entry_key_field().kind(),
+          entry.map_entry_value_field().kind(),
+        ),
+        _ => (Kind::String, Kind::String),
+      };
+
+      let mut entries: Vec<(&MapKey, &Value)> = map.iter().collect();
+      entries.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+
+      OptionValue::List(
+        entries
+          .into_iter()
+          .map(|(key, value)| {
+            OptionValue::new_message(vec![
+              ProtoOption {
+                name: "key".into(),
+                value: option_value(&map_key_value(key), &key_kind),
+              },
+              ProtoOption {
+                name: "value".into(),
+                value: option_value(value, &value_kind),
+              },
+            ])
+          })
+          .collect(),
+      )
+    }
+  }
+}
+
+fn map_key_value(key: &MapKey) -> Value {
+  match key {
+    MapKey::Bool(boolean) => Value::Bool(*boolean),
+    MapKey::I32(int) => Value::I32(*int),
+    MapKey::I64(int) => Value::I64(*int),
+    MapKey::U32(int) => Value::U32(*int),
+    MapKey::U64(int) => Value::U64(*int),
+    MapKey::String(string) => Value::String(string.clone()),
+  }
+}
+
+fn message_option_value(message: &DynamicMessage) -> OptionValue {
+  let get_int = |name: &str| {
+    message
+      .get_field_by_name(name)
+      .and_then(|value| match value.as_ref() {
+        Value::I64(int) => Some(*int),
+        Value::I32(int) => Some(i64::from(*int)),
+        _ => None,
+      })
+      .unwrap_or_default()
+  };
+
+  match message.descriptor().full_name() {
+    "google.protobuf.Duration" => OptionValue::Duration(Duration {
+      seconds: get_int("seconds"),
+      nanos: i32::try_from(get_int("nanos")).unwrap_or_default(),
+    }),
+    "google.protobuf.Timestamp" => OptionValue::Timestamp(Timestamp {
+      seconds: get_int("seconds"),
+      nanos: i32::try_from(get_int("nanos")).unwrap_or_default(),
+    }),
+    _ => {
+      let fields: Vec<ProtoOption> = message
+        .fields()
+        .map(|(field, value)| ProtoOption {
+          name: field.name().to_string().into(),
+          value: option_value(value, &field.kind()),
+        })
+        .chain(
+          message
+            .extensions()
+            .map(|(extension, value)| ProtoOption {
+              name: format!("({})", extension.full_name()).into(),
+              value: option_value(value, &extension.kind()),
+            }),
+        )
+        .collect();
+
+      OptionValue::new_message(fields)
+    }
+  }
+}
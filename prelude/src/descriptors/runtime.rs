@@ -0,0 +1,302 @@
+use std::sync::{Mutex, PoisonError};
+
+use crate::*;
+use prost::Message as ProstMessage;
+use prost_reflect::{DescriptorError, DescriptorPool, FileDescriptor, MessageDescriptor};
+
+use super::{
+  DescriptorBuilder, OptionEncodingError,
+  known_options::{KNOWN_RULES_PROTO, VALIDATE_PROTO, validate_file_descriptor},
+  option_encoding::{encode_file, encode_len_delimited},
+};
+
+static PACKAGE_POOLS: Lazy<Mutex<HashMap<&'static str, DescriptorPool>>> =
+  Lazy::new(|| Mutex::new(HashMap::new()));
+
+static ENCODED_SETS: Lazy<Mutex<HashMap<&'static str, Arc<[u8]>>>> =
+  Lazy::new(|| Mutex::new(HashMap::new()));
+
+impl PackageReference {
+  /// Returns the [`DescriptorPool`] for this package.
+  ///
+  /// The pool is built from the registry the first time that it is requested, and then cached.
+  /// See [`Package::build_descriptor_pool`] for more information.
+  #[must_use]
+  pub fn descriptor_pool(&self) -> DescriptorPool {
+    package_descriptor_pool(self.name)
+  }
+
+  /// Returns the encoded `FileDescriptorSet` for this package, including the files that it imports.
+  ///
+  /// The set is built from the [`descriptor_pool`](PackageReference::descriptor_pool) of the package the first time that
+  /// it is requested, and then cached, so that it can be passed to the gRPC reflection service
+  /// (i.e. `tonic_reflection::server::Builder::register_encoded_file_descriptor_set`) without being rebuilt.
+  /// See [`Package::encode_file_descriptor_set_with_imports`] for more information.
+  #[must_use]
+  pub fn encoded_file_descriptor_set(&self) -> Arc<[u8]> {
+    let mut sets = ENCODED_SETS
+      .lock()
+      .unwrap_or_else(PoisonError::into_inner);
+
+    sets
+      .entry(self.name)
+      .or_insert_with(|| {
+        encode_files_with_imports(&package_descriptor_pool(self.name), self.name).into()
+      })
+      .clone()
+  }
+}
+
+impl Package {
+  /// Builds a [`DescriptorPool`] that contains the files of this package.
+  ///
+  /// The pool starts from a copy of the [global pool](DescriptorPool::global), which contains the well known types.
+  /// The files of other rust-defined packages that are imported by this package are collected from the registry
+  /// and added to the pool as well.
+  ///
+  /// If the package uses the `buf.validate` rules and `buf/validate/validate.proto` is not in the global pool, a partial descriptor
+  /// of that file, which only covers the rules supported by this crate, is added to the pool as `buf/validate/validate_known_rules.proto`,
+  /// and the files of the package import it instead. This way, the rules are still available as extensions of the options messages,
+  /// without passing off the partial file as the real one.
+  ///
+  /// Other imports that cannot be resolved are dropped, and the options that they define are kept as unknown fields of the options messages.
+  pub fn build_descriptor_pool(&self) -> Result<DescriptorPool, DescriptorPoolError> {
+    let mut pool = DescriptorPool::global();
+
+    let dependencies = imported_packages(self);
+
+    let files: Vec<&ProtoFile> = dependencies
+      .iter()
+      .rev()
+      .chain(core::iter::once(self))
+      .flat_map(|pkg| &pkg.files)
+      .filter(|file| pool.get_file_by_name(&file.name).is_none())
+      .collect();
+
+    let uses_validate_proto = files.iter().any(|file| {
+      file
+        .imports
+        .set
+        .iter()
+        .any(|import| *import == VALIDATE_PROTO)
+    });
+
+    let validate_import = if pool.get_file_by_name(VALIDATE_PROTO).is_some() {
+      VALIDATE_PROTO
+    } else {
+      KNOWN_RULES_PROTO
+    };
+
+    if uses_validate_proto && pool.get_file_by_name(validate_import).is_none() {
+      pool.decode_file_descriptor_proto(
+        validate_file_descriptor()
+          .encode_to_vec()
+          .as_slice(),
+      )?;
+    }
+
+    let is_available = |import: &str| {
+      import.starts_with("google/protobuf/")
+        || pool.get_file_by_name(import).is_some()
+        || files.iter().any(|file| file.name == import)
+    };
+
+    let mut buf = Vec::new();
+
+    for file in &files {
+      encode_len_delimited(
+        1,
+        &encode_file_with_available_imports(file, validate_import, is_available)?,
+        &mut buf,
+      );
+    }
+
+    pool.decode_file_descriptor_set(buf.as_slice())?;
+
+    Ok(pool)
+  }
+
+  /// Encodes the `FileDescriptorSet` for this package, along with all of the files that it imports, directly or indirectly.
+  ///
+  /// Unlike [`Package::encode_file_descriptor_set`], the output is self-contained, so it can be used as is by
+  /// the gRPC reflection service, or decoded into a new [`DescriptorPool`]. The imports are resolved in the same way as in
+  /// [`Package::build_descriptor_pool`], so the well known types and `buf/validate/validate.proto` (or its partial version,
+  /// if the package uses the `buf.validate` rules) are always included.
+  ///
+  /// Each file comes after the files that it depends on.
+  pub fn encode_file_descriptor_set_with_imports(&self) -> Result<Vec<u8>, DescriptorPoolError> {
+    let pool = self.build_descriptor_pool()?;
+
+    Ok(encode_files_with_imports(&pool, &self.name))
+  }
+}
+
+fn encode_files_with_imports(pool: &DescriptorPool, package: &str) -> Vec<u8> {
+  let mut visited: HashSet<String> = HashSet::new();
+  let mut buf = Vec::new();
+
+  for file in pool
+    .files()
+    .filter(|file| file.package_name() == package)
+  {
+    encode_file_and_dependencies(&file, &mut visited, &mut buf);
+  }
+
+  buf
+}
+
+fn encode_file_and_dependencies(
+  file: &FileDescriptor,
+  visited: &mut HashSet<String>,
+  buf: &mut Vec<u8>,
+) {
+  if !visited.insert(file.name().to_string()) {
+    return;
+  }
+
+  for dependency in file.dependencies() {
+    encode_file_and_dependencies(&dependency, visited, buf);
+  }
+
+  encode_len_delimited(1, &file.encode_to_vec(), buf);
+}
+
+/// Collects the rust-defined packages (other than the given one) that contain the files imported by the given package,
+/// directly or indirectly.
+pub(crate) fn imported_packages(package: &Package) -> Vec<Package> {
+  let mut visited: HashSet<&str> = HashSet::new();
+  visited.insert(&package.name);
+
+  let mut imports: Vec<FixedStr> = package
+    .files
+    .iter()
+    .flat_map(|file| file.imports.as_sorted_vec())
+    .collect();
+
+  let mut packages = Vec::new();
+
+  while let Some(import) = imports.pop() {
+    let Some(registry_file) = inventory::iter::<RegistryFile>().find(|f| import == f.name) else {
+      continue;
+    };
+
+    if !visited.insert(registry_file.package) {
+      continue;
+    }
+
+    let dependency = collect_package(registry_file.package);
+
+    imports.extend(
+      dependency
+        .files
+        .iter()
+        .flat_map(|file| file.imports.as_sorted_vec()),
+    );
+
+    packages.push(dependency);
+  }
+
+  packages
+}
+
+/// Encodes the descriptor of a file, keeping only the imports that are available in the target pool.
+///
+/// The import of `buf/validate/validate.proto` is replaced with `validate_import`, which is the name of the file that provides
+/// the `buf.validate` rules in the target pool.
+pub(crate) fn encode_file_with_available_imports(
+  file: &ProtoFile,
+  validate_import: &str,
+  is_available: impl Fn(&str) -> bool,
+) -> Result<Vec<u8>, OptionEncodingError> {
+  let mut builder = DescriptorBuilder::new(file);
+
+  let mut descriptor = builder.build_file(file);
+
+  for import in &mut descriptor.dependency {
+    if import == VALIDATE_PROTO {
+      *import = validate_import.to_string();
+    }
+  }
+
+  descriptor
+    .dependency
+    .retain(|import| is_available(import));
+
+  encode_file(&descriptor, &builder.option_sites)
+}
+
+/// An error that occurs when building the [`DescriptorPool`] of a package.
+#[derive(Debug, Error)]
+pub enum DescriptorPoolError {
+  /// One of the options of the package does not match its schema.
+  #[error(transparent)]
+  Encoding(#[from] OptionEncodingError),
+  /// The descriptors could not be added to the pool.
+  #[error(transparent)]
+  Descriptor(#[from] DescriptorError),
+}
+
+/// An error that occurs when looking up the [`MessageDescriptor`] of a message.
+#[derive(Debug, Error)]
+pub enum MessageDescriptorError {
+  #[error("failed to build the descriptor pool for package `{package}`: {source}")]
+  Pool {
+    package: &'static str,
+    source: DescriptorPoolError,
+  },
+  #[error("could not find message `{full_name}` in the descriptor pool of package `{package}`")]
+  NotFound {
+    package: &'static str,
+    full_name: String,
+  },
+}
+
+// Only successfully built pools are cached
+fn try_package_descriptor_pool(
+  package: &'static str,
+) -> Result<DescriptorPool, DescriptorPoolError> {
+  let mut pools = PACKAGE_POOLS
+    .lock()
+    .unwrap_or_else(PoisonError::into_inner);
+
+  if let Some(pool) = pools.get(package) {
+    return Ok(pool.clone());
+  }
+
+  let pool = collect_package(package).build_descriptor_pool()?;
+
+  pools.insert(package, pool.clone());
+
+  Ok(pool)
+}
+
+pub(crate) fn package_descriptor_pool(package: &'static str) -> DescriptorPool {
+  try_package_descriptor_pool(package)
+    .unwrap_or_else(|e| panic!("Failed to build the descriptor pool for package `{package}`: {e}"))
+}
+
+/// Returns the [`MessageDescriptor`] for the message with the given full name, taken from the
+/// [`descriptor_pool`](PackageReference::descriptor_pool) of its package.
+///
+/// Unlike [`ProtoMessage::message_descriptor`], this returns an error if the pool cannot be built
+/// or if it does not contain the message.
+pub fn try_message_descriptor(
+  package: &'static str,
+  full_name: &str,
+) -> Result<MessageDescriptor, MessageDescriptorError> {
+  let pool = try_package_descriptor_pool(package)
+    .map_err(|source| MessageDescriptorError::Pool { package, source })?;
+
+  pool
+    .get_message_by_name(full_name)
+    .ok_or_else(|| MessageDescriptorError::NotFound {
+      package,
+      full_name: full_name.to_string(),
+    })
+}
+
+#[doc(hidden)]
+#[must_use]
+pub fn message_descriptor(package: &'static str, full_name: &str) -> MessageDescriptor {
+  try_message_descriptor(package, full_name).unwrap_or_else(|e| panic!("{e}"))
+}
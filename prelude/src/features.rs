@@ -0,0 +1,230 @@
+use crate::*;
+
+/// The set of `features` that can be applied to a file, message, enum or field in an editions file.
+///
+/// Each feature that is left as `None` is inherited from the parent scope (or from the edition's defaults).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FeatureSet {
+  pub field_presence: Option<FieldPresence>,
+  pub enum_type: Option<EnumType>,
+  pub repeated_field_encoding: Option<RepeatedFieldEncoding>,
+  pub utf8_validation: Option<Utf8Validation>,
+  pub message_encoding: Option<MessageEncoding>,
+  pub json_format: Option<JsonFormat>,
+}
+
+macro_rules! feature_enum {
+  (
+    $(#[$meta:meta])*
+    $name:ident { $($variant:ident = $number:literal => $proto_name:literal),* $(,)? }
+  ) => {
+    $(#[$meta])*
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub enum $name {
+      $($variant),*
+    }
+
+    impl $name {
+      /// Returns the name of the variant as it appears in protobuf files.
+      #[must_use]
+      pub const fn as_str(&self) -> &'static str {
+        match self {
+          $(Self::$variant => $proto_name),*
+        }
+      }
+
+      /// Returns the number of the variant in `google/protobuf/descriptor.proto`.
+      #[must_use]
+      pub const fn number(&self) -> i32 {
+        match self {
+          $(Self::$variant => $number),*
+        }
+      }
+
+      /// Returns the variant with the given number in `google/protobuf/descriptor.proto`, if there is one.
+      #[must_use]
+      pub const fn from_number(number: i32) -> Option<Self> {
+        match number {
+          $($number => Some(Self::$variant),)*
+          _ => None,
+        }
+      }
+
+      pub(crate) fn number_from_name(name: &str) -> Option<i32> {
+        match name {
+          $($proto_name => Some($number),)*
+          _ => None,
+        }
+      }
+    }
+
+    impl Display for $name {
+      fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.as_str())
+      }
+    }
+  };
+}
+
+feature_enum!(
+  /// The value for `features.field_presence`.
+  FieldPresence {
+    Explicit = 1 => "EXPLICIT",
+    Implicit = 2 => "IMPLICIT",
+    LegacyRequired = 3 => "LEGACY_REQUIRED",
+  }
+);
+
+feature_enum!(
+  /// The value for `features.enum_type`.
+  EnumType {
+    Open = 1 => "OPEN",
+    Closed = 2 => "CLOSED",
+  }
+);
+
+feature_enum!(
+  /// The value for `features.repeated_field_encoding`.
+  RepeatedFieldEncoding {
+    Packed = 1 => "PACKED",
+    Expanded = 2 => "EXPANDED",
+  }
+);
+
+feature_enum!(
+  /// The value for `features.utf8_validation`.
+  Utf8Validation {
+    Verify = 2 => "VERIFY",
+    None = 3 => "NONE",
+  }
+);
+
+feature_enum!(
+  /// The value for `features.message_encoding`.
+  MessageEncoding {
+    LengthPrefixed = 1 => "LENGTH_PREFIXED",
+    Delimited = 2 => "DELIMITED",
+  }
+);
+
+feature_enum!(
+  /// The value for `features.json_format`.
+  JsonFormat {
+    Allow = 1 => "ALLOW",
+    LegacyBestEffort = 2 => "LEGACY_BEST_EFFORT",
+  }
+);
+
+impl FeatureSet {
+  /// Creates a new, empty instance.
+  #[must_use]
+  pub const fn new() -> Self {
+    Self {
+      field_presence: None,
+      enum_type: None,
+      repeated_field_encoding: None,
+      utf8_validation: None,
+      message_encoding: None,
+      json_format: None,
+    }
+  }
+
+  #[must_use]
+  pub const fn with_field_presence(mut self, value: FieldPresence) -> Self {
+    self.field_presence = Some(value);
+    self
+  }
+
+  #[must_use]
+  pub const fn with_enum_type(mut self, value: EnumType) -> Self {
+    self.enum_type = Some(value);
+    self
+  }
+
+  #[must_use]
+  pub const fn with_repeated_field_encoding(mut self, value: RepeatedFieldEncoding) -> Self {
+    self.repeated_field_encoding = Some(value);
+    self
+  }
+
+  #[must_use]
+  pub const fn with_utf8_validation(mut self, value: Utf8Validation) -> Self {
+    self.utf8_validation = Some(value);
+    self
+  }
+
+  #[must_use]
+  pub const fn with_message_encoding(mut self, value: MessageEncoding) -> Self {
+    self.message_encoding = Some(value);
+    self
+  }
+
+  #[must_use]
+  pub const fn with_json_format(mut self, value: JsonFormat) -> Self {
+    self.json_format = Some(value);
+    self
+  }
+
+  /// Returns `true` if no feature has been set.
+  #[must_use]
+  pub const fn is_empty(&self) -> bool {
+    self.field_presence.is_none()
+      && self.enum_type.is_none()
+      && self.repeated_field_encoding.is_none()
+      && self.utf8_validation.is_none()
+      && self.message_encoding.is_none()
+      && self.json_format.is_none()
+  }
+
+  /// Returns the features that are set in this instance as a list of [`ProtoOption`]s, such as `features.field_presence = IMPLICIT`.
+  #[must_use]
+  pub fn options(&self) -> Vec<ProtoOption> {
+    let Self {
+      field_presence,
+      enum_type,
+      repeated_field_encoding,
+      utf8_validation,
+      message_encoding,
+      json_format,
+    } = self;
+
+    [
+      (
+        "features.field_presence",
+        field_presence.map(|v| v.as_str()),
+      ),
+      ("features.enum_type", enum_type.map(|v| v.as_str())),
+      (
+        "features.repeated_field_encoding",
+        repeated_field_encoding.map(|v| v.as_str()),
+      ),
+      (
+        "features.utf8_validation",
+        utf8_validation.map(|v| v.as_str()),
+      ),
+      (
+        "features.message_encoding",
+        message_encoding.map(|v| v.as_str()),
+      ),
+      ("features.json_format", json_format.map(|v| v.as_str())),
+    ]
+    .into_iter()
+    .filter_map(|(name, value)| {
+      value.map(|value| ProtoOption {
+        name: name.into(),
+        value: OptionValue::Enum(value.into()),
+      })
+    })
+    .collect()
+  }
+}
+
+impl Edition {
+  /// Returns `true` if this is an editions file (as opposed to a `proto2` or `proto3` file).
+  #[must_use]
+  pub const fn is_edition(&self) -> bool {
+    matches!(self, Self::E2023)
+  }
+}
@@ -5,12 +5,22 @@ use crate::*;
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Field {
   pub name: FixedStr,
+  pub comments: Option<FixedStr>,
   pub tag: i32,
   pub type_: FieldType,
   pub options: Vec<ProtoOption>,
+  pub features: FeatureSet,
   pub validators: Vec<ValidatorSchema>,
 }
 
+/// The item that contains a field, which determines how its label and presence are rendered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldScope {
+  Message,
+  Oneof,
+  Extension,
+}
+
 /// A schema representation for a validator.
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -21,11 +31,67 @@ pub struct ValidatorSchema {
 }
 
 impl Field {
-  pub(crate) fn options_with_validators(&self) -> impl Iterator<Item = &options::ProtoOption> {
+  pub(crate) fn render_comments(&self) -> Option<String> {
+    render_comments(self.comments.as_ref())
+  }
+
+  pub(crate) fn render_type(
+    &self,
+    current_package: &FixedStr,
+    edition: Edition,
+    scope: FieldScope,
+  ) -> Cow<'_, str> {
+    self.type_.render(current_package, edition, scope)
+  }
+
+  /// Returns the features of this field, including the `field_presence` feature that is
+  /// automatically added in editions files to match the presence of the rust type.
+  #[must_use]
+  pub fn resolved_features(&self, edition: Edition, scope: FieldScope) -> FeatureSet {
+    let mut features = self.features;
+
+    if scope == FieldScope::Message && features.field_presence.is_none() {
+      features.field_presence = self.type_.required_field_presence(edition);
+    }
+
+    features
+  }
+
+  /// Returns the value of the `default` option of this field, if there is one.
+  #[must_use]
+  pub fn default_value(&self) -> Option<&OptionValue> {
     self
       .options
       .iter()
-      .chain(self.validators.iter().map(|v| &v.schema))
+      .find_map(|option| (option.name.as_ref() == "default").then_some(&option.value))
+  }
+
+  /// Returns the name used for this field in the JSON mapping, which is either the one set with the `json_name` option
+  /// or the default lowerCamelCase version of the field name.
+  #[must_use]
+  pub fn json_name(&self) -> String {
+    self
+      .options
+      .iter()
+      .find_map(|option| match (option.name.as_ref(), &option.value) {
+        ("json_name", OptionValue::String(name)) => Some(String::from(name)),
+        _ => None,
+      })
+      .unwrap_or_else(|| default_json_name(&self.name))
+  }
+
+  pub(crate) fn options_with_validators(
+    &self,
+    edition: Edition,
+    scope: FieldScope,
+  ) -> Vec<ProtoOption> {
+    self
+      .options
+      .iter()
+      .cloned()
+      .chain(self.resolved_features(edition, scope).options())
+      .chain(self.validators.iter().map(|v| v.schema.clone()))
+      .collect()
   }
 
   pub(crate) fn register_import_path(&self, imports: &mut FileImports) {
@@ -38,13 +104,115 @@ impl Field {
     }
 
     match &self.type_ {
-      FieldType::Normal(ty) | FieldType::Repeated(ty) | FieldType::Optional(ty) => {
-        ty.register_import(imports)
-      }
+      FieldType::Normal(ty)
+      | FieldType::Repeated(ty)
+      | FieldType::Optional(ty)
+      | FieldType::Required(ty) => ty.register_import(imports),
       FieldType::Map { keys, values } => {
         keys.into_type().register_import(imports);
         values.register_import(imports);
       }
     };
   }
+
+  /// Returns the list of errors for the constructs used by this field that are not valid for the given edition.
+  #[must_use]
+  pub fn edition_errors(&self, edition: Edition, scope: FieldScope) -> Vec<String> {
+    let mut errors = Vec::new();
+    let name = &self.name;
+
+    if !edition.is_edition() && !self.features.is_empty() {
+      errors.push(format!(
+        "Field `{name}` uses `features`, which are only supported by editions files"
+      ));
+    }
+
+    match edition {
+      Edition::Proto3 => {
+        if matches!(self.type_, FieldType::Required(_)) {
+          errors.push(format!(
+            "Field `{name}` is marked as required, which is not supported by proto3"
+          ));
+        }
+
+        if self.default_value().is_some() {
+          errors.push(format!(
+            "Field `{name}` has a `default` value, which is not supported by proto3"
+          ));
+        }
+      }
+      Edition::Proto2 => {
+        if scope == FieldScope::Message
+          && let FieldType::Normal(inner) = &self.type_
+          && !inner.is_message()
+        {
+          errors.push(format!(
+            "Field `{name}` has implicit presence, which is not supported by proto2. Use an Option or mark the field as required"
+          ));
+        }
+      }
+      Edition::E2023 => {
+        if let Some(presence) = self.features.field_presence {
+          let expected = if scope == FieldScope::Message {
+            self
+              .type_
+              .required_field_presence(edition)
+              .unwrap_or(FieldPresence::Explicit)
+          } else {
+            FieldPresence::Explicit
+          };
+
+          let is_singular = !matches!(self.type_, FieldType::Repeated(_) | FieldType::Map { .. });
+
+          if !is_singular || scope == FieldScope::Oneof {
+            errors.push(format!(
+              "Field `{name}` sets `features.field_presence`, which cannot be used for repeated, map or oneof fields"
+            ));
+          } else if presence != expected {
+            errors.push(format!(
+              "Field `{name}` sets `features.field_presence = {presence}`, but its rust type has `{expected}` presence"
+            ));
+          }
+        }
+
+        if self.features.enum_type.is_some() {
+          errors.push(format!(
+            "Field `{name}` sets `features.enum_type`, which can only be set on enums or files"
+          ));
+        }
+
+        if self.features.message_encoding.is_some()
+          && !self
+            .type_
+            .inner_type()
+            .is_some_and(|inner| inner.is_message())
+        {
+          errors.push(format!(
+            "Field `{name}` sets `features.message_encoding`, but it is not a message field"
+          ));
+        }
+      }
+    }
+
+    errors
+  }
+}
+
+/// Converts a field name to its default json name, following the same logic as `protoc`.
+pub(crate) fn default_json_name(name: &str) -> String {
+  let mut output = String::with_capacity(name.len());
+  let mut capitalize_next = false;
+
+  for ch in name.chars() {
+    if ch == '_' {
+      capitalize_next = true;
+    } else if capitalize_next {
+      output.push(ch.to_ascii_uppercase());
+      capitalize_next = false;
+    } else {
+      output.push(ch);
+    }
+  }
+
+  output
 }
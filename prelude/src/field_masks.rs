@@ -0,0 +1,466 @@
+//! Application of [`FieldMask`]s to messages.
+//!
+//! With the `field-mask` feature, the messages defined with the macros implement [`ProtoMask`], which can:
+//!
+//! - Check that every path of a mask resolves to a field of the message ([`ProtoMask::check_mask`]).
+//! - Prune a message, so that only the fields in a mask are kept ([`ProtoMask::prune_to_mask`]).
+//! - Merge the fields in a mask from a source message into a destination ([`ProtoMask::merge_masked`]).
+//!
+//! Paths use the proto names of the fields, separated by dots. Fields that belong to a oneof are addressed by their
+//! own names, like any other field. Only singular message fields can be traversed, so a path such as `items.name`
+//! is rejected if `items` is a repeated field or a map.
+//!
+//! Masked fields are replaced as a whole when merging, which means that repeated fields and maps are not appended,
+//! and that a masked field that is not set in the source is cleared in the destination.
+//!
+//! The same check is also available as a validation rule for `FieldMask` fields, with
+//! `FieldMaskValidator::builder().paths_of::<MyMsg>()`.
+//!
+//! Masks can also restrict validation to the fields that they contain, with
+//! [`validate_masked`](crate::ValidatedMessage::validate_masked) or [`ValidationCtx::with_mask`].
+//! Fields that are not in the mask are skipped along with their nested messages, and message-level rules
+//! only run if they are typed rules whose fields are all in the mask, since the fields used by CEL programs
+//! and custom rules are not known.
+
+use crate::*;
+
+use core::borrow::BorrowMut;
+use proto_types::FieldMask;
+
+/// An error that occurs when a path of a [`FieldMask`] does not resolve to a field.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum FieldMaskError {
+  #[error("path `{0}` is empty or contains empty segments")]
+  InvalidPath(String),
+  #[error("path `{path}` refers to `{field}`, which is not a field of `{message}`")]
+  UnknownField {
+    path: String,
+    field: String,
+    message: &'static str,
+  },
+  #[error("path `{path}` traverses `{field}`, which is not a singular message field")]
+  NotAMessage { path: String, field: String },
+}
+
+/// A [`FieldMask`] parsed into a tree of field names.
+///
+/// A node without children selects its field as a whole, so a mask with the paths `a` and `a.b` is
+/// equivalent to one with just `a`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MaskTree {
+  children: BTreeMap<String, Self>,
+}
+
+impl MaskTree {
+  /// Builds the tree of a [`FieldMask`].
+  #[must_use]
+  pub fn new(mask: &FieldMask) -> Self {
+    Self::from_paths(&mask.paths)
+  }
+
+  /// Builds a tree from a list of paths. Empty paths are ignored.
+  #[must_use]
+  pub fn from_paths<I>(paths: I) -> Self
+  where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+  {
+    let mut tree = Self::default();
+
+    for path in paths {
+      let path = path.as_ref();
+
+      if !path.is_empty() {
+        tree.insert(path);
+      }
+    }
+
+    tree
+  }
+
+  fn insert(&mut self, path: &str) {
+    let (field, rest) = split_path(path);
+
+    let is_new = !self.children.contains_key(field);
+    let node = self
+      .children
+      .entry(field.to_string())
+      .or_default();
+
+    match rest {
+      Some(rest) => {
+        // A field that is already selected as a whole stays that way
+        if is_new || !node.is_leaf() {
+          node.insert(rest);
+        }
+      }
+      None => node.children.clear(),
+    }
+  }
+
+  /// Returns `true` if this node selects its field as a whole.
+  #[must_use]
+  #[inline]
+  pub fn is_leaf(&self) -> bool {
+    self.children.is_empty()
+  }
+
+  /// Returns the node of a field, if it is part of the mask.
+  #[must_use]
+  #[inline]
+  pub fn get(&self, field: &str) -> Option<&Self> {
+    self.children.get(field)
+  }
+
+  /// Returns the canonical paths of this tree, in alphabetical order.
+  #[must_use]
+  pub fn paths(&self) -> Vec<String> {
+    let mut paths = Vec::new();
+
+    for (field, node) in &self.children {
+      if node.is_leaf() {
+        paths.push(field.clone());
+      } else {
+        paths.extend(
+          node
+            .paths()
+            .into_iter()
+            .map(|path| format!("{field}.{path}")),
+        );
+      }
+    }
+
+    paths
+  }
+
+  /// Converts this tree into a [`FieldMask`] with canonical paths.
+  #[must_use]
+  #[inline]
+  pub fn to_field_mask(&self) -> FieldMask {
+    FieldMask {
+      paths: self.paths(),
+    }
+  }
+}
+
+fn split_path(path: &str) -> (&str, Option<&str>) {
+  match path.split_once('.') {
+    Some((field, rest)) => (field, Some(rest)),
+    None => (path, None),
+  }
+}
+
+/// Applies [`FieldMask`]s to a message.
+///
+/// It is implemented automatically by the messages defined with the macros.
+pub trait ProtoMask: Default {
+  /// The name of the message, used in error messages.
+  #[doc(hidden)]
+  const MASK_MESSAGE_NAME: &'static str;
+
+  /// Checks a path, given the name of its first segment and the rest of it.
+  #[doc(hidden)]
+  fn check_mask_field(path: &str, field: &str, rest: Option<&str>) -> Result<(), FieldMaskError>;
+
+  #[doc(hidden)]
+  fn prune_to_tree(&mut self, tree: &MaskTree);
+
+  #[doc(hidden)]
+  fn merge_tree(&mut self, source: &Self, tree: &MaskTree);
+
+  /// Checks that a path (such as `nested.name`) resolves to a field of this message.
+  fn check_mask_path(path: &str) -> Result<(), FieldMaskError> {
+    if path.is_empty() || path.split('.').any(str::is_empty) {
+      return Err(FieldMaskError::InvalidPath(path.to_string()));
+    }
+
+    check_nested_mask_path::<Self>(path, path)
+  }
+
+  /// Checks that every path of a mask resolves to a field of this message.
+  fn check_mask(mask: &FieldMask) -> Result<(), FieldMaskError> {
+    mask
+      .paths
+      .iter()
+      .try_for_each(|path| Self::check_mask_path(path))
+  }
+
+  /// Resets all the fields that are not in the mask to their default value.
+  fn prune_to_mask(&mut self, mask: &FieldMask) -> Result<(), FieldMaskError> {
+    Self::check_mask(mask)?;
+
+    self.prune_to_tree(&MaskTree::new(mask));
+
+    Ok(())
+  }
+
+  /// Returns a copy of this message that only contains the fields in the mask.
+  fn masked(&self, mask: &FieldMask) -> Result<Self, FieldMaskError>
+  where
+    Self: Clone,
+  {
+    let mut output = self.clone();
+
+    output.prune_to_mask(mask)?;
+
+    Ok(output)
+  }
+
+  /// Replaces the fields in the mask with their values in `source`.
+  fn merge_masked(&mut self, source: &Self, mask: &FieldMask) -> Result<(), FieldMaskError> {
+    Self::check_mask(mask)?;
+
+    self.merge_tree(source, &MaskTree::new(mask));
+
+    Ok(())
+  }
+}
+
+/// Applies [`FieldMask`]s to the fields of a oneof.
+///
+/// It is implemented automatically by the oneofs defined with the macros.
+pub trait ProtoMaskOneof: Sized {
+  #[doc(hidden)]
+  const FIELD_NAMES: &'static [&'static str];
+
+  /// Checks a path that starts with one of the fields of this oneof.
+  #[doc(hidden)]
+  fn check_mask_field(path: &str, field: &str, rest: Option<&str>) -> Result<(), FieldMaskError>;
+
+  /// Resets the oneof if its current field is not in the mask.
+  #[doc(hidden)]
+  fn prune_oneof(target: &mut Option<Self>, tree: &MaskTree);
+
+  /// Merges the fields of this oneof that are in the mask.
+  #[doc(hidden)]
+  fn merge_oneof(target: &mut Option<Self>, source: Option<&Self>, tree: &MaskTree);
+}
+
+#[doc(hidden)]
+pub fn check_nested_mask_path<T: ProtoMask>(
+  full_path: &str,
+  path: &str,
+) -> Result<(), FieldMaskError> {
+  let (field, rest) = split_path(path);
+
+  T::check_mask_field(full_path, field, rest)
+}
+
+#[doc(hidden)]
+#[inline]
+pub fn unknown_mask_field<T: ProtoMask>(path: &str, field: &str) -> Result<(), FieldMaskError> {
+  Err(FieldMaskError::UnknownField {
+    path: path.to_string(),
+    field: field.to_string(),
+    message: T::MASK_MESSAGE_NAME,
+  })
+}
+
+/// Checks a path that refers to a field which cannot be traversed.
+#[doc(hidden)]
+#[inline]
+pub fn check_mask_leaf(path: &str, field: &str, rest: Option<&str>) -> Result<(), FieldMaskError> {
+  if rest.is_some() {
+    Err(FieldMaskError::NotAMessage {
+      path: path.to_string(),
+      field: field.to_string(),
+    })
+  } else {
+    Ok(())
+  }
+}
+
+/// Checks a path that refers to a message field.
+#[doc(hidden)]
+#[inline]
+pub fn check_mask_message<T: ProtoMask>(
+  path: &str,
+  rest: Option<&str>,
+) -> Result<(), FieldMaskError> {
+  rest.map_or(Ok(()), |rest| check_nested_mask_path::<T>(path, rest))
+}
+
+/// Prunes an optional message field.
+#[doc(hidden)]
+pub fn prune_message_field<T, B>(target: &mut Option<B>, tree: Option<&MaskTree>)
+where
+  T: ProtoMask,
+  B: BorrowMut<T>,
+{
+  match tree {
+    None => *target = None,
+    Some(tree) if tree.is_leaf() => {}
+    Some(tree) => {
+      if let Some(msg) = target {
+        msg.borrow_mut().prune_to_tree(tree);
+      }
+    }
+  }
+}
+
+/// Merges an optional message field.
+#[doc(hidden)]
+pub fn merge_message_field<T, B>(target: &mut Option<B>, source: Option<&B>, tree: &MaskTree)
+where
+  T: ProtoMask,
+  B: BorrowMut<T> + Clone + From<T>,
+{
+  if tree.is_leaf() {
+    *target = source.cloned();
+    return;
+  }
+
+  match (target.as_mut(), source) {
+    (Some(msg), Some(source)) => msg.borrow_mut().merge_tree(source.borrow(), tree),
+    // Without a value in the source, the masked fields are reset to their defaults
+    (Some(msg), None) => msg.borrow_mut().merge_tree(&T::default(), tree),
+    (None, Some(source)) => {
+      let mut msg = T::default();
+      msg.merge_tree(source.borrow(), tree);
+      *target = Some(msg.into());
+    }
+    (None, None) => {}
+  }
+}
+
+/// Merges a message that is stored in a oneof variant.
+#[doc(hidden)]
+pub fn merge_message_variant<T, B>(
+  target: Option<B>,
+  source: Option<&B>,
+  tree: &MaskTree,
+) -> Option<B>
+where
+  T: ProtoMask,
+  B: BorrowMut<T> + Clone + From<T>,
+{
+  let mut target = target;
+
+  merge_message_field::<T, B>(&mut target, source, tree);
+
+  target
+}
+
+/// The state of a field entered with [`ValidationCtx::enter_masked_field`].
+#[doc(hidden)]
+pub enum MaskScope {
+  /// No mask is active.
+  Unmasked,
+  /// The field is not in the mask.
+  Skipped,
+  /// The field is in the mask, and this is the mask of the parent message, to be restored afterwards.
+  Entered(MaskTree),
+}
+
+impl MaskScope {
+  #[inline]
+  #[must_use]
+  pub const fn is_skipped(&self) -> bool {
+    matches!(self, Self::Skipped)
+  }
+}
+
+impl ValidationCtx {
+  /// Restricts the validation to the fields in the mask. An empty mask means that every field should be validated.
+  pub fn with_mask(&mut self, mask: &FieldMask) -> &mut Self {
+    let tree = MaskTree::new(mask);
+
+    self.mask = (!tree.is_leaf()).then_some(tree);
+    self
+  }
+
+  /// Returns `true` if the validation of the current message is restricted by a mask.
+  #[inline]
+  #[must_use]
+  pub const fn is_masked(&self) -> bool {
+    self.mask.is_some()
+  }
+
+  /// Returns `true` if there is no active mask, or if the field is in it.
+  #[inline]
+  #[must_use]
+  pub fn is_in_mask(&self, field: &str) -> bool {
+    self
+      .mask
+      .as_ref()
+      .is_none_or(|mask| mask.get(field).is_some())
+  }
+
+  /// Returns `true` if there is no active mask, or if any of the fields is in it.
+  #[doc(hidden)]
+  #[inline]
+  #[must_use]
+  pub fn is_any_in_mask(&self, fields: &[&str]) -> bool {
+    fields.iter().any(|field| self.is_in_mask(field))
+  }
+
+  /// Narrows the mask to the subtree of a field, before validating it.
+  #[doc(hidden)]
+  pub fn enter_masked_field(&mut self, field: &str) -> MaskScope {
+    let Some(mask) = &self.mask else {
+      return MaskScope::Unmasked;
+    };
+
+    match mask.get(field) {
+      None => MaskScope::Skipped,
+      Some(node) => {
+        // Fields selected as a whole are validated without a mask
+        let inner = (!node.is_leaf()).then(|| node.clone());
+
+        match core::mem::replace(&mut self.mask, inner) {
+          Some(parent) => MaskScope::Entered(parent),
+          None => MaskScope::Unmasked,
+        }
+      }
+    }
+  }
+
+  /// Restores the mask of the parent message after validating a field.
+  #[doc(hidden)]
+  #[inline]
+  pub fn exit_masked_field(&mut self, scope: MaskScope) {
+    if let MaskScope::Entered(parent) = scope {
+      self.mask = Some(parent);
+    }
+  }
+}
+
+/// The paths of a message, which are used by [`FieldMaskValidator`] to check that the paths of a mask
+/// resolve to its fields.
+#[derive(Clone, Copy)]
+pub struct MessagePaths {
+  message: &'static str,
+  check: fn(&str) -> Result<(), FieldMaskError>,
+}
+
+impl MessagePaths {
+  #[must_use]
+  #[inline]
+  pub fn of<T: ProtoMask>() -> Self {
+    Self {
+      message: T::MASK_MESSAGE_NAME,
+      check: T::check_mask_path,
+    }
+  }
+
+  /// Returns the name of the message.
+  #[must_use]
+  #[inline]
+  pub const fn message(&self) -> &'static str {
+    self.message
+  }
+
+  /// Checks that a path resolves to a field of the message.
+  #[inline]
+  pub fn check(&self, path: &str) -> Result<(), FieldMaskError> {
+    (self.check)(path)
+  }
+}
+
+impl Debug for MessagePaths {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.debug_tuple("MessagePaths")
+      .field(&self.message)
+      .finish()
+  }
+}
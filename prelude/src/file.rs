@@ -14,6 +14,7 @@ pub struct ProtoFile {
   pub enums: Vec<Enum>,
   pub options: Vec<ProtoOption>,
   pub edition: Edition,
+  pub features: FeatureSet,
   pub services: Vec<Service>,
   pub extensions: Vec<Extension>,
 }
@@ -23,6 +24,7 @@ pub struct FileReference {
   pub name: &'static str,
   pub package: &'static str,
   pub extern_path: &'static str,
+  pub edition: Edition,
 }
 
 /// The protobuf edition for a file.
@@ -140,6 +142,7 @@ impl ProtoFile {
       enums: Default::default(),
       options: Default::default(),
       edition: Default::default(),
+      features: Default::default(),
       services: Default::default(),
       extensions: Default::default(),
     }
@@ -160,9 +163,71 @@ impl ProtoFile {
     self
   }
 
-  pub const fn with_edition(&mut self, edition: Edition) -> &mut Self {
+  pub fn with_edition(&mut self, edition: Edition) -> &mut Self {
     self.edition = edition;
+
+    for msg in &mut self.messages {
+      msg.set_edition(edition);
+    }
+
+    self
+  }
+
+  pub const fn with_features(&mut self, features: FeatureSet) -> &mut Self {
+    self.features = features;
+    self
+  }
+
+  /// Checks that the features and the items defined in this file are valid for its edition.
+  pub fn check_edition_consistency(&self) -> Result<(), String> {
+    let edition = self.edition;
+    let mut errors = Vec::new();
+
+    if !edition.is_edition() && !self.features.is_empty() {
+      errors.push(format!(
+        "[{} ({edition:?})]: `features` are only supported by editions files",
+        self.name
+      ));
+    }
+
+    if self.features.field_presence.is_some() {
+      errors.push(format!(
+        "[{} ({edition:?})]: `features.field_presence` must be set on individual fields, as it is inferred from their rust types",
+        self.name
+      ));
+    }
+
+    for msg in &self.messages {
+      msg.collect_edition_errors(&mut errors);
+    }
+
+    for enum_ in &self.enums {
+      enum_.collect_edition_errors(edition, &mut errors);
+    }
+
+    for field in self.extensions.iter().flat_map(|e| &e.fields) {
+      errors.extend(
+        field
+          .edition_errors(edition, FieldScope::Extension)
+          .into_iter()
+          .map(|e| format!("[{} ({edition:?})]: {e}", self.name)),
+      );
+    }
+
+    if errors.is_empty() {
+      Ok(())
+    } else {
+      Err(errors.join("\n"))
+    }
+  }
+
+  pub(crate) fn options_with_features(&self) -> Vec<ProtoOption> {
     self
+      .options
+      .iter()
+      .cloned()
+      .chain(self.features.options())
+      .collect()
   }
 
   #[track_caller]
@@ -195,6 +260,7 @@ impl ProtoFile {
     for mut message in messages {
       message.register_imports(&mut self.imports);
       message.file = self.name.clone();
+      message.set_edition(self.edition);
 
       self.messages.push(message);
     }
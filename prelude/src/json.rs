@@ -0,0 +1,1280 @@
+//! Support for the [canonical JSON mapping](https://protobuf.dev/programming-guides/json/) of protobuf messages.
+//!
+//! With the `json` feature, the messages, oneofs and enums defined with the macros implement [`serde::Serialize`] and [`serde::Deserialize`]
+//! in a way that follows the protobuf JSON spec, so they can be used directly with `serde_json`.
+//!
+//! - Fields use their json name (lowerCamelCase by default, or the name set with the `json_name` attribute). When deserializing, the original proto name of the field is accepted as well.
+//! - Fields with a default value (and no explicit presence) are omitted from the output.
+//! - Enums are serialized with the name of the variant. Unknown values are serialized as numbers.
+//! - 64-bit integers are serialized as strings, and bytes as base64 strings.
+//! - The fields of a oneof are flattened into the message that contains it.
+//! - `google.protobuf.Timestamp`, `google.protobuf.Duration`, `google.protobuf.FieldMask` and `google.protobuf.Any` use their canonical representations.
+//!
+//! Messages packed inside of an `Any` are resolved by their type url. With the `inventory` feature, all of the messages defined with the macros are
+//! available automatically. Without it, only the well known types listed above can be resolved.
+
+use crate::*;
+
+use core::str::FromStr;
+use proto_types::{Any, Duration, Empty, FieldMask, Timestamp};
+use serde::{
+  Deserialize, Serialize,
+  de::{self, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Unexpected, Visitor},
+  ser::{self, SerializeMap, SerializeSeq, Serializer},
+};
+
+/// Implemented by the types that can be (de)serialized with the protobuf JSON mapping.
+///
+/// It is implemented automatically by the messages defined with the macros, which also implement [`serde::Serialize`] and [`serde::Deserialize`] by
+/// calling these methods, as well as by the well known types that have a dedicated JSON representation.
+pub trait ProtoJson: Sized {
+  fn serialize_json<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>;
+
+  fn deserialize_json<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error>;
+}
+
+impl<T: ProtoJson> ProtoJson for Box<T> {
+  #[inline]
+  fn serialize_json<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    T::serialize_json(self, serializer)
+  }
+
+  #[inline]
+  fn deserialize_json<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    T::deserialize_json(deserializer).map(Self::new)
+  }
+}
+
+/// Implemented by the oneofs defined with the macros, so that their fields can be flattened into the JSON object of the parent message.
+#[doc(hidden)]
+pub trait ProtoJsonOneof: Sized {
+  /// The json names and the proto names of the fields of the oneof.
+  const FIELD_NAMES: &'static [&'static str];
+
+  fn serialize_json_field<M: SerializeMap>(&self, map: &mut M) -> Result<(), M::Error>;
+
+  fn deserialize_json_field<'de, A: MapAccess<'de>>(
+    key: &str,
+    map: &mut A,
+  ) -> Result<Option<Self>, A::Error>;
+}
+
+/// Describes how a value of type `T` is mapped to and from JSON.
+#[doc(hidden)]
+pub trait JsonCodec<T> {
+  fn serialize<S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error>;
+
+  fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error>;
+
+  /// Whether the value should be omitted from the JSON output.
+  fn is_default(value: &T) -> bool;
+}
+
+/// Describes how a value of type `T` is mapped to and from the key of a JSON object.
+#[doc(hidden)]
+pub trait JsonKeyCodec<T> {
+  fn to_key(value: &T) -> String;
+
+  fn from_key(key: &str) -> Option<T>;
+}
+
+/// Serializes a value with the given codec.
+#[doc(hidden)]
+pub struct JsonRef<'a, C, T> {
+  value: &'a T,
+  _codec: PhantomData<fn() -> C>,
+}
+
+impl<'a, C, T> JsonRef<'a, C, T> {
+  #[inline]
+  pub const fn new(value: &'a T) -> Self {
+    Self {
+      value,
+      _codec: PhantomData,
+    }
+  }
+}
+
+impl<C: JsonCodec<T>, T> Serialize for JsonRef<'_, C, T> {
+  #[inline]
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    C::serialize(self.value, serializer)
+  }
+}
+
+/// Deserializes the value of a field with the given codec, using the default value if the JSON value is `null`.
+#[doc(hidden)]
+pub struct JsonSeed<C, T>(PhantomData<fn() -> (C, T)>);
+
+impl<C, T> JsonSeed<C, T> {
+  #[inline]
+  #[must_use]
+  pub const fn new() -> Self {
+    Self(PhantomData)
+  }
+}
+
+impl<C, T> Default for JsonSeed<C, T> {
+  #[inline]
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<'de, C: JsonCodec<T>, T: Default> DeserializeSeed<'de> for JsonSeed<C, T> {
+  type Value = T;
+
+  #[inline]
+  fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<T, D::Error> {
+    deserializer.deserialize_option(self)
+  }
+}
+
+impl<'de, C: JsonCodec<T>, T: Default> Visitor<'de> for JsonSeed<C, T> {
+  type Value = T;
+
+  fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+    formatter.write_str("a protobuf JSON value")
+  }
+
+  #[inline]
+  fn visit_none<E: de::Error>(self) -> Result<T, E> {
+    Ok(T::default())
+  }
+
+  #[inline]
+  fn visit_unit<E: de::Error>(self) -> Result<T, E> {
+    Ok(T::default())
+  }
+
+  #[inline]
+  fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<T, D::Error> {
+    C::deserialize(deserializer)
+  }
+}
+
+/// Deserializes the elements of lists and the values of maps, which cannot be `null`.
+struct ElementSeed<C, T>(PhantomData<fn() -> (C, T)>);
+
+impl<'de, C: JsonCodec<T>, T> DeserializeSeed<'de> for ElementSeed<C, T> {
+  type Value = T;
+
+  #[inline]
+  fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<T, D::Error> {
+    C::deserialize(deserializer)
+  }
+}
+
+/// Accepts integers, floats with no fractional part and strings containing either of them.
+struct IntVisitor<T>(PhantomData<T>);
+
+impl<'de, T> Visitor<'de> for IntVisitor<T>
+where
+  T: TryFrom<i64> + TryFrom<u64> + FromStr,
+{
+  type Value = T;
+
+  fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+    formatter.write_str("an integer or a string containing an integer")
+  }
+
+  fn visit_i64<E: de::Error>(self, v: i64) -> Result<T, E> {
+    T::try_from(v).map_err(|_| E::invalid_value(Unexpected::Signed(v), &self))
+  }
+
+  fn visit_u64<E: de::Error>(self, v: u64) -> Result<T, E> {
+    T::try_from(v).map_err(|_| E::invalid_value(Unexpected::Unsigned(v), &self))
+  }
+
+  #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+  fn visit_f64<E: de::Error>(self, v: f64) -> Result<T, E> {
+    if !v.is_finite() || v.fract() != 0.0 || v.abs() >= 2f64.powi(64) {
+      return Err(E::invalid_value(Unexpected::Float(v), &self));
+    }
+
+    if v < 0.0 {
+      self.visit_i64(v as i64)
+    } else {
+      self.visit_u64(v as u64)
+    }
+  }
+
+  fn visit_str<E: de::Error>(self, v: &str) -> Result<T, E> {
+    if let Ok(int) = v.parse::<T>() {
+      return Ok(int);
+    }
+
+    match v.parse::<f64>() {
+      Ok(float) if !v.contains(char::is_whitespace) => self.visit_f64(float),
+      _ => Err(E::invalid_value(Unexpected::Str(v), &self)),
+    }
+  }
+}
+
+/// Accepts numbers, strings containing a number, and the special values `"NaN"`, `"Infinity"` and `"-Infinity"`.
+struct FloatVisitor;
+
+impl Visitor<'_> for FloatVisitor {
+  type Value = f64;
+
+  fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+    formatter.write_str("a number or a string containing a number")
+  }
+
+  #[allow(clippy::cast_precision_loss)]
+  fn visit_i64<E: de::Error>(self, v: i64) -> Result<f64, E> {
+    Ok(v as f64)
+  }
+
+  #[allow(clippy::cast_precision_loss)]
+  fn visit_u64<E: de::Error>(self, v: u64) -> Result<f64, E> {
+    Ok(v as f64)
+  }
+
+  fn visit_f64<E: de::Error>(self, v: f64) -> Result<f64, E> {
+    Ok(v)
+  }
+
+  fn visit_str<E: de::Error>(self, v: &str) -> Result<f64, E> {
+    match v {
+      "NaN" => Ok(f64::NAN),
+      "Infinity" => Ok(f64::INFINITY),
+      "-Infinity" => Ok(f64::NEG_INFINITY),
+      _ => v
+        .parse::<f64>()
+        .ok()
+        .filter(|float| float.is_finite() && !v.contains(char::is_whitespace))
+        .ok_or_else(|| E::invalid_value(Unexpected::Str(v), &self)),
+    }
+  }
+}
+
+macro_rules! int_codec {
+  ($($codec:ident => $int:ty, $as_string:literal);* $(;)?) => {
+    $(
+      #[doc(hidden)]
+      pub struct $codec;
+
+      impl JsonCodec<$int> for $codec {
+        #[inline]
+        fn serialize<S: Serializer>(value: &$int, serializer: S) -> Result<S::Ok, S::Error> {
+          if $as_string {
+            serializer.collect_str(value)
+          } else {
+            value.serialize(serializer)
+          }
+        }
+
+        #[inline]
+        fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<$int, D::Error> {
+          deserializer.deserialize_any(IntVisitor::<$int>(PhantomData))
+        }
+
+        #[inline]
+        fn is_default(value: &$int) -> bool {
+          *value == 0
+        }
+      }
+
+      impl JsonKeyCodec<$int> for $codec {
+        #[inline]
+        fn to_key(value: &$int) -> String {
+          value.to_string()
+        }
+
+        #[inline]
+        fn from_key(key: &str) -> Option<$int> {
+          key.parse().ok()
+        }
+      }
+    )*
+  };
+}
+
+int_codec!(
+  Int32Codec => i32, false;
+  Uint32Codec => u32, false;
+  Int64Codec => i64, true;
+  Uint64Codec => u64, true;
+);
+
+macro_rules! float_codec {
+  ($($codec:ident => $float:ty);* $(;)?) => {
+    $(
+      #[doc(hidden)]
+      pub struct $codec;
+
+      impl JsonCodec<$float> for $codec {
+        fn serialize<S: Serializer>(value: &$float, serializer: S) -> Result<S::Ok, S::Error> {
+          if value.is_nan() {
+            serializer.serialize_str("NaN")
+          } else if value.is_infinite() {
+            serializer.serialize_str(if value.is_sign_positive() { "Infinity" } else { "-Infinity" })
+          } else {
+            value.serialize(serializer)
+          }
+        }
+
+        #[allow(clippy::cast_possible_truncation, clippy::unnecessary_cast)]
+        fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<$float, D::Error> {
+          let value = deserializer.deserialize_any(FloatVisitor)?;
+
+          if value.is_finite() && value.abs() > f64::from(<$float>::MAX) {
+            return Err(de::Error::invalid_value(Unexpected::Float(value), &stringify!($float)));
+          }
+
+          Ok(value as $float)
+        }
+
+        #[inline]
+        #[allow(clippy::float_cmp)]
+        fn is_default(value: &$float) -> bool {
+          *value == 0.0
+        }
+      }
+    )*
+  };
+}
+
+float_codec!(
+  FloatCodec => f32;
+  DoubleCodec => f64;
+);
+
+#[doc(hidden)]
+pub struct BoolCodec;
+
+impl JsonCodec<bool> for BoolCodec {
+  #[inline]
+  fn serialize<S: Serializer>(value: &bool, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_bool(*value)
+  }
+
+  #[inline]
+  fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<bool, D::Error> {
+    bool::deserialize(deserializer)
+  }
+
+  #[inline]
+  fn is_default(value: &bool) -> bool {
+    !*value
+  }
+}
+
+impl JsonKeyCodec<bool> for BoolCodec {
+  #[inline]
+  fn to_key(value: &bool) -> String {
+    value.to_string()
+  }
+
+  #[inline]
+  fn from_key(key: &str) -> Option<bool> {
+    match key {
+      "true" => Some(true),
+      "false" => Some(false),
+      _ => None,
+    }
+  }
+}
+
+#[doc(hidden)]
+pub struct StringCodec;
+
+impl JsonCodec<String> for StringCodec {
+  #[inline]
+  fn serialize<S: Serializer>(value: &String, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(value)
+  }
+
+  #[inline]
+  fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    String::deserialize(deserializer)
+  }
+
+  #[inline]
+  fn is_default(value: &String) -> bool {
+    value.is_empty()
+  }
+}
+
+impl JsonKeyCodec<String> for StringCodec {
+  #[inline]
+  fn to_key(value: &String) -> String {
+    value.clone()
+  }
+
+  #[inline]
+  fn from_key(key: &str) -> Option<String> {
+    Some(key.to_string())
+  }
+}
+
+#[doc(hidden)]
+pub struct BytesCodec;
+
+impl<T> JsonCodec<T> for BytesCodec
+where
+  T: AsRef<[u8]> + From<Vec<u8>>,
+{
+  #[inline]
+  fn serialize<S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&encode_base64(value.as_ref()))
+  }
+
+  fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error> {
+    let encoded = String::deserialize(deserializer)?;
+
+    decode_base64(&encoded)
+      .map(T::from)
+      .ok_or_else(|| de::Error::invalid_value(Unexpected::Str(&encoded), &"a base64 string"))
+  }
+
+  #[inline]
+  fn is_default(value: &T) -> bool {
+    value.as_ref().is_empty()
+  }
+}
+
+/// Maps the numeric value of an enum to the name of its variant.
+#[doc(hidden)]
+pub struct EnumCodec<E>(PhantomData<E>);
+
+impl<E> JsonCodec<i32> for EnumCodec<E>
+where
+  E: ProtoEnumSchema + Into<i32>,
+{
+  fn serialize<S: Serializer>(value: &i32, serializer: S) -> Result<S::Ok, S::Error> {
+    match E::try_from(*value) {
+      Ok(variant) => serializer.serialize_str(variant.as_proto_name()),
+      Err(_) => serializer.serialize_i32(*value),
+    }
+  }
+
+  fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<i32, D::Error> {
+    deserializer.deserialize_any(EnumVisitor::<E>(PhantomData))
+  }
+
+  #[inline]
+  fn is_default(value: &i32) -> bool {
+    *value == 0
+  }
+}
+
+struct EnumVisitor<E>(PhantomData<E>);
+
+impl<E: ProtoEnumSchema + Into<i32>> Visitor<'_> for EnumVisitor<E> {
+  type Value = i32;
+
+  fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+    write!(
+      formatter,
+      "the name or the number of a variant of the enum `{}`",
+      E::proto_name()
+    )
+  }
+
+  fn visit_i64<Er: de::Error>(self, v: i64) -> Result<i32, Er> {
+    i32::try_from(v).map_err(|_| Er::invalid_value(Unexpected::Signed(v), &self))
+  }
+
+  fn visit_u64<Er: de::Error>(self, v: u64) -> Result<i32, Er> {
+    i32::try_from(v).map_err(|_| Er::invalid_value(Unexpected::Unsigned(v), &self))
+  }
+
+  fn visit_str<Er: de::Error>(self, v: &str) -> Result<i32, Er> {
+    E::from_proto_name(v)
+      .map(Into::into)
+      .ok_or_else(|| Er::invalid_value(Unexpected::Str(v), &self))
+  }
+}
+
+/// Serializes an enum with the name of its variant.
+#[doc(hidden)]
+pub fn serialize_enum<E, S>(value: &E, serializer: S) -> Result<S::Ok, S::Error>
+where
+  E: ProtoEnumSchema,
+  S: Serializer,
+{
+  serializer.serialize_str(value.as_proto_name())
+}
+
+/// Deserializes an enum from either the name or the number of one of its variants.
+#[doc(hidden)]
+pub fn deserialize_enum<'de, E, D>(deserializer: D) -> Result<E, D::Error>
+where
+  E: ProtoEnumSchema + Into<i32>,
+  D: Deserializer<'de>,
+{
+  let value = deserializer.deserialize_any(EnumVisitor::<E>(PhantomData))?;
+
+  E::try_from(value).map_err(|_| {
+    de::Error::invalid_value(
+      Unexpected::Signed(value.into()),
+      &EnumVisitor::<E>(PhantomData),
+    )
+  })
+}
+
+#[doc(hidden)]
+pub struct MessageCodec;
+
+impl<T> JsonCodec<T> for MessageCodec
+where
+  T: ProtoJson + PartialEq + Default,
+{
+  #[inline]
+  fn serialize<S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+    value.serialize_json(serializer)
+  }
+
+  #[inline]
+  fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error> {
+    T::deserialize_json(deserializer)
+  }
+
+  #[inline]
+  fn is_default(value: &T) -> bool {
+    *value == T::default()
+  }
+}
+
+/// Used for fields with explicit presence, which are serialized whenever they are set.
+#[doc(hidden)]
+pub struct OptionalCodec<C>(PhantomData<C>);
+
+impl<C: JsonCodec<T>, T> JsonCodec<Option<T>> for OptionalCodec<C> {
+  #[inline]
+  fn serialize<S: Serializer>(value: &Option<T>, serializer: S) -> Result<S::Ok, S::Error> {
+    match value {
+      Some(inner) => C::serialize(inner, serializer),
+      None => serializer.serialize_none(),
+    }
+  }
+
+  #[inline]
+  fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<T>, D::Error> {
+    C::deserialize(deserializer).map(Some)
+  }
+
+  #[inline]
+  fn is_default(value: &Option<T>) -> bool {
+    value.is_none()
+  }
+}
+
+#[doc(hidden)]
+pub struct RepeatedCodec<C>(PhantomData<C>);
+
+impl<C: JsonCodec<T>, T> JsonCodec<Vec<T>> for RepeatedCodec<C> {
+  fn serialize<S: Serializer>(value: &Vec<T>, serializer: S) -> Result<S::Ok, S::Error> {
+    let mut seq = serializer.serialize_seq(Some(value.len()))?;
+
+    for item in value {
+      seq.serialize_element(&JsonRef::<C, T>::new(item))?;
+    }
+
+    seq.end()
+  }
+
+  fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<T>, D::Error> {
+    struct SeqVisitor<C, T>(PhantomData<fn() -> (C, T)>);
+
+    impl<'de, C: JsonCodec<T>, T> Visitor<'de> for SeqVisitor<C, T> {
+      type Value = Vec<T>;
+
+      fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter.write_str("a list")
+      }
+
+      fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Vec<T>, A::Error> {
+        let mut output = Vec::with_capacity(seq.size_hint().unwrap_or_default().min(4096));
+
+        while let Some(item) = seq.next_element_seed(ElementSeed::<C, T>(PhantomData))? {
+          output.push(item);
+        }
+
+        Ok(output)
+      }
+    }
+
+    deserializer.deserialize_seq(SeqVisitor::<C, T>(PhantomData))
+  }
+
+  #[inline]
+  fn is_default(value: &Vec<T>) -> bool {
+    value.is_empty()
+  }
+}
+
+#[doc(hidden)]
+pub struct MapCodec<K, V>(PhantomData<(K, V)>);
+
+macro_rules! impl_map_codec {
+  ($map:ident $(, $bound:path)*) => {
+    impl<K, V, KT, VT> JsonCodec<$map<KT, VT>> for MapCodec<K, V>
+    where
+      K: JsonKeyCodec<KT>,
+      V: JsonCodec<VT>,
+      KT: $($bound +)*,
+    {
+      fn serialize<S: Serializer>(value: &$map<KT, VT>, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(value.len()))?;
+
+        for (key, val) in value {
+          map.serialize_entry(&K::to_key(key), &JsonRef::<V, VT>::new(val))?;
+        }
+
+        map.end()
+      }
+
+      fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<$map<KT, VT>, D::Error> {
+        struct MapVisitor<K, V, KT, VT>(PhantomData<fn() -> (K, V, KT, VT)>);
+
+        impl<'de, K, V, KT, VT> Visitor<'de> for MapVisitor<K, V, KT, VT>
+        where
+          K: JsonKeyCodec<KT>,
+          V: JsonCodec<VT>,
+          KT: $($bound +)*,
+        {
+          type Value = $map<KT, VT>;
+
+          fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+            formatter.write_str("a map")
+          }
+
+          fn visit_map<A: MapAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+            let mut output = $map::new();
+
+            while let Some(key) = access.next_key::<String>()? {
+              let parsed_key = K::from_key(&key).ok_or_else(|| {
+                <A::Error as de::Error>::invalid_value(Unexpected::Str(&key), &"a valid map key")
+              })?;
+
+              let value = access.next_value_seed(ElementSeed::<V, VT>(PhantomData))?;
+
+              output.insert(parsed_key, value);
+            }
+
+            Ok(output)
+          }
+        }
+
+        deserializer.deserialize_map(MapVisitor::<K, V, KT, VT>(PhantomData))
+      }
+
+      #[inline]
+      fn is_default(value: &$map<KT, VT>) -> bool {
+        value.is_empty()
+      }
+    }
+  };
+}
+
+impl_map_codec!(HashMap, Eq, Hash);
+impl_map_codec!(BTreeMap, Ord);
+
+const SECONDS_PER_DAY: i64 = 86_400;
+// 0001-01-01T00:00:00Z
+const MIN_TIMESTAMP_SECONDS: i64 = -62_135_596_800;
+// 9999-12-31T23:59:59Z
+const MAX_TIMESTAMP_SECONDS: i64 = 253_402_300_799;
+// Roughly 10,000 years
+const MAX_DURATION_SECONDS: i64 = 315_576_000_000;
+const NANOS_PER_SECOND: i32 = 1_000_000_000;
+
+/// Converts a number of days since the unix epoch into a (year, month, day) date.
+const fn civil_from_days(days: i64) -> (i64, i64, i64) {
+  let z = days + 719_468;
+  let era = z.div_euclid(146_097);
+  let day_of_era = z.rem_euclid(146_097);
+  let year_of_era =
+    (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+  let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+  let month_index = (5 * day_of_year + 2) / 153;
+  let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+  let month = if month_index < 10 {
+    month_index + 3
+  } else {
+    month_index - 9
+  };
+  let year = year_of_era + era * 400;
+
+  (if month <= 2 { year + 1 } else { year }, month, day)
+}
+
+/// Converts a (year, month, day) date into the number of days since the unix epoch.
+const fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+  let year = if month <= 2 { year - 1 } else { year };
+  let era = year.div_euclid(400);
+  let year_of_era = year.rem_euclid(400);
+  let month_index = (month + 9) % 12;
+  let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+  let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+  era * 146_097 + day_of_era - 719_468
+}
+
+const fn days_in_month(year: i64, month: i64) -> i64 {
+  match month {
+    2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+    2 => 28,
+    4 | 6 | 9 | 11 => 30,
+    _ => 31,
+  }
+}
+
+/// Writes the fractional part of a second, using 3, 6 or 9 digits as required by the spec.
+fn write_nanos(output: &mut String, nanos: u32) {
+  if nanos == 0 {
+    return;
+  }
+
+  let _ = if nanos % 1_000_000 == 0 {
+    write!(output, ".{:03}", nanos / 1_000_000)
+  } else if nanos % 1_000 == 0 {
+    write!(output, ".{:06}", nanos / 1_000)
+  } else {
+    write!(output, ".{nanos:09}")
+  };
+}
+
+fn parse_digits(digits: &str) -> Option<i64> {
+  if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+    return None;
+  }
+
+  digits.parse().ok()
+}
+
+/// Parses up to 9 digits of a fractional second into nanoseconds.
+fn parse_nanos(digits: &str) -> Option<i32> {
+  if digits.len() > 9 {
+    return None;
+  }
+
+  let mut nanos = parse_digits(digits)?;
+
+  for _ in digits.len()..9 {
+    nanos *= 10;
+  }
+
+  i32::try_from(nanos).ok()
+}
+
+fn format_timestamp(timestamp: &Timestamp) -> Option<String> {
+  if !(MIN_TIMESTAMP_SECONDS..=MAX_TIMESTAMP_SECONDS).contains(&timestamp.seconds)
+    || !(0..NANOS_PER_SECOND).contains(&timestamp.nanos)
+  {
+    return None;
+  }
+
+  let days = timestamp.seconds.div_euclid(SECONDS_PER_DAY);
+  let seconds_of_day = timestamp.seconds.rem_euclid(SECONDS_PER_DAY);
+  let (year, month, day) = civil_from_days(days);
+
+  let mut output = format!(
+    "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}",
+    seconds_of_day / 3600,
+    (seconds_of_day % 3600) / 60,
+    seconds_of_day % 60
+  );
+
+  write_nanos(&mut output, timestamp.nanos.unsigned_abs());
+  output.push('Z');
+
+  Some(output)
+}
+
+fn parse_timestamp(input: &str) -> Option<Timestamp> {
+  let bytes = input.as_bytes();
+
+  if !input.is_ascii()
+    || bytes.len() < 20
+    || bytes[4] != b'-'
+    || bytes[7] != b'-'
+    || !matches!(bytes[10], b'T' | b't')
+    || bytes[13] != b':'
+    || bytes[16] != b':'
+  {
+    return None;
+  }
+
+  let year = parse_digits(&input[0..4])?;
+  let month = parse_digits(&input[5..7])?;
+  let day = parse_digits(&input[8..10])?;
+  let hour = parse_digits(&input[11..13])?;
+  let minute = parse_digits(&input[14..16])?;
+  let second = parse_digits(&input[17..19])?;
+
+  if !(1..=12).contains(&month)
+    || day < 1
+    || day > days_in_month(year, month)
+    || hour > 23
+    || minute > 59
+    || second > 59
+  {
+    return None;
+  }
+
+  let mut rest = &input[19..];
+
+  let nanos = if let Some(fraction) = rest.strip_prefix('.') {
+    let digits_len = fraction
+      .bytes()
+      .take_while(u8::is_ascii_digit)
+      .count();
+    let (digits, after) = fraction.split_at(digits_len);
+    rest = after;
+
+    parse_nanos(digits)?
+  } else {
+    0
+  };
+
+  let offset = match rest {
+    "Z" | "z" => 0,
+    _ => {
+      let (sign, offset) = match rest.split_at_checked(1)? {
+        ("+", offset) => (1, offset),
+        ("-", offset) => (-1, offset),
+        _ => return None,
+      };
+
+      let (hours, minutes) = offset.split_once(':')?;
+
+      if hours.len() != 2 || minutes.len() != 2 {
+        return None;
+      }
+
+      sign * (parse_digits(hours)? * 3600 + parse_digits(minutes)? * 60)
+    }
+  };
+
+  let seconds =
+    days_from_civil(year, month, day) * SECONDS_PER_DAY + hour * 3600 + minute * 60 + second
+      - offset;
+
+  (MIN_TIMESTAMP_SECONDS..=MAX_TIMESTAMP_SECONDS)
+    .contains(&seconds)
+    .then_some(Timestamp { seconds, nanos })
+}
+
+fn format_duration(duration: &Duration) -> Option<String> {
+  let (seconds, nanos) = (duration.seconds, duration.nanos);
+
+  if !(-MAX_DURATION_SECONDS..=MAX_DURATION_SECONDS).contains(&seconds)
+    || nanos.unsigned_abs() >= NANOS_PER_SECOND.unsigned_abs()
+    || (seconds > 0 && nanos < 0)
+    || (seconds < 0 && nanos > 0)
+  {
+    return None;
+  }
+
+  let mut output = String::new();
+
+  if seconds < 0 || nanos < 0 {
+    output.push('-');
+  }
+
+  let _ = write!(output, "{}", seconds.unsigned_abs());
+  write_nanos(&mut output, nanos.unsigned_abs());
+  output.push('s');
+
+  Some(output)
+}
+
+fn parse_duration(input: &str) -> Option<Duration> {
+  let body = input.strip_suffix('s')?;
+
+  let (is_negative, body) = match body.strip_prefix('-') {
+    Some(rest) => (true, rest),
+    None => (false, body),
+  };
+
+  let (seconds, nanos) = match body.split_once('.') {
+    Some((seconds, fraction)) => (parse_digits(seconds)?, parse_nanos(fraction)?),
+    None => (parse_digits(body)?, 0),
+  };
+
+  if seconds > MAX_DURATION_SECONDS {
+    return None;
+  }
+
+  Some(if is_negative {
+    Duration {
+      seconds: -seconds,
+      nanos: -nanos,
+    }
+  } else {
+    Duration { seconds, nanos }
+  })
+}
+
+/// Converts a field path to lowerCamelCase, returning `None` if the conversion would not be reversible.
+fn field_path_to_json(path: &str) -> Option<String> {
+  let mut output = String::with_capacity(path.len());
+  let mut capitalize_next = false;
+
+  for ch in path.chars() {
+    if ch.is_ascii_uppercase() {
+      return None;
+    }
+
+    if ch == '_' {
+      capitalize_next = true;
+    } else if capitalize_next {
+      if !ch.is_ascii_lowercase() {
+        return None;
+      }
+
+      output.push(ch.to_ascii_uppercase());
+      capitalize_next = false;
+    } else {
+      output.push(ch);
+    }
+  }
+
+  (!capitalize_next).then_some(output)
+}
+
+fn field_path_from_json(path: &str) -> String {
+  let mut output = String::with_capacity(path.len() + 4);
+
+  for ch in path.chars() {
+    if ch.is_ascii_uppercase() {
+      output.push('_');
+      output.push(ch.to_ascii_lowercase());
+    } else {
+      output.push(ch);
+    }
+  }
+
+  output
+}
+
+const BASE64_ALPHABET: &[u8; 64] =
+  b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encodes bytes with the standard base64 alphabet, with padding.
+fn encode_base64(bytes: &[u8]) -> String {
+  let mut output = String::with_capacity(bytes.len().div_ceil(3) * 4);
+
+  for chunk in bytes.chunks(3) {
+    let group = (u32::from(chunk[0]) << 16)
+      | (u32::from(chunk.get(1).copied().unwrap_or_default()) << 8)
+      | u32::from(chunk.get(2).copied().unwrap_or_default());
+
+    for i in 0..4 {
+      if i <= chunk.len() {
+        let index = (group >> (18 - 6 * i)) & 0b11_1111;
+
+        output.push(char::from(BASE64_ALPHABET[index as usize]));
+      } else {
+        output.push('=');
+      }
+    }
+  }
+
+  output
+}
+
+/// Decodes a base64 string, accepting both the standard and the url-safe alphabets, with or without padding.
+#[allow(clippy::cast_possible_truncation)]
+fn decode_base64(input: &str) -> Option<Vec<u8>> {
+  let input = input.trim_end_matches('=');
+
+  if input.len() % 4 == 1 {
+    return None;
+  }
+
+  let mut output = Vec::with_capacity(input.len() * 3 / 4);
+  let mut buffer: u32 = 0;
+  let mut bits: u32 = 0;
+
+  for byte in input.bytes() {
+    let value = match byte {
+      b'A'..=b'Z' => byte - b'A',
+      b'a'..=b'z' => byte - b'a' + 26,
+      b'0'..=b'9' => byte - b'0' + 52,
+      b'+' | b'-' => 62,
+      b'/' | b'_' => 63,
+      _ => return None,
+    };
+
+    buffer = (buffer << 6) | u32::from(value);
+    bits += 6;
+
+    if bits >= 8 {
+      bits -= 8;
+      output.push((buffer >> bits) as u8);
+      buffer &= (1 << bits) - 1;
+    }
+  }
+
+  Some(output)
+}
+
+impl ProtoJson for Timestamp {
+  fn serialize_json<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let formatted = format_timestamp(self).ok_or_else(|| {
+      <S::Error as ser::Error>::custom(format!(
+        "Timestamp with {} seconds and {} nanos is out of range",
+        self.seconds, self.nanos
+      ))
+    })?;
+
+    serializer.serialize_str(&formatted)
+  }
+
+  fn deserialize_json<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let input = String::deserialize(deserializer)?;
+
+    parse_timestamp(&input)
+      .ok_or_else(|| de::Error::invalid_value(Unexpected::Str(&input), &"an RFC 3339 timestamp"))
+  }
+}
+
+impl ProtoJson for Duration {
+  fn serialize_json<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let formatted = format_duration(self).ok_or_else(|| {
+      <S::Error as ser::Error>::custom(format!(
+        "Duration with {} seconds and {} nanos is invalid",
+        self.seconds, self.nanos
+      ))
+    })?;
+
+    serializer.serialize_str(&formatted)
+  }
+
+  fn deserialize_json<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let input = String::deserialize(deserializer)?;
+
+    parse_duration(&input).ok_or_else(|| {
+      de::Error::invalid_value(
+        Unexpected::Str(&input),
+        &"a duration in seconds with the `s` suffix",
+      )
+    })
+  }
+}
+
+impl ProtoJson for FieldMask {
+  fn serialize_json<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let paths = self
+      .paths
+      .iter()
+      .map(|path| {
+        field_path_to_json(path).ok_or_else(|| {
+          <S::Error as ser::Error>::custom(format!(
+            "FieldMask path `{path}` cannot be converted to lowerCamelCase"
+          ))
+        })
+      })
+      .collect::<Result<Vec<_>, _>>()?;
+
+    serializer.serialize_str(&paths.join(","))
+  }
+
+  fn deserialize_json<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let input = String::deserialize(deserializer)?;
+
+    let paths = if input.is_empty() {
+      Vec::new()
+    } else {
+      input
+        .split(',')
+        .map(field_path_from_json)
+        .collect()
+    };
+
+    Ok(Self { paths })
+  }
+}
+
+impl ProtoJson for Empty {
+  fn serialize_json<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_map(Some(0))?.end()
+  }
+
+  fn deserialize_json<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    struct EmptyVisitor;
+
+    impl<'de> Visitor<'de> for EmptyVisitor {
+      type Value = Empty;
+
+      fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter.write_str("an empty object")
+      }
+
+      fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Empty, A::Error> {
+        if let Some(key) = map.next_key::<String>()? {
+          return Err(de::Error::custom(format!("unknown field `{key}`")));
+        }
+
+        Ok(Empty {})
+      }
+    }
+
+    deserializer.deserialize_map(EmptyVisitor)
+  }
+}
+
+impl ProtoJson for () {
+  #[inline]
+  fn serialize_json<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    Empty {}.serialize_json(serializer)
+  }
+
+  #[inline]
+  fn deserialize_json<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    Empty::deserialize_json(deserializer).map(|_| ())
+  }
+}
+
+type ToJsonFn = fn(&[u8]) -> Result<serde_json::Value, String>;
+type FromJsonFn = fn(serde_json::Value) -> Result<Vec<u8>, String>;
+
+/// An entry in the registry of the messages that can be packed inside of a `google.protobuf.Any`.
+#[doc(hidden)]
+pub struct RegistryJsonMessage {
+  pub full_name: fn() -> &'static str,
+  pub to_json: ToJsonFn,
+  pub from_json: FromJsonFn,
+}
+
+#[cfg(feature = "inventory")]
+inventory::collect!(RegistryJsonMessage);
+
+/// Decodes a message from its binary encoding and converts it to JSON.
+#[doc(hidden)]
+pub fn message_to_json<M>(bytes: &[u8]) -> Result<serde_json::Value, String>
+where
+  M: prost::Message + ProtoJson + PartialEq + Default,
+{
+  let message = M::decode(bytes).map_err(|e| e.to_string())?;
+
+  serde_json::to_value(JsonRef::<MessageCodec, M>::new(&message)).map_err(|e| e.to_string())
+}
+
+/// Parses a message from JSON and returns its binary encoding.
+#[doc(hidden)]
+pub fn message_from_json<M>(value: serde_json::Value) -> Result<Vec<u8>, String>
+where
+  M: prost::Message + ProtoJson,
+{
+  let message = M::deserialize_json(value).map_err(|e| e.to_string())?;
+
+  Ok(message.encode_to_vec())
+}
+
+/// The well known types that are represented with the `value` key when they are packed inside of an `Any`.
+const SPECIAL_ANY_TYPES: &[&str] = &[
+  "google.protobuf.Any",
+  "google.protobuf.Duration",
+  "google.protobuf.FieldMask",
+  "google.protobuf.Timestamp",
+  "google.protobuf.Struct",
+  "google.protobuf.Value",
+  "google.protobuf.ListValue",
+  "google.protobuf.BoolValue",
+  "google.protobuf.BytesValue",
+  "google.protobuf.DoubleValue",
+  "google.protobuf.FloatValue",
+  "google.protobuf.Int32Value",
+  "google.protobuf.Int64Value",
+  "google.protobuf.StringValue",
+  "google.protobuf.UInt32Value",
+  "google.protobuf.UInt64Value",
+];
+
+fn any_codec(full_name: &str) -> Option<(ToJsonFn, FromJsonFn)> {
+  match full_name {
+    "google.protobuf.Any" => Some((message_to_json::<Any>, message_from_json::<Any>)),
+    "google.protobuf.Duration" => {
+      Some((message_to_json::<Duration>, message_from_json::<Duration>))
+    }
+    "google.protobuf.Empty" => Some((message_to_json::<Empty>, message_from_json::<Empty>)),
+    "google.protobuf.FieldMask" => {
+      Some((message_to_json::<FieldMask>, message_from_json::<FieldMask>))
+    }
+    "google.protobuf.Timestamp" => {
+      Some((message_to_json::<Timestamp>, message_from_json::<Timestamp>))
+    }
+    #[cfg(feature = "inventory")]
+    _ => inventory::iter::<RegistryJsonMessage>()
+      .find(|entry| (entry.full_name)() == full_name)
+      .map(|entry| (entry.to_json, entry.from_json)),
+    #[cfg(not(feature = "inventory"))]
+    _ => None,
+  }
+}
+
+fn type_name_from_url(type_url: &str) -> &str {
+  type_url
+    .rsplit_once('/')
+    .map_or(type_url, |(_, name)| name)
+}
+
+impl ProtoJson for Any {
+  fn serialize_json<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let full_name = type_name_from_url(&self.type_url);
+
+    let (to_json, _) = any_codec(full_name).ok_or_else(|| {
+      <S::Error as ser::Error>::custom(format!(
+        "Cannot serialize Any with unknown type url `{}`",
+        self.type_url
+      ))
+    })?;
+
+    let value = to_json(self.value.as_ref()).map_err(<S::Error as ser::Error>::custom)?;
+
+    let mut map = serializer.serialize_map(None)?;
+    map.serialize_entry("@type", &self.type_url)?;
+
+    if SPECIAL_ANY_TYPES.contains(&full_name) {
+      map.serialize_entry("value", &value)?;
+    } else if let serde_json::Value::Object(fields) = &value {
+      for (key, field) in fields {
+        map.serialize_entry(key, field)?;
+      }
+    }
+
+    map.end()
+  }
+
+  fn deserialize_json<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let serde_json::Value::Object(mut fields) = serde_json::Value::deserialize(deserializer)?
+    else {
+      return Err(de::Error::custom(
+        "expected a JSON object for google.protobuf.Any",
+      ));
+    };
+
+    let Some(serde_json::Value::String(type_url)) = fields.remove("@type") else {
+      return Err(de::Error::missing_field("@type"));
+    };
+
+    let full_name = type_name_from_url(&type_url);
+
+    let (_, from_json) = any_codec(full_name).ok_or_else(|| {
+      <D::Error as de::Error>::custom(format!(
+        "Cannot deserialize Any with unknown type url `{type_url}`"
+      ))
+    })?;
+
+    let payload = if SPECIAL_ANY_TYPES.contains(&full_name) {
+      fields
+        .remove("value")
+        .unwrap_or(serde_json::Value::Null)
+    } else {
+      serde_json::Value::Object(fields)
+    };
+
+    let value = from_json(payload).map_err(<D::Error as de::Error>::custom)?;
+
+    Ok(Self {
+      type_url,
+      value: value.into(),
+    })
+  }
+}
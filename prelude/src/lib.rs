@@ -62,6 +62,11 @@ pub mod macros {
   pub use proc_macro_impls::*;
 }
 pub use proto_types;
+#[cfg(feature = "descriptor-pool")]
+pub use prost_reflect;
+#[cfg(feature = "json")]
+#[doc(hidden)]
+pub use serde;
 #[doc(inline)]
 pub use proto_types::protovalidate::{FieldPathElement, Violations};
 use thiserror::Error;
@@ -72,6 +77,18 @@ pub mod validators;
 use hashbrown::HashSet;
 #[cfg(feature = "std")]
 use std::collections::HashSet;
+mod compatibility;
+pub use compatibility::*;
+mod descriptors;
+#[cfg(feature = "runtime-descriptors")]
+#[doc(hidden)]
+pub use descriptors::message_descriptor;
+#[cfg(feature = "descriptor-pool")]
+pub use descriptors::DescriptorConversionError;
+pub use descriptors::{OptionEncodingError, OptionEncodingErrorKind};
+#[cfg(feature = "runtime-descriptors")]
+pub use descriptors::{DescriptorPoolError, MessageDescriptorError, try_message_descriptor};
+mod features;
 mod field;
 mod file;
 mod package;
@@ -84,6 +101,7 @@ pub mod test_utils;
 #[doc(inline)]
 pub use test_utils::*;
 mod well_known_types;
+pub use features::*;
 pub use field::*;
 pub use file::*;
 pub use oneof::*;
@@ -103,6 +121,23 @@ pub use extension::*;
 #[cfg(feature = "serde")]
 pub(crate) mod serde_impls;
 
+#[cfg(feature = "json")]
+pub mod json;
+
+#[cfg(feature = "text-format")]
+pub mod text_format;
+
+#[cfg(feature = "samples")]
+pub mod samples;
+
+#[cfg(feature = "field-mask")]
+pub mod field_masks;
+
+pub mod type_registry;
+
+#[cfg(feature = "normalize")]
+pub mod normalize;
+
 #[cfg(not(feature = "std"))]
 mod lazy;
 #[cfg(not(feature = "std"))]
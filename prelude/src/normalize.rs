@@ -0,0 +1,207 @@
+//! Normalization of field values before validation.
+//!
+//! With the `normalize` feature, the fields of the messages and oneofs defined with the macros can be normalized
+//! with the `normalize` and `transform` attributes:
+//!
+//! - `normalize` receives a [`StringNormalizerBuilder`] and can be used with string fields (including optional and repeated ones).
+//!   The steps are applied in the order in which they are declared.
+//! - `transform` receives a path to a function (or a closure) that takes a mutable reference to the field, and can be used with any field.
+//!   It runs after the `normalize` steps.
+//!
+//! ```rust,ignore
+//! #[proto_message]
+//! pub struct User {
+//!   #[proto(normalize = |n| n.trim().lowercase(), validate = |v| v.email())]
+//!   pub email: String,
+//!   #[proto(transform = dedup_tags)]
+//!   pub tags: Vec<String>,
+//! }
+//!
+//! let user = user.normalized_and_validated()?;
+//! ```
+//!
+//! Normalization is applied recursively to nested messages and oneofs with [`ValidatedMessage::normalize`], and
+//! [`ValidatedMessage::normalized_and_validated`] runs it right before the validation.
+
+use crate::*;
+
+/// Normalizes the fields of a message or oneof.
+///
+/// It is implemented automatically by the messages and oneofs defined with the macros.
+pub trait ProtoNormalize {
+  #[doc(hidden)]
+  fn normalize_fields(&mut self);
+}
+
+impl<T: ProtoNormalize> ProtoNormalize for Box<T> {
+  #[inline]
+  fn normalize_fields(&mut self) {
+    T::normalize_fields(self);
+  }
+}
+
+/// A single step of a [`StringNormalizer`].
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub enum StringTransform {
+  /// Removes the leading and trailing whitespace.
+  Trim,
+  /// Converts all characters to lowercase.
+  Lowercase,
+  /// Converts all characters to uppercase.
+  Uppercase,
+  /// Replaces every sequence of whitespace characters with a single space, and removes the leading and trailing whitespace.
+  CollapseWhitespace,
+  /// Applies the Unicode Normalization Form C.
+  Nfc,
+  /// Applies a custom function.
+  Custom(fn(&mut String)),
+}
+
+impl StringTransform {
+  /// Applies this step to a string.
+  pub fn apply(&self, value: &mut String) {
+    match self {
+      Self::Trim => {
+        let trimmed = value.trim();
+
+        if trimmed.len() != value.len() {
+          *value = trimmed.to_string();
+        }
+      }
+      Self::Lowercase => {
+        if value.chars().any(char::is_uppercase) {
+          *value = value.to_lowercase();
+        }
+      }
+      Self::Uppercase => {
+        if value.chars().any(char::is_lowercase) {
+          *value = value.to_uppercase();
+        }
+      }
+      Self::CollapseWhitespace => {
+        let mut output = String::with_capacity(value.len());
+
+        for (i, word) in value.split_whitespace().enumerate() {
+          if i != 0 {
+            output.push(' ');
+          }
+
+          output.push_str(word);
+        }
+
+        *value = output;
+      }
+      Self::Nfc => {
+        use unicode_normalization::{IsNormalized, UnicodeNormalization, is_nfc_quick};
+
+        if is_nfc_quick(value.chars()) != IsNormalized::Yes {
+          *value = value.nfc().collect();
+        }
+      }
+      Self::Custom(func) => func(value),
+    }
+  }
+}
+
+/// Normalizes strings by applying a list of [`StringTransform`]s, in order.
+#[derive(Debug, Clone, Default)]
+pub struct StringNormalizer {
+  pub steps: Vec<StringTransform>,
+}
+
+impl StringNormalizer {
+  #[must_use]
+  #[inline]
+  pub fn builder() -> StringNormalizerBuilder {
+    StringNormalizerBuilder::default()
+  }
+
+  #[doc(hidden)]
+  #[inline]
+  pub fn from_closure<F>(config_fn: F) -> Self
+  where
+    F: FnOnce(StringNormalizerBuilder) -> StringNormalizerBuilder,
+  {
+    config_fn(Self::builder()).build()
+  }
+
+  /// Applies the steps of this normalizer to a string.
+  #[inline]
+  pub fn normalize(&self, value: &mut String) {
+    for step in &self.steps {
+      step.apply(value);
+    }
+  }
+
+  /// Applies the steps of this normalizer to each string in a collection.
+  #[inline]
+  pub fn normalize_all<'a>(&self, values: impl IntoIterator<Item = &'a mut String>) {
+    for value in values {
+      self.normalize(value);
+    }
+  }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct StringNormalizerBuilder {
+  data: StringNormalizer,
+}
+
+#[allow(clippy::must_use_candidate, clippy::return_self_not_must_use)]
+impl StringNormalizerBuilder {
+  #[inline]
+  fn step(mut self, step: StringTransform) -> Self {
+    self.data.steps.push(step);
+    self
+  }
+
+  /// Removes the leading and trailing whitespace.
+  #[inline]
+  pub fn trim(self) -> Self {
+    self.step(StringTransform::Trim)
+  }
+
+  /// Converts all characters to lowercase.
+  #[inline]
+  pub fn lowercase(self) -> Self {
+    self.step(StringTransform::Lowercase)
+  }
+
+  /// Converts all characters to uppercase.
+  #[inline]
+  pub fn uppercase(self) -> Self {
+    self.step(StringTransform::Uppercase)
+  }
+
+  /// Replaces every sequence of whitespace characters with a single space, and removes the leading and trailing whitespace.
+  #[inline]
+  pub fn collapse_whitespace(self) -> Self {
+    self.step(StringTransform::CollapseWhitespace)
+  }
+
+  /// Applies the Unicode Normalization Form C.
+  #[inline]
+  pub fn nfc(self) -> Self {
+    self.step(StringTransform::Nfc)
+  }
+
+  /// Applies a custom function.
+  #[inline]
+  pub fn custom(self, func: fn(&mut String)) -> Self {
+    self.step(StringTransform::Custom(func))
+  }
+
+  #[must_use]
+  #[inline]
+  pub fn build(self) -> StringNormalizer {
+    self.data
+  }
+}
+
+impl From<StringNormalizerBuilder> for StringNormalizer {
+  #[inline]
+  fn from(value: StringNormalizerBuilder) -> Self {
+    value.build()
+  }
+}
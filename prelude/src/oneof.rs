@@ -66,12 +66,17 @@ pub trait ValidatedOneof: ProtoValidation + Clone {
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Oneof {
   pub name: FixedStr,
+  pub comments: Option<FixedStr>,
   pub fields: Vec<Field>,
   pub options: Vec<ProtoOption>,
   pub validators: Vec<ValidatorSchema>,
 }
 
 impl Oneof {
+  pub(crate) fn render_comments(&self) -> Option<String> {
+    render_comments(self.comments.as_ref())
+  }
+
   pub(crate) fn options_with_validators(&self) -> impl Iterator<Item = &options::ProtoOption> {
     self
       .options
@@ -85,6 +90,12 @@ impl Oneof {
     self
   }
 
+  #[must_use]
+  pub fn with_comments(mut self, comments: Option<FixedStr>) -> Self {
+    self.comments = comments;
+    self
+  }
+
   #[must_use]
   pub fn with_validators<I: IntoIterator<Item = ValidatorSchema>>(mut self, validators: I) -> Self {
     self.validators.extend(validators);
@@ -7,3 +7,21 @@ pub fn proto_deprecated() -> ProtoOption {
     value: OptionValue::Bool(true),
   }
 }
+
+/// Creates the `default` option for a field.
+#[must_use]
+pub fn proto_default(value: impl Into<OptionValue>) -> ProtoOption {
+  ProtoOption {
+    name: "default".into(),
+    value: value.into(),
+  }
+}
+
+/// Creates the `json_name` option for a field.
+#[must_use]
+pub fn proto_json_name(name: impl Into<FixedStr>) -> ProtoOption {
+  ProtoOption {
+    name: "json_name".into(),
+    value: OptionValue::String(name.into()),
+  }
+}
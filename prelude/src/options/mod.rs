@@ -220,11 +220,17 @@ impl OptionMessageBuilder {
   }
 
   pub(crate) fn add_cel_options(&mut self, rules: Vec<CelProgram>) -> &mut Self {
-    if !rules.is_empty() {
-      let rule_options: Vec<OptionValue> = rules
+    self.add_cel_rules(
+      rules
         .into_iter()
-        .map(|program| program.rule().clone().into())
-        .collect();
+        .map(|program| program.rule().clone()),
+    )
+  }
+
+  pub(crate) fn add_cel_rules(&mut self, rules: impl IntoIterator<Item = CelRule>) -> &mut Self {
+    let rule_options: Vec<OptionValue> = rules.into_iter().map(OptionValue::from).collect();
+
+    if !rule_options.is_empty() {
       self.set("cel", OptionValue::List(rule_options.into()));
     }
     self
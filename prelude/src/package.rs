@@ -49,6 +49,127 @@ fn insert_message_extern_path(message: &Message, entries: &mut Vec<(String, Stri
   }
 }
 
+/// The content of a tag lockfile.
+#[derive(Default)]
+struct TagLockfileEntries {
+  // The locked tags, keyed by the proto path of their item followed by the name of the field or variant
+  tags: BTreeMap<String, i32>,
+  // The proto paths of the messages with nested items, keyed by their Rust ident,
+  // which is how nested items refer to them with `parent_message`
+  parents: BTreeMap<String, String>,
+}
+
+impl TagLockfileEntries {
+  fn parse(content: &str) -> Self {
+    let mut entries = Self::default();
+
+    for line in content
+      .lines()
+      .map(str::trim)
+      .filter(|line| !line.is_empty() && !line.starts_with('#'))
+    {
+      let Some((key, value)) = line.split_once('=') else {
+        continue;
+      };
+
+      let (key, value) = (key.trim(), value.trim());
+
+      if let Some(ident) = key.strip_prefix('@') {
+        entries
+          .parents
+          .insert(ident.to_string(), value.to_string());
+      } else if let Ok(tag) = value.parse() {
+        entries.tags.insert(key.to_string(), tag);
+      }
+    }
+
+    entries
+  }
+
+  fn insert_message(&mut self, message: &Message) {
+    for entry in &message.entries {
+      // Oneof fields are not tracked by the lockfile
+      if let MessageEntry::Field(field) = entry {
+        self
+          .tags
+          .insert(format!("{}.{}", message.name, field.name), field.tag);
+      }
+    }
+
+    if !message.messages.is_empty() || !message.enums.is_empty() {
+      let rust_ident = message
+        .rust_path
+        .rsplit("::")
+        .next()
+        .unwrap_or_default();
+
+      self
+        .parents
+        .insert(rust_ident.to_string(), message.name.to_string());
+    }
+
+    for nested_msg in &message.messages {
+      self.insert_message(nested_msg);
+    }
+
+    for nested_enum in &message.enums {
+      self.insert_enum(nested_enum);
+    }
+  }
+
+  fn insert_enum(&mut self, enum_: &Enum) {
+    for variant in &enum_.variants {
+      self
+        .tags
+        .insert(format!("{}.{}", enum_.name, variant.name), variant.tag);
+    }
+  }
+}
+
+/// Checks that the tag lockfile at the given path records the tags assigned to an item.
+///
+/// This is used by the tests generated for the items that use the `tag_lockfile` attribute.
+#[doc(hidden)]
+#[cfg(feature = "std")]
+pub fn check_tag_lockfile(
+  path: &str,
+  item: &str,
+  parent: Option<(&str, &str)>,
+  assigned: &[(&str, i32)],
+) -> Result<(), String> {
+  let content = match std::fs::read_to_string(path) {
+    Ok(content) => content,
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+    Err(e) => return Err(format!("Failed to read the tag lockfile at `{path}`: {e}")),
+  };
+
+  let entries = TagLockfileEntries::parse(&content);
+
+  let mut missing = String::new();
+
+  if let Some((ident, parent_path)) = parent
+    && entries.parents.get(ident).map(String::as_str) != Some(parent_path)
+  {
+    writeln!(missing, "@{ident} = {parent_path}").unwrap();
+  }
+
+  for (name, tag) in assigned {
+    let key = format!("{item}.{name}");
+
+    if entries.tags.get(&key) != Some(tag) {
+      writeln!(missing, "{key} = {tag}").unwrap();
+    }
+  }
+
+  if missing.is_empty() {
+    Ok(())
+  } else {
+    Err(format!(
+      "The tag lockfile at `{path}` is missing these entries, or it records different values for them:\n{missing}Update it with `Package::write_tag_lockfile`"
+    ))
+  }
+}
+
 fn insert_enum_extern_path(enum_: &Enum, entries: &mut Vec<(String, String)>) {
   let Enum {
     name: full_name,
@@ -80,6 +201,66 @@ impl Package {
     entries
   }
 
+  /// Renders the content of a tag lockfile (used with the `tag_lockfile` attribute) with the tags of all the fields
+  /// and enum variants of this package.
+  ///
+  /// The tags are keyed by the proto path of their item (such as `Outer.Inner.field`). The lockfile also maps
+  /// the Rust ident of each message with nested items to its proto path (such as `@Inner = Outer.Inner`),
+  /// so that the macros of the nested items can find their entries.
+  ///
+  /// The entries in `existing` that are not present in this package (such as the ones for removed fields) are kept,
+  /// so that their tags are not assigned to other fields.
+  #[must_use]
+  pub fn render_tag_lockfile(&self, existing: &str) -> String {
+    let mut entries = TagLockfileEntries::parse(existing);
+
+    for file in &self.files {
+      for message in &file.messages {
+        entries.insert_message(message);
+      }
+
+      for enum_ in &file.enums {
+        entries.insert_enum(enum_);
+      }
+    }
+
+    let mut output = String::from(
+      "# This file records the tags assigned to each item, and it is updated with `Package::write_tag_lockfile`.\n# It should be committed to version control.\n",
+    );
+
+    for (ident, path) in entries.parents {
+      writeln!(output, "@{ident} = {path}").unwrap();
+    }
+
+    for (key, tag) in entries.tags {
+      writeln!(output, "{key} = {tag}").unwrap();
+    }
+
+    output
+  }
+
+  /// Writes the tag lockfile at the given path, keeping its existing entries.
+  ///
+  /// The proto macros only read the lockfile, so this should be called explicitly (for example from a test or a small binary)
+  /// whenever new fields or variants are added to the items that use it. Until then, the new fields are allocated
+  /// the next free tags, and the tests generated for the items report the entries that are missing from the lockfile.
+  /// See [`Package::render_tag_lockfile`] for more information.
+  #[cfg(feature = "std")]
+  pub fn write_tag_lockfile<P>(&self, path: P) -> std::io::Result<()>
+  where
+    P: AsRef<std::path::Path>,
+  {
+    let path = path.as_ref();
+
+    let existing = match std::fs::read_to_string(path) {
+      Ok(content) => content,
+      Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+      Err(e) => return Err(e),
+    };
+
+    std::fs::write(path, self.render_tag_lockfile(&existing))
+  }
+
   #[cfg(feature = "std")]
   pub fn render_files<P>(&self, output_root: P) -> std::io::Result<()>
   where
@@ -122,6 +303,21 @@ impl Package {
     self
   }
 
+  /// Checks that the items in each file of this package are valid for the edition of their file.
+  pub fn check_edition_consistency(&self) -> Result<(), String> {
+    let errors: Vec<String> = self
+      .files
+      .iter()
+      .filter_map(|f| f.check_edition_consistency().err())
+      .collect();
+
+    if errors.is_empty() {
+      Ok(())
+    } else {
+      Err(errors.join("\n"))
+    }
+  }
+
   #[cfg(feature = "cel")]
   pub fn check_unique_cel_rules(self) -> Result<(), String> {
     for mut message in self.files.into_iter().flat_map(|f| f.messages) {
@@ -41,12 +41,14 @@ pub trait ProtoEnumSchema: TryFrom<i32> + Default + ProtoEnum {
 pub struct Enum {
   pub short_name: FixedStr,
   pub name: FixedStr,
+  pub comments: Option<FixedStr>,
   pub package: FixedStr,
   pub file: FixedStr,
   pub variants: Vec<EnumVariant>,
   pub reserved_numbers: Vec<Range<i32>>,
   pub reserved_names: Vec<FixedStr>,
   pub options: Vec<ProtoOption>,
+  pub features: FeatureSet,
   pub rust_path: FixedStr,
 }
 
@@ -54,11 +56,57 @@ pub struct Enum {
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EnumVariant {
   pub name: FixedStr,
+  pub comments: Option<FixedStr>,
   pub tag: i32,
   pub options: Vec<ProtoOption>,
 }
 
+impl EnumVariant {
+  pub(crate) fn render_comments(&self) -> Option<String> {
+    render_comments(self.comments.as_ref())
+  }
+}
+
 impl Enum {
+  pub(crate) fn render_comments(&self) -> Option<String> {
+    render_comments(self.comments.as_ref())
+  }
+
+  pub(crate) fn collect_edition_errors(&self, edition: Edition, errors: &mut Vec<String>) {
+    let name = &self.name;
+
+    if !edition.is_edition() && !self.features.is_empty() {
+      errors.push(format!(
+        "[{name} ({edition:?})]: `features` are only supported by editions files"
+      ));
+    }
+
+    let FeatureSet {
+      enum_type,
+      json_format,
+      ..
+    } = self.features;
+
+    let mut enum_features = FeatureSet::new();
+    enum_features.enum_type = enum_type;
+    enum_features.json_format = json_format;
+
+    if enum_features != self.features {
+      errors.push(format!(
+        "[{name} ({edition:?})]: Only `features.enum_type` and `features.json_format` can be set on enums"
+      ));
+    }
+  }
+
+  pub(crate) fn options_with_features(&self) -> Vec<ProtoOption> {
+    self
+      .options
+      .iter()
+      .cloned()
+      .chain(self.features.options())
+      .collect()
+  }
+
   pub(crate) fn render_reserved_names(&self) -> Option<String> {
     render_reserved_names(&self.reserved_names)
   }
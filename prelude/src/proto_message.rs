@@ -49,6 +49,43 @@ pub trait ProtoMessage: Default + MessagePath {
   fn proto_name() -> &'static str;
   fn full_name() -> &'static str;
   fn type_url() -> &'static str;
+
+  /// Returns the [`MessageDescriptor`](prost_reflect::MessageDescriptor) for this message, taken from
+  /// the [`DescriptorPool`](prost_reflect::DescriptorPool) of its package.
+  ///
+  /// Panics if the pool cannot be built. See [`try_message_descriptor`](ProtoMessage::try_message_descriptor)
+  /// for the fallible version.
+  #[cfg(feature = "runtime-descriptors")]
+  #[must_use]
+  fn message_descriptor() -> prost_reflect::MessageDescriptor {
+    message_descriptor(Self::PACKAGE, Self::full_name())
+  }
+
+  /// Returns the [`MessageDescriptor`](prost_reflect::MessageDescriptor) for this message, or an error
+  /// if the [`DescriptorPool`](prost_reflect::DescriptorPool) of its package cannot be built.
+  #[cfg(feature = "runtime-descriptors")]
+  fn try_message_descriptor() -> Result<prost_reflect::MessageDescriptor, MessageDescriptorError> {
+    try_message_descriptor(Self::PACKAGE, Self::full_name())
+  }
+
+  /// Encodes this message in the protobuf [text format](crate::text_format).
+  #[cfg(feature = "text-format")]
+  #[must_use]
+  fn to_text_format(&self) -> String
+  where
+    Self: text_format::ProtoText,
+  {
+    text_format::to_text_format(self)
+  }
+
+  /// Decodes a message from the protobuf [text format](crate::text_format).
+  #[cfg(feature = "text-format")]
+  fn from_text_format(input: &str) -> Result<Self, text_format::TextFormatError>
+  where
+    Self: text_format::ProtoText,
+  {
+    text_format::from_text_format(input)
+  }
 }
 
 #[derive(Debug, Default, Clone, PartialEq)]
@@ -58,12 +95,15 @@ pub trait ProtoMessage: Default + MessagePath {
 pub struct Message {
   pub short_name: FixedStr,
   pub name: FixedStr,
+  pub comments: Option<FixedStr>,
   pub package: FixedStr,
   pub file: FixedStr,
   pub entries: Vec<MessageEntry>,
   pub messages: Vec<Self>,
   pub enums: Vec<Enum>,
   pub options: Vec<ProtoOption>,
+  pub features: FeatureSet,
+  pub edition: Edition,
   pub reserved_names: Vec<FixedStr>,
   pub reserved_numbers: Vec<Range<i32>>,
   pub validators: Vec<ValidatorSchema>,
@@ -71,15 +111,88 @@ pub struct Message {
 }
 
 impl Message {
+  pub(crate) fn render_comments(&self) -> Option<String> {
+    render_comments(self.comments.as_ref())
+  }
+
   pub(crate) fn options_with_validators(&self) -> Vec<ProtoOption> {
     self
       .options
       .clone()
       .into_iter()
+      .chain(self.features.options())
       .chain(self.validators.iter().map(|v| v.schema.clone()))
       .collect()
   }
 
+  /// Checks that the features, fields and presence semantics of this message (and its nested messages)
+  /// are valid for the edition of the file that it belongs to.
+  pub fn check_edition_consistency(&self) -> Result<(), String> {
+    let mut errors = Vec::new();
+
+    self.collect_edition_errors(&mut errors);
+
+    if errors.is_empty() {
+      Ok(())
+    } else {
+      Err(errors.join("\n"))
+    }
+  }
+
+  pub(crate) fn collect_edition_errors(&self, errors: &mut Vec<String>) {
+    let edition = self.edition;
+
+    let mut msg_errors = Vec::new();
+
+    if !edition.is_edition() && !self.features.is_empty() {
+      msg_errors.push(String::from(
+        "`features` are only supported by editions files",
+      ));
+    }
+
+    if self.features.field_presence.is_some() {
+      msg_errors.push(String::from(
+        "`features.field_presence` must be set on individual fields, as it is inferred from their rust types",
+      ));
+    }
+
+    for entry in &self.entries {
+      match entry {
+        MessageEntry::Field(field) => {
+          msg_errors.extend(field.edition_errors(edition, FieldScope::Message));
+        }
+        MessageEntry::Oneof(oneof) => {
+          for field in &oneof.fields {
+            msg_errors.extend(field.edition_errors(edition, FieldScope::Oneof));
+          }
+        }
+      }
+    }
+
+    errors.extend(
+      msg_errors
+        .into_iter()
+        .map(|e| format!("[{} ({edition:?})]: {e}", self.name)),
+    );
+
+    for nested_msg in &self.messages {
+      nested_msg.collect_edition_errors(errors);
+    }
+
+    for nested_enum in &self.enums {
+      nested_enum.collect_edition_errors(edition, errors);
+    }
+  }
+
+  /// Sets the edition for this message and all of its nested messages.
+  pub fn set_edition(&mut self, edition: Edition) {
+    self.edition = edition;
+
+    for nested_msg in &mut self.messages {
+      nested_msg.set_edition(edition);
+    }
+  }
+
   pub fn fields(&self) -> impl Iterator<Item = &Field> {
     self.entries.iter().flat_map(|entry| {
       let (field_opt, oneof_vec) = match entry {
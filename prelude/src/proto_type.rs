@@ -42,6 +42,8 @@ pub enum FieldType {
   },
   Repeated(ProtoType),
   Optional(ProtoType),
+  /// A `required` field (or a field with `LEGACY_REQUIRED` presence in editions files).
+  Required(ProtoType),
 }
 
 pub(crate) struct Sealed;
@@ -232,27 +234,62 @@ impl ProtoType {
 }
 
 impl FieldType {
-  pub(crate) fn render(&self, current_package: &FixedStr) -> Cow<'_, str> {
+  pub(crate) fn render(
+    &self,
+    current_package: &FixedStr,
+    edition: Edition,
+    scope: FieldScope,
+  ) -> Cow<'_, str> {
     let name = self.render_name(current_package);
 
+    // Fields inside oneofs never have a label
+    if scope == FieldScope::Oneof {
+      return name;
+    }
+
     match self {
-      Self::Normal(_) | Self::Map { .. } => name,
+      Self::Map { .. } => name,
       Self::Repeated(_) => format!("repeated {name}").into(),
-      Self::Optional(inner) => {
-        if inner.is_message() {
-          name
-        } else {
-          format!("optional {name}").into()
+      Self::Required(_) => match edition {
+        // Editions files use `features.field_presence = LEGACY_REQUIRED` instead
+        Edition::E2023 => name,
+        Edition::Proto2 | Edition::Proto3 => format!("required {name}").into(),
+      },
+      Self::Normal(inner) | Self::Optional(inner) => match edition {
+        // Every singular field must have a label in proto2
+        Edition::Proto2 => format!("optional {name}").into(),
+        Edition::Proto3 => {
+          if matches!(self, Self::Optional(_)) && !inner.is_message() {
+            format!("optional {name}").into()
+          } else {
+            name
+          }
         }
-      }
+        Edition::E2023 => name,
+      },
+    }
+  }
+
+  /// Returns the value of `features.field_presence` that must be set on the field
+  /// (in an editions file) so that its presence matches the one of the generated rust type.
+  pub(crate) const fn required_field_presence(&self, edition: Edition) -> Option<FieldPresence> {
+    if !edition.is_edition() {
+      return None;
+    }
+
+    match self {
+      Self::Normal(inner) if !inner.is_message() => Some(FieldPresence::Implicit),
+      Self::Required(_) => Some(FieldPresence::LegacyRequired),
+      _ => None,
     }
   }
 
   pub(crate) fn render_name(&self, current_package: &FixedStr) -> Cow<'_, str> {
     match self {
-      Self::Normal(type_info) | Self::Repeated(type_info) | Self::Optional(type_info) => {
-        type_info.render_name(current_package)
-      }
+      Self::Normal(type_info)
+      | Self::Repeated(type_info)
+      | Self::Optional(type_info)
+      | Self::Required(type_info) => type_info.render_name(current_package),
       Self::Map { keys, values } => format!(
         "map<{}, {}>",
         keys.into_type().render_name(current_package),
@@ -261,6 +298,27 @@ impl FieldType {
       .into(),
     }
   }
+
+  /// Turns a singular field into a `required` field. Map and repeated fields are left unchanged.
+  #[must_use]
+  pub fn into_required(self) -> Self {
+    match self {
+      Self::Normal(inner) | Self::Optional(inner) => Self::Required(inner),
+      other => other,
+    }
+  }
+
+  /// Returns the inner [`ProtoType`], unless this is a map field.
+  #[must_use]
+  pub const fn inner_type(&self) -> Option<&ProtoType> {
+    match self {
+      Self::Normal(inner)
+      | Self::Repeated(inner)
+      | Self::Optional(inner)
+      | Self::Required(inner) => Some(inner),
+      Self::Map { .. } => None,
+    }
+  }
 }
 
 impl ProtoType {
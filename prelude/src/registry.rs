@@ -189,6 +189,7 @@ pub struct RegistryFile {
   pub name: &'static str,
   pub package: &'static str,
   pub edition: Edition,
+  pub features: fn() -> FeatureSet,
   pub options: fn() -> Vec<ProtoOption>,
   pub imports: fn() -> Vec<&'static str>,
   pub extensions: fn() -> Vec<Extension>,
@@ -209,6 +210,7 @@ impl Into<ProtoFile> for &RegistryFile {
 
     file.options = (self.options)();
     file.edition = self.edition;
+    file.features = (self.features)();
 
     file
   }
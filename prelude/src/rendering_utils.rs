@@ -2,6 +2,23 @@ use crate::*;
 
 pub(crate) const PROTOBUF_MAX_TAG: i32 = 536_870_911;
 
+/// Renders the leading comments of an item, with a trailing newline.
+pub(crate) fn render_comments(comments: Option<&FixedStr>) -> Option<String> {
+  let comments = comments?;
+
+  let mut output_str = String::new();
+
+  for line in comments.lines() {
+    if line.is_empty() {
+      output_str.push_str("//\n");
+    } else {
+      writeln!(output_str, "// {line}").unwrap();
+    }
+  }
+
+  Some(output_str)
+}
+
 pub(crate) fn render_reserved_numbers(ranges: &[Range<i32>]) -> Option<String> {
   if ranges.is_empty() {
     return None;
@@ -0,0 +1,1605 @@
+//! Generation of sample values from the rules of validators.
+//!
+//! With the `samples` feature, every built-in validator implements [`SampleValidator`], which derives a generator
+//! from the rules that it holds, while the messages and oneofs defined with the macros implement [`ProtoSample`],
+//! which composes the samples of their fields.
+//!
+//! - [`valid_sample`](ProtoSample::valid_sample) produces a value that passes validation.
+//! - [`invalid_samples`](ProtoSample::invalid_samples) produces a minimal value for each rule that can be violated,
+//!   along with the violation that it triggers and the path of the field that holds it.
+//!
+//! Every candidate is checked against the validator before being returned, so the rules that the generator cannot
+//! satisfy (such as regex patterns or CEL expressions) result in missing samples rather than in wrong ones.
+//!
+//! ```rust,ignore
+//! let mut ctx = SampleCtx::new(42);
+//!
+//! let msg = MyMsg::valid_sample(&mut ctx).unwrap();
+//! assert!(msg.validate().is_ok());
+//!
+//! for sample in MyMsg::invalid_samples(&mut ctx) {
+//!   let errors = sample.value.validate().unwrap_err();
+//!   assert!(errors.iter().any(|v| v.meta.kind == sample.violation));
+//! }
+//! ```
+
+use crate::*;
+
+use crate::validators::string::well_known_strings::WellKnownStrings;
+use crate::well_known_types::NoOpValidator;
+use proto_types::{Any, Duration, FieldMask, Timestamp, protovalidate::*};
+
+/// The maximum nesting level of the generated messages, which prevents recursive messages from being generated forever.
+pub const MAX_SAMPLE_DEPTH: usize = 4;
+
+/// How many times the generation of a valid message or oneof is attempted before giving up.
+const SAMPLE_ATTEMPTS: usize = 4;
+
+/// The state of the generation of samples.
+///
+/// It uses a seeded pseudo-random generator, so the same seed always produces the same samples.
+#[derive(Debug, Clone)]
+pub struct SampleCtx {
+  state: u64,
+  depth: usize,
+}
+
+impl Default for SampleCtx {
+  #[inline]
+  fn default() -> Self {
+    Self::new(0)
+  }
+}
+
+impl SampleCtx {
+  #[must_use]
+  #[inline]
+  pub const fn new(seed: u64) -> Self {
+    Self {
+      state: seed,
+      depth: 0,
+    }
+  }
+
+  /// Returns the next pseudo-random number (using SplitMix64).
+  #[inline]
+  pub const fn next_u64(&mut self) -> u64 {
+    self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+
+    let mut z = self.state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+
+    z ^ (z >> 31)
+  }
+
+  /// Returns a number in the `0..bound` range, or 0 if `bound` is 0.
+  #[inline]
+  pub fn below(&mut self, bound: usize) -> usize {
+    let Ok(bound_u64) = u64::try_from(bound) else {
+      return 0;
+    };
+
+    if bound_u64 == 0 {
+      return 0;
+    }
+
+    usize::try_from(self.next_u64() % bound_u64).unwrap_or_default()
+  }
+
+  /// Picks a random element of a slice.
+  #[inline]
+  pub fn choose<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+    items.get(self.below(items.len()))
+  }
+
+  fn small_number(&mut self) -> u8 {
+    u8::try_from(self.below(100)).unwrap_or_default()
+  }
+
+  fn letters(&mut self, len: usize) -> String {
+    (0..len)
+      .map(|_| char::from(b'a' + u8::try_from(self.below(26)).unwrap_or_default()))
+      .collect()
+  }
+
+  /// Runs a generator for a nested message, or returns `None` if the maximum depth has been reached.
+  #[doc(hidden)]
+  pub fn nested<T>(&mut self, generate: impl FnOnce(&mut Self) -> Option<T>) -> Option<T> {
+    if self.depth >= MAX_SAMPLE_DEPTH {
+      return None;
+    }
+
+    self.depth += 1;
+    let output = generate(self);
+    self.depth -= 1;
+
+    output
+  }
+}
+
+/// A value that violates a specific rule.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvalidSample<T> {
+  /// The violation that the value triggers.
+  pub violation: ViolationKind,
+  /// The path of the field that holds the invalid value, such as `nested.name`. It is empty if the value
+  /// is invalid on its own.
+  pub path: String,
+  /// The invalid value. For validators, `None` stands for a missing value.
+  pub value: T,
+}
+
+impl<T> InvalidSample<T> {
+  #[must_use]
+  #[inline]
+  pub const fn new(violation: ViolationKind, value: T) -> Self {
+    Self {
+      violation,
+      path: String::new(),
+      value,
+    }
+  }
+
+  /// Returns the id of the violated rule, which matches the `rule_id` of the resulting violation.
+  #[must_use]
+  #[inline]
+  pub fn rule_id(&self) -> &'static str {
+    self.violation.data().name
+  }
+
+  #[doc(hidden)]
+  #[inline]
+  pub fn map<U>(self, f: impl FnOnce(T) -> U) -> InvalidSample<U> {
+    InvalidSample {
+      violation: self.violation,
+      path: self.path,
+      value: f(self.value),
+    }
+  }
+
+  /// Adds the name of a field at the start of the path.
+  #[doc(hidden)]
+  #[must_use]
+  pub fn in_field(mut self, name: &str) -> Self {
+    if self.path.is_empty() {
+      self.path = name.to_string();
+    } else {
+      self.path = format!("{name}.{}", self.path);
+    }
+
+    self
+  }
+}
+
+fn contains_violation(errors: &ValidationErrors, violation: ViolationKind) -> bool {
+  errors.iter().any(|v| v.meta.kind == violation)
+}
+
+fn validator_violations<T, V>(
+  validator: &V,
+  value: Option<&<V::Target as ToOwned>::Owned>,
+) -> ValidationErrors
+where
+  T: ?Sized,
+  V: Validator<T> + ?Sized,
+{
+  let mut ctx = ValidationCtx {
+    fail_fast: false,
+    ..Default::default()
+  };
+
+  let _ = validator.validate_core(&mut ctx, value);
+
+  ctx.violations
+}
+
+/// Returns all of the violations of a message.
+#[doc(hidden)]
+#[inline]
+pub fn message_violations<T: ValidatedMessage>(message: &T) -> ValidationErrors {
+  message.validate_all().err().unwrap_or_default()
+}
+
+/// Returns all of the violations of a oneof.
+#[doc(hidden)]
+pub fn oneof_violations<T: ValidatedOneof>(oneof: &T) -> ValidationErrors {
+  let mut ctx = ValidationCtx {
+    fail_fast: false,
+    ..Default::default()
+  };
+
+  let _ = oneof.validate(&mut ctx);
+
+  ctx.violations
+}
+
+/// Keeps the first sample for each rule and path, if it actually triggers the expected violation.
+fn retain_invalid<T>(
+  candidates: Vec<InvalidSample<T>>,
+  violations: impl Fn(&T) -> ValidationErrors,
+) -> Vec<InvalidSample<T>> {
+  let mut samples: Vec<InvalidSample<T>> = Vec::new();
+
+  for candidate in candidates {
+    let is_duplicate = samples
+      .iter()
+      .any(|s| s.violation == candidate.violation && s.path == candidate.path);
+
+    if !is_duplicate && contains_violation(&violations(&candidate.value), candidate.violation) {
+      samples.push(candidate);
+    }
+  }
+
+  samples
+}
+
+/// Derives sample values from the rules of a validator.
+///
+/// It is implemented by all of the built-in validators.
+pub trait SampleValidator<T: ?Sized>: Validator<T> {
+  /// Returns the values that are likely to satisfy the rules of this validator.
+  #[doc(hidden)]
+  fn valid_candidates(&self, ctx: &mut SampleCtx) -> Vec<<Self::Target as ToOwned>::Owned>;
+
+  /// Returns the values that are likely to violate each rule of this validator.
+  #[doc(hidden)]
+  fn invalid_candidates(
+    &self,
+    ctx: &mut SampleCtx,
+  ) -> Vec<InvalidSample<Option<<Self::Target as ToOwned>::Owned>>>;
+
+  /// Generates a value that satisfies the rules of this validator, or `None` if no valid value could be found.
+  fn valid_sample(&self, ctx: &mut SampleCtx) -> Option<<Self::Target as ToOwned>::Owned> {
+    let mut candidates = self.valid_candidates(ctx);
+
+    // Starting from a random candidate gives some variety to the samples
+    let offset = ctx.below(candidates.len());
+    candidates.rotate_left(offset);
+
+    candidates
+      .into_iter()
+      .find(|c| validator_violations::<T, Self>(self, Some(c)).is_empty())
+  }
+
+  /// Generates a minimal value for each rule of this validator that can be violated.
+  ///
+  /// Each sample is guaranteed to trigger the violation that it is associated with,
+  /// so the rules that the generator cannot violate are skipped.
+  fn invalid_samples(
+    &self,
+    ctx: &mut SampleCtx,
+  ) -> Vec<InvalidSample<Option<<Self::Target as ToOwned>::Owned>>> {
+    retain_invalid(self.invalid_candidates(ctx), |value| {
+      validator_violations::<T, Self>(self, value.as_ref())
+    })
+  }
+
+  /// Generates a minimal value that triggers a specific violation, if possible.
+  fn invalid_sample_for(
+    &self,
+    ctx: &mut SampleCtx,
+    violation: ViolationKind,
+  ) -> Option<InvalidSample<Option<<Self::Target as ToOwned>::Owned>>> {
+    self
+      .invalid_samples(ctx)
+      .into_iter()
+      .find(|s| s.violation == violation)
+  }
+}
+
+/// Generates samples for messages and oneofs, by composing the samples of their fields.
+///
+/// It is implemented automatically by the messages and oneofs defined with the macros.
+/// Fields with custom validators are left with their default value.
+pub trait ProtoSample: Sized {
+  #[doc(hidden)]
+  fn sample_candidate(ctx: &mut SampleCtx) -> Option<Self>;
+
+  #[doc(hidden)]
+  fn invalid_candidates(base: &Self, ctx: &mut SampleCtx) -> Vec<InvalidSample<Self>>;
+
+  #[doc(hidden)]
+  fn sample_violations(&self) -> ValidationErrors;
+
+  /// Generates an instance where every field satisfies its rules, or `None` if some of them could not be satisfied.
+  fn valid_sample(ctx: &mut SampleCtx) -> Option<Self> {
+    (0..SAMPLE_ATTEMPTS).find_map(|_| {
+      Self::sample_candidate(ctx).filter(|sample| sample.sample_violations().is_empty())
+    })
+  }
+
+  /// Generates instances that are valid, except for a single rule in one of their fields.
+  ///
+  /// Each sample is guaranteed to trigger the violation that it is associated with.
+  fn invalid_samples(ctx: &mut SampleCtx) -> Vec<InvalidSample<Self>> {
+    let Some(base) = Self::valid_sample(ctx) else {
+      return Vec::new();
+    };
+
+    retain_invalid(
+      Self::invalid_candidates(&base, ctx),
+      Self::sample_violations,
+    )
+  }
+
+  /// Generates an instance that triggers a specific violation, if possible.
+  fn invalid_sample_for(
+    ctx: &mut SampleCtx,
+    violation: ViolationKind,
+  ) -> Option<InvalidSample<Self>> {
+    Self::invalid_samples(ctx)
+      .into_iter()
+      .find(|s| s.violation == violation)
+  }
+}
+
+/// Collects the invalid candidates of a validator, skipping the rules that are not set.
+struct InvalidCandidates<T>(Vec<InvalidSample<Option<T>>>);
+
+impl<T> InvalidCandidates<T> {
+  const fn new() -> Self {
+    Self(Vec::new())
+  }
+
+  fn push(&mut self, violation: impl Into<ViolationKind>, value: Option<T>) {
+    if let Some(value) = value {
+      self
+        .0
+        .push(InvalidSample::new(violation.into(), Some(value)));
+    }
+  }
+
+  fn push_missing(&mut self, violation: impl Into<ViolationKind>) {
+    self
+      .0
+      .push(InvalidSample::new(violation.into(), None));
+  }
+}
+
+impl<T> SampleValidator<T> for NoOpValidator<T>
+where
+  T: ?Sized + Send + Sync + ToOwned,
+  T::Owned: Default,
+{
+  fn valid_candidates(&self, _: &mut SampleCtx) -> Vec<T::Owned> {
+    vec![T::Owned::default()]
+  }
+
+  fn invalid_candidates(&self, _: &mut SampleCtx) -> Vec<InvalidSample<Option<T::Owned>>> {
+    Vec::new()
+  }
+}
+
+impl<T> SampleValidator<T> for MessageValidator
+where
+  T: ValidatedMessage + PartialEq + CelFields + ProtoSample,
+{
+  fn valid_candidates(&self, ctx: &mut SampleCtx) -> Vec<T> {
+    ctx.nested(T::valid_sample).into_iter().collect()
+  }
+
+  fn invalid_candidates(&self, ctx: &mut SampleCtx) -> Vec<InvalidSample<Option<T>>> {
+    let mut candidates = InvalidCandidates::new();
+
+    if self.required {
+      candidates.push_missing(ViolationKind::Required);
+    }
+
+    let mut candidates = candidates.0;
+
+    if let Some(nested) = ctx.nested(|ctx| Some(T::invalid_samples(ctx))) {
+      candidates.extend(nested.into_iter().map(|s| s.map(Some)));
+    }
+
+    candidates
+  }
+}
+
+impl SampleValidator<bool> for BoolValidator {
+  fn valid_candidates(&self, _: &mut SampleCtx) -> Vec<bool> {
+    self
+      .const_
+      .map_or_else(|| vec![true, false], |c| vec![c])
+  }
+
+  fn invalid_candidates(&self, _: &mut SampleCtx) -> Vec<InvalidSample<Option<bool>>> {
+    let mut candidates = InvalidCandidates::new();
+
+    if self.required {
+      candidates.push(ViolationKind::Bool(BoolViolation::Required), Some(false));
+    }
+
+    candidates.push(
+      ViolationKind::Bool(BoolViolation::Const),
+      self.const_.map(|c| !c),
+    );
+
+    candidates.0
+  }
+}
+
+/// The integer types used by [`IntValidator`].
+#[doc(hidden)]
+pub trait SampleInt: Copy + Ord + Default {
+  fn step_up(self) -> Option<Self>;
+
+  fn step_down(self) -> Option<Self>;
+
+  fn from_small(value: u8) -> Self;
+}
+
+macro_rules! impl_sample_int {
+  ($($typ:ty),*) => {
+    $(
+      impl SampleInt for $typ {
+        #[inline]
+        fn step_up(self) -> Option<Self> {
+          self.checked_add(1)
+        }
+
+        #[inline]
+        fn step_down(self) -> Option<Self> {
+          self.checked_sub(1)
+        }
+
+        #[inline]
+        fn from_small(value: u8) -> Self {
+          value.into()
+        }
+      }
+    )*
+  };
+}
+
+impl_sample_int!(i32, i64, u32, u64);
+
+/// Returns the highest of two optional bounds.
+fn max_bound<T: PartialOrd>(a: Option<T>, b: Option<T>) -> Option<T> {
+  match (a, b) {
+    (Some(a), Some(b)) => Some(if b > a { b } else { a }),
+    (a, b) => a.or(b),
+  }
+}
+
+/// Returns the lowest of two optional bounds.
+fn min_bound<T: PartialOrd>(a: Option<T>, b: Option<T>) -> Option<T> {
+  match (a, b) {
+    (Some(a), Some(b)) => Some(if b < a { b } else { a }),
+    (a, b) => a.or(b),
+  }
+}
+
+impl<Num> SampleValidator<Num> for IntValidator<Num>
+where
+  Num: IntWrapper,
+  Num::RustType: SampleInt,
+{
+  fn valid_candidates(&self, ctx: &mut SampleCtx) -> Vec<Num::RustType> {
+    if let Some(const_val) = self.const_ {
+      return vec![const_val];
+    }
+
+    if let Some(allowed_list) = &self.in_ {
+      return allowed_list.iter().copied().collect();
+    }
+
+    let mut candidates = Vec::new();
+
+    // Walking away from the bounds for a few steps skips the values in the `not_in` list
+    let steps = self.not_in.as_ref().map_or(0, |list| list.len()) + 2;
+
+    let lower = max_bound(self.gt.and_then(SampleInt::step_up), self.gte);
+    let upper = min_bound(self.lt.and_then(SampleInt::step_down), self.lte);
+
+    let mut current = lower;
+    for _ in 0..steps {
+      let Some(value) = current else { break };
+      candidates.push(value);
+      current = value.step_up();
+    }
+
+    let mut current = upper;
+    for _ in 0..steps {
+      let Some(value) = current else { break };
+      candidates.push(value);
+      current = value.step_down();
+    }
+
+    candidates.extend([
+      Num::RustType::from_small(ctx.small_number()),
+      Num::RustType::from_small(1),
+      Num::RustType::default(),
+    ]);
+
+    candidates
+  }
+
+  fn invalid_candidates(&self, _: &mut SampleCtx) -> Vec<InvalidSample<Option<Num::RustType>>> {
+    let mut candidates = InvalidCandidates::new();
+
+    if self.required {
+      candidates.push(Num::REQUIRED_VIOLATION, Some(Num::RustType::default()));
+    }
+
+    candidates.push(
+      Num::CONST_VIOLATION,
+      self
+        .const_
+        .and_then(|c| c.step_up().or_else(|| c.step_down())),
+    );
+    candidates.push(Num::GT_VIOLATION, self.gt);
+    candidates.push(Num::GTE_VIOLATION, self.gte.and_then(SampleInt::step_down));
+    candidates.push(Num::LT_VIOLATION, self.lt);
+    candidates.push(Num::LTE_VIOLATION, self.lte.and_then(SampleInt::step_up));
+    candidates.push(
+      Num::IN_VIOLATION,
+      self.in_.as_ref().and_then(|list| {
+        list
+          .last()
+          .and_then(|v| v.step_up())
+          .or_else(|| list.first().and_then(|v| v.step_down()))
+      }),
+    );
+    candidates.push(
+      Num::NOT_IN_VIOLATION,
+      self
+        .not_in
+        .as_ref()
+        .and_then(|list| list.first().copied()),
+    );
+
+    candidates.0
+  }
+}
+
+/// The float types used by [`FloatValidator`].
+#[doc(hidden)]
+pub trait SampleFloat: Copy {
+  fn from_f64(value: f64) -> Self;
+
+  fn to_f64(self) -> f64;
+}
+
+impl SampleFloat for f32 {
+  #[allow(clippy::cast_possible_truncation)]
+  #[inline]
+  fn from_f64(value: f64) -> Self {
+    value as Self
+  }
+
+  #[inline]
+  fn to_f64(self) -> f64 {
+    self.into()
+  }
+}
+
+impl SampleFloat for f64 {
+  #[inline]
+  fn from_f64(value: f64) -> Self {
+    value
+  }
+
+  #[inline]
+  fn to_f64(self) -> f64 {
+    self
+  }
+}
+
+impl<Num> FloatValidator<Num>
+where
+  Num: FloatWrapper + SampleFloat,
+{
+  /// A distance from `value` that is large enough to be outside of the tolerances.
+  fn sample_margin(&self, value: f64) -> f64 {
+    let tolerance = self
+      .abs_tolerance
+      .to_f64()
+      .mul_add(2.0, value.abs() * self.rel_tolerance.to_f64() * 2.0);
+
+    1.0 + value.abs() * 0.01 + tolerance
+  }
+}
+
+impl<Num> SampleValidator<Num> for FloatValidator<Num>
+where
+  Num: FloatWrapper + SampleFloat,
+{
+  fn valid_candidates(&self, ctx: &mut SampleCtx) -> Vec<Num> {
+    if let Some(const_val) = self.const_ {
+      return vec![const_val];
+    }
+
+    if let Some(allowed_list) = &self.in_ {
+      return allowed_list.iter().map(|v| v.0).collect();
+    }
+
+    let lower = max_bound(
+      self
+        .gt
+        .map(|v| v.to_f64() + self.sample_margin(v.to_f64())),
+      self.gte.map(SampleFloat::to_f64),
+    );
+    let upper = min_bound(
+      self
+        .lt
+        .map(|v| v.to_f64() - self.sample_margin(v.to_f64())),
+      self.lte.map(SampleFloat::to_f64),
+    );
+
+    let mut candidates: Vec<f64> = Vec::new();
+
+    if let (Some(lower), Some(upper)) = (lower, upper) {
+      candidates.push(lower.midpoint(upper));
+    }
+
+    candidates.extend(lower);
+    candidates.extend(upper);
+    candidates.extend([f64::from(ctx.small_number()), 1.0, 0.0]);
+
+    candidates
+      .into_iter()
+      .map(Num::from_f64)
+      .collect()
+  }
+
+  fn invalid_candidates(&self, _: &mut SampleCtx) -> Vec<InvalidSample<Option<Num>>> {
+    let mut candidates = InvalidCandidates::new();
+
+    let shift = |value: Num, up: bool| {
+      let value = value.to_f64();
+      let margin = self.sample_margin(value);
+
+      Num::from_f64(if up { value + margin } else { value - margin })
+    };
+
+    if self.required {
+      candidates.push(Num::REQUIRED_VIOLATION, Some(Num::from_f64(0.0)));
+    }
+
+    candidates.push(Num::CONST_VIOLATION, self.const_.map(|c| shift(c, true)));
+
+    if self.finite {
+      candidates.push(Num::FINITE_VIOLATION, Some(Num::from_f64(f64::INFINITY)));
+    }
+
+    candidates.push(Num::GT_VIOLATION, self.gt);
+    candidates.push(Num::GTE_VIOLATION, self.gte.map(|v| shift(v, false)));
+    candidates.push(Num::LT_VIOLATION, self.lt);
+    candidates.push(Num::LTE_VIOLATION, self.lte.map(|v| shift(v, true)));
+    candidates.push(
+      Num::IN_VIOLATION,
+      self
+        .in_
+        .as_ref()
+        .and_then(|list| list.last().map(|v| shift(v.0, true))),
+    );
+    candidates.push(
+      Num::NOT_IN_VIOLATION,
+      self
+        .not_in
+        .as_ref()
+        .and_then(|list| list.first().map(|v| v.0)),
+    );
+
+    candidates.0
+  }
+}
+
+impl<T: ProtoEnum> SampleValidator<T> for EnumValidator<T> {
+  fn valid_candidates(&self, ctx: &mut SampleCtx) -> Vec<i32> {
+    if let Some(const_val) = self.const_ {
+      return vec![const_val];
+    }
+
+    if let Some(allowed_list) = &self.in_ {
+      return allowed_list.iter().copied().collect();
+    }
+
+    let mut candidates: Vec<i32> = (0..16).collect();
+    candidates.push(i32::from(ctx.small_number()));
+
+    candidates
+  }
+
+  fn invalid_candidates(&self, _: &mut SampleCtx) -> Vec<InvalidSample<Option<i32>>> {
+    let mut candidates = InvalidCandidates::new();
+
+    if self.required {
+      candidates.push(ViolationKind::Enum(EnumViolation::Required), Some(0));
+    }
+
+    candidates.push(
+      ViolationKind::Enum(EnumViolation::Const),
+      self.const_.map(|c| c.wrapping_add(1)),
+    );
+
+    if self.defined_only {
+      candidates.push(
+        ViolationKind::Enum(EnumViolation::DefinedOnly),
+        Some(i32::MAX),
+      );
+    }
+
+    candidates.push(
+      ViolationKind::Enum(EnumViolation::In),
+      self
+        .in_
+        .as_ref()
+        .and_then(|list| list.last().map(|v| v.wrapping_add(1))),
+    );
+    candidates.push(
+      ViolationKind::Enum(EnumViolation::NotIn),
+      self
+        .not_in
+        .as_ref()
+        .and_then(|list| list.first().copied()),
+    );
+
+    candidates.0
+  }
+}
+
+/// Examples of values for the well known string formats.
+const WELL_KNOWN_STRINGS: &[&str] = &[
+  "user@example.com",
+  "example.com",
+  "127.0.0.1",
+  "::1",
+  "https://example.com/path",
+  "./path",
+  "01ARZ3NDEKTSV4RRFFQ69G5FAV",
+  "d9428888-122b-11e1-b85c-61cd3cbb3210",
+  "d9428888122b11e1b85c61cd3cbb3210",
+  "127.0.0.1/8",
+  "127.0.0.0/8",
+  "2001:db8::1/32",
+  "2001:db8::/32",
+  "example.com:8080",
+  "content-type",
+  "value",
+];
+
+const fn well_known_string_violation(well_known: WellKnownStrings) -> StringViolation {
+  match well_known {
+    #[cfg(feature = "regex")]
+    WellKnownStrings::Email => StringViolation::Email,
+    WellKnownStrings::Hostname => StringViolation::Hostname,
+    WellKnownStrings::Ip => StringViolation::Ip,
+    WellKnownStrings::Ipv4 => StringViolation::Ipv4,
+    WellKnownStrings::Ipv6 => StringViolation::Ipv6,
+    WellKnownStrings::Uri => StringViolation::Uri,
+    WellKnownStrings::UriRef => StringViolation::UriRef,
+    WellKnownStrings::Address => StringViolation::Address,
+    #[cfg(feature = "regex")]
+    WellKnownStrings::Ulid => StringViolation::Ulid,
+    #[cfg(feature = "regex")]
+    WellKnownStrings::Uuid => StringViolation::Uuid,
+    #[cfg(feature = "regex")]
+    WellKnownStrings::Tuuid => StringViolation::Tuuid,
+    WellKnownStrings::IpWithPrefixlen => StringViolation::IpWithPrefixlen,
+    WellKnownStrings::Ipv4WithPrefixlen => StringViolation::Ipv4WithPrefixlen,
+    WellKnownStrings::Ipv6WithPrefixlen => StringViolation::Ipv6WithPrefixlen,
+    WellKnownStrings::IpPrefix => StringViolation::IpPrefix,
+    WellKnownStrings::Ipv4Prefix => StringViolation::Ipv4Prefix,
+    WellKnownStrings::Ipv6Prefix => StringViolation::Ipv6Prefix,
+    WellKnownStrings::HostAndPort => StringViolation::HostAndPort,
+    #[cfg(feature = "regex")]
+    WellKnownStrings::HeaderNameLoose
+    | WellKnownStrings::HeaderNameStrict
+    | WellKnownStrings::HeaderValueLoose
+    | WellKnownStrings::HeaderValueStrict => StringViolation::WellKnownRegex,
+  }
+}
+
+/// The length limits of a string or bytes value, combining the rules of its validator.
+struct LengthLimits {
+  min: usize,
+  max: usize,
+}
+
+impl LengthLimits {
+  fn new(
+    required: bool,
+    exact: &[Option<usize>],
+    min: &[Option<usize>],
+    max: &[Option<usize>],
+  ) -> Self {
+    let min = exact
+      .iter()
+      .chain(min)
+      .flatten()
+      .copied()
+      .fold(usize::from(required), usize::max);
+    let max = exact
+      .iter()
+      .chain(max)
+      .flatten()
+      .copied()
+      .fold(usize::MAX, usize::min);
+
+    Self { min, max }
+  }
+
+  /// Picks the length for a value, given the length of the parts that must be included in it.
+  fn pick(&self, ctx: &mut SampleCtx, required_len: usize) -> usize {
+    let extra = ctx.below(4);
+
+    self
+      .min
+      .saturating_add(extra)
+      .min(self.max)
+      .max(required_len)
+  }
+}
+
+impl StringValidator {
+  /// Builds a string out of the required parts, filled with random letters up to the target length.
+  fn sample_text(&self, ctx: &mut SampleCtx, limits: &LengthLimits) -> String {
+    let prefix = self.prefix.as_deref().unwrap_or_default();
+    let contains = self.contains.as_deref().unwrap_or_default();
+    let suffix = self.suffix.as_deref().unwrap_or_default();
+
+    let required_len = prefix.chars().count() + contains.chars().count() + suffix.chars().count();
+    let len = limits.pick(ctx, required_len);
+
+    let filler = ctx.letters(len - required_len);
+
+    format!("{prefix}{contains}{filler}{suffix}")
+  }
+}
+
+impl SampleValidator<String> for StringValidator {
+  fn valid_candidates(&self, ctx: &mut SampleCtx) -> Vec<String> {
+    if let Some(const_val) = &self.const_ {
+      return vec![const_val.to_string()];
+    }
+
+    if let Some(allowed_list) = &self.in_ {
+      return allowed_list
+        .iter()
+        .map(|v| v.to_string())
+        .collect();
+    }
+
+    if self.well_known.is_some() {
+      return WELL_KNOWN_STRINGS
+        .iter()
+        .map(|v| (*v).to_string())
+        .collect();
+    }
+
+    let limits = LengthLimits::new(
+      self.required,
+      &[self.len, self.len_bytes],
+      &[self.min_len, self.min_bytes],
+      &[self.max_len, self.max_bytes],
+    );
+
+    (0..4)
+      .map(|_| self.sample_text(ctx, &limits))
+      .collect()
+  }
+
+  fn invalid_candidates(&self, ctx: &mut SampleCtx) -> Vec<InvalidSample<Option<String>>> {
+    let mut candidates = InvalidCandidates::new();
+
+    macro_rules! push {
+      ($id:ident, $value:expr) => {
+        candidates.push(ViolationKind::String(StringViolation::$id), $value)
+      };
+    }
+
+    let base = self.valid_sample(ctx).unwrap_or_default();
+    let repeat = |len: usize| "a".repeat(len);
+
+    if self.required {
+      push!(Required, Some(String::new()));
+    }
+
+    push!(Const, self.const_.as_ref().map(|c| format!("{c}a")));
+    push!(Len, self.len.map(|len| repeat(len + 1)));
+    push!(
+      MinLen,
+      self
+        .min_len
+        .and_then(|min| min.checked_sub(1))
+        .map(repeat)
+    );
+    push!(MaxLen, self.max_len.map(|max| repeat(max + 1)));
+    push!(LenBytes, self.len_bytes.map(|len| repeat(len + 1)));
+    push!(
+      MinBytes,
+      self
+        .min_bytes
+        .and_then(|min| min.checked_sub(1))
+        .map(repeat)
+    );
+    push!(MaxBytes, self.max_bytes.map(|max| repeat(max + 1)));
+
+    if self.prefix.is_some() {
+      push!(Prefix, Some(format!("_{base}")));
+      push!(Prefix, Some(format!("a{base}")));
+    }
+
+    if self.suffix.is_some() {
+      push!(Suffix, Some(format!("{base}_")));
+      push!(Suffix, Some(format!("{base}a")));
+    }
+
+    push!(
+      Contains,
+      self
+        .contains
+        .as_ref()
+        .map(|c| base.replace(c.as_str(), ""))
+    );
+    push!(
+      NotContains,
+      self
+        .not_contains
+        .as_ref()
+        .map(|c| format!("{base}{c}"))
+    );
+
+    #[cfg(feature = "regex")]
+    if self.pattern.is_some() {
+      push!(Pattern, Some("!".to_string()));
+      push!(Pattern, Some(String::new()));
+    }
+
+    push!(
+      In,
+      self
+        .in_
+        .as_ref()
+        .and_then(|list| list.last().map(|v| format!("{v}a")))
+    );
+    push!(
+      NotIn,
+      self
+        .not_in
+        .as_ref()
+        .and_then(|list| list.first().map(|v| v.to_string()))
+    );
+
+    if let Some(well_known) = self.well_known {
+      candidates.push(
+        ViolationKind::String(well_known_string_violation(well_known)),
+        Some("!".to_string()),
+      );
+    }
+
+    candidates.0
+  }
+}
+
+impl BytesValidator {
+  fn sample_bytes(&self, ctx: &mut SampleCtx, limits: &LengthLimits) -> Bytes {
+    let prefix = self.prefix.as_deref().unwrap_or_default();
+    let contains = self.contains.as_deref().unwrap_or_default();
+    let suffix = self.suffix.as_deref().unwrap_or_default();
+
+    let required_len = prefix.len() + contains.len() + suffix.len();
+    let len = limits.pick(ctx, required_len);
+
+    let mut output = Vec::with_capacity(len);
+
+    output.extend_from_slice(prefix);
+    output.extend_from_slice(contains);
+    output.extend_from_slice(ctx.letters(len - required_len).as_bytes());
+    output.extend_from_slice(suffix);
+
+    output.into()
+  }
+}
+
+impl SampleValidator<Bytes> for BytesValidator {
+  fn valid_candidates(&self, ctx: &mut SampleCtx) -> Vec<Bytes> {
+    if let Some(const_val) = &self.const_ {
+      return vec![const_val.clone()];
+    }
+
+    if let Some(allowed_list) = &self.in_ {
+      return allowed_list.iter().cloned().collect();
+    }
+
+    if self.well_known.is_some() {
+      return vec![
+        Bytes::from_static(&[127, 0, 0, 1]),
+        Bytes::from_static(&[
+          0xd9, 0x42, 0x88, 0x88, 0x12, 0x2b, 0x11, 0xe1, 0xb8, 0x5c, 0x61, 0xcd, 0x3c, 0xbb, 0x32,
+          0x10,
+        ]),
+      ];
+    }
+
+    let limits = LengthLimits::new(self.required, &[self.len], &[self.min_len], &[self.max_len]);
+
+    (0..4)
+      .map(|_| self.sample_bytes(ctx, &limits))
+      .collect()
+  }
+
+  fn invalid_candidates(&self, ctx: &mut SampleCtx) -> Vec<InvalidSample<Option<Bytes>>> {
+    let mut candidates = InvalidCandidates::new();
+
+    macro_rules! push {
+      ($id:ident, $value:expr) => {
+        candidates.push(ViolationKind::Bytes(BytesViolation::$id), $value)
+      };
+    }
+
+    let base = self.valid_sample(ctx).unwrap_or_default();
+    let repeat = |len: usize| Bytes::from(vec![b'a'; len]);
+    let concat = |parts: &[&[u8]]| Bytes::from(parts.concat());
+
+    if self.required {
+      push!(Required, Some(Bytes::new()));
+    }
+
+    push!(
+      Const,
+      self
+        .const_
+        .as_ref()
+        .map(|c| concat(&[&c[..], b"a"]))
+    );
+    push!(Len, self.len.map(|len| repeat(len + 1)));
+    push!(
+      MinLen,
+      self
+        .min_len
+        .and_then(|min| min.checked_sub(1))
+        .map(repeat)
+    );
+    push!(MaxLen, self.max_len.map(|max| repeat(max + 1)));
+
+    if self.prefix.is_some() {
+      push!(Prefix, Some(concat(&[b"_", &base[..]])));
+      push!(Prefix, Some(concat(&[b"a", &base[..]])));
+    }
+
+    if self.suffix.is_some() {
+      push!(Suffix, Some(concat(&[&base[..], b"_"])));
+      push!(Suffix, Some(concat(&[&base[..], b"a"])));
+    }
+
+    push!(
+      Contains,
+      self.contains.as_ref().map(|c| {
+        // Dropping the first byte of every occurrence is enough to remove it
+        let mut output = base.to_vec();
+
+        while let Some(idx) = output
+          .windows(c.len().max(1))
+          .position(|w| w == &c[..])
+        {
+          output.remove(idx);
+        }
+
+        Bytes::from(output)
+      })
+    );
+
+    #[cfg(feature = "regex")]
+    if self.pattern.is_some() {
+      push!(Pattern, Some(Bytes::from_static(&[0xff])));
+      push!(Pattern, Some(Bytes::new()));
+    }
+
+    push!(
+      In,
+      self
+        .in_
+        .as_ref()
+        .and_then(|list| list.last().map(|v| concat(&[&v[..], b"a"])))
+    );
+    push!(
+      NotIn,
+      self
+        .not_in
+        .as_ref()
+        .and_then(|list| list.first().cloned())
+    );
+
+    if let Some(well_known) = self.well_known {
+      let violation = match well_known {
+        #[cfg(feature = "regex")]
+        WellKnownBytes::Uuid => BytesViolation::Uuid,
+        WellKnownBytes::Ip => BytesViolation::Ip,
+        WellKnownBytes::Ipv4 => BytesViolation::Ipv4,
+        WellKnownBytes::Ipv6 => BytesViolation::Ipv6,
+      };
+
+      candidates.push(
+        ViolationKind::Bytes(violation),
+        Some(Bytes::from_static(&[1, 2, 3])),
+      );
+    }
+
+    candidates.0
+  }
+}
+
+/// Moves a duration by a number of seconds.
+const fn shift_duration(duration: Duration, seconds: i64) -> Duration {
+  Duration {
+    seconds: duration.seconds.saturating_add(seconds),
+    nanos: duration.nanos,
+  }
+}
+
+/// Moves a timestamp by a number of seconds.
+const fn shift_timestamp(timestamp: Timestamp, seconds: i64) -> Timestamp {
+  Timestamp {
+    seconds: timestamp.seconds.saturating_add(seconds),
+    nanos: timestamp.nanos,
+  }
+}
+
+impl SampleValidator<Duration> for DurationValidator {
+  fn valid_candidates(&self, ctx: &mut SampleCtx) -> Vec<Duration> {
+    if let Some(const_val) = self.const_ {
+      return vec![const_val];
+    }
+
+    if let Some(allowed_list) = &self.in_ {
+      return allowed_list.iter().copied().collect();
+    }
+
+    let lower = max_bound(self.gt.map(|v| shift_duration(v, 1)), self.gte);
+    let upper = min_bound(self.lt.map(|v| shift_duration(v, -1)), self.lte);
+
+    let mut candidates: Vec<Duration> = Vec::new();
+
+    candidates.extend(lower);
+    candidates.extend(lower.map(|v| shift_duration(v, 1)));
+    candidates.extend(upper);
+    candidates.extend(upper.map(|v| shift_duration(v, -1)));
+    candidates.extend([
+      Duration::new(ctx.small_number().into(), 0),
+      Duration::new(1, 0),
+      Duration::default(),
+    ]);
+
+    candidates
+  }
+
+  fn invalid_candidates(&self, _: &mut SampleCtx) -> Vec<InvalidSample<Option<Duration>>> {
+    let mut candidates = InvalidCandidates::new();
+
+    macro_rules! push {
+      ($id:ident, $value:expr) => {
+        candidates.push(ViolationKind::Duration(DurationViolation::$id), $value)
+      };
+    }
+
+    if self.required {
+      candidates.push_missing(ViolationKind::Duration(DurationViolation::Required));
+    }
+
+    push!(Const, self.const_.map(|c| shift_duration(c, 1)));
+    push!(Gt, self.gt);
+    push!(Gte, self.gte.map(|v| shift_duration(v, -1)));
+    push!(Lt, self.lt);
+    push!(Lte, self.lte.map(|v| shift_duration(v, 1)));
+    push!(
+      In,
+      self
+        .in_
+        .as_ref()
+        .and_then(|list| list.last().map(|v| shift_duration(*v, 1)))
+    );
+    push!(
+      NotIn,
+      self
+        .not_in
+        .as_ref()
+        .and_then(|list| list.first().copied())
+    );
+
+    candidates.0
+  }
+}
+
+impl SampleValidator<Timestamp> for TimestampValidator {
+  fn valid_candidates(&self, ctx: &mut SampleCtx) -> Vec<Timestamp> {
+    if let Some(const_val) = self.const_ {
+      return vec![const_val];
+    }
+
+    let lower = max_bound(self.gt.map(|v| shift_timestamp(v, 1)), self.gte);
+    let upper = min_bound(self.lt.map(|v| shift_timestamp(v, -1)), self.lte);
+
+    let mut candidates: Vec<Timestamp> = Vec::new();
+
+    candidates.extend(lower);
+    candidates.extend(lower.map(|v| shift_timestamp(v, 1)));
+    candidates.extend(upper);
+    candidates.extend(upper.map(|v| shift_timestamp(v, -1)));
+
+    #[cfg(all(feature = "chrono", any(feature = "std", feature = "chrono-wasm")))]
+    {
+      let now = Timestamp::now();
+      let offset = self
+        .within
+        .map_or(3600, |range| (range.seconds / 2).max(1));
+
+      candidates.extend([shift_timestamp(now, offset), shift_timestamp(now, -offset)]);
+    }
+
+    candidates.extend([
+      Timestamp::new(ctx.small_number().into(), 0),
+      Timestamp::default(),
+    ]);
+
+    candidates
+  }
+
+  fn invalid_candidates(&self, _: &mut SampleCtx) -> Vec<InvalidSample<Option<Timestamp>>> {
+    let mut candidates = InvalidCandidates::new();
+
+    macro_rules! push {
+      ($id:ident, $value:expr) => {
+        candidates.push(ViolationKind::Timestamp(TimestampViolation::$id), $value)
+      };
+    }
+
+    if self.required {
+      candidates.push_missing(ViolationKind::Timestamp(TimestampViolation::Required));
+    }
+
+    push!(Const, self.const_.map(|c| shift_timestamp(c, 1)));
+    push!(Gt, self.gt);
+    push!(Gte, self.gte.map(|v| shift_timestamp(v, -1)));
+    push!(Lt, self.lt);
+    push!(Lte, self.lte.map(|v| shift_timestamp(v, 1)));
+
+    #[cfg(all(feature = "chrono", any(feature = "std", feature = "chrono-wasm")))]
+    {
+      let now = Timestamp::now();
+      // Far enough from now to be outside of the tolerance
+      let distance = self.now_tolerance.seconds.saturating_add(3600);
+
+      if self.gt_now {
+        push!(GtNow, Some(shift_timestamp(now, -distance)));
+      }
+
+      if self.lt_now {
+        push!(LtNow, Some(shift_timestamp(now, distance)));
+      }
+
+      push!(
+        Within,
+        self
+          .within
+          .map(|range| shift_timestamp(now, range.seconds.saturating_add(3600)))
+      );
+    }
+
+    candidates.0
+  }
+}
+
+impl SampleValidator<Any> for AnyValidator {
+  fn valid_candidates(&self, _: &mut SampleCtx) -> Vec<Any> {
+    if let Some(allowed_list) = &self.in_ {
+      return allowed_list
+        .iter()
+        .map(|type_url| Any {
+          type_url: type_url.to_string(),
+          ..Default::default()
+        })
+        .collect();
+    }
+
+    vec![
+      Any {
+        type_url: "type.googleapis.com/google.protobuf.Empty".to_string(),
+        ..Default::default()
+      },
+      Any::default(),
+    ]
+  }
+
+  fn invalid_candidates(&self, _: &mut SampleCtx) -> Vec<InvalidSample<Option<Any>>> {
+    let mut candidates = InvalidCandidates::new();
+
+    let with_type_url = |type_url: String| Any {
+      type_url,
+      ..Default::default()
+    };
+
+    if self.required {
+      candidates.push_missing(ViolationKind::Any(AnyViolation::Required));
+    }
+
+    candidates.push(
+      ViolationKind::Any(AnyViolation::In),
+      self.in_.as_ref().and_then(|list| {
+        list
+          .last()
+          .map(|v| with_type_url(format!("{v}a")))
+      }),
+    );
+    candidates.push(
+      ViolationKind::Any(AnyViolation::NotIn),
+      self
+        .not_in
+        .as_ref()
+        .and_then(|list| list.first().map(|v| with_type_url(v.to_string()))),
+    );
+
+    candidates.0
+  }
+}
+
+impl SampleValidator<FieldMask> for FieldMaskValidator {
+  fn valid_candidates(&self, _: &mut SampleCtx) -> Vec<FieldMask> {
+    let paths = |list: &SortedList<FixedStr>| FieldMask {
+      paths: list.iter().map(|p| p.to_string()).collect(),
+    };
+
+    if let Some(const_val) = &self.const_ {
+      return vec![paths(const_val)];
+    }
+
+    let mut candidates = Vec::new();
+
+    candidates.extend(self.in_.as_ref().map(paths));
+    candidates.push(FieldMask::default());
+
+    candidates
+  }
+
+  fn invalid_candidates(&self, _: &mut SampleCtx) -> Vec<InvalidSample<Option<FieldMask>>> {
+    let mut candidates = InvalidCandidates::new();
+
+    let with_path = |path: String| FieldMask { paths: vec![path] };
+
+    if self.required {
+      candidates.push_missing(ViolationKind::FieldMask(FieldMaskViolation::Required));
+    }
+
+    candidates.push(
+      ViolationKind::FieldMask(FieldMaskViolation::Const),
+      self
+        .const_
+        .as_ref()
+        .and_then(|list| list.last().map(|v| with_path(format!("{v}a")))),
+    );
+    candidates.push(
+      ViolationKind::FieldMask(FieldMaskViolation::In),
+      self
+        .in_
+        .as_ref()
+        .and_then(|list| list.last().map(|v| with_path(format!("{v}a")))),
+    );
+    candidates.push(
+      ViolationKind::FieldMask(FieldMaskViolation::NotIn),
+      self
+        .not_in
+        .as_ref()
+        .and_then(|list| list.first().map(|v| with_path(v.to_string()))),
+    );
+
+    candidates.0
+  }
+}
+
+/// Draws up to `count` distinct values from a generator.
+fn distinct_samples<T: PartialEq>(
+  ctx: &mut SampleCtx,
+  count: usize,
+  mut generate: impl FnMut(&mut SampleCtx) -> Option<T>,
+) -> Vec<T> {
+  let mut output: Vec<T> = Vec::with_capacity(count);
+
+  for _ in 0..count.saturating_mul(4) {
+    if output.len() == count {
+      break;
+    }
+
+    if let Some(value) = generate(ctx)
+      && !output.contains(&value)
+    {
+      output.push(value);
+    }
+  }
+
+  output
+}
+
+/// Picks the size of a collection, given its limits.
+fn collection_size(ctx: &mut SampleCtx, min: Option<usize>, max: Option<usize>) -> usize {
+  let min = min.unwrap_or_default();
+
+  min
+    .saturating_add(ctx.below(3))
+    .min(max.unwrap_or(usize::MAX).max(min))
+}
+
+impl<T> SampleValidator<Vec<T>> for RepeatedValidator<T>
+where
+  T: ProtoValidation + Send + Sync,
+  T::Stored: TryIntoCel + Sized + Clone + PartialEq,
+  T::Target: ToOwned<Owned = T::Stored>,
+  T::Validator: SampleValidator<T>,
+{
+  fn valid_candidates(&self, ctx: &mut SampleCtx) -> Vec<Vec<T::Stored>> {
+    let items_validator = self.items.clone().unwrap_or_default();
+
+    (0..2)
+      .map(|_| {
+        let size = collection_size(ctx, self.min_items, self.max_items);
+
+        distinct_samples(ctx, size, |ctx| items_validator.valid_sample(ctx))
+      })
+      .collect()
+  }
+
+  fn invalid_candidates(&self, ctx: &mut SampleCtx) -> Vec<InvalidSample<Option<Vec<T::Stored>>>> {
+    let mut candidates = InvalidCandidates::new();
+
+    macro_rules! push {
+      ($id:ident, $value:expr) => {
+        candidates.push(ViolationKind::Repeated(RepeatedViolation::$id), $value)
+      };
+    }
+
+    let items_validator = self.items.clone().unwrap_or_default();
+    let mut items = |ctx: &mut SampleCtx, count: usize| {
+      distinct_samples(ctx, count, |ctx| items_validator.valid_sample(ctx))
+    };
+
+    push!(
+      MinItems,
+      self
+        .min_items
+        .and_then(|min| min.checked_sub(1))
+        .map(|count| items(ctx, count))
+    );
+    push!(MaxItems, self.max_items.map(|max| items(ctx, max + 1)));
+
+    if self.unique
+      && let Some(item) = items_validator.valid_sample(ctx)
+    {
+      push!(Unique, Some(vec![item.clone(), item]));
+    }
+
+    let mut candidates = candidates.0;
+
+    if let Some(items_validator) = &self.items {
+      // The invalid item is added to an otherwise valid list
+      let filler = self
+        .min_items
+        .unwrap_or_default()
+        .saturating_sub(1);
+
+      for sample in items_validator.invalid_samples(ctx) {
+        if let Some(invalid_item) = sample.value {
+          let mut list = items(ctx, filler);
+          list.insert(0, invalid_item);
+
+          candidates.push(InvalidSample {
+            violation: sample.violation,
+            path: sample.path,
+            value: Some(list),
+          });
+        }
+      }
+    }
+
+    candidates
+  }
+}
+
+impl<K, V, M> SampleValidator<M> for MapValidator<K, V>
+where
+  K: ProtoValidation + Send + Sync + AsProtoType,
+  V: ProtoValidation + Send + Sync + AsProtoType,
+  M: ProtoMap<K, V> + ToOwned,
+  M::Target: Clone + Default + FromIterator<(K::Stored, V::Stored)>,
+  K::Stored: Sized + Clone + IntoCelKey + Into<Subscript> + PartialEq,
+  V::Stored: Sized + Clone + TryIntoCel + Default,
+  K::Target: ToOwned<Owned = K::Stored>,
+  V::Target: ToOwned<Owned = V::Stored>,
+  K::Validator: SampleValidator<K>,
+  V::Validator: SampleValidator<V>,
+{
+  fn valid_candidates(&self, ctx: &mut SampleCtx) -> Vec<M::Target> {
+    let keys_validator = self.keys.clone().unwrap_or_default();
+    let values_validator = self.values.clone().unwrap_or_default();
+
+    (0..2)
+      .map(|_| {
+        let size = collection_size(ctx, self.min_pairs, self.max_pairs);
+
+        distinct_samples(ctx, size, |ctx| keys_validator.valid_sample(ctx))
+          .into_iter()
+          .map(|key| {
+            let value = values_validator
+              .valid_sample(ctx)
+              .unwrap_or_default();
+
+            (key, value)
+          })
+          .collect()
+      })
+      .collect()
+  }
+
+  fn invalid_candidates(&self, ctx: &mut SampleCtx) -> Vec<InvalidSample<Option<M::Target>>> {
+    let mut candidates = InvalidCandidates::new();
+
+    let keys_validator = self.keys.clone().unwrap_or_default();
+    let values_validator = self.values.clone().unwrap_or_default();
+
+    let mut pairs = |ctx: &mut SampleCtx, count: usize| -> M::Target {
+      distinct_samples(ctx, count, |ctx| keys_validator.valid_sample(ctx))
+        .into_iter()
+        .map(|key| {
+          let value = values_validator
+            .valid_sample(ctx)
+            .unwrap_or_default();
+
+          (key, value)
+        })
+        .collect()
+    };
+
+    candidates.push(
+      ViolationKind::Map(MapViolation::MinPairs),
+      self
+        .min_pairs
+        .and_then(|min| min.checked_sub(1))
+        .map(|count| pairs(ctx, count)),
+    );
+    candidates.push(
+      ViolationKind::Map(MapViolation::MaxPairs),
+      self.max_pairs.map(|max| pairs(ctx, max + 1)),
+    );
+
+    let mut candidates = candidates.0;
+
+    // Invalid keys and values are placed in a map with a single entry
+    if let Some(keys_validator) = &self.keys {
+      for sample in keys_validator.invalid_samples(ctx) {
+        if let Some(key) = sample.value {
+          let value = values_validator
+            .valid_sample(ctx)
+            .unwrap_or_default();
+
+          candidates.push(InvalidSample {
+            violation: sample.violation,
+            path: sample.path,
+            value: Some(M::Target::from_iter([(key, value)])),
+          });
+        }
+      }
+    }
+
+    if let Some(values_validator) = &self.values {
+      let key = self
+        .keys
+        .clone()
+        .unwrap_or_default()
+        .valid_sample(ctx);
+
+      if let Some(key) = key {
+        for sample in values_validator.invalid_samples(ctx) {
+          if let Some(value) = sample.value {
+            candidates.push(InvalidSample {
+              violation: sample.violation,
+              path: sample.path,
+              value: Some(M::Target::from_iter([(key.clone(), value)])),
+            });
+          }
+        }
+      }
+    }
+
+    candidates
+  }
+}
@@ -10,6 +10,7 @@ pub trait ProtoService {
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Service {
   pub name: FixedStr,
+  pub comments: Option<FixedStr>,
   pub file: FixedStr,
   pub options: Vec<ProtoOption>,
   pub handlers: Vec<ServiceHandler>,
@@ -20,7 +21,40 @@ pub struct Service {
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ServiceHandler {
   pub name: FixedStr,
+  pub comments: Option<FixedStr>,
   pub options: Vec<ProtoOption>,
   pub request: ProtoPath,
   pub response: ProtoPath,
+  pub client_streaming: bool,
+  pub server_streaming: bool,
+}
+
+impl Service {
+  pub(crate) fn render_comments(&self) -> Option<String> {
+    render_comments(self.comments.as_ref())
+  }
+}
+
+impl ServiceHandler {
+  pub(crate) fn render_comments(&self) -> Option<String> {
+    render_comments(self.comments.as_ref())
+  }
+
+  pub(crate) fn render_request(&self, package: &FixedStr) -> String {
+    render_handler_type(&self.request, self.client_streaming, package)
+  }
+
+  pub(crate) fn render_response(&self, package: &FixedStr) -> String {
+    render_handler_type(&self.response, self.server_streaming, package)
+  }
+}
+
+fn render_handler_type(path: &ProtoPath, is_stream: bool, package: &FixedStr) -> String {
+  let name = path.render_name(package);
+
+  if is_stream {
+    format!("stream {name}")
+  } else {
+    name.into_owned()
+  }
 }
@@ -0,0 +1,1160 @@
+//! Support for the [text format](https://protobuf.dev/reference/protobuf/textformat-spec/) of protobuf messages.
+//!
+//! With the `text-format` feature, the messages defined with the macros implement [`ProtoText`], so they can be encoded and decoded
+//! with [`ProtoMessage::to_text_format`] and [`ProtoMessage::from_text_format`].
+//!
+//! - Fields use their proto name. Fields with a default value (and no explicit presence) are omitted from the output.
+//! - Enums are written with the name of the variant, and unknown values are written as numbers. Both forms are accepted when decoding.
+//! - Repeated fields are written with one entry per element. When decoding, the list syntax (`values: [1, 2]`) is accepted as well.
+//! - Map fields are written as repeated entries with a `key` and a `value` field, sorted by key.
+//! - The fields of a oneof appear directly inside of the message that contains it.
+//! - The well known types use their regular message representation. The expanded form of `google.protobuf.Any` and extensions are not supported.
+//!
+//! Decoding errors report the position in the input and the path of the field that caused them.
+
+use crate::*;
+
+use core::{iter::Peekable, str::Chars, str::FromStr};
+
+/// Implemented by the types that can be encoded and decoded with the protobuf text format.
+///
+/// It is implemented automatically by the messages defined with the macros, as well as by the well known types.
+pub trait ProtoText {
+  /// Writes the fields of this message.
+  fn write_text(&self, writer: &mut TextWriter);
+
+  /// Merges the fields of a parsed message into this one.
+  fn merge_text(&mut self, message: TextMessage) -> Result<(), TextFormatError>;
+}
+
+impl<T: ProtoText> ProtoText for Box<T> {
+  #[inline]
+  fn write_text(&self, writer: &mut TextWriter) {
+    T::write_text(self, writer);
+  }
+
+  #[inline]
+  fn merge_text(&mut self, message: TextMessage) -> Result<(), TextFormatError> {
+    T::merge_text(self, message)
+  }
+}
+
+/// Implemented by the oneofs defined with the macros, so that their fields can appear directly inside of the parent message.
+#[doc(hidden)]
+pub trait ProtoTextOneof: Sized {
+  /// The proto names of the fields of the oneof.
+  const FIELD_NAMES: &'static [&'static str];
+
+  fn write_text_field(&self, writer: &mut TextWriter);
+
+  fn merge_text_field(
+    target: &mut Option<Self>,
+    name: &str,
+    value: TextValue,
+    position: TextPosition,
+  ) -> Result<(), TextFormatError>;
+}
+
+/// Encodes a message in the text format.
+#[must_use]
+pub fn to_text_format<T: ProtoText + ?Sized>(message: &T) -> String {
+  let mut writer = TextWriter::new();
+
+  message.write_text(&mut writer);
+
+  writer.finish()
+}
+
+/// Decodes a message from the text format.
+pub fn from_text_format<T: ProtoText + Default>(input: &str) -> Result<T, TextFormatError> {
+  let message = TextMessage::parse(input)?;
+
+  let mut output = T::default();
+  output.merge_text(message)?;
+
+  Ok(output)
+}
+
+/// A position in the text format input. Lines and columns start from 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextPosition {
+  pub line: usize,
+  pub column: usize,
+}
+
+/// An error that occurred while decoding a message from the text format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextFormatError {
+  /// The path of the field that caused the error, such as `items[2].name`.
+  ///
+  /// It is empty for syntax errors, and for errors that are not caused by a specific field.
+  pub path: String,
+  /// The position of the field or of the token that caused the error.
+  pub position: Option<TextPosition>,
+  pub message: String,
+}
+
+impl Display for TextFormatError {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    if let Some(TextPosition { line, column }) = self.position {
+      write!(f, "line {line}, column {column}: ")?;
+    }
+
+    if !self.path.is_empty() {
+      write!(f, "at `{}`: ", self.path)?;
+    }
+
+    f.write_str(&self.message)
+  }
+}
+
+impl core::error::Error for TextFormatError {}
+
+impl TextFormatError {
+  #[must_use]
+  pub fn new(message: impl Into<String>) -> Self {
+    Self {
+      path: String::new(),
+      position: None,
+      message: message.into(),
+    }
+  }
+
+  fn at(position: TextPosition, message: impl Into<String>) -> Self {
+    Self {
+      position: Some(position),
+      ..Self::new(message)
+    }
+  }
+
+  #[doc(hidden)]
+  #[must_use]
+  pub fn unknown_field(name: &str, position: TextPosition) -> Self {
+    Self::at(position, format!("unknown field `{name}`"))
+  }
+
+  /// Adds the name of the field that contains the error to its path.
+  #[doc(hidden)]
+  #[must_use]
+  pub fn in_field(mut self, name: &str, position: TextPosition) -> Self {
+    self.prepend_path(name);
+
+    // The innermost position is the most precise one
+    if self.position.is_none() {
+      self.position = Some(position);
+    }
+
+    self
+  }
+
+  fn in_index(mut self, index: usize) -> Self {
+    self.prepend_path(&format!("[{index}]"));
+    self
+  }
+
+  fn prepend_path(&mut self, segment: &str) {
+    if self.path.is_empty() {
+      self.path = segment.to_string();
+    } else if self.path.starts_with('[') {
+      self.path = format!("{segment}{}", self.path);
+    } else {
+      self.path = format!("{segment}.{}", self.path);
+    }
+  }
+}
+
+/// A message parsed from the text format, before its fields are matched with those of a rust type.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TextMessage {
+  pub fields: Vec<TextField>,
+}
+
+impl TextMessage {
+  /// Parses the fields of a message from the text format.
+  pub fn parse(input: &str) -> Result<Self, TextFormatError> {
+    Parser::new(input).parse_message(None)
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextField {
+  pub name: String,
+  pub value: TextValue,
+  pub position: TextPosition,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TextValue {
+  Scalar(TextScalar),
+  Message(TextMessage),
+  /// A list of values, as in `values: [1, 2]`.
+  List(Vec<Self>),
+}
+
+impl TextValue {
+  fn into_scalar(self) -> Result<TextScalar, TextFormatError> {
+    match self {
+      Self::Scalar(scalar) => Ok(scalar),
+      Self::Message(_) => Err(TextFormatError::new(
+        "expected a scalar value, found a message",
+      )),
+      Self::List(_) => Err(TextFormatError::new(
+        "lists can only be used for repeated fields",
+      )),
+    }
+  }
+
+  fn into_message(self) -> Result<TextMessage, TextFormatError> {
+    match self {
+      Self::Message(message) => Ok(message),
+      Self::Scalar(scalar) => Err(TextFormatError::new(format!(
+        "expected a message, found {scalar}"
+      ))),
+      Self::List(_) => Err(TextFormatError::new(
+        "lists can only be used for repeated fields",
+      )),
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TextScalar {
+  /// An identifier, such as the name of an enum variant, `true` or `inf`. It may start with a `-`.
+  Identifier(String),
+  /// A number, including its sign.
+  Number(String),
+  /// A string or bytes literal, with its escape sequences already resolved. Adjacent literals are concatenated.
+  String(Vec<u8>),
+}
+
+impl Display for TextScalar {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    match self {
+      Self::Identifier(value) | Self::Number(value) => write!(f, "`{value}`"),
+      Self::String(bytes) => write!(f, "`{}`", quote_bytes(bytes)),
+    }
+  }
+}
+
+fn invalid_value(scalar: &TextScalar, expected: &str) -> TextFormatError {
+  TextFormatError::new(format!("expected {expected}, found {scalar}"))
+}
+
+/// Writes the fields of a message in the text format, with one field per line.
+#[derive(Debug, Default)]
+pub struct TextWriter {
+  output: String,
+  indent: usize,
+}
+
+impl TextWriter {
+  #[must_use]
+  pub const fn new() -> Self {
+    Self {
+      output: String::new(),
+      indent: 0,
+    }
+  }
+
+  fn write_indent(&mut self) {
+    for _ in 0..self.indent {
+      self.output.push_str("  ");
+    }
+  }
+
+  /// Writes a field with a scalar value, which must already be formatted as a text format literal.
+  pub fn write_scalar(&mut self, name: &str, value: impl Display) {
+    self.write_indent();
+    let _ = writeln!(self.output, "{name}: {value}");
+  }
+
+  /// Writes a field with a message value, whose fields are written by the given closure.
+  pub fn write_message(&mut self, name: &str, write_fields: impl FnOnce(&mut Self)) {
+    self.write_indent();
+    let _ = writeln!(self.output, "{name} {{");
+
+    self.indent += 1;
+    write_fields(self);
+    self.indent -= 1;
+
+    self.write_indent();
+    self.output.push_str("}\n");
+  }
+
+  #[must_use]
+  pub fn finish(self) -> String {
+    self.output
+  }
+}
+
+/// Writes a field, unless it has its default value.
+#[doc(hidden)]
+#[inline]
+pub fn write_field<C: TextCodec<T>, T>(writer: &mut TextWriter, name: &str, value: &T) {
+  if !C::is_default(value) {
+    C::write(value, name, writer);
+  }
+}
+
+/// Merges the value of a field, adding its name to the path of the error in case of failure.
+#[doc(hidden)]
+#[inline]
+pub fn merge_field<C: TextCodec<T>, T>(
+  target: &mut T,
+  name: &str,
+  value: TextValue,
+  position: TextPosition,
+) -> Result<(), TextFormatError> {
+  C::merge(target, value).map_err(|e| e.in_field(name, position))
+}
+
+/// Describes how a value of type `T` is mapped to and from the text format.
+#[doc(hidden)]
+pub trait TextCodec<T> {
+  /// Writes the value as one or more entries of the field with the given name.
+  fn write(value: &T, name: &str, writer: &mut TextWriter);
+
+  /// Merges a single entry of the field into the target.
+  fn merge(target: &mut T, value: TextValue) -> Result<(), TextFormatError>;
+
+  /// Whether the value should be omitted from the output.
+  fn is_default(value: &T) -> bool;
+}
+
+fn parse_integer<T: TryFrom<i128>>(scalar: &TextScalar) -> Option<T> {
+  let TextScalar::Number(number) = scalar else {
+    return None;
+  };
+
+  let (negative, digits) = match number.strip_prefix('-') {
+    Some(digits) => (true, digits),
+    None => (false, number.as_str()),
+  };
+
+  let value = if let Some(hex) = digits
+    .strip_prefix("0x")
+    .or_else(|| digits.strip_prefix("0X"))
+  {
+    i128::from_str_radix(hex, 16).ok()?
+  } else if digits.len() > 1 && digits.starts_with('0') {
+    i128::from_str_radix(&digits[1..], 8).ok()?
+  } else {
+    digits.parse::<i128>().ok()?
+  };
+
+  T::try_from(if negative { -value } else { value }).ok()
+}
+
+macro_rules! int_codec {
+  ($($codec:ident => $int:ty);* $(;)?) => {
+    $(
+      #[doc(hidden)]
+      pub struct $codec;
+
+      impl TextCodec<$int> for $codec {
+        #[inline]
+        fn write(value: &$int, name: &str, writer: &mut TextWriter) {
+          writer.write_scalar(name, value);
+        }
+
+        fn merge(target: &mut $int, value: TextValue) -> Result<(), TextFormatError> {
+          let scalar = value.into_scalar()?;
+
+          *target = parse_integer(&scalar)
+            .ok_or_else(|| invalid_value(&scalar, concat!("a valid ", stringify!($int))))?;
+
+          Ok(())
+        }
+
+        #[inline]
+        fn is_default(value: &$int) -> bool {
+          *value == 0
+        }
+      }
+    )*
+  };
+}
+
+int_codec!(
+  Int32Codec => i32;
+  Uint32Codec => u32;
+  Int64Codec => i64;
+  Uint64Codec => u64;
+);
+
+macro_rules! float_codec {
+  ($($codec:ident => $float:ty);* $(;)?) => {
+    $(
+      #[doc(hidden)]
+      pub struct $codec;
+
+      impl TextCodec<$float> for $codec {
+        fn write(value: &$float, name: &str, writer: &mut TextWriter) {
+          if value.is_nan() {
+            writer.write_scalar(name, "nan");
+          } else if value.is_infinite() {
+            writer.write_scalar(name, if value.is_sign_positive() { "inf" } else { "-inf" });
+          } else {
+            writer.write_scalar(name, value);
+          }
+        }
+
+        fn merge(target: &mut $float, value: TextValue) -> Result<(), TextFormatError> {
+          let scalar = value.into_scalar()?;
+
+          let parsed = match &scalar {
+            // Accepts `inf`, `infinity` and `nan` in any case
+            TextScalar::Identifier(ident) => <$float>::from_str(ident).ok().filter(|v| !v.is_finite()),
+            TextScalar::Number(number) if !number.contains(['x', 'X']) => {
+              <$float>::from_str(number.trim_end_matches(['f', 'F'])).ok()
+            }
+            _ => None,
+          };
+
+          *target = parsed.ok_or_else(|| invalid_value(&scalar, concat!("a valid ", stringify!($float))))?;
+
+          Ok(())
+        }
+
+        #[inline]
+        #[allow(clippy::float_cmp)]
+        fn is_default(value: &$float) -> bool {
+          *value == 0.0
+        }
+      }
+    )*
+  };
+}
+
+float_codec!(
+  FloatCodec => f32;
+  DoubleCodec => f64;
+);
+
+#[doc(hidden)]
+pub struct BoolCodec;
+
+impl TextCodec<bool> for BoolCodec {
+  #[inline]
+  fn write(value: &bool, name: &str, writer: &mut TextWriter) {
+    writer.write_scalar(name, value);
+  }
+
+  fn merge(target: &mut bool, value: TextValue) -> Result<(), TextFormatError> {
+    let scalar = value.into_scalar()?;
+
+    *target = match &scalar {
+      TextScalar::Identifier(ident) => match ident.as_str() {
+        "true" | "True" | "t" => true,
+        "false" | "False" | "f" => false,
+        _ => return Err(invalid_value(&scalar, "a bool")),
+      },
+      TextScalar::Number(number) => match number.as_str() {
+        "1" => true,
+        "0" => false,
+        _ => return Err(invalid_value(&scalar, "a bool")),
+      },
+      TextScalar::String(_) => return Err(invalid_value(&scalar, "a bool")),
+    };
+
+    Ok(())
+  }
+
+  #[inline]
+  fn is_default(value: &bool) -> bool {
+    !*value
+  }
+}
+
+#[doc(hidden)]
+pub struct StringCodec;
+
+impl TextCodec<String> for StringCodec {
+  #[inline]
+  fn write(value: &String, name: &str, writer: &mut TextWriter) {
+    writer.write_scalar(name, quote_str(value));
+  }
+
+  fn merge(target: &mut String, value: TextValue) -> Result<(), TextFormatError> {
+    let TextScalar::String(bytes) = value.into_scalar()? else {
+      return Err(TextFormatError::new("expected a string"));
+    };
+
+    *target = String::from_utf8(bytes)
+      .map_err(|_| TextFormatError::new("the string is not valid UTF-8"))?;
+
+    Ok(())
+  }
+
+  #[inline]
+  fn is_default(value: &String) -> bool {
+    value.is_empty()
+  }
+}
+
+#[doc(hidden)]
+pub struct BytesCodec;
+
+impl<T> TextCodec<T> for BytesCodec
+where
+  T: AsRef<[u8]> + From<Vec<u8>>,
+{
+  #[inline]
+  fn write(value: &T, name: &str, writer: &mut TextWriter) {
+    writer.write_scalar(name, quote_bytes(value.as_ref()));
+  }
+
+  fn merge(target: &mut T, value: TextValue) -> Result<(), TextFormatError> {
+    let TextScalar::String(bytes) = value.into_scalar()? else {
+      return Err(TextFormatError::new("expected a bytes literal"));
+    };
+
+    *target = T::from(bytes);
+
+    Ok(())
+  }
+
+  #[inline]
+  fn is_default(value: &T) -> bool {
+    value.as_ref().is_empty()
+  }
+}
+
+/// Maps the numeric value of an enum to the name of its variant.
+#[doc(hidden)]
+pub struct EnumCodec<E>(PhantomData<E>);
+
+impl<E> TextCodec<i32> for EnumCodec<E>
+where
+  E: ProtoEnumSchema + Into<i32>,
+{
+  fn write(value: &i32, name: &str, writer: &mut TextWriter) {
+    match E::try_from(*value) {
+      Ok(variant) => writer.write_scalar(name, variant.as_proto_name()),
+      Err(_) => writer.write_scalar(name, value),
+    }
+  }
+
+  fn merge(target: &mut i32, value: TextValue) -> Result<(), TextFormatError> {
+    let scalar = value.into_scalar()?;
+
+    *target = match &scalar {
+      TextScalar::Identifier(ident) => E::from_proto_name(ident)
+        .map(Into::into)
+        .ok_or_else(|| {
+          TextFormatError::new(format!(
+            "unknown variant `{ident}` for the enum `{}`",
+            E::proto_name()
+          ))
+        })?,
+      TextScalar::Number(_) => {
+        parse_integer(&scalar).ok_or_else(|| invalid_value(&scalar, "a valid enum number"))?
+      }
+      TextScalar::String(_) => return Err(invalid_value(&scalar, "the name of an enum variant")),
+    };
+
+    Ok(())
+  }
+
+  #[inline]
+  fn is_default(value: &i32) -> bool {
+    *value == 0
+  }
+}
+
+#[doc(hidden)]
+pub struct MessageCodec;
+
+impl<T> TextCodec<T> for MessageCodec
+where
+  T: ProtoText + PartialEq + Default,
+{
+  #[inline]
+  fn write(value: &T, name: &str, writer: &mut TextWriter) {
+    writer.write_message(name, |writer| value.write_text(writer));
+  }
+
+  #[inline]
+  fn merge(target: &mut T, value: TextValue) -> Result<(), TextFormatError> {
+    target.merge_text(value.into_message()?)
+  }
+
+  #[inline]
+  fn is_default(value: &T) -> bool {
+    *value == T::default()
+  }
+}
+
+/// Used for fields with explicit presence, which are written whenever they are set.
+///
+/// Messages that appear more than once are merged together, as per the protobuf spec.
+#[doc(hidden)]
+pub struct OptionalCodec<C>(PhantomData<C>);
+
+impl<C: TextCodec<T>, T: Default> TextCodec<Option<T>> for OptionalCodec<C> {
+  #[inline]
+  fn write(value: &Option<T>, name: &str, writer: &mut TextWriter) {
+    if let Some(inner) = value {
+      C::write(inner, name, writer);
+    }
+  }
+
+  #[inline]
+  fn merge(target: &mut Option<T>, value: TextValue) -> Result<(), TextFormatError> {
+    C::merge(target.get_or_insert_with(T::default), value)
+  }
+
+  #[inline]
+  fn is_default(value: &Option<T>) -> bool {
+    value.is_none()
+  }
+}
+
+/// Used for the fields that use the wrapper types (like `google.protobuf.FloatValue`),
+/// which are represented as an `Option` of their inner value.
+#[doc(hidden)]
+pub struct WrapperCodec<C>(PhantomData<C>);
+
+impl<C: TextCodec<T>, T: Default> TextCodec<Option<T>> for WrapperCodec<C> {
+  fn write(value: &Option<T>, name: &str, writer: &mut TextWriter) {
+    if let Some(inner) = value {
+      writer.write_message(name, |writer| write_field::<C, T>(writer, "value", inner));
+    }
+  }
+
+  fn merge(target: &mut Option<T>, value: TextValue) -> Result<(), TextFormatError> {
+    let inner = target.get_or_insert_with(T::default);
+
+    for TextField {
+      name,
+      value,
+      position,
+    } in value.into_message()?.fields
+    {
+      if name != "value" {
+        return Err(TextFormatError::unknown_field(&name, position));
+      }
+
+      merge_field::<C, T>(inner, &name, value, position)?;
+    }
+
+    Ok(())
+  }
+
+  #[inline]
+  fn is_default(value: &Option<T>) -> bool {
+    value.is_none()
+  }
+}
+
+#[doc(hidden)]
+pub struct RepeatedCodec<C>(PhantomData<C>);
+
+impl<C: TextCodec<T>, T: Default> TextCodec<Vec<T>> for RepeatedCodec<C> {
+  #[inline]
+  fn write(value: &Vec<T>, name: &str, writer: &mut TextWriter) {
+    for item in value {
+      C::write(item, name, writer);
+    }
+  }
+
+  fn merge(target: &mut Vec<T>, value: TextValue) -> Result<(), TextFormatError> {
+    let items = match value {
+      TextValue::List(items) => items,
+      other => vec![other],
+    };
+
+    for item in items {
+      let mut element = T::default();
+
+      C::merge(&mut element, item).map_err(|e| e.in_index(target.len()))?;
+
+      target.push(element);
+    }
+
+    Ok(())
+  }
+
+  #[inline]
+  fn is_default(value: &Vec<T>) -> bool {
+    value.is_empty()
+  }
+}
+
+#[doc(hidden)]
+pub struct MapCodec<K, V>(PhantomData<(K, V)>);
+
+fn merge_map_entry<K, V, KT, VT>(value: TextValue) -> Result<(KT, VT), TextFormatError>
+where
+  K: TextCodec<KT>,
+  V: TextCodec<VT>,
+  KT: Default,
+  VT: Default,
+{
+  let mut key = KT::default();
+  let mut val = VT::default();
+
+  for TextField {
+    name,
+    value,
+    position,
+  } in value.into_message()?.fields
+  {
+    match name.as_str() {
+      "key" => merge_field::<K, KT>(&mut key, &name, value, position)?,
+      "value" => merge_field::<V, VT>(&mut val, &name, value, position)?,
+      _ => return Err(TextFormatError::unknown_field(&name, position)),
+    }
+  }
+
+  Ok((key, val))
+}
+
+macro_rules! impl_map_codec {
+  ($map:ident $(, $bound:path)*) => {
+    impl<K, V, KT, VT> TextCodec<$map<KT, VT>> for MapCodec<K, V>
+    where
+      K: TextCodec<KT>,
+      V: TextCodec<VT>,
+      KT: Ord + Default $(+ $bound)*,
+      VT: Default,
+    {
+      fn write(value: &$map<KT, VT>, name: &str, writer: &mut TextWriter) {
+        // The entries are sorted so that the output is deterministic
+        let mut entries: Vec<(&KT, &VT)> = value.iter().collect();
+        entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        for (key, val) in entries {
+          writer.write_message(name, |writer| {
+            K::write(key, "key", writer);
+            V::write(val, "value", writer);
+          });
+        }
+      }
+
+      fn merge(target: &mut $map<KT, VT>, value: TextValue) -> Result<(), TextFormatError> {
+        let entries = match value {
+          TextValue::List(entries) => entries,
+          other => vec![other],
+        };
+
+        for (idx, entry) in entries.into_iter().enumerate() {
+          let (key, val) = merge_map_entry::<K, V, KT, VT>(entry).map_err(|e| e.in_index(idx))?;
+
+          target.insert(key, val);
+        }
+
+        Ok(())
+      }
+
+      #[inline]
+      fn is_default(value: &$map<KT, VT>) -> bool {
+        value.is_empty()
+      }
+    }
+  };
+}
+
+impl_map_codec!(HashMap, Hash);
+impl_map_codec!(BTreeMap);
+
+fn escape_byte(byte: u8, output: &mut String) {
+  match byte {
+    b'"' => output.push_str("\\\""),
+    b'\\' => output.push_str("\\\\"),
+    b'\n' => output.push_str("\\n"),
+    b'\r' => output.push_str("\\r"),
+    b'\t' => output.push_str("\\t"),
+    0x20..0x7f => output.push(char::from(byte)),
+    _ => {
+      let _ = write!(output, "\\{byte:03o}");
+    }
+  }
+}
+
+fn quote_bytes(bytes: &[u8]) -> String {
+  let mut output = String::with_capacity(bytes.len() + 2);
+
+  output.push('"');
+
+  for byte in bytes {
+    escape_byte(*byte, &mut output);
+  }
+
+  output.push('"');
+
+  output
+}
+
+/// Quotes a string, keeping the non-ASCII characters as they are.
+fn quote_str(value: &str) -> String {
+  let mut output = String::with_capacity(value.len() + 2);
+
+  output.push('"');
+
+  for ch in value.chars() {
+    match u8::try_from(ch) {
+      Ok(byte) if byte.is_ascii() => escape_byte(byte, &mut output),
+      _ => output.push(ch),
+    }
+  }
+
+  output.push('"');
+
+  output
+}
+
+struct Parser<'a> {
+  chars: Peekable<Chars<'a>>,
+  line: usize,
+  column: usize,
+}
+
+impl<'a> Parser<'a> {
+  fn new(input: &'a str) -> Self {
+    Self {
+      chars: input.chars().peekable(),
+      line: 1,
+      column: 1,
+    }
+  }
+
+  const fn position(&self) -> TextPosition {
+    TextPosition {
+      line: self.line,
+      column: self.column,
+    }
+  }
+
+  fn error(&self, message: impl Into<String>) -> TextFormatError {
+    TextFormatError::at(self.position(), message)
+  }
+
+  fn peek(&mut self) -> Option<char> {
+    self.chars.peek().copied()
+  }
+
+  fn bump(&mut self) -> Option<char> {
+    let ch = self.chars.next()?;
+
+    if ch == '\n' {
+      self.line += 1;
+      self.column = 1;
+    } else {
+      self.column += 1;
+    }
+
+    Some(ch)
+  }
+
+  fn skip_whitespace(&mut self) {
+    while let Some(ch) = self.peek() {
+      if ch == '#' {
+        while self.bump().is_some_and(|ch| ch != '\n') {}
+      } else if ch.is_whitespace() {
+        self.bump();
+      } else {
+        break;
+      }
+    }
+  }
+
+  fn expect(&mut self, expected: char) -> Result<(), TextFormatError> {
+    self.skip_whitespace();
+
+    if self.peek() == Some(expected) {
+      self.bump();
+      Ok(())
+    } else {
+      Err(self.error(format!("expected `{expected}`")))
+    }
+  }
+
+  fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> String {
+    let mut output = String::new();
+
+    while let Some(ch) = self.peek().filter(|ch| predicate(*ch)) {
+      output.push(ch);
+      self.bump();
+    }
+
+    output
+  }
+
+  /// Parses the fields of a message until the given terminator, or until the end of the input.
+  fn parse_message(&mut self, terminator: Option<char>) -> Result<TextMessage, TextFormatError> {
+    let mut fields = Vec::new();
+
+    loop {
+      self.skip_whitespace();
+
+      match (self.peek(), terminator) {
+        (None, None) => break,
+        (None, Some(terminator)) => {
+          return Err(self.error(format!(
+            "expected `{terminator}`, found the end of the input"
+          )));
+        }
+        (Some(ch), Some(terminator)) if ch == terminator => {
+          self.bump();
+          break;
+        }
+        _ => {}
+      }
+
+      fields.push(self.parse_field()?);
+
+      self.skip_whitespace();
+
+      if matches!(self.peek(), Some(',' | ';')) {
+        self.bump();
+      }
+    }
+
+    Ok(TextMessage { fields })
+  }
+
+  fn parse_field(&mut self) -> Result<TextField, TextFormatError> {
+    let position = self.position();
+
+    let name = if self.peek() == Some('[') {
+      // Extensions and expanded `Any` messages, which are rejected when the fields are merged
+      self.bump();
+      self.skip_whitespace();
+      let name = self.take_while(|ch| is_identifier_char(ch) || ch == '.' || ch == '/');
+      self.expect(']')?;
+
+      format!("[{name}]")
+    } else {
+      let name = self.take_while(is_identifier_char);
+
+      if name.is_empty() || name.starts_with(|ch: char| ch.is_ascii_digit()) {
+        return Err(TextFormatError::at(
+          position,
+          "expected the name of a field",
+        ));
+      }
+
+      name
+    };
+
+    self.skip_whitespace();
+
+    let value = match self.peek() {
+      Some(':') => {
+        self.bump();
+        self.skip_whitespace();
+        self.parse_value()?
+      }
+      // The colon is optional before messages
+      Some('{' | '<') => self.parse_value()?,
+      _ => return Err(self.error(format!("expected `:` after the name of the field `{name}`"))),
+    };
+
+    Ok(TextField {
+      name,
+      value,
+      position,
+    })
+  }
+
+  fn parse_value(&mut self) -> Result<TextValue, TextFormatError> {
+    match self.peek() {
+      Some('{') => {
+        self.bump();
+        Ok(TextValue::Message(self.parse_message(Some('}'))?))
+      }
+      Some('<') => {
+        self.bump();
+        Ok(TextValue::Message(self.parse_message(Some('>'))?))
+      }
+      Some('[') => {
+        self.bump();
+        self.parse_list()
+      }
+      Some('"' | '\'') => {
+        let mut bytes = Vec::new();
+
+        // Adjacent string literals are concatenated
+        while let Some(quote @ ('"' | '\'')) = self.peek() {
+          self.parse_string(quote, &mut bytes)?;
+          self.skip_whitespace();
+        }
+
+        Ok(TextValue::Scalar(TextScalar::String(bytes)))
+      }
+      Some(_) => self.parse_scalar().map(TextValue::Scalar),
+      None => Err(self.error("expected a value, found the end of the input")),
+    }
+  }
+
+  fn parse_list(&mut self) -> Result<TextValue, TextFormatError> {
+    let mut items = Vec::new();
+
+    loop {
+      self.skip_whitespace();
+
+      if self.peek() == Some(']') {
+        self.bump();
+        break;
+      }
+
+      items.push(self.parse_value()?);
+
+      self.skip_whitespace();
+
+      match self.peek() {
+        Some(',') => {
+          self.bump();
+        }
+        Some(']') => {}
+        _ => return Err(self.error("expected `,` or `]`")),
+      }
+    }
+
+    Ok(TextValue::List(items))
+  }
+
+  fn parse_scalar(&mut self) -> Result<TextScalar, TextFormatError> {
+    let mut token = String::new();
+
+    if self.peek() == Some('-') {
+      self.bump();
+      self.skip_whitespace();
+      token.push('-');
+    }
+
+    let is_number = self
+      .peek()
+      .is_some_and(|ch| ch.is_ascii_digit() || ch == '.');
+
+    while let Some(ch) = self.peek() {
+      // Signs are only allowed after the exponent of a number
+      let is_exponent_sign = is_number
+        && matches!(ch, '+' | '-')
+        && token.ends_with(['e', 'E'])
+        && !token.contains(['x', 'X']);
+
+      if is_identifier_char(ch) || (is_number && ch == '.') || is_exponent_sign {
+        token.push(ch);
+        self.bump();
+      } else {
+        break;
+      }
+    }
+
+    if token.is_empty() || token == "-" {
+      return Err(match self.peek() {
+        Some(ch) => self.error(format!("unexpected character `{ch}`")),
+        None => self.error("expected a value, found the end of the input"),
+      });
+    }
+
+    if is_number {
+      Ok(TextScalar::Number(token))
+    } else {
+      Ok(TextScalar::Identifier(token))
+    }
+  }
+
+  fn parse_string(&mut self, quote: char, output: &mut Vec<u8>) -> Result<(), TextFormatError> {
+    let start = self.position();
+    self.bump();
+
+    loop {
+      let position = self.position();
+
+      let ch = match self.bump() {
+        Some(ch) if ch == quote => return Ok(()),
+        Some('\n') | None => return Err(TextFormatError::at(start, "unterminated string")),
+        Some(ch) => ch,
+      };
+
+      if ch != '\\' {
+        let mut buf = [0; 4];
+        output.extend_from_slice(ch.encode_utf8(&mut buf).as_bytes());
+        continue;
+      }
+
+      let escaped = self
+        .bump()
+        .ok_or_else(|| TextFormatError::at(start, "unterminated string"))?;
+
+      match escaped {
+        'n' => output.push(b'\n'),
+        't' => output.push(b'\t'),
+        'r' => output.push(b'\r'),
+        'a' => output.push(0x07),
+        'b' => output.push(0x08),
+        'f' => output.push(0x0c),
+        'v' => output.push(0x0b),
+        '\\' => output.push(b'\\'),
+        '\'' => output.push(b'\''),
+        '"' => output.push(b'"'),
+        '?' => output.push(b'?'),
+        '0'..='7' => {
+          let mut value = escaped.to_digit(8).unwrap_or_default();
+
+          for _ in 0..2 {
+            match self.peek().and_then(|ch| ch.to_digit(8)) {
+              Some(digit) => {
+                value = value * 8 + digit;
+                self.bump();
+              }
+              None => break,
+            }
+          }
+
+          output.push(
+            u8::try_from(value)
+              .map_err(|_| TextFormatError::at(position, "octal escape out of range"))?,
+          );
+        }
+        'x' => {
+          let digits = self.take_hex_digits(2);
+
+          let value = u8::from_str_radix(&digits, 16)
+            .map_err(|_| TextFormatError::at(position, "invalid hex escape"))?;
+
+          output.push(value);
+        }
+        'u' | 'U' => {
+          let digits = self.take_hex_digits(if escaped == 'u' { 4 } else { 8 });
+
+          let ch = u32::from_str_radix(&digits, 16)
+            .ok()
+            .and_then(char::from_u32)
+            .ok_or_else(|| TextFormatError::at(position, "invalid unicode escape"))?;
+
+          let mut buf = [0; 4];
+          output.extend_from_slice(ch.encode_utf8(&mut buf).as_bytes());
+        }
+        other => {
+          return Err(TextFormatError::at(
+            position,
+            format!("invalid escape sequence `\\{other}`"),
+          ));
+        }
+      }
+    }
+  }
+
+  fn take_hex_digits(&mut self, max: usize) -> String {
+    let mut digits = String::new();
+
+    while digits.len() < max {
+      let Some(ch) = self.peek().filter(char::is_ascii_hexdigit) else {
+        break;
+      };
+
+      digits.push(ch);
+      self.bump();
+    }
+
+    digits
+  }
+}
+
+const fn is_identifier_char(ch: char) -> bool {
+  ch.is_ascii_alphanumeric() || ch == '_'
+}
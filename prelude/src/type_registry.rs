@@ -0,0 +1,250 @@
+//! A runtime registry of the message types that can be packed inside of a `google.protobuf.Any`.
+//!
+//! With the `inventory` feature, every message defined with the macros is registered automatically, keyed by its
+//! [`type_url`](ProtoMessage::type_url). Without it, only `google.protobuf.Any`, `Duration`, `Empty`, `FieldMask` and `Timestamp`
+//! can be resolved.
+//!
+//! Type URLs are resolved by the segment that follows their last `/`, so `/my_pkg.MyMsg` and
+//! `type.googleapis.com/my_pkg.MyMsg` refer to the same type.
+//!
+//! The registry is used by [`AnyValidator`] when [`validate_payload`](AnyValidator::validate_payload) is set,
+//! which decodes the payload of the `Any` and validates it with the rules of its message. The violations of the
+//! payload are reported with the path of the `Any` field as their prefix.
+//!
+//! Messages can be packed into and unpacked from an `Any` with [`AnyExt`]:
+//!
+//! ```ignore
+//! use prelude::type_registry::AnyExt;
+//!
+//! let any = Any::pack(&msg);
+//! let unpacked: MyMsg = any.unpack()?;
+//! ```
+
+use crate::*;
+
+use proto_types::{
+  Any, Duration, Empty, FieldMask, Timestamp,
+  protovalidate::{AnyViolation, ViolationKind},
+};
+
+/// An error that occurs when unpacking a message from an `Any`.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum AnyError {
+  #[error("expected a message of type `{expected}`, found type URL `{found}`")]
+  TypeMismatch {
+    expected: &'static str,
+    found: String,
+  },
+  #[error("failed to decode the payload: {0}")]
+  Decode(prost::DecodeError),
+}
+
+/// Packs messages into a `google.protobuf.Any` and unpacks them from it.
+pub trait AnyExt: Sized {
+  /// Packs a message, using its [`type_url`](ProtoMessage::type_url).
+  fn pack<T: ProtoMessage + prost::Message>(msg: &T) -> Self;
+
+  /// Returns `true` if the type URL of this `Any` refers to `T`.
+  fn is<T: ProtoMessage>(&self) -> bool;
+
+  /// Decodes the message inside of this `Any`, after checking that its type URL refers to `T`.
+  fn unpack<T: ProtoMessage + prost::Message>(&self) -> Result<T, AnyError>;
+}
+
+impl AnyExt for Any {
+  #[inline]
+  fn pack<T: ProtoMessage + prost::Message>(msg: &T) -> Self {
+    Self {
+      type_url: T::type_url().to_string(),
+      value: msg.encode_to_vec().into(),
+    }
+  }
+
+  #[inline]
+  fn is<T: ProtoMessage>(&self) -> bool {
+    type_name_from_url(&self.type_url) == T::full_name()
+  }
+
+  fn unpack<T: ProtoMessage + prost::Message>(&self) -> Result<T, AnyError> {
+    if !self.is::<T>() {
+      return Err(AnyError::TypeMismatch {
+        expected: T::full_name(),
+        found: self.type_url.clone(),
+      });
+    }
+
+    T::decode(&self.value[..]).map_err(AnyError::Decode)
+  }
+}
+
+type ValidatePayloadFn =
+  fn(&[u8], &mut ValidationCtx) -> Result<ValidationResult, prost::DecodeError>;
+
+/// An entry in the registry of the messages that can be packed inside of a `google.protobuf.Any`.
+#[doc(hidden)]
+pub struct RegistryAnyMessage {
+  pub type_url: fn() -> &'static str,
+  pub validate: ValidatePayloadFn,
+}
+
+#[cfg(feature = "inventory")]
+inventory::collect!(RegistryAnyMessage);
+
+impl RegistryAnyMessage {
+  /// Returns the type URL of the message.
+  #[must_use]
+  #[inline]
+  pub fn type_url(&self) -> &'static str {
+    (self.type_url)()
+  }
+
+  /// Returns the full name of the message, such as `my_pkg.MyMsg`.
+  #[must_use]
+  #[inline]
+  pub fn full_name(&self) -> &'static str {
+    type_name_from_url(self.type_url())
+  }
+}
+
+impl Debug for RegistryAnyMessage {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.debug_tuple("RegistryAnyMessage")
+      .field(&self.type_url())
+      .finish()
+  }
+}
+
+/// Decodes a message from its binary encoding and validates it.
+#[doc(hidden)]
+pub fn validate_payload<M>(
+  bytes: &[u8],
+  ctx: &mut ValidationCtx,
+) -> Result<ValidationResult, prost::DecodeError>
+where
+  M: prost::Message + ValidatedMessage,
+{
+  let message = M::decode(bytes)?;
+
+  Ok(message.validate_with_ctx(ctx))
+}
+
+/// Decodes a message that has no validation rules.
+fn decode_payload<M: prost::Message + Default>(
+  bytes: &[u8],
+  _: &mut ValidationCtx,
+) -> Result<ValidationResult, prost::DecodeError> {
+  M::decode(bytes).map(|_| Ok(IsValid::Yes))
+}
+
+static WELL_KNOWN_TYPES: [RegistryAnyMessage; 5] = [
+  RegistryAnyMessage {
+    type_url: || "/google.protobuf.Any",
+    validate: decode_payload::<Any>,
+  },
+  RegistryAnyMessage {
+    type_url: || "/google.protobuf.Duration",
+    validate: decode_payload::<Duration>,
+  },
+  RegistryAnyMessage {
+    type_url: || "/google.protobuf.Empty",
+    validate: decode_payload::<Empty>,
+  },
+  RegistryAnyMessage {
+    type_url: || "/google.protobuf.FieldMask",
+    validate: decode_payload::<FieldMask>,
+  },
+  RegistryAnyMessage {
+    type_url: || "/google.protobuf.Timestamp",
+    validate: decode_payload::<Timestamp>,
+  },
+];
+
+#[cfg(feature = "inventory")]
+static TYPE_REGISTRY: Lazy<HashMap<&'static str, &'static RegistryAnyMessage>> = Lazy::new(|| {
+  WELL_KNOWN_TYPES
+    .iter()
+    .chain(inventory::iter::<RegistryAnyMessage>())
+    .map(|entry| (entry.full_name(), entry))
+    .collect()
+});
+
+fn type_name_from_url(type_url: &str) -> &str {
+  type_url
+    .rsplit_once('/')
+    .map_or(type_url, |(_, name)| name)
+}
+
+/// Looks up the registered message that a type URL refers to.
+#[must_use]
+pub fn find_type(type_url: &str) -> Option<&'static RegistryAnyMessage> {
+  let full_name = type_name_from_url(type_url);
+
+  #[cfg(feature = "inventory")]
+  {
+    TYPE_REGISTRY.get(full_name).copied()
+  }
+
+  #[cfg(not(feature = "inventory"))]
+  {
+    WELL_KNOWN_TYPES
+      .iter()
+      .find(|entry| entry.full_name() == full_name)
+  }
+}
+
+/// Returns the type URLs of all of the registered messages.
+pub fn registered_type_urls() -> impl Iterator<Item = &'static str> {
+  #[cfg(feature = "inventory")]
+  {
+    TYPE_REGISTRY
+      .values()
+      .map(|entry| entry.type_url())
+  }
+
+  #[cfg(not(feature = "inventory"))]
+  {
+    WELL_KNOWN_TYPES
+      .iter()
+      .map(RegistryAnyMessage::type_url)
+  }
+}
+
+/// Decodes the payload of an `Any` and validates it, with the current field as the prefix of the paths of its violations.
+pub(crate) fn validate_any_payload(ctx: &mut ValidationCtx, any: &Any) -> ValidationResult {
+  let Some(entry) = find_type(&any.type_url) else {
+    return ctx.add_violation_with_custom_id(
+      "any.registered",
+      ViolationKind::Any(AnyViolation::In),
+      format!("type URL `{}` is not registered", any.type_url),
+    );
+  };
+
+  let parent_element = ctx
+    .field_context
+    .as_ref()
+    .map(FieldContext::as_path_element);
+  let has_parent_element = parent_element.is_some();
+
+  if let Some(element) = parent_element {
+    ctx.parent_elements.push(element);
+  }
+
+  let field_context = core::mem::take(&mut ctx.field_context);
+
+  let result = (entry.validate)(&any.value[..], ctx);
+
+  ctx.field_context = field_context;
+
+  if has_parent_element {
+    ctx.parent_elements.pop();
+  }
+
+  match result {
+    Ok(result) => result,
+    Err(e) => ctx.add_violation_with_custom_id(
+      "any.decodable",
+      ViolationKind::Any(AnyViolation::In),
+      format!("cannot decode the payload of `{}`: {e}", any.type_url),
+    ),
+  }
+}
@@ -23,6 +23,12 @@ pub struct AnyValidator {
   /// Specifies that the values in this list will be considered NOT valid for this field.
   pub not_in: Option<SortedList<FixedStr>>,
 
+  /// Specifies that the payload must be decoded through the [type registry](crate::type_registry) and validated
+  /// with the rules of its message.
+  ///
+  /// This is a runtime-only rule, so it is not included in the schema of the validator.
+  pub validate_payload: bool,
+
   pub error_messages: Option<ErrorMessages<AnyViolation>>,
 }
 
@@ -82,6 +88,10 @@ impl AnyValidator {
 
         is_valid &= cel_ctx.execute_programs()?;
       }
+
+      if self.validate_payload {
+        is_valid &= type_registry::validate_any_payload(ctx, val)?;
+      }
     } else if self.required {
       handle_violation!(Required, "is required".to_string());
     }
@@ -123,6 +123,20 @@ impl<S: State> AnyValidatorBuilder<S> {
     }
   }
 
+  /// Decodes the payload through the [type registry](crate::type_registry) and validates it with the rules of its message.
+  #[inline]
+  pub fn validate_payload(mut self) -> AnyValidatorBuilder<SetValidatePayload<S>>
+  where
+    S::ValidatePayload: IsUnset,
+  {
+    self.data.validate_payload = true;
+
+    AnyValidatorBuilder {
+      _state: PhantomData,
+      data: self.data,
+    }
+  }
+
   #[must_use]
   #[inline]
   pub fn build(self) -> AnyValidator {
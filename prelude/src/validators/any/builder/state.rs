@@ -1,3 +1,3 @@
 use crate::validators::builder_internals::*;
 use proc_macro_impls::builder_state_macro;
-builder_state_macro!(Ignore, Required, In, NotIn, ErrorMessages);
+builder_state_macro!(Ignore, Required, In, NotIn, ValidatePayload, ErrorMessages);
@@ -0,0 +1,49 @@
+use super::*;
+
+use core::future::Future;
+
+/// A validator that needs to await some IO, such as checking that a username is not taken.
+///
+/// Async validators can be added to the `validate` attribute of fields and messages with the `async` keyword,
+/// as in `#[proto(validate = [ |v| v.min_len(3), async UsernameNotTaken ])]`.
+/// They are executed by [`validate_async`](ValidatedMessage::validate_async) after all the sync validators,
+/// and their violations are collected in the same [`ValidationErrors`], with the path of the field being validated.
+///
+/// ```rust,ignore
+/// pub struct UsernameNotTaken;
+///
+/// impl AsyncValidator<String> for UsernameNotTaken {
+///   async fn validate_async(&self, ctx: &mut ValidationCtx, val: Option<&str>) -> ValidationResult {
+///     if let Some(username) = val && db::username_exists(username).await {
+///       ctx.add_violation_with_custom_id("username.taken", ViolationKind::Cel, "is already taken")
+///     } else {
+///       Ok(IsValid::Yes)
+///     }
+///   }
+/// }
+/// ```
+pub trait AsyncValidator<T: ProtoValidation + ?Sized>: Send + Sync {
+  fn validate_async(
+    &self,
+    ctx: &mut ValidationCtx,
+    val: Option<&T::Target>,
+  ) -> impl Future<Output = ValidationResult> + Send;
+}
+
+/// Runs an [`AsyncValidator`] on a field, borrowing the stored value as the target of the validator.
+#[doc(hidden)]
+#[inline]
+pub async fn validate_field_async<T, A, V>(
+  validator: &A,
+  ctx: &mut ValidationCtx,
+  val: Option<&V>,
+) -> ValidationResult
+where
+  T: ProtoValidation + ?Sized,
+  A: AsyncValidator<T>,
+  V: Borrow<T::Target> + Sync + ?Sized,
+{
+  validator
+    .validate_async(ctx, val.map(Borrow::borrow))
+    .await
+}
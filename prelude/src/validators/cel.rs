@@ -1,5 +1,13 @@
 mod cel_trait;
 pub use cel_trait::*;
+#[cfg(feature = "cel")]
+mod cel_functions;
+#[cfg(feature = "cel")]
+pub use cel_functions::*;
+#[cfg(feature = "cel")]
+mod cel_variables;
+#[cfg(feature = "cel")]
+pub use cel_variables::*;
 
 use super::*;
 
@@ -90,15 +98,136 @@ pub use cel_impls::*;
 mod cel_impls {
   use super::*;
 
-  use ::cel::{Context, ExecutionError, Program, Value, objects::ValueType};
+  use ::cel::{
+    Context, ExecutionError, Program, Value,
+    objects::{Key, ValueType},
+  };
   use chrono::Utc;
   use core::convert::Infallible;
-  use std::sync::OnceLock;
+  use std::{collections::HashMap, sync::OnceLock};
 
   #[derive(Debug)]
   pub(crate) struct CelProgramInner {
     pub(crate) rule: CelRule,
     program: OnceLock<Program>,
+    this_usage: OnceLock<ThisUsage>,
+  }
+
+  /// Describes how an expression uses the `this` variable.
+  #[derive(Debug, Clone, PartialEq, Eq)]
+  pub(crate) enum ThisUsage {
+    /// `this` is used as a whole value, so it must be fully converted.
+    Whole,
+    /// Only these fields of `this` are accessed, with the `this.field` syntax.
+    Fields(Vec<String>),
+  }
+
+  impl ThisUsage {
+    /// Scans an expression for the uses of `this`.
+    ///
+    /// Whenever the usage cannot be determined with certainty (for example with raw or multiline strings),
+    /// this falls back to [`ThisUsage::Whole`].
+    pub(crate) fn from_expression(expression: &str) -> Self {
+      const fn is_ident_char(b: u8) -> bool {
+        b.is_ascii_alphanumeric() || b == b'_'
+      }
+
+      const fn skip_whitespace(bytes: &[u8], mut i: usize) -> usize {
+        while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+          i += 1;
+        }
+        i
+      }
+
+      const fn read_ident(bytes: &[u8], mut i: usize) -> usize {
+        while i < bytes.len() && is_ident_char(bytes[i]) {
+          i += 1;
+        }
+        i
+      }
+
+      let bytes = expression.as_bytes();
+      let mut fields: Vec<String> = Vec::new();
+      let mut prev_significant: Option<u8> = None;
+      let mut i = 0;
+
+      while i < bytes.len() {
+        let b = bytes[i];
+
+        match b {
+          b'"' | b'\'' => {
+            // Raw, bytes and triple-quoted strings are not worth handling here
+            if (i > 0 && is_ident_char(bytes[i - 1]))
+              || (bytes.get(i + 1) == Some(&b) && bytes.get(i + 2) == Some(&b))
+            {
+              return Self::Whole;
+            }
+
+            i += 1;
+
+            while i < bytes.len() && bytes[i] != b {
+              if bytes[i] == b'\\' {
+                i += 1;
+              }
+              i += 1;
+            }
+
+            i += 1;
+            prev_significant = Some(b);
+          }
+          b'/' if bytes.get(i + 1) == Some(&b'/') => {
+            while i < bytes.len() && bytes[i] != b'\n' {
+              i += 1;
+            }
+          }
+          _ if b.is_ascii_alphabetic() || b == b'_' => {
+            let end = read_ident(bytes, i);
+            let ident = &expression[i..end];
+
+            // A field named `this` in another value is not the `this` variable
+            if ident == "this" && prev_significant != Some(b'.') {
+              let dot = skip_whitespace(bytes, end);
+
+              if bytes.get(dot) != Some(&b'.') {
+                return Self::Whole;
+              }
+
+              let field_start = skip_whitespace(bytes, dot + 1);
+              let field_end = read_ident(bytes, field_start);
+
+              if field_start == field_end {
+                return Self::Whole;
+              }
+
+              let field = &expression[field_start..field_end];
+
+              if !fields.iter().any(|f| f == field) {
+                fields.push(field.to_string());
+              }
+
+              i = field_end;
+            } else {
+              i = end;
+            }
+
+            prev_significant = Some(b'a');
+          }
+          _ if b.is_ascii_digit() => {
+            i = read_ident(bytes, i);
+            prev_significant = Some(b'0');
+          }
+          _ => {
+            if !b.is_ascii_whitespace() {
+              prev_significant = Some(b);
+            }
+
+            i += 1;
+          }
+        }
+      }
+
+      Self::Fields(fields)
+    }
   }
 
   impl PartialEq for CelProgramInner {
@@ -115,17 +244,79 @@ mod cel_impls {
     }
   }
 
-  fn initialize_context<'a, T>(value: T) -> Result<Context<'a>, CelError>
-  where
-    T: TryIntoCel,
-  {
+  fn initialize_context<'a>(this: Value, data: Option<&ValidationData>) -> Context<'a> {
     let mut ctx = Context::default();
 
-    ctx.add_variable_from_value("this", value.__try_into_cel()?);
+    install_cel_functions(&mut ctx);
+
+    if let Some(data) = data {
+      for (name, value) in data.cel_variables() {
+        ctx.add_variable_from_value(name.to_string(), value.clone());
+      }
+    }
+
+    ctx.add_variable_from_value("this", this);
     #[cfg(all(feature = "chrono", any(feature = "std", feature = "chrono-wasm")))]
     ctx.add_variable_from_value("now", Value::Timestamp(Utc::now().into()));
 
-    Ok(ctx)
+    ctx
+  }
+
+  /// Builds the value of `this` for a list of programs executed on a message.
+  ///
+  /// If the programs only access some fields of `this`, only those fields are converted,
+  /// without cloning the rest of the message.
+  fn message_this<T>(programs: &[CelProgram], value: &T) -> Result<Value, CelError>
+  where
+    T: CelFields,
+  {
+    let mut used_fields: Vec<&str> = Vec::new();
+
+    for program in programs {
+      match program.inner.this_usage() {
+        ThisUsage::Whole => return value.clone().__try_into_cel(),
+        ThisUsage::Fields(fields) => {
+          for field in fields {
+            if !used_fields.contains(&field.as_str()) {
+              used_fields.push(field.as_str());
+            }
+          }
+        }
+      }
+    }
+
+    let mut fields: HashMap<Key, Value> = HashMap::new();
+
+    for name in used_fields {
+      if let Some(field_value) = value.__cel_field(name)? {
+        fields.insert(name.to_string().into(), field_value);
+      }
+    }
+
+    Ok(Value::Map(fields.into()))
+  }
+
+  fn run_programs(
+    programs: &[CelProgram],
+    this: Value,
+    ctx: &mut ValidationCtx,
+  ) -> ValidationResult {
+    let mut is_valid = IsValid::Yes;
+
+    let cel_ctx = initialize_context(this, ctx.data.as_deref());
+
+    for program in programs {
+      match program.execute(&cel_ctx) {
+        Ok(was_successful) => {
+          if !was_successful {
+            is_valid &= ctx.add_cel_violation(&program.inner.rule)?;
+          }
+        }
+        Err(e) => is_valid &= ctx.add_cel_error_violation(e)?,
+      };
+    }
+
+    Ok(is_valid)
   }
 
   pub(crate) struct ProgramsExecutionCtx<'a, T> {
@@ -145,32 +336,49 @@ mod cel_impls {
         ctx,
       } = self;
 
-      let mut is_valid = IsValid::Yes;
-
-      let cel_ctx = match initialize_context(value) {
-        Ok(cel_ctx) => cel_ctx,
+      match value.__try_into_cel() {
+        Ok(this) => run_programs(programs, this, ctx),
         Err(e) => {
           let _ = ctx.add_cel_error_violation(e);
-          return Err(FailFast);
+          Err(FailFast)
         }
-      };
-
-      for program in programs {
-        match program.execute(&cel_ctx) {
-          Ok(was_successful) => {
-            if !was_successful {
-              is_valid &= ctx.add_cel_violation(&program.inner.rule)?;
-            }
-          }
-          Err(e) => is_valid &= ctx.add_cel_error_violation(e)?,
-        };
       }
+    }
+  }
 
-      Ok(is_valid)
+  /// Like [`ProgramsExecutionCtx`], but for messages, which are borrowed and only converted
+  /// as much as the programs require.
+  pub(crate) struct MessageProgramsExecutionCtx<'a, T> {
+    pub programs: &'a [CelProgram],
+    pub value: &'a T,
+    pub ctx: &'a mut ValidationCtx,
+  }
+
+  impl<T> MessageProgramsExecutionCtx<'_, T>
+  where
+    T: CelFields,
+  {
+    pub fn execute_programs(self) -> ValidationResult {
+      let Self {
+        programs,
+        value,
+        ctx,
+      } = self;
+
+      match message_this(programs, value) {
+        Ok(this) => run_programs(programs, this, ctx),
+        Err(e) => {
+          let _ = ctx.add_cel_error_violation(e);
+          Err(FailFast)
+        }
+      }
     }
   }
 
   /// Tests the validity of CEL programs.
+  ///
+  /// References to the variables registered with [`register_cel_variables`](crate::register_cel_variables) are not reported,
+  /// since they are provided by a [`ValidationData`] at validation time.
   #[inline(never)]
   #[cold]
   pub fn test_programs<T>(programs: &[CelProgram], value: T) -> Result<(), Vec<CelError>>
@@ -179,8 +387,8 @@ mod cel_impls {
   {
     let mut errors: Vec<CelError> = Vec::new();
 
-    let ctx = match initialize_context(value) {
-      Ok(ctx) => ctx,
+    let ctx = match value.__try_into_cel() {
+      Ok(this) => initialize_context(this, None),
       Err(e) => {
         errors.push(e);
         return Err(errors);
@@ -188,7 +396,11 @@ mod cel_impls {
     };
 
     for program in programs {
-      if let Err(e) = program.execute(&ctx) {
+      if let Err(e) = program.execute(&ctx)
+        && !e
+          .undeclared_reference()
+          .is_some_and(is_declared_cel_variable)
+      {
         errors.push(e);
       }
     }
@@ -201,6 +413,13 @@ mod cel_impls {
   }
 
   impl CelProgramInner {
+    #[inline]
+    pub(crate) fn this_usage(&self) -> &ThisUsage {
+      self
+        .this_usage
+        .get_or_init(|| ThisUsage::from_expression(&self.rule.expression))
+    }
+
     #[inline]
     fn get_program(&self) -> &Program {
       self
@@ -228,6 +447,7 @@ mod cel_impls {
         inner: Arc::new(CelProgramInner {
           rule,
           program: OnceLock::new(),
+          this_usage: OnceLock::new(),
         }),
       }
     }
@@ -260,6 +480,21 @@ mod cel_impls {
   }
 
   impl CelError {
+    /// Returns the name of the unknown variable that the program refers to, if that is the cause of the error.
+    ///
+    /// This can happen when testing programs that use the variables of a [`ValidationData`] without it.
+    #[must_use]
+    #[inline]
+    pub fn undeclared_reference(&self) -> Option<&str> {
+      match self {
+        Self::ExecutionError { source, .. } => match &**source {
+          ExecutionError::UndeclaredReference(name) => Some(name.as_str()),
+          _ => None,
+        },
+        _ => None,
+      }
+    }
+
     #[must_use]
     #[inline]
     pub fn rule_id(&self) -> Option<&str> {
@@ -398,4 +633,46 @@ mod cel_impls {
       match value {}
     }
   }
+
+  #[cfg(test)]
+  mod test {
+    use super::*;
+
+    fn fields(list: &[&str]) -> ThisUsage {
+      ThisUsage::Fields(list.iter().map(|f| f.to_string()).collect())
+    }
+
+    #[test]
+    fn this_usage() {
+      assert_eq!(
+        ThisUsage::from_expression("this.start < this.end"),
+        fields(&["start", "end"])
+      );
+      assert_eq!(
+        ThisUsage::from_expression("has(this.email) || this . phone.size() > 0"),
+        fields(&["email", "phone"])
+      );
+      assert_eq!(
+        ThisUsage::from_expression("this.items.all(i, i.this > 0) && 'this' != ''"),
+        fields(&["items"])
+      );
+      assert_eq!(
+        ThisUsage::from_expression("// uses this\nthis.id == 1"),
+        fields(&["id"])
+      );
+      assert_eq!(
+        ThisUsage::from_expression("size(this) > 0"),
+        ThisUsage::Whole
+      );
+      assert_eq!(
+        ThisUsage::from_expression("this['id'] == 1"),
+        ThisUsage::Whole
+      );
+      assert_eq!(
+        ThisUsage::from_expression("this.id == 1 && r'\\' != this"),
+        ThisUsage::Whole
+      );
+      assert_eq!(ThisUsage::from_expression("now > now"), fields(&[]));
+    }
+  }
 }
@@ -0,0 +1,45 @@
+use crate::*;
+
+use ::cel::Context;
+use std::sync::{PoisonError, RwLock};
+
+/// A function that adds custom functions to a CEL [`Context`] with [`Context::add_function`].
+pub type CelFunctionsInstaller = fn(&mut Context<'_>);
+
+/// An entry in the global registry of custom CEL functions.
+#[doc(hidden)]
+pub struct RegistryCelFunctions {
+  pub install: CelFunctionsInstaller,
+}
+
+#[cfg(feature = "inventory")]
+inventory::collect!(RegistryCelFunctions);
+
+static RUNTIME_CEL_FUNCTIONS: RwLock<Vec<CelFunctionsInstaller>> = RwLock::new(Vec::new());
+
+/// Registers custom CEL functions at runtime. They are installed in every CEL context created after this call.
+///
+/// Prefer [`register_cel_functions`](crate::register_cel_functions) when the `inventory` feature is available,
+/// so that the functions are also available in the automatically generated consistency checks.
+pub fn register_runtime_cel_functions(install: CelFunctionsInstaller) {
+  RUNTIME_CEL_FUNCTIONS
+    .write()
+    .unwrap_or_else(PoisonError::into_inner)
+    .push(install);
+}
+
+/// Installs all of the registered custom functions in a CEL context.
+pub(crate) fn install_cel_functions(ctx: &mut Context<'_>) {
+  #[cfg(feature = "inventory")]
+  for entry in inventory::iter::<RegistryCelFunctions>() {
+    (entry.install)(ctx);
+  }
+
+  for install in RUNTIME_CEL_FUNCTIONS
+    .read()
+    .unwrap_or_else(PoisonError::into_inner)
+    .iter()
+  {
+    install(ctx);
+  }
+}
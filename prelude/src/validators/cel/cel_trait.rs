@@ -37,6 +37,33 @@ where
   }
 }
 
+/// Converts single fields of a message into CEL values, so that the CEL programs that only read
+/// some fields of `this` do not need to clone and convert the whole message.
+#[cfg(feature = "cel")]
+#[doc(hidden)]
+pub trait CelFields: TryIntoCel {
+  /// Returns the CEL value of the field with the given name, or `None` if the field is not present
+  /// in the CEL representation of this message.
+  fn __cel_field(&self, name: &str) -> Result<Option<::cel::Value>, CelError> {
+    match self.clone().__try_into_cel()? {
+      ::cel::Value::Map(map) => Ok(
+        map
+          .map
+          .get(&::cel::objects::Key::from(name.to_string()))
+          .cloned(),
+      ),
+      _ => Ok(None),
+    }
+  }
+}
+
+#[cfg(not(feature = "cel"))]
+#[doc(hidden)]
+pub trait CelFields {}
+#[cfg(not(feature = "cel"))]
+#[doc(hidden)]
+impl<T> CelFields for T {}
+
 #[cfg(feature = "cel")]
 pub trait CelValue: Clone + TryInto<::cel::Value, Error = CelConversionError> {
   #[inline]
@@ -0,0 +1,39 @@
+use crate::*;
+
+use std::sync::{PoisonError, RwLock};
+
+/// An entry in the global registry of the CEL variables provided by a [`ValidationData`].
+#[doc(hidden)]
+pub struct RegistryCelVariables {
+  pub names: &'static [&'static str],
+}
+
+#[cfg(feature = "inventory")]
+inventory::collect!(RegistryCelVariables);
+
+static RUNTIME_CEL_VARIABLES: RwLock<Vec<&'static str>> = RwLock::new(Vec::new());
+
+/// Declares the names of CEL variables at runtime, so that the consistency checks performed after this call
+/// do not report them as undeclared.
+///
+/// Prefer [`register_cel_variables`](crate::register_cel_variables) when the `inventory` feature is available,
+/// so that the variables are also known to the automatically generated consistency checks.
+pub fn register_runtime_cel_variables(names: &'static [&'static str]) {
+  RUNTIME_CEL_VARIABLES
+    .write()
+    .unwrap_or_else(PoisonError::into_inner)
+    .extend_from_slice(names);
+}
+
+/// Checks if the name belongs to a registered CEL variable.
+pub(crate) fn is_declared_cel_variable(name: &str) -> bool {
+  #[cfg(feature = "inventory")]
+  if inventory::iter::<RegistryCelVariables>().any(|entry| entry.names.contains(&name)) {
+    return true;
+  }
+
+  RUNTIME_CEL_VARIABLES
+    .read()
+    .unwrap_or_else(PoisonError::into_inner)
+    .contains(&name)
+}
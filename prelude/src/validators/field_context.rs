@@ -65,6 +65,15 @@ pub struct ValidationCtx {
   pub parent_elements: Vec<FieldPathElement>,
   pub violations: ValidationErrors,
   pub fail_fast: bool,
+  /// The active [validation profile](ValidationProfile). `None` stands for the default profile.
+  pub profile: Option<&'static str>,
+  /// The mask that restricts the validation of the current message, if there is one.
+  ///
+  /// Set it with [`with_mask`](ValidationCtx::with_mask).
+  #[cfg(feature = "field-mask")]
+  pub mask: Option<crate::field_masks::MaskTree>,
+  /// The request-scoped [`ValidationData`] that is available to the validators.
+  pub data: Option<Arc<ValidationData>>,
 }
 
 impl Default for ValidationCtx {
@@ -75,11 +84,47 @@ impl Default for ValidationCtx {
       parent_elements: vec![],
       violations: ValidationErrors::new(),
       fail_fast: true,
+      profile: None,
+      #[cfg(feature = "field-mask")]
+      mask: None,
+      data: None,
     }
   }
 }
 
 impl ValidationCtx {
+  /// Makes the given [`ValidationData`] available to the validators.
+  #[inline]
+  pub fn with_data(&mut self, data: impl Into<Arc<ValidationData>>) -> &mut Self {
+    self.data = Some(data.into());
+    self
+  }
+
+  /// Returns the value of the given type from the [`ValidationData`], if present.
+  #[inline]
+  #[must_use]
+  pub fn data<T: core::any::Any>(&self) -> Option<&T> {
+    self
+      .data
+      .as_deref()
+      .and_then(|data| data.get::<T>())
+  }
+
+  /// Returns the name of the active validation profile.
+  #[inline]
+  #[must_use]
+  pub fn profile(&self) -> &'static str {
+    self.profile.unwrap_or(DEFAULT_PROFILE)
+  }
+
+  /// Checks if the active profile is one of the given groups.
+  #[doc(hidden)]
+  #[inline]
+  #[must_use]
+  pub fn is_in_groups(&self, groups: &[&str]) -> bool {
+    groups.contains(&self.profile())
+  }
+
   #[inline]
   pub fn with_field_context(&mut self, field_context: FieldContext) -> &mut Self {
     self.field_context = Some(field_context);
@@ -25,6 +25,11 @@ pub struct FieldMaskValidator {
   /// Specifies that only this specific value will be considered valid for this field.
   pub const_: Option<SortedList<FixedStr>>,
 
+  /// Specifies that every path must resolve to a field of a specific message.
+  #[cfg(feature = "field-mask")]
+  #[cfg_attr(feature = "serde", serde(skip))]
+  pub message_paths: Option<field_masks::MessagePaths>,
+
   pub error_messages: Option<ErrorMessages<FieldMaskViolation>>,
 }
 
@@ -106,6 +111,21 @@ impl FieldMaskValidator {
         }
       }
 
+      #[cfg(feature = "field-mask")]
+      if let Some(message_paths) = &self.message_paths {
+        for path in &val.paths {
+          if let Err(e) = message_paths.check(path) {
+            is_valid &= ctx.add_violation_with_custom_id(
+              "field_mask.message_paths",
+              ViolationKind::FieldMask(FieldMaskViolation::In),
+              e.to_string(),
+            )?;
+
+            break;
+          }
+        }
+      }
+
       #[cfg(feature = "cel")]
       if !self.cel.is_empty() {
         let cel_ctx = ProgramsExecutionCtx {
@@ -155,7 +175,14 @@ impl Validator<FieldMask> for FieldMaskValidator {
       };
     }
 
-    if self.const_.is_some() && (!self.cel.is_empty() || check_prop_some!(in_, not_in)) {
+    #[cfg(feature = "field-mask")]
+    let has_message_paths = self.message_paths.is_some();
+    #[cfg(not(feature = "field-mask"))]
+    let has_message_paths = false;
+
+    if self.const_.is_some()
+      && (!self.cel.is_empty() || check_prop_some!(in_, not_in) || has_message_paths)
+    {
       errors.push(ConsistencyError::ConstWithOtherRules);
     }
 
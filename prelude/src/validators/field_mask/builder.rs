@@ -135,6 +135,25 @@ impl<S: State> FieldMaskValidatorBuilder<S> {
     }
   }
 
+  /// Specifies that every path must resolve to a field of the message `T`.
+  ///
+  /// This rule is only checked at runtime, so it is not included in the schema.
+  #[cfg(feature = "field-mask")]
+  #[inline]
+  pub fn paths_of<T: field_masks::ProtoMask>(
+    mut self,
+  ) -> FieldMaskValidatorBuilder<SetMessagePaths<S>>
+  where
+    S::MessagePaths: IsUnset,
+  {
+    self.data.message_paths = Some(field_masks::MessagePaths::of::<T>());
+
+    FieldMaskValidatorBuilder {
+      _state: PhantomData,
+      data: self.data,
+    }
+  }
+
   #[inline]
   #[must_use]
   pub fn build(self) -> FieldMaskValidator {
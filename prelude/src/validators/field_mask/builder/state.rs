@@ -1,3 +1,11 @@
 use crate::validators::builder_internals::*;
 use proc_macro_impls::builder_state_macro;
-builder_state_macro!(Const, Required, Ignore, In, NotIn, ErrorMessages);
+builder_state_macro!(
+  Const,
+  Required,
+  Ignore,
+  In,
+  NotIn,
+  MessagePaths,
+  ErrorMessages
+);
@@ -1,8 +1,15 @@
 mod builder;
 pub use builder::MessageValidatorBuilder;
+mod conditional;
+pub use conditional::*;
+mod rules;
+pub use rules::*;
 
 use super::*;
 
+#[cfg(feature = "async")]
+use core::future::Future;
+
 pub trait ValidatedMessage: ProtoValidation + Default + Clone {
   #[inline]
   fn validate_all(&self) -> Result<(), ValidationErrors> {
@@ -11,10 +18,8 @@ pub trait ValidatedMessage: ProtoValidation + Default + Clone {
     }
 
     let mut ctx = ValidationCtx {
-      field_context: None,
-      parent_elements: vec![],
-      violations: ValidationErrors::new(),
       fail_fast: false,
+      ..Default::default()
     };
 
     let _ = self.validate_with_ctx(&mut ctx);
@@ -43,6 +48,237 @@ pub trait ValidatedMessage: ProtoValidation + Default + Clone {
     }
   }
 
+  /// Validates this message with the given [`ValidationProfile`], stopping at the first violation.
+  ///
+  /// Validators without groups are always executed, whereas those with groups only run if the profile is one of them.
+  #[inline]
+  fn validate_with_profile(&self, profile: impl ValidationProfile) -> Result<(), ValidationErrors> {
+    if !Self::HAS_DEFAULT_VALIDATOR {
+      return Ok(());
+    }
+
+    let mut ctx = ValidationCtx {
+      profile: Some(profile.profile_name()),
+      ..Default::default()
+    };
+
+    let _ = self.validate_with_ctx(&mut ctx);
+
+    if ctx.violations.is_empty() {
+      Ok(())
+    } else {
+      Err(ctx.violations)
+    }
+  }
+
+  /// Validates this message with the given [`ValidationProfile`], collecting all of the violations.
+  #[inline]
+  fn validate_all_with_profile(
+    &self,
+    profile: impl ValidationProfile,
+  ) -> Result<(), ValidationErrors> {
+    if !Self::HAS_DEFAULT_VALIDATOR {
+      return Ok(());
+    }
+
+    let mut ctx = ValidationCtx {
+      fail_fast: false,
+      profile: Some(profile.profile_name()),
+      ..Default::default()
+    };
+
+    let _ = self.validate_with_ctx(&mut ctx);
+
+    if ctx.violations.is_empty() {
+      Ok(())
+    } else {
+      Err(ctx.violations)
+    }
+  }
+
+  /// Validates this message with the given [`ValidationData`], stopping at the first violation.
+  ///
+  /// The data can be read by custom validators with [`ValidationCtx::data`], and its CEL variables can be used in CEL expressions.
+  /// It can be passed as an [`Arc`] so that it can be shared between requests without being cloned.
+  #[inline]
+  fn validate_with(&self, data: impl Into<Arc<ValidationData>>) -> Result<(), ValidationErrors> {
+    if !Self::HAS_DEFAULT_VALIDATOR {
+      return Ok(());
+    }
+
+    let mut ctx = ValidationCtx {
+      data: Some(data.into()),
+      ..Default::default()
+    };
+
+    let _ = self.validate_with_ctx(&mut ctx);
+
+    if ctx.violations.is_empty() {
+      Ok(())
+    } else {
+      Err(ctx.violations)
+    }
+  }
+
+  /// Validates this message with the given [`ValidationData`], collecting all of the violations.
+  #[inline]
+  fn validate_all_with(
+    &self,
+    data: impl Into<Arc<ValidationData>>,
+  ) -> Result<(), ValidationErrors> {
+    if !Self::HAS_DEFAULT_VALIDATOR {
+      return Ok(());
+    }
+
+    let mut ctx = ValidationCtx {
+      fail_fast: false,
+      data: Some(data.into()),
+      ..Default::default()
+    };
+
+    let _ = self.validate_with_ctx(&mut ctx);
+
+    if ctx.violations.is_empty() {
+      Ok(())
+    } else {
+      Err(ctx.violations)
+    }
+  }
+
+  /// Validates only the fields in the mask (and the nested messages in its paths), stopping at the first violation.
+  ///
+  /// An empty mask validates the whole message. See the [`field_masks`](crate::field_masks) module for more information.
+  #[cfg(feature = "field-mask")]
+  #[inline]
+  fn validate_masked(&self, mask: &proto_types::FieldMask) -> Result<(), ValidationErrors> {
+    if !Self::HAS_DEFAULT_VALIDATOR {
+      return Ok(());
+    }
+
+    let mut ctx = ValidationCtx::default();
+    ctx.with_mask(mask);
+
+    let _ = self.validate_with_ctx(&mut ctx);
+
+    if ctx.violations.is_empty() {
+      Ok(())
+    } else {
+      Err(ctx.violations)
+    }
+  }
+
+  /// Validates this message with the sync validators and then, if they succeeded, with the [async validators](AsyncValidator),
+  /// stopping at the first violation.
+  #[cfg(feature = "async")]
+  #[inline]
+  fn validate_async(&self) -> impl Future<Output = Result<(), ValidationErrors>> + Send
+  where
+    Self: Sync,
+  {
+    async move {
+      let mut ctx = ValidationCtx::default();
+
+      if self.validate_with_ctx(&mut ctx).is_ok() && ctx.violations.is_empty() {
+        let _ = self.validate_async_with_ctx(&mut ctx).await;
+      }
+
+      if ctx.violations.is_empty() {
+        Ok(())
+      } else {
+        Err(ctx.violations)
+      }
+    }
+  }
+
+  /// Validates this message with the sync validators and then with the [async validators](AsyncValidator),
+  /// collecting all of the violations.
+  #[cfg(feature = "async")]
+  #[inline]
+  fn validate_all_async(&self) -> impl Future<Output = Result<(), ValidationErrors>> + Send
+  where
+    Self: Sync,
+  {
+    async move {
+      let mut ctx = ValidationCtx {
+        fail_fast: false,
+        ..Default::default()
+      };
+
+      let _ = self.validate_with_ctx(&mut ctx);
+      let _ = self.validate_async_with_ctx(&mut ctx).await;
+
+      if ctx.violations.is_empty() {
+        Ok(())
+      } else {
+        Err(ctx.violations)
+      }
+    }
+  }
+
+  /// Validates this message with the given [`ValidationData`], running the sync validators and then, if they succeeded,
+  /// the [async validators](AsyncValidator), stopping at the first violation.
+  ///
+  /// The data is available to both kinds of validators through [`ValidationCtx::data`].
+  #[cfg(feature = "async")]
+  #[inline]
+  fn validate_async_with(
+    &self,
+    data: impl Into<Arc<ValidationData>>,
+  ) -> impl Future<Output = Result<(), ValidationErrors>> + Send
+  where
+    Self: Sync,
+  {
+    let data = data.into();
+
+    async move {
+      let mut ctx = ValidationCtx {
+        data: Some(data),
+        ..Default::default()
+      };
+
+      if self.validate_with_ctx(&mut ctx).is_ok() && ctx.violations.is_empty() {
+        let _ = self.validate_async_with_ctx(&mut ctx).await;
+      }
+
+      if ctx.violations.is_empty() {
+        Ok(())
+      } else {
+        Err(ctx.violations)
+      }
+    }
+  }
+
+  /// Validates this message with the given [`ValidationData`], running the sync validators and then the
+  /// [async validators](AsyncValidator), collecting all of the violations.
+  #[cfg(feature = "async")]
+  #[inline]
+  fn validate_all_async_with(
+    &self,
+    data: impl Into<Arc<ValidationData>>,
+  ) -> impl Future<Output = Result<(), ValidationErrors>> + Send
+  where
+    Self: Sync,
+  {
+    let data = data.into();
+
+    async move {
+      let mut ctx = ValidationCtx {
+        fail_fast: false,
+        data: Some(data),
+        ..Default::default()
+      };
+
+      let _ = self.validate_with_ctx(&mut ctx);
+      let _ = self.validate_async_with_ctx(&mut ctx).await;
+
+      if ctx.violations.is_empty() {
+        Ok(())
+      } else {
+        Err(ctx.violations)
+      }
+    }
+  }
+
   #[inline]
   fn is_valid(&self) -> bool {
     if Self::HAS_DEFAULT_VALIDATOR {
@@ -64,13 +300,46 @@ pub trait ValidatedMessage: ProtoValidation + Default + Clone {
     }
   }
 
+  /// Applies the [normalizers](crate::normalize) of the fields of this message, and of its nested messages and oneofs.
+  #[cfg(feature = "normalize")]
+  #[inline]
+  fn normalize(&mut self)
+  where
+    Self: crate::normalize::ProtoNormalize,
+  {
+    crate::normalize::ProtoNormalize::normalize_fields(self);
+  }
+
+  /// Normalizes this message and then validates it.
+  #[cfg(feature = "normalize")]
+  #[inline]
+  fn normalized_and_validated(mut self) -> Result<Self, ValidationErrors>
+  where
+    Self: crate::normalize::ProtoNormalize,
+  {
+    self.normalize();
+
+    self.validated()
+  }
+
   #[doc(hidden)]
   fn validate_with_ctx(&self, ctx: &mut ValidationCtx) -> ValidationResult;
+
+  /// Runs the async validators of this message and of its nested messages.
+  #[cfg(feature = "async")]
+  #[doc(hidden)]
+  #[inline]
+  fn validate_async_with_ctx(
+    &self,
+    _ctx: &mut ValidationCtx,
+  ) -> impl Future<Output = ValidationResult> + Send {
+    core::future::ready(Ok(IsValid::Yes))
+  }
 }
 
 impl<T, S: builder::State> ValidatorBuilderFor<T> for MessageValidatorBuilder<S>
 where
-  T: ValidatedMessage + PartialEq + TryIntoCel,
+  T: ValidatedMessage + PartialEq + CelFields,
 {
   type Target = T;
   type Validator = MessageValidator;
@@ -83,7 +352,7 @@ where
 
 impl<T> Validator<T> for MessageValidator
 where
-  T: ValidatedMessage + PartialEq + TryIntoCel,
+  T: ValidatedMessage + PartialEq + CelFields,
 {
   type Target = T;
 
@@ -156,9 +425,9 @@ where
 
       #[cfg(feature = "cel")]
       if !self.cel.is_empty() {
-        let cel_ctx = ProgramsExecutionCtx {
+        let cel_ctx = MessageProgramsExecutionCtx {
           programs: &self.cel,
-          value: val.clone(),
+          value: val,
           ctx,
         };
 
@@ -200,7 +469,7 @@ impl CelValidator {
 
 impl<T> Validator<T> for CelValidator
 where
-  T: ValidatedMessage + PartialEq + TryIntoCel + Default + Clone,
+  T: ValidatedMessage + PartialEq + CelFields + Default + Clone,
 {
   type Target = T;
 
@@ -259,9 +528,9 @@ where
 
       #[cfg(feature = "cel")]
       if !self.programs.is_empty() {
-        let cel_ctx = ProgramsExecutionCtx {
+        let cel_ctx = MessageProgramsExecutionCtx {
           programs: &self.programs,
-          value: val.clone(),
+          value: val,
           ctx,
         };
 
@@ -0,0 +1,129 @@
+use super::*;
+
+/// Builds the message-level rules for a field whose validators only apply when a condition is met.
+///
+/// Field rules cannot refer to other fields in protovalidate, so the rules of a conditional field
+/// are rendered as message-level CEL rules guarded by the condition.
+/// `required` is turned into a presence check, and the CEL rules of the field are rewritten to refer to
+/// the field rather than to `this`. Other standard rules have no CEL equivalent, so they are only
+/// enforced by the generated validator.
+#[doc(hidden)]
+#[must_use]
+pub fn conditional_field_rules(
+  field_name: &str,
+  has_presence: bool,
+  condition: &str,
+  validators: impl IntoIterator<Item = Option<ValidatorSchema>>,
+) -> Option<ValidatorSchema> {
+  let mut rules: Vec<CelRule> = Vec::new();
+
+  for validator in validators.into_iter().flatten() {
+    let is_required = matches!(
+      &validator.schema.value,
+      OptionValue::Message(options) if matches!(options.get("required"), Some(OptionValue::Bool(true)))
+    );
+
+    if is_required {
+      rules.push(CelRule {
+        id: format!("{field_name}.required").into(),
+        message: "is required".into(),
+        expression: format!("!({condition}) || has(this.{field_name})").into(),
+      });
+    }
+
+    for rule in validator.cel_rules {
+      let expression = rebind_this(&rule.expression, field_name);
+
+      // Field rules are not evaluated for fields with presence that are not set
+      let expression = if has_presence {
+        format!("!({condition}) || !has(this.{field_name}) || ({expression})")
+      } else {
+        format!("!({condition}) || ({expression})")
+      };
+
+      rules.push(CelRule {
+        id: rule.id,
+        message: rule.message,
+        expression: expression.into(),
+      });
+    }
+  }
+
+  if rules.is_empty() {
+    return None;
+  }
+
+  let mut options = OptionMessageBuilder::new();
+
+  options.add_cel_rules(rules.iter().cloned());
+
+  Some(ValidatorSchema {
+    schema: ProtoOption {
+      name: "(buf.validate.message)".into(),
+      value: OptionValue::Message(options.into()),
+    },
+    cel_rules: rules,
+    imports: vec!["buf/validate/validate.proto".into()],
+  })
+}
+
+/// Replaces the `this` identifier of a field-level CEL expression with an access to the field
+/// from the parent message, leaving string literals and member accesses untouched.
+fn rebind_this(expression: &str, field_name: &str) -> String {
+  let mut output = String::with_capacity(expression.len() + field_name.len());
+  let mut chars = expression.char_indices().peekable();
+  let mut previous: Option<char> = None;
+
+  while let Some((i, c)) = chars.next() {
+    match c {
+      '"' | '\'' => {
+        output.push(c);
+
+        while let Some((_, inner)) = chars.next() {
+          output.push(inner);
+
+          if inner == '\\' {
+            if let Some((_, escaped)) = chars.next() {
+              output.push(escaped);
+            }
+          } else if inner == c {
+            break;
+          }
+        }
+
+        previous = Some(c);
+        continue;
+      }
+      c if c.is_alphabetic() || c == '_' => {
+        let mut end = i + c.len_utf8();
+
+        while let Some(&(j, next)) = chars.peek() {
+          if next.is_alphanumeric() || next == '_' {
+            end = j + next.len_utf8();
+            chars.next();
+          } else {
+            break;
+          }
+        }
+
+        let ident = &expression[i..end];
+
+        if ident == "this" && previous != Some('.') {
+          write!(output, "this.{field_name}").unwrap();
+        } else {
+          output.push_str(ident);
+        }
+
+        previous = ident.chars().last();
+        continue;
+      }
+      _ => output.push(c),
+    }
+
+    if !c.is_whitespace() {
+      previous = Some(c);
+    }
+  }
+
+  output
+}
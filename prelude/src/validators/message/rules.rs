@@ -0,0 +1,540 @@
+use super::*;
+
+use proto_types::field_descriptor_proto::Type as ProtoPrimitive;
+
+/// A reference to a field of a message, used to define the typed rules of a [`MessageRules`] validator.
+///
+/// In the closures used with the message-level `validate` attribute, a `FieldRef` is available for each
+/// (non-oneof) field of the message, with the same name as the field:
+///
+/// ```rust,ignore
+/// #[proto_message]
+/// #[proto(validate = |v| v.field_lt(start, end))]
+/// pub struct Interval {
+///   pub start: i64,
+///   pub end: i64,
+/// }
+/// ```
+pub struct FieldRef<T, V: ?Sized> {
+  /// The proto name of the field.
+  pub name: &'static str,
+  pub tag: i32,
+  pub field_type: ProtoPrimitive,
+  /// Whether the field tracks presence, in which case the rules that compare its value are skipped when it's not set.
+  pub has_presence: bool,
+  /// Returns the value of the field, if it's set.
+  pub get: fn(&T) -> Option<&V>,
+  /// Returns `true` if the field is set (or has a non-default value, for fields with implicit presence).
+  pub is_set: fn(&T) -> bool,
+}
+
+impl<T, V: ?Sized> Clone for FieldRef<T, V> {
+  #[inline]
+  fn clone(&self) -> Self {
+    *self
+  }
+}
+
+impl<T, V: ?Sized> Copy for FieldRef<T, V> {}
+
+impl<T, V: ?Sized> Debug for FieldRef<T, V> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.debug_struct("FieldRef")
+      .field("name", &self.name)
+      .field("tag", &self.tag)
+      .finish_non_exhaustive()
+  }
+}
+
+impl<T, V: ?Sized> FieldRef<T, V> {
+  /// Returns the [`FieldContext`] for this field, which is used to report violations with its path.
+  #[must_use]
+  pub fn field_context(&self) -> FieldContext {
+    FieldContext {
+      name: self.name.into(),
+      tag: self.tag,
+      subscript: None,
+      map_key_type: None,
+      map_value_type: None,
+      field_type: self.field_type,
+      field_kind: FieldKind::default(),
+    }
+  }
+
+  /// Adds a violation for this field, with the path of the message being validated as its prefix.
+  pub fn add_violation(
+    &self,
+    ctx: &mut ValidationCtx,
+    rule_id: impl Into<String>,
+    error_message: impl Into<String>,
+  ) -> ValidationResult {
+    let parent_context = ctx.field_context.replace(self.field_context());
+
+    let result = ctx.add_violation_with_custom_id(rule_id, ViolationKind::Cel, error_message);
+
+    ctx.field_context = parent_context;
+
+    result
+  }
+
+  fn cel_access(&self) -> String {
+    format!("this.{}", self.name)
+  }
+
+  fn cel_presence_check(&self) -> String {
+    format!("has(this.{})", self.name)
+  }
+
+  fn erase(&self) -> FieldPresenceRef<T> {
+    FieldPresenceRef {
+      name: self.name,
+      is_set: self.is_set,
+    }
+  }
+}
+
+/// The name and the presence check of a field, used by the rules that only check whether fields are set.
+#[doc(hidden)]
+pub struct FieldPresenceRef<T> {
+  name: &'static str,
+  is_set: fn(&T) -> bool,
+}
+
+/// A list of [`FieldRef`]s with possibly different value types, such as a tuple or an array.
+pub trait FieldRefList<T> {
+  #[doc(hidden)]
+  fn presence_refs(&self) -> Vec<FieldPresenceRef<T>>;
+}
+
+impl<T, V: ?Sized, const N: usize> FieldRefList<T> for [FieldRef<T, V>; N] {
+  fn presence_refs(&self) -> Vec<FieldPresenceRef<T>> {
+    self.iter().map(FieldRef::erase).collect()
+  }
+}
+
+macro_rules! impl_field_ref_list {
+  ($($ty:ident => $idx:tt),+) => {
+    impl<T, $($ty: ?Sized),+> FieldRefList<T> for ($(FieldRef<T, $ty>,)+) {
+      fn presence_refs(&self) -> Vec<FieldPresenceRef<T>> {
+        vec![$(self.$idx.erase()),+]
+      }
+    }
+  };
+}
+
+impl_field_ref_list!(A => 0, B => 1);
+impl_field_ref_list!(A => 0, B => 1, C => 2);
+impl_field_ref_list!(A => 0, B => 1, C => 2, D => 3);
+impl_field_ref_list!(A => 0, B => 1, C => 2, D => 3, E => 4);
+impl_field_ref_list!(A => 0, B => 1, C => 2, D => 3, E => 4, F => 5);
+
+type PredicateFn<T> = dyn Fn(&T) -> bool + Send + Sync;
+type CustomRuleFn<T> = dyn Fn(&T, &mut ValidationCtx) -> ValidationResult + Send + Sync;
+
+enum RuleCheck<T> {
+  Predicate {
+    check: Arc<PredicateFn<T>>,
+    rule: CelRule,
+    // The field that the violation is reported for
+    field: Option<FieldContext>,
+    // The fields used by the rule
+    fields: Vec<&'static str>,
+  },
+  Custom(Arc<CustomRuleFn<T>>),
+}
+
+impl<T> Clone for RuleCheck<T> {
+  fn clone(&self) -> Self {
+    match self {
+      Self::Predicate {
+        check,
+        rule,
+        field,
+        fields,
+      } => Self::Predicate {
+        check: check.clone(),
+        rule: rule.clone(),
+        field: field.clone(),
+        fields: fields.clone(),
+      },
+      Self::Custom(rule) => Self::Custom(rule.clone()),
+    }
+  }
+}
+
+/// The validator used for the message-level rules defined with closures.
+///
+/// Other than [`CelProgram`]s, it can hold typed rules that compare the fields of the message, which are executed natively
+/// and are rendered as an equivalent CEL rule in the `(buf.validate.message).cel` option, as well as custom closures
+/// that receive the message and the [`ValidationCtx`].
+///
+/// Violations of the rules that compare two fields are reported with the path of the first field.
+#[non_exhaustive]
+pub struct MessageRules<T> {
+  pub programs: Vec<CelProgram>,
+  rules: Vec<RuleCheck<T>>,
+}
+
+impl<T> Default for MessageRules<T> {
+  #[inline]
+  fn default() -> Self {
+    Self {
+      programs: Vec::new(),
+      rules: Vec::new(),
+    }
+  }
+}
+
+impl<T> Clone for MessageRules<T> {
+  #[inline]
+  fn clone(&self) -> Self {
+    Self {
+      programs: self.programs.clone(),
+      rules: self.rules.clone(),
+    }
+  }
+}
+
+impl<T> MessageRules<T> {
+  fn typed_cel_rules(&self) -> impl Iterator<Item = &CelRule> {
+    self.rules.iter().filter_map(|rule| match rule {
+      RuleCheck::Predicate { rule, .. } => Some(rule),
+      RuleCheck::Custom(_) => None,
+    })
+  }
+
+  fn all_cel_rules(&self) -> impl Iterator<Item = &CelRule> {
+    self
+      .programs
+      .iter()
+      .map(CelProgram::rule)
+      .chain(self.typed_cel_rules())
+  }
+}
+
+impl<T> Debug for MessageRules<T> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.debug_struct("MessageRules")
+      .field("programs", &self.programs)
+      .field("rules", &self.typed_cel_rules().collect::<Vec<_>>())
+      .finish()
+  }
+}
+
+#[allow(clippy::must_use_candidate, clippy::return_self_not_must_use)]
+impl<T: 'static> MessageRules<T> {
+  /// Adds a CEL program to this validator.
+  #[inline]
+  pub fn cel(mut self, program: CelProgram) -> Self {
+    self.programs.push(program);
+    self
+  }
+
+  /// Adds a custom rule, which receives the message and the [`ValidationCtx`].
+  ///
+  /// Custom rules are not rendered in the schema.
+  pub fn custom<F>(mut self, rule: F) -> Self
+  where
+    F: Fn(&T, &mut ValidationCtx) -> ValidationResult + Send + Sync + 'static,
+  {
+    self.rules.push(RuleCheck::Custom(Arc::new(rule)));
+    self
+  }
+
+  fn comparison<V>(
+    mut self,
+    first: FieldRef<T, V>,
+    second: FieldRef<T, V>,
+    (id, operator, description): (&str, &str, &str),
+    compare: fn(&V, &V) -> bool,
+  ) -> Self
+  where
+    V: ?Sized + 'static,
+  {
+    let mut expression = String::new();
+
+    for field in [&first, &second] {
+      if field.has_presence {
+        write!(expression, "!{} || ", field.cel_presence_check()).unwrap();
+      }
+    }
+
+    write!(
+      expression,
+      "{} {operator} {}",
+      first.cel_access(),
+      second.cel_access()
+    )
+    .unwrap();
+
+    let rule = CelRule {
+      id: format!("{}.{id}.{}", first.name, second.name).into(),
+      message: format!("must be {description} `{}`", second.name).into(),
+      expression: expression.into(),
+    };
+
+    self.rules.push(RuleCheck::Predicate {
+      check: Arc::new(move |msg| match ((first.get)(msg), (second.get)(msg)) {
+        (Some(first), Some(second)) => compare(first, second),
+        _ => true,
+      }),
+      rule,
+      field: Some(first.field_context()),
+      fields: vec![first.name, second.name],
+    });
+
+    self
+  }
+
+  /// Checks that the value of the first field is less than the value of the second one.
+  ///
+  /// The check is skipped if either field is not set.
+  pub fn field_lt<V: PartialOrd + ?Sized + 'static>(
+    self,
+    first: FieldRef<T, V>,
+    second: FieldRef<T, V>,
+  ) -> Self {
+    self.comparison(first, second, ("lt", "<", "less than"), |a, b| a < b)
+  }
+
+  /// Checks that the value of the first field is less than or equal to the value of the second one.
+  ///
+  /// The check is skipped if either field is not set.
+  pub fn field_lte<V: PartialOrd + ?Sized + 'static>(
+    self,
+    first: FieldRef<T, V>,
+    second: FieldRef<T, V>,
+  ) -> Self {
+    self.comparison(
+      first,
+      second,
+      ("lte", "<=", "less than or equal to"),
+      |a, b| a <= b,
+    )
+  }
+
+  /// Checks that the value of the first field is greater than the value of the second one.
+  ///
+  /// The check is skipped if either field is not set.
+  pub fn field_gt<V: PartialOrd + ?Sized + 'static>(
+    self,
+    first: FieldRef<T, V>,
+    second: FieldRef<T, V>,
+  ) -> Self {
+    self.comparison(first, second, ("gt", ">", "greater than"), |a, b| a > b)
+  }
+
+  /// Checks that the value of the first field is greater than or equal to the value of the second one.
+  ///
+  /// The check is skipped if either field is not set.
+  pub fn field_gte<V: PartialOrd + ?Sized + 'static>(
+    self,
+    first: FieldRef<T, V>,
+    second: FieldRef<T, V>,
+  ) -> Self {
+    self.comparison(
+      first,
+      second,
+      ("gte", ">=", "greater than or equal to"),
+      |a, b| a >= b,
+    )
+  }
+
+  /// Checks that the two fields have the same value.
+  ///
+  /// The check is skipped if either field is not set.
+  pub fn field_eq<V: PartialEq + ?Sized + 'static>(
+    self,
+    first: FieldRef<T, V>,
+    second: FieldRef<T, V>,
+  ) -> Self {
+    self.comparison(first, second, ("eq", "==", "equal to"), |a, b| a == b)
+  }
+
+  /// Checks that the two fields have different values.
+  ///
+  /// The check is skipped if either field is not set.
+  pub fn field_ne<V: PartialEq + ?Sized + 'static>(
+    self,
+    first: FieldRef<T, V>,
+    second: FieldRef<T, V>,
+  ) -> Self {
+    self.comparison(first, second, ("ne", "!=", "different from"), |a, b| a != b)
+  }
+
+  /// Checks that at least one of the given fields is set.
+  ///
+  /// ```rust,ignore
+  /// #[proto(validate = |v| v.at_least_one_of((email, phone)))]
+  /// ```
+  pub fn at_least_one_of(mut self, fields: impl FieldRefList<T>) -> Self {
+    let fields = fields.presence_refs();
+
+    let names: Vec<&'static str> = fields.iter().map(|f| f.name).collect();
+
+    let expression = names
+      .iter()
+      .map(|name| format!("has(this.{name})"))
+      .collect::<Vec<_>>()
+      .join(" || ");
+
+    let rule = CelRule {
+      id: format!("at_least_one_of.{}", names.join(".")).into(),
+      message: format!("at least one of `{}` must be set", names.join("`, `")).into(),
+      expression: expression.into(),
+    };
+
+    let checks: Vec<fn(&T) -> bool> = fields.iter().map(|f| f.is_set).collect();
+
+    self.rules.push(RuleCheck::Predicate {
+      check: Arc::new(move |msg| checks.iter().any(|is_set| is_set(msg))),
+      rule,
+      field: None,
+      fields: names,
+    });
+
+    self
+  }
+}
+
+impl<T> Validator<T> for MessageRules<T>
+where
+  T: ValidatedMessage + PartialEq + CelFields + Default + Clone + 'static,
+{
+  type Target = T;
+
+  #[cfg(feature = "cel")]
+  #[inline(never)]
+  #[cold]
+  fn check_cel_programs_with(&self, val: Self::Target) -> Result<(), Vec<CelError>> {
+    let programs: Vec<CelProgram> = self
+      .all_cel_rules()
+      .cloned()
+      .map(CelProgram::from)
+      .collect();
+
+    if programs.is_empty() {
+      Ok(())
+    } else {
+      test_programs(&programs, val)
+    }
+  }
+
+  #[cfg(feature = "cel")]
+  #[inline(never)]
+  #[cold]
+  fn check_cel_programs(&self) -> Result<(), Vec<CelError>> {
+    <Self as Validator<T>>::check_cel_programs_with(self, Self::Target::default())
+  }
+
+  #[doc(hidden)]
+  fn cel_rules(&self) -> Vec<CelRule> {
+    self.all_cel_rules().cloned().collect()
+  }
+
+  #[inline(never)]
+  #[cold]
+  fn check_consistency(&self) -> Result<(), Vec<ConsistencyError>> {
+    let mut errors = Vec::new();
+
+    #[cfg(feature = "cel")]
+    if let Err(e) = <Self as Validator<T>>::check_cel_programs(self) {
+      errors.extend(e.into_iter().map(ConsistencyError::from));
+    }
+
+    if errors.is_empty() {
+      Ok(())
+    } else {
+      Err(errors)
+    }
+  }
+
+  fn validate_core<V>(&self, ctx: &mut ValidationCtx, val: Option<&V>) -> ValidationResult
+  where
+    V: Borrow<Self::Target> + ?Sized,
+  {
+    let mut is_valid = IsValid::Yes;
+
+    if let Some(val) = val {
+      let val = val.borrow();
+
+      // With a mask, only the typed rules whose fields are all in the mask can be checked
+      #[cfg(feature = "field-mask")]
+      let is_masked = ctx.is_masked();
+      #[cfg(not(feature = "field-mask"))]
+      let is_masked = false;
+
+      for rule in &self.rules {
+        match rule {
+          RuleCheck::Predicate {
+            check,
+            rule,
+            field,
+            fields,
+          } => {
+            #[cfg(feature = "field-mask")]
+            if !fields.iter().all(|f| ctx.is_in_mask(f)) {
+              continue;
+            }
+            #[cfg(not(feature = "field-mask"))]
+            let _ = fields;
+
+            if !check(val) {
+              let parent_context = field
+                .clone()
+                .map(|field| ctx.field_context.replace(field));
+
+              let result = ctx.add_cel_violation(rule);
+
+              if let Some(parent_context) = parent_context {
+                ctx.field_context = parent_context;
+              }
+
+              is_valid &= result?;
+            }
+          }
+          RuleCheck::Custom(_) if is_masked => {}
+          RuleCheck::Custom(rule) => is_valid &= rule(val, ctx)?,
+        }
+      }
+
+      #[cfg(feature = "cel")]
+      if !self.programs.is_empty() && !is_masked {
+        let cel_ctx = MessageProgramsExecutionCtx {
+          programs: &self.programs,
+          value: val,
+          ctx,
+        };
+
+        is_valid &= cel_ctx.execute_programs()?;
+      }
+    }
+
+    Ok(is_valid)
+  }
+
+  #[inline(never)]
+  #[cold]
+  fn schema(&self) -> Option<ValidatorSchema> {
+    Some(ValidatorSchema {
+      schema: self.clone().into(),
+      cel_rules: <Self as Validator<T>>::cel_rules(self),
+      imports: vec!["buf/validate/validate.proto".into()],
+    })
+  }
+}
+
+impl<T> From<MessageRules<T>> for ProtoOption {
+  #[inline(never)]
+  #[cold]
+  fn from(value: MessageRules<T>) -> Self {
+    let mut rules = OptionMessageBuilder::new();
+
+    rules.add_cel_rules(value.all_cel_rules().cloned());
+
+    Self {
+      name: "(buf.validate.message)".into(),
+      value: OptionValue::Message(rules.into()),
+    }
+  }
+}
@@ -277,6 +277,8 @@ impl<T: Default + PartialEq> IsDefault for T {}
 type ErrorMessages<T> = Box<BTreeMap<T, FixedStr>>;
 
 pub mod any;
+#[cfg(feature = "async")]
+mod async_validator;
 pub mod bool;
 mod builder_internals;
 pub mod bytes;
@@ -286,9 +288,11 @@ pub mod enums;
 pub mod field_context;
 pub mod map;
 pub mod message;
+mod profiles;
 pub mod repeated;
 pub mod string;
 pub mod timestamp;
+mod validation_data;
 
 pub mod floats;
 pub use floats::*;
@@ -300,6 +304,8 @@ pub mod lookup;
 pub use lookup::*;
 
 pub use any::*;
+#[cfg(feature = "async")]
+pub use async_validator::*;
 pub use bool::*;
 use builder_internals::*;
 pub use bytes::*;
@@ -309,8 +315,10 @@ pub use enums::*;
 pub use field_context::*;
 pub use map::*;
 pub use message::*;
+pub use profiles::*;
 pub use repeated::*;
 pub use string::*;
 pub use timestamp::*;
+pub use validation_data::*;
 mod violations;
 pub use violations::*;
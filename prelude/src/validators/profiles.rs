@@ -0,0 +1,41 @@
+use super::*;
+
+/// The name of the profile that is used by [`validate`](ValidatedMessage::validate) and rendered in the schema.
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// A validation profile, which selects the validators that are tagged with a matching group.
+///
+/// Validators without groups are executed in every profile, whereas those tagged with the `groups`
+/// attribute only run when the active profile is one of them.
+///
+/// It is implemented for string literals, and it can be implemented for marker types:
+///
+/// ```rust
+/// use prelude::ValidationProfile;
+///
+/// pub struct Create;
+///
+/// impl ValidationProfile for Create {
+///   fn profile_name(&self) -> &'static str {
+///     "create"
+///   }
+/// }
+/// ```
+pub trait ValidationProfile {
+  fn profile_name(&self) -> &'static str;
+}
+
+impl ValidationProfile for &'static str {
+  #[inline]
+  fn profile_name(&self) -> &'static str {
+    self
+  }
+}
+
+/// Checks if the validators of these groups are part of the default profile, and should therefore be rendered in the schema.
+#[doc(hidden)]
+#[inline]
+#[must_use]
+pub fn includes_default_profile(groups: &[&str]) -> bool {
+  groups.contains(&DEFAULT_PROFILE)
+}
@@ -0,0 +1,116 @@
+use super::*;
+
+use core::any::{Any, TypeId};
+
+/// Request-scoped data that is made available to the validators, such as the current tenant or the role of the caller.
+///
+/// Custom validators can read the typed values with [`ValidationCtx::data`], and the values added with
+/// [`with_cel_variable`](ValidationData::with_cel_variable) can be used in CEL expressions alongside `this` and `now`.
+/// The data is passed with [`validate_with`](ValidatedMessage::validate_with) and the other `*_with` methods of [`ValidatedMessage`],
+/// including the async ones, so that it is also available to the async validators.
+///
+/// ```rust,ignore
+/// let data = ValidationData::new()
+///   .with(CallerRole::Admin)
+///   .with_cel_variable("tenant", "acme");
+///
+/// msg.validate_with(data)?;
+/// ```
+#[derive(Default)]
+pub struct ValidationData {
+  extensions: BTreeMap<TypeId, Box<dyn Any + Send + Sync>>,
+  #[cfg(feature = "cel")]
+  cel_variables: Vec<(FixedStr, ::cel::Value)>,
+}
+
+impl Debug for ValidationData {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    let mut debug = f.debug_struct("ValidationData");
+
+    debug.field("extensions", &self.extensions.len());
+
+    #[cfg(feature = "cel")]
+    debug.field("cel_variables", &self.cel_variables);
+
+    debug.finish()
+  }
+}
+
+impl ValidationData {
+  #[must_use]
+  #[inline]
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Adds a typed value, replacing the previous value of the same type.
+  #[must_use]
+  #[inline]
+  pub fn with<T: Any + Send + Sync>(mut self, value: T) -> Self {
+    self.insert(value);
+    self
+  }
+
+  /// Inserts a typed value, returning the previous value of the same type if there was one.
+  pub fn insert<T: Any + Send + Sync>(&mut self, value: T) -> Option<T> {
+    self
+      .extensions
+      .insert(TypeId::of::<T>(), Box::new(value))
+      .and_then(|prev| prev.downcast().ok())
+      .map(|prev| *prev)
+  }
+
+  /// Returns the value of the given type, if present.
+  #[must_use]
+  #[inline]
+  pub fn get<T: Any>(&self) -> Option<&T> {
+    self
+      .extensions
+      .get(&TypeId::of::<T>())
+      .and_then(|value| value.downcast_ref())
+  }
+
+  /// Adds a variable that can be used by name in the CEL expressions, replacing the previous variable with the same name.
+  ///
+  /// The name should also be registered with [`register_cel_variables`](crate::register_cel_variables),
+  /// so that the consistency checks do not report it as undeclared.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the name is `this` or `now`, which are reserved for the value being validated and the current time.
+  #[cfg(feature = "cel")]
+  #[must_use]
+  pub fn with_cel_variable(
+    mut self,
+    name: impl Into<FixedStr>,
+    value: impl Into<::cel::Value>,
+  ) -> Self {
+    let name = name.into();
+    let value = value.into();
+
+    assert!(
+      !matches!(&*name, "this" | "now"),
+      "`{name}` is a reserved CEL variable name"
+    );
+
+    if let Some(entry) = self
+      .cel_variables
+      .iter_mut()
+      .find(|(existing, _)| *existing == name)
+    {
+      entry.1 = value;
+    } else {
+      self.cel_variables.push((name, value));
+    }
+
+    self
+  }
+
+  /// The variables that are added to the context of CEL programs.
+  #[cfg(feature = "cel")]
+  #[must_use]
+  #[inline]
+  pub fn cel_variables(&self) -> &[(FixedStr, ::cel::Value)] {
+    &self.cel_variables
+  }
+}
@@ -31,12 +31,157 @@ macro_rules! impl_no_op_validator {
           Ok(IsValid::Yes)
         }
       }
+
+      #[cfg(feature = "samples")]
+      impl samples::ProtoSample for $name {
+        #[inline]
+        #[doc(hidden)]
+        fn sample_candidate(_: &mut samples::SampleCtx) -> Option<Self> {
+          Some(Self::default())
+        }
+
+        #[inline]
+        #[doc(hidden)]
+        fn invalid_candidates(_: &Self, _: &mut samples::SampleCtx) -> Vec<samples::InvalidSample<Self>> {
+          Vec::new()
+        }
+
+        #[inline]
+        #[doc(hidden)]
+        fn sample_violations(&self) -> ValidationErrors {
+          ValidationErrors::new()
+        }
+      }
+
+      // These types are treated as atomic values, so their fields cannot be selected by a mask
+      #[cfg(feature = "field-mask")]
+      impl field_masks::ProtoMask for $name {
+        #[doc(hidden)]
+        const MASK_MESSAGE_NAME: &'static str = stringify!($name);
+
+        #[inline]
+        #[doc(hidden)]
+        fn check_mask_field(path: &str, field: &str, _: Option<&str>) -> Result<(), field_masks::FieldMaskError> {
+          field_masks::unknown_mask_field::<Self>(path, field)
+        }
+
+        #[inline]
+        #[doc(hidden)]
+        fn prune_to_tree(&mut self, _: &field_masks::MaskTree) {}
+
+        #[inline]
+        #[doc(hidden)]
+        fn merge_tree(&mut self, _: &Self, _: &field_masks::MaskTree) {}
+      }
+
+      #[cfg(feature = "normalize")]
+      impl normalize::ProtoNormalize for $name {
+        #[inline]
+        #[doc(hidden)]
+        fn normalize_fields(&mut self) {}
+      }
     )*
   };
 }
 
 impl_no_op_validator!(Empty, Status, Code, ());
 
+/// Implements the JSON mapping for the types from `proto_types` that do not have a dedicated
+/// JSON representation, by using their serde implementations.
+#[cfg(feature = "json")]
+macro_rules! impl_json_via_serde {
+  ($($name:ty),*) => {
+    $(
+      impl json::ProtoJson for $name {
+        #[inline]
+        fn serialize_json<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+          serde::Serialize::serialize(self, serializer)
+        }
+
+        #[inline]
+        fn deserialize_json<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+          serde::Deserialize::deserialize(deserializer)
+        }
+      }
+    )*
+  };
+}
+
+#[cfg(not(feature = "json"))]
+macro_rules! impl_json_via_serde {
+  ($($name:ty),*) => {};
+}
+
+impl_json_via_serde!(Status);
+
+/// Implements the text format for the types from `proto_types`, given the codec of each of their fields.
+/// Raw identifiers must be followed by the proto name of the field (i.e. `r#type as "type"`).
+#[cfg(feature = "text-format")]
+macro_rules! impl_text_format {
+  ($($name:ty { $($field:ident $(as $proto_name:literal)?: $codec:ty),* $(,)? }),* $(,)?) => {
+    const _: () = {
+      use crate::text_format::*;
+
+      $(
+        impl ProtoText for $name {
+          #[allow(unused_variables)]
+          fn write_text(&self, writer: &mut TextWriter) {
+            $(
+              write_field::<$codec, _>(writer, text_field_name!($field $(, $proto_name)?), &self.$field);
+            )*
+          }
+
+          #[allow(unused_variables, clippy::match_single_binding, clippy::never_loop)]
+          fn merge_text(&mut self, message: TextMessage) -> Result<(), TextFormatError> {
+            for TextField { name, value, position } in message.fields {
+              match name.as_str() {
+                $(
+                  text_field_name!($field $(, $proto_name)?) => {
+                    merge_field::<$codec, _>(&mut self.$field, &name, value, position)?;
+                  }
+                )*
+                _ => return Err(TextFormatError::unknown_field(&name, position)),
+              }
+            }
+
+            Ok(())
+          }
+        }
+      )*
+    };
+  };
+}
+
+#[cfg(feature = "text-format")]
+macro_rules! text_field_name {
+  ($field:ident) => {
+    stringify!($field)
+  };
+
+  ($field:ident, $proto_name:literal) => {
+    $proto_name
+  };
+}
+
+#[cfg(not(feature = "text-format"))]
+macro_rules! impl_text_format {
+  ($($tokens:tt)*) => {};
+}
+
+impl_text_format!(
+  Timestamp { seconds: Int64Codec, nanos: Int32Codec },
+  Duration { seconds: Int64Codec, nanos: Int32Codec },
+  FieldMask { paths: RepeatedCodec<StringCodec> },
+  Any { type_url: StringCodec, value: BytesCodec },
+  Empty {},
+  () {},
+  Status {
+    code: Int32Codec,
+    message: StringCodec,
+    details: RepeatedCodec<MessageCodec>,
+  },
+);
+
 #[derive(Clone, Copy, Default)]
 pub struct NoOpValidator<T: ?Sized>(PhantomData<T>);
 
@@ -175,6 +320,7 @@ mod google_dot_type {
           }
 
           impl_no_op_validator!($name);
+          impl_json_via_serde!($name);
         )*
       }
     };
@@ -200,6 +346,179 @@ mod google_dot_type {
     TimeOfDay => "timeofday"
   );
 
+  impl_text_format!(
+    Date { year: Int32Codec, month: Int32Codec, day: Int32Codec },
+    Interval {
+      start_time: OptionalCodec<MessageCodec>,
+      end_time: OptionalCodec<MessageCodec>,
+    },
+    Money {
+      currency_code: StringCodec,
+      units: Int64Codec,
+      nanos: Int32Codec,
+    },
+    Color {
+      red: FloatCodec,
+      green: FloatCodec,
+      blue: FloatCodec,
+      alpha: WrapperCodec<FloatCodec>,
+    },
+    Fraction { numerator: Int64Codec, denominator: Int64Codec },
+    Decimal { value: StringCodec },
+    PostalAddress {
+      revision: Int32Codec,
+      region_code: StringCodec,
+      language_code: StringCodec,
+      postal_code: StringCodec,
+      sorting_code: StringCodec,
+      administrative_area: StringCodec,
+      locality: StringCodec,
+      sublocality: StringCodec,
+      address_lines: RepeatedCodec<StringCodec>,
+      recipients: RepeatedCodec<StringCodec>,
+      organization: StringCodec,
+    },
+    phone_number::ShortCode { region_code: StringCodec, number: StringCodec },
+    Quaternion {
+      x: DoubleCodec,
+      y: DoubleCodec,
+      z: DoubleCodec,
+      w: DoubleCodec,
+    },
+    LocalizedText { text: StringCodec, language_code: StringCodec },
+    Expr {
+      expression: StringCodec,
+      title: StringCodec,
+      description: StringCodec,
+      location: StringCodec,
+    },
+    TimeZone { id: StringCodec, version: StringCodec },
+    LatLng { latitude: DoubleCodec, longitude: DoubleCodec },
+    TimeOfDay {
+      hours: Int32Codec,
+      minutes: Int32Codec,
+      seconds: Int32Codec,
+      nanos: Int32Codec,
+    },
+  );
+
+  // The messages with a oneof are implemented manually
+  #[cfg(feature = "text-format")]
+  const _: () = {
+    use crate::text_format::*;
+
+    impl ProtoText for PhoneNumber {
+      fn write_text(&self, writer: &mut TextWriter) {
+        match &self.kind {
+          Some(phone_number::Kind::E164Number(number)) => {
+            StringCodec::write(number, "e164_number", writer);
+          }
+          Some(phone_number::Kind::ShortCode(code)) => {
+            MessageCodec::write(code, "short_code", writer);
+          }
+          None => {}
+        }
+
+        write_field::<StringCodec, _>(writer, "extension", &self.extension);
+      }
+
+      fn merge_text(&mut self, message: TextMessage) -> Result<(), TextFormatError> {
+        for TextField {
+          name,
+          value,
+          position,
+        } in message.fields
+        {
+          match name.as_str() {
+            "e164_number" => {
+              let mut number = String::new();
+              merge_field::<StringCodec, _>(&mut number, &name, value, position)?;
+              self.kind = Some(phone_number::Kind::E164Number(number));
+            }
+            "short_code" => {
+              let mut code = match self.kind.take() {
+                Some(phone_number::Kind::ShortCode(code)) => code,
+                _ => phone_number::ShortCode::default(),
+              };
+              merge_field::<MessageCodec, _>(&mut code, &name, value, position)?;
+              self.kind = Some(phone_number::Kind::ShortCode(code));
+            }
+            "extension" => {
+              merge_field::<StringCodec, _>(&mut self.extension, &name, value, position)?;
+            }
+            _ => return Err(TextFormatError::unknown_field(&name, position)),
+          }
+        }
+
+        Ok(())
+      }
+    }
+
+    impl ProtoText for DateTime {
+      fn write_text(&self, writer: &mut TextWriter) {
+        write_field::<Int32Codec, _>(writer, "year", &self.year);
+        write_field::<Int32Codec, _>(writer, "month", &self.month);
+        write_field::<Int32Codec, _>(writer, "day", &self.day);
+        write_field::<Int32Codec, _>(writer, "hours", &self.hours);
+        write_field::<Int32Codec, _>(writer, "minutes", &self.minutes);
+        write_field::<Int32Codec, _>(writer, "seconds", &self.seconds);
+        write_field::<Int32Codec, _>(writer, "nanos", &self.nanos);
+
+        match &self.time_offset {
+          Some(date_time::TimeOffset::UtcOffset(offset)) => {
+            MessageCodec::write(offset, "utc_offset", writer);
+          }
+          Some(date_time::TimeOffset::TimeZone(time_zone)) => {
+            MessageCodec::write(time_zone, "time_zone", writer);
+          }
+          None => {}
+        }
+      }
+
+      fn merge_text(&mut self, message: TextMessage) -> Result<(), TextFormatError> {
+        for TextField {
+          name,
+          value,
+          position,
+        } in message.fields
+        {
+          let target = match name.as_str() {
+            "year" => &mut self.year,
+            "month" => &mut self.month,
+            "day" => &mut self.day,
+            "hours" => &mut self.hours,
+            "minutes" => &mut self.minutes,
+            "seconds" => &mut self.seconds,
+            "nanos" => &mut self.nanos,
+            "utc_offset" => {
+              let mut offset = match self.time_offset.take() {
+                Some(date_time::TimeOffset::UtcOffset(offset)) => offset,
+                _ => Duration::default(),
+              };
+              merge_field::<MessageCodec, _>(&mut offset, &name, value, position)?;
+              self.time_offset = Some(date_time::TimeOffset::UtcOffset(offset));
+              continue;
+            }
+            "time_zone" => {
+              let mut time_zone = match self.time_offset.take() {
+                Some(date_time::TimeOffset::TimeZone(time_zone)) => time_zone,
+                _ => TimeZone::default(),
+              };
+              merge_field::<MessageCodec, _>(&mut time_zone, &name, value, position)?;
+              self.time_offset = Some(date_time::TimeOffset::TimeZone(time_zone));
+              continue;
+            }
+            _ => return Err(TextFormatError::unknown_field(&name, position)),
+          };
+
+          merge_field::<Int32Codec, _>(target, &name, value, position)?;
+        }
+
+        Ok(())
+      }
+    }
+  };
+
   impl_no_op_validator!(DayOfWeek);
 
   impl AsProtoType for DayOfWeek {
@@ -240,6 +559,7 @@ mod rpc_types {
         }
 
         impl_no_op_validator!($name);
+        impl_json_via_serde!($name);
       )*
     };
   }
@@ -267,6 +587,59 @@ mod rpc_types {
     help::Link
   );
 
+  impl_json_via_serde!(
+    quota_failure::Violation,
+    precondition_failure::Violation,
+    bad_request::FieldViolation,
+    help::Link
+  );
+
+  impl_text_format!(
+    ErrorInfo {
+      reason: StringCodec,
+      domain: StringCodec,
+      metadata: MapCodec<StringCodec, StringCodec>,
+    },
+    DebugInfo {
+      stack_entries: RepeatedCodec<StringCodec>,
+      detail: StringCodec,
+    },
+    RetryInfo { retry_delay: OptionalCodec<MessageCodec> },
+    QuotaFailure { violations: RepeatedCodec<MessageCodec> },
+    quota_failure::Violation { subject: StringCodec, description: StringCodec },
+    PreconditionFailure { violations: RepeatedCodec<MessageCodec> },
+    precondition_failure::Violation {
+      r#type as "type": StringCodec,
+      subject: StringCodec,
+      description: StringCodec,
+    },
+    BadRequest { field_violations: RepeatedCodec<MessageCodec> },
+    bad_request::FieldViolation { field: StringCodec, description: StringCodec },
+    RequestInfo { request_id: StringCodec, serving_data: StringCodec },
+    ResourceInfo {
+      resource_type: StringCodec,
+      resource_name: StringCodec,
+      owner: StringCodec,
+      description: StringCodec,
+    },
+    Help { links: RepeatedCodec<MessageCodec> },
+    help::Link { description: StringCodec, url: StringCodec },
+    LocalizedMessage { locale: StringCodec, message: StringCodec },
+    HttpRequest {
+      method: StringCodec,
+      uri: StringCodec,
+      headers: RepeatedCodec<MessageCodec>,
+      body: BytesCodec,
+    },
+    HttpResponse {
+      status: Int32Codec,
+      reason: StringCodec,
+      headers: RepeatedCodec<MessageCodec>,
+      body: BytesCodec,
+    },
+    HttpHeader { key: StringCodec, value: StringCodec },
+  );
+
   impl MessagePath for quota_failure::Violation {
     fn proto_path() -> ProtoPath {
       ProtoPath {
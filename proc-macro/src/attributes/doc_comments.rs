@@ -0,0 +1,63 @@
+use crate::*;
+
+/// The contents of the doc comments of an item, which are rendered as comments in the proto output.
+#[derive(Clone, Default, Debug)]
+pub struct DocComments {
+  pub text: Option<String>,
+}
+
+impl DocComments {
+  pub fn from_attributes(attrs: &[Attribute]) -> Self {
+    let mut lines: Vec<String> = Vec::new();
+
+    for attr in attrs {
+      if !attr.path().is_ident("doc") {
+        continue;
+      }
+
+      if let Meta::NameValue(nv) = &attr.meta
+        && let Expr::Lit(expr_lit) = &nv.value
+        && let Lit::Str(lit_str) = &expr_lit.lit
+      {
+        let content = lit_str.value();
+
+        for line in content.lines() {
+          // Strips the leading space that comes after `///`
+          let line = line.strip_prefix(' ').unwrap_or(line);
+
+          lines.push(line.trim_end().to_string());
+        }
+      }
+    }
+
+    while lines.last().is_some_and(|l| l.is_empty()) {
+      lines.pop();
+    }
+
+    let first_non_empty = lines
+      .iter()
+      .position(|l| !l.is_empty())
+      .unwrap_or(lines.len());
+
+    let text = lines[first_non_empty..].join("\n");
+
+    Self {
+      text: (!text.is_empty()).then_some(text),
+    }
+  }
+
+  pub const fn is_empty(&self) -> bool {
+    self.text.is_none()
+  }
+}
+
+impl ToTokens for DocComments {
+  fn to_tokens(&self, tokens: &mut TokenStream2) {
+    let output = match &self.text {
+      Some(text) => quote! { Some(#text.into()) },
+      None => quote! { None },
+    };
+
+    tokens.extend(output);
+  }
+}
@@ -4,10 +4,13 @@ use crate::*;
 pub struct EnumAttrs {
   pub reserved_names: Vec<String>,
   pub reserved_numbers: ReservedNumbers,
+  pub tag_lockfile: Option<TagLockfileAttr>,
   pub options: TokensOr<TokenStream2>,
+  pub features: TokensOr<TokenStream2>,
   pub parent_message: Option<Ident>,
   pub name: ParsedStr,
   pub deprecated: bool,
+  pub comments: DocComments,
 }
 
 pub fn process_derive_enum_attrs(
@@ -16,7 +19,9 @@ pub fn process_derive_enum_attrs(
 ) -> Result<EnumAttrs, Error> {
   let mut reserved_names: Vec<String> = Vec::new();
   let mut reserved_numbers = ReservedNumbers::default();
+  let mut tag_lockfile: Option<TagLockfileAttr> = None;
   let mut options = TokensOr::<TokenStream2>::new(|_| quote! { ::prelude::vec![] });
+  let mut features = features_tokens_or_default();
   let mut proto_name: Option<ParsedStr> = None;
   let mut parent_message: Option<Ident> = None;
   let mut deprecated = false;
@@ -52,6 +57,9 @@ pub fn process_derive_enum_attrs(
 
               reserved_numbers = numbers;
             }
+            "tag_lockfile" => {
+              tag_lockfile = Some(TagLockfileAttr::parse(&meta)?);
+            }
             "parent_message" => {
               parent_message = Some(
                 meta
@@ -61,6 +69,10 @@ pub fn process_derive_enum_attrs(
                   .clone(),
               );
             }
+            "features" => {
+              features.span = meta.input.span();
+              features.set(meta.expr_value()?.into_token_stream());
+            }
             "options" => {
               options.span = meta.input.span();
               options.set(meta.expr_value()?.into_token_stream());
@@ -84,9 +96,12 @@ pub fn process_derive_enum_attrs(
   Ok(EnumAttrs {
     reserved_names,
     reserved_numbers,
+    tag_lockfile,
     options,
+    features,
     parent_message,
     name,
     deprecated,
+    comments: DocComments::from_attributes(attrs),
   })
 }
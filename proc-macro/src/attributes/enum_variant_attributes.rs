@@ -4,6 +4,7 @@ pub struct EnumVariantAttrs {
   pub name: String,
   pub options: TokensOr<TokenStream2>,
   pub deprecated: bool,
+  pub comments: DocComments,
 }
 
 pub fn process_derive_enum_variants_attrs(
@@ -66,5 +67,6 @@ pub fn process_derive_enum_variants_attrs(
     name,
     options,
     deprecated,
+    comments: DocComments::from_attributes(attrs),
   })
 }
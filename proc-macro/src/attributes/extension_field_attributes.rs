@@ -6,6 +6,7 @@ pub struct ExtensionFieldAttrs {
   pub options: TokensOr<TokenStream2>,
   pub proto_name: String,
   pub proto_field: ProtoField,
+  pub comments: DocComments,
 }
 
 pub fn process_extension_field_attrs(field: &Field) -> Result<ExtensionFieldAttrs, Error> {
@@ -82,5 +83,6 @@ pub fn process_extension_field_attrs(field: &Field) -> Result<ExtensionFieldAttr
     options,
     proto_name: name.unwrap_or_else(|| rust_ident_to_proto_name(&field_ident)),
     proto_field,
+    comments: DocComments::from_attributes(&field.attrs),
   })
 }
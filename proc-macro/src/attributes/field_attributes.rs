@@ -10,6 +10,7 @@ pub enum ValidatorKind {
   Default,
   DefaultOneof,
   RequiredOneof,
+  Async,
 }
 
 impl ValidatorKind {
@@ -40,6 +41,14 @@ impl ValidatorKind {
   pub const fn is_closure(self) -> bool {
     matches!(self, Self::Closure)
   }
+
+  /// Returns `true` if the validator kind is [`Async`].
+  ///
+  /// [`Async`]: ValidatorKind::Async
+  #[must_use]
+  pub const fn is_async(self) -> bool {
+    matches!(self, Self::Async)
+  }
 }
 
 #[derive(Clone)]
@@ -89,6 +98,26 @@ impl Validators {
     self.validators.iter()
   }
 
+  /// Removes the validators marked with `async` and returns them separately, since they are
+  /// executed in a separate pass by `validate_async`.
+  pub fn take_async(&mut self) -> syn::Result<Self> {
+    let (async_validators, validators): (Vec<_>, Vec<_>) = std::mem::take(&mut self.validators)
+      .into_iter()
+      .partition(|v| v.kind.is_async());
+
+    self.validators = validators;
+
+    if let Some(first) = async_validators.first()
+      && !cfg!(feature = "async")
+    {
+      bail_with_span!(first.span, "Async validators require the `async` feature");
+    }
+
+    Ok(Self {
+      validators: async_validators,
+    })
+  }
+
   pub fn adjust_closures(&mut self, proto_field: &ProtoField) -> syn::Result<()> {
     for validator in &mut self.validators {
       if validator.kind.is_closure() {
@@ -108,10 +137,51 @@ impl Validators {
 
     Ok(())
   }
+
+  /// Turns the closures used for message-level validators into [`MessageRules`] builders,
+  /// with a `FieldRef` for each field of the message in scope.
+  pub fn adjust_message_closures(&mut self, target_ident: &Ident, fields: &[FieldDataKind]) {
+    let field_refs: TokenStream2 = fields
+      .iter()
+      .filter_map(|d| d.as_normal())
+      .filter_map(|data| data.field_ref_tokens(target_ident))
+      .collect();
+
+    for validator in &mut self.validators {
+      if validator.kind.is_closure() {
+        validator.expr = quote_spanned! {validator.span=>
+          {
+            #field_refs
+
+            ::prelude::apply(::prelude::MessageRules::<#target_ident>::default(), #validator)
+          }
+        };
+      }
+    }
+  }
 }
 
 impl Parse for ValidatorTokens {
   fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+    // `async { .. }` and `async move { .. }` are regular expressions
+    if input.peek(Token![async]) && !input.peek2(token::Brace) && !input.peek2(Token![move]) {
+      let async_token: Token![async] = input.parse()?;
+      let validator: Expr = input.parse()?;
+
+      if let Expr::Closure(closure) = &validator {
+        bail!(
+          closure,
+          "Closures cannot be used as async validators, use an implementor of `AsyncValidator` instead"
+        );
+      }
+
+      return Ok(Self {
+        span: async_token.span,
+        kind: ValidatorKind::Async,
+        expr: validator.into_token_stream(),
+      });
+    }
+
     let validator: Expr = input.parse()?;
 
     let kind = match &validator {
@@ -166,13 +236,23 @@ pub struct FieldData {
   pub ident_str: String,
   pub tag: Option<ParsedNum>,
   pub validators: Validators,
+  pub async_validators: Validators,
   pub options: TokensOr<TokenStream2>,
+  pub features: TokensOr<TokenStream2>,
   pub proto_name: String,
+  pub json_name: Option<String>,
   pub proto_field: ProtoField,
+  pub required: bool,
   pub from_proto: Option<PathOrClosure>,
   pub into_proto: Option<PathOrClosure>,
+  pub normalizer: Option<TokenStream2>,
+  pub transform: Option<PathOrClosure>,
+  pub default: Option<FieldDefault>,
+  pub when: Option<FieldCondition>,
+  pub groups: Option<ValidationGroups>,
   pub deprecated: bool,
   pub forwarded_attrs: Vec<Meta>,
+  pub comments: DocComments,
 }
 
 impl FieldData {
@@ -226,11 +306,19 @@ pub fn process_field_data(field: FieldOrVariant) -> Result<FieldDataKind, Error>
   let mut validators = Validators::default();
   let mut tag: Option<ParsedNum> = None;
   let mut options = TokensOr::<TokenStream2>::vec();
+  let mut features = features_tokens_or_default();
   let mut name: Option<String> = None;
+  let mut json_name: Option<String> = None;
   let mut proto_field: Option<ProtoField> = None;
   let mut is_ignored = false;
+  let mut required = false;
   let mut from_proto: Option<PathOrClosure> = None;
   let mut into_proto: Option<PathOrClosure> = None;
+  let mut normalizer: Option<TokenStream2> = None;
+  let mut transform: Option<PathOrClosure> = None;
+  let mut default: Option<FieldDefault> = None;
+  let mut when: Option<FieldCondition> = None;
+  let mut groups: Option<ValidationGroups> = None;
   let mut deprecated = false;
   let mut forwarded_attrs: Vec<Meta> = Vec::new();
   let field_ident = field.ident()?.clone();
@@ -264,12 +352,22 @@ pub fn process_field_data(field: FieldOrVariant) -> Result<FieldDataKind, Error>
               options.span = meta.input.span();
               options.set(meta.expr_value()?.into_token_stream());
             }
+            "features" => {
+              features.span = meta.input.span();
+              features.set(meta.expr_value()?.into_token_stream());
+            }
+            "required" => {
+              required = true;
+            }
             "tag" => {
               tag = Some(meta.parse_value::<ParsedNum>()?);
             }
             "name" => {
               name = Some(meta.expr_value()?.as_string()?);
             }
+            "json_name" => {
+              json_name = Some(meta.expr_value()?.as_string()?);
+            }
             "validate" => {
               validators = meta.parse_value::<Validators>()?;
             }
@@ -282,6 +380,35 @@ pub fn process_field_data(field: FieldOrVariant) -> Result<FieldDataKind, Error>
             "ignore" => {
               is_ignored = true;
             }
+            "normalize" => {
+              if !cfg!(feature = "normalize") {
+                return Err(meta.error("`normalize` requires the `normalize` feature"));
+              }
+
+              let expr = meta.expr_value()?;
+
+              normalizer = Some(if let Expr::Closure(closure) = &expr {
+                quote_spanned! {closure.span()=> ::prelude::normalize::StringNormalizer::from_closure(#closure) }
+              } else {
+                expr.into_token_stream()
+              });
+            }
+            "transform" => {
+              if !cfg!(feature = "normalize") {
+                return Err(meta.error("`transform` requires the `normalize` feature"));
+              }
+
+              transform = Some(meta.expr_value()?.as_path_or_closure()?);
+            }
+            "default" => {
+              default = Some(FieldDefault::from_expr(&meta.expr_value()?)?);
+            }
+            "when" => {
+              when = Some(FieldCondition::from_expr(&meta.expr_value()?)?);
+            }
+            "groups" => {
+              groups = Some(ValidationGroups::from_expr(meta.expr_value()?)?);
+            }
 
             _ => {
               proto_field = Some(ProtoField::from_meta(&ident, &meta, &type_info)?);
@@ -368,6 +495,86 @@ pub fn process_field_data(field: FieldOrVariant) -> Result<FieldDataKind, Error>
     }
   };
 
+  if required {
+    if field.is_variant() {
+      bail_with_span!(field_span, "Oneof variants cannot be marked as required");
+    }
+
+    if !matches!(&proto_field, ProtoField::Single(inner) if !inner.is_message()) {
+      bail_with_span!(
+        field_span,
+        "`required` can only be used for singular scalar or enum fields"
+      );
+    }
+  }
+
+  if normalizer.is_some()
+    && !matches!(
+      &proto_field,
+      ProtoField::Single(ProtoType::String)
+        | ProtoField::Optional(ProtoType::String)
+        | ProtoField::Repeated(ProtoType::String)
+    )
+  {
+    bail_with_span!(
+      field_span,
+      "`normalize` can only be used with string fields. Use `transform` for other types"
+    );
+  }
+
+  if let Some(default) = &default {
+    if field.is_variant() {
+      bail_with_span!(field_span, "Oneof variants cannot have a default value");
+    }
+
+    default.check(&proto_field, required, field_span)?;
+  }
+
+  if when.is_some() {
+    if field.is_variant() {
+      bail_with_span!(
+        field_span,
+        "Oneof variants cannot have a `when` condition, since it's evaluated against the parent message"
+      );
+    }
+
+    if proto_field.is_oneof() {
+      bail_with_span!(field_span, "`when` cannot be used on oneofs");
+    }
+  }
+
+  if groups.is_some() && field.is_variant() {
+    bail_with_span!(
+      field_span,
+      "Oneof variants cannot have validation groups, they must be set on the oneof field"
+    );
+  }
+
+  if json_name.is_some() && proto_field.is_oneof() {
+    bail_with_span!(
+      field_span,
+      "`json_name` cannot be used on oneofs, it must be set on their variants"
+    );
+  }
+
+  let async_validators = validators.take_async()?;
+
+  if !async_validators.is_empty() {
+    if field.is_variant() {
+      bail_with_span!(
+        async_validators.span(),
+        "Oneof variants cannot have async validators"
+      );
+    }
+
+    if proto_field.is_oneof() {
+      bail_with_span!(
+        async_validators.span(),
+        "Async validators cannot be used on oneofs"
+      );
+    }
+  }
+
   if let Some(default) = proto_field.default_validator_expr(field_span) {
     validators.validators.push(default);
   }
@@ -386,21 +593,35 @@ pub fn process_field_data(field: FieldOrVariant) -> Result<FieldDataKind, Error>
 
   Ok(FieldDataKind::Normal(FieldData {
     validators,
+    async_validators,
     tag,
     options,
+    features,
     proto_name,
+    json_name,
     proto_field,
+    required,
     from_proto,
     into_proto,
+    normalizer,
+    transform,
+    default,
+    when,
+    groups,
     span: field_span,
     ident_str: field_ident.to_string(),
     ident: field_ident,
     type_info,
     deprecated,
     forwarded_attrs,
+    comments: DocComments::from_attributes(field.attributes()),
   }))
 }
 
+pub fn features_tokens_or_default() -> TokensOr<TokenStream2> {
+  TokensOr::<TokenStream2>::new(|_| quote! { ::prelude::FeatureSet::new() })
+}
+
 pub fn rust_ident_to_proto_name(rust_ident: &Ident) -> String {
   let str = rust_ident.to_string();
 
@@ -0,0 +1,494 @@
+use syn::{BinOp, ExprBinary, ExprUnary, UnOp};
+
+use crate::*;
+
+/// The condition set with `#[proto(when = ...)]`, which is evaluated against the parent message
+/// to decide whether the validators of a field should be executed.
+#[derive(Clone)]
+pub enum FieldCondition {
+  And(Box<Self>, Box<Self>),
+  Or(Box<Self>, Box<Self>),
+  Not(Box<Self>),
+  IsSet(Ident),
+  Compare {
+    field: Ident,
+    op: CompareOp,
+    value: ConditionValue,
+  },
+}
+
+#[derive(Clone, Copy)]
+pub enum CompareOp {
+  Eq,
+  Ne,
+  Lt,
+  Le,
+  Gt,
+  Ge,
+}
+
+impl CompareOp {
+  const fn from_bin_op(op: &BinOp) -> Option<Self> {
+    let output = match op {
+      BinOp::Eq(_) => Self::Eq,
+      BinOp::Ne(_) => Self::Ne,
+      BinOp::Lt(_) => Self::Lt,
+      BinOp::Le(_) => Self::Le,
+      BinOp::Gt(_) => Self::Gt,
+      BinOp::Ge(_) => Self::Ge,
+      _ => return None,
+    };
+
+    Some(output)
+  }
+
+  const fn as_str(self) -> &'static str {
+    match self {
+      Self::Eq => "==",
+      Self::Ne => "!=",
+      Self::Lt => "<",
+      Self::Le => "<=",
+      Self::Gt => ">",
+      Self::Ge => ">=",
+    }
+  }
+
+  const fn is_equality(self) -> bool {
+    matches!(self, Self::Eq | Self::Ne)
+  }
+
+  fn to_tokens(self, span: Span) -> TokenStream2 {
+    match self {
+      Self::Eq => quote_spanned! {span=> == },
+      Self::Ne => quote_spanned! {span=> != },
+      Self::Lt => quote_spanned! {span=> < },
+      Self::Le => quote_spanned! {span=> <= },
+      Self::Gt => quote_spanned! {span=> > },
+      Self::Ge => quote_spanned! {span=> >= },
+    }
+  }
+}
+
+/// The value on the right side of a comparison.
+#[derive(Clone)]
+pub enum ConditionValue {
+  Lit { lit: Lit, negative: bool },
+  EnumVariant(Path),
+}
+
+impl ConditionValue {
+  fn from_expr(expr: &Expr) -> syn::Result<Self> {
+    let output = match expr {
+      Expr::Lit(expr_lit) => Self::Lit {
+        lit: expr_lit.lit.clone(),
+        negative: false,
+      },
+      Expr::Unary(ExprUnary {
+        op: UnOp::Neg(_),
+        expr: inner,
+        ..
+      }) => match inner.as_ref() {
+        Expr::Lit(expr_lit) if matches!(expr_lit.lit, Lit::Int(_) | Lit::Float(_)) => Self::Lit {
+          lit: expr_lit.lit.clone(),
+          negative: true,
+        },
+        _ => return Err(error!(expr, "Only numeric literals can be negated")),
+      },
+      Expr::Path(expr_path) => Self::EnumVariant(expr_path.path.clone()),
+      _ => {
+        return Err(error!(
+          expr,
+          "Fields can only be compared with literals or enum variants"
+        ));
+      }
+    };
+
+    Ok(output)
+  }
+}
+
+/// The generated checks for a [`FieldCondition`].
+pub struct ConditionTokens {
+  /// The check on the proto struct, which is available as `self`.
+  pub rust: TokenStream2,
+  /// An expression that returns the equivalent CEL expression as a `String`.
+  pub cel: TokenStream2,
+}
+
+enum CelPart {
+  Str(String),
+  Arg(TokenStream2),
+}
+
+#[derive(Default)]
+struct CelBuilder {
+  parts: Vec<CelPart>,
+}
+
+impl CelBuilder {
+  fn push_str(&mut self, str: &str) {
+    if let Some(CelPart::Str(last)) = self.parts.last_mut() {
+      last.push_str(str);
+    } else {
+      self.parts.push(CelPart::Str(str.to_string()));
+    }
+  }
+
+  fn push_arg(&mut self, arg: TokenStream2) {
+    self.parts.push(CelPart::Arg(arg));
+  }
+
+  fn into_tokens(self) -> TokenStream2 {
+    let has_args = self
+      .parts
+      .iter()
+      .any(|part| matches!(part, CelPart::Arg(_)));
+
+    if !has_args {
+      let expr: String = self
+        .parts
+        .into_iter()
+        .filter_map(|part| match part {
+          CelPart::Str(str) => Some(str),
+          CelPart::Arg(_) => None,
+        })
+        .collect();
+
+      return quote! { ::prelude::String::from(#expr) };
+    }
+
+    let mut format_str = String::new();
+    let mut args: Vec<TokenStream2> = Vec::new();
+
+    for part in self.parts {
+      match part {
+        CelPart::Str(str) => format_str.push_str(&str.replace('{', "{{").replace('}', "}}")),
+        CelPart::Arg(arg) => {
+          format_str.push_str("{}");
+          args.push(arg);
+        }
+      }
+    }
+
+    quote! { ::prelude::format!(#format_str, #(#args),*) }
+  }
+}
+
+fn cel_string_literal(value: &str) -> String {
+  let mut output = String::with_capacity(value.len() + 2);
+
+  output.push('"');
+
+  for c in value.chars() {
+    match c {
+      '"' => output.push_str("\\\""),
+      '\\' => output.push_str("\\\\"),
+      '\n' => output.push_str("\\n"),
+      '\r' => output.push_str("\\r"),
+      '\t' => output.push_str("\\t"),
+      _ => output.push(c),
+    }
+  }
+
+  output.push('"');
+
+  output
+}
+
+impl FieldCondition {
+  pub fn from_expr(expr: &Expr) -> syn::Result<Self> {
+    let output = match expr {
+      Expr::Paren(paren) => Self::from_expr(&paren.expr)?,
+      Expr::Group(group) => Self::from_expr(&group.expr)?,
+      Expr::Unary(ExprUnary {
+        op: UnOp::Not(_),
+        expr: inner,
+        ..
+      }) => Self::Not(Box::new(Self::from_expr(inner)?)),
+      Expr::Binary(ExprBinary {
+        left, op, right, ..
+      }) => match op {
+        BinOp::And(_) => Self::And(
+          Box::new(Self::from_expr(left)?),
+          Box::new(Self::from_expr(right)?),
+        ),
+        BinOp::Or(_) => Self::Or(
+          Box::new(Self::from_expr(left)?),
+          Box::new(Self::from_expr(right)?),
+        ),
+        _ => {
+          let op = CompareOp::from_bin_op(op)
+            .ok_or_else(|| error!(op, "Unsupported operator in a field condition"))?;
+
+          Self::Compare {
+            field: field_ident(left)?,
+            op,
+            value: ConditionValue::from_expr(right)?,
+          }
+        }
+      },
+      Expr::Path(_) => Self::IsSet(field_ident(expr)?),
+      _ => {
+        return Err(error!(
+          expr,
+          "Conditions can only contain field names, comparisons between fields and values, and the `!`, `&&` and `||` operators"
+        ));
+      }
+    };
+
+    Ok(output)
+  }
+
+  /// Generates the checks for this condition, resolving the fields it refers to.
+  pub fn resolve(&self, fields: &[FieldDataKind]) -> syn::Result<ConditionTokens> {
+    let mut cel = CelBuilder::default();
+
+    let rust = self.write_tokens(fields, &mut cel)?;
+
+    Ok(ConditionTokens {
+      rust,
+      cel: cel.into_tokens(),
+    })
+  }
+
+  fn write_tokens(
+    &self,
+    fields: &[FieldDataKind],
+    cel: &mut CelBuilder,
+  ) -> syn::Result<TokenStream2> {
+    let output = match self {
+      Self::And(left, right) | Self::Or(left, right) => {
+        let is_and = matches!(self, Self::And(..));
+
+        cel.push_str("(");
+        let left = left.write_tokens(fields, cel)?;
+        cel.push_str(if is_and { ") && (" } else { ") || (" });
+        let right = right.write_tokens(fields, cel)?;
+        cel.push_str(")");
+
+        if is_and {
+          quote! { (#left) && (#right) }
+        } else {
+          quote! { (#left) || (#right) }
+        }
+      }
+      Self::Not(inner) => {
+        cel.push_str("!(");
+        let inner = inner.write_tokens(fields, cel)?;
+        cel.push_str(")");
+
+        quote! { !(#inner) }
+      }
+      Self::IsSet(ident) => {
+        let data = find_field(ident, fields)?;
+
+        cel.push_str(&format!("has(this.{})", data.proto_name));
+
+        let (_, is_set, _) = data.presence_accessors(&quote_spanned! {ident.span()=> self });
+
+        is_set
+      }
+      Self::Compare { field, op, value } => {
+        let data = find_field(field, fields)?;
+        let span = field.span();
+
+        let (ProtoField::Single(proto_type) | ProtoField::Optional(proto_type)) = &data.proto_field
+        else {
+          bail!(
+            field,
+            "Only singular fields can be compared with a value. Use the field name to check whether it's set"
+          );
+        };
+
+        let (rust_value, cel_value) = comparison_value(proto_type, *op, value, field)?;
+
+        let is_optional = matches!(data.proto_field, ProtoField::Optional(_));
+        let proto_name = &data.proto_name;
+        let ident = &data.ident;
+        let op_tokens = op.to_tokens(span);
+
+        if is_optional {
+          cel.push_str(&format!("(has(this.{proto_name}) && this.{proto_name} "));
+        } else {
+          cel.push_str(&format!("this.{proto_name} "));
+        }
+
+        cel.push_str(op.as_str());
+        cel.push_str(" ");
+
+        match cel_value {
+          CelPart::Str(str) => cel.push_str(&str),
+          CelPart::Arg(arg) => cel.push_arg(arg),
+        };
+
+        if is_optional {
+          cel.push_str(")");
+        }
+
+        match (proto_type, is_optional) {
+          (ProtoType::String, false) => {
+            quote_spanned! {span=> self.#ident.as_str() #op_tokens #rust_value }
+          }
+          (ProtoType::String, true) => {
+            quote_spanned! {span=> self.#ident.as_deref().is_some_and(|v| v #op_tokens #rust_value) }
+          }
+          (_, false) => quote_spanned! {span=> self.#ident #op_tokens #rust_value },
+          (_, true) => {
+            quote_spanned! {span=> self.#ident.is_some_and(|v| v #op_tokens #rust_value) }
+          }
+        }
+      }
+    };
+
+    Ok(output)
+  }
+}
+
+/// Checks that the `when` conditions of the fields of a message refer to valid fields and values.
+pub fn check_field_conditions(fields: &[FieldDataKind]) -> syn::Result<()> {
+  for condition in fields
+    .iter()
+    .filter_map(|d| d.as_normal())
+    .filter_map(|d| d.when.as_ref())
+  {
+    condition.resolve(fields)?;
+  }
+
+  Ok(())
+}
+
+fn field_ident(expr: &Expr) -> syn::Result<Ident> {
+  if let Expr::Path(expr_path) = expr
+    && let Some(ident) = expr_path.path.get_ident()
+  {
+    Ok(ident.clone())
+  } else {
+    Err(error!(expr, "Expected the name of a field"))
+  }
+}
+
+fn find_field<'a>(ident: &Ident, fields: &'a [FieldDataKind]) -> syn::Result<&'a FieldData> {
+  let data = fields
+    .iter()
+    .filter_map(|d| d.as_normal())
+    .find(|d| d.ident == *ident)
+    .ok_or_else(|| error!(ident, "Unknown field `{ident}`"))?;
+
+  if data.proto_field.is_oneof() {
+    bail!(ident, "Oneofs cannot be used in field conditions");
+  }
+
+  Ok(data)
+}
+
+/// Returns the Rust tokens and the CEL representation of the value being compared with a field.
+fn comparison_value(
+  proto_type: &ProtoType,
+  op: CompareOp,
+  value: &ConditionValue,
+  field: &Ident,
+) -> syn::Result<(TokenStream2, CelPart)> {
+  let span = field.span();
+
+  let mismatch = || error!(field, "The value does not match the type of the field");
+
+  let output = match (proto_type, value) {
+    (ProtoType::Enum(_), ConditionValue::EnumVariant(path)) => (
+      quote_spanned! {span=> (#path as i32) },
+      CelPart::Arg(quote_spanned! {span=> #path as i32 }),
+    ),
+    (
+      ProtoType::Bool,
+      ConditionValue::Lit {
+        lit: Lit::Bool(lit),
+        ..
+      },
+    ) => {
+      if !op.is_equality() {
+        bail!(field, "Booleans can only be compared with `==` or `!=`");
+      }
+
+      (lit.to_token_stream(), CelPart::Str(lit.value.to_string()))
+    }
+    (
+      ProtoType::String,
+      ConditionValue::Lit {
+        lit: Lit::Str(lit), ..
+      },
+    ) => (
+      lit.to_token_stream(),
+      CelPart::Str(cel_string_literal(&lit.value())),
+    ),
+    (
+      ProtoType::Float | ProtoType::Double,
+      ConditionValue::Lit {
+        lit: lit @ (Lit::Int(_) | Lit::Float(_)),
+        negative,
+      },
+    ) => {
+      let digits = match lit {
+        Lit::Int(int) => format!("{}.0", int.base10_digits()),
+        Lit::Float(float) => float.base10_digits().to_string(),
+        _ => unreachable!(),
+      };
+
+      let sign = if *negative { "-" } else { "" };
+      let value = format!("{sign}{digits}");
+      let float: TokenStream2 = value.parse().map_err(|_| mismatch())?;
+
+      (quote_spanned! {span=> (#float) }, CelPart::Str(value))
+    }
+    (
+      ProtoType::Int32
+      | ProtoType::Int64
+      | ProtoType::Sint32
+      | ProtoType::Sint64
+      | ProtoType::Sfixed32
+      | ProtoType::Sfixed64,
+      ConditionValue::Lit {
+        lit: Lit::Int(int),
+        negative,
+      },
+    ) => {
+      let sign = if *negative { "-" } else { "" };
+      let digits = int.base10_digits();
+
+      if *negative {
+        (
+          quote_spanned! {span=> (-#int) },
+          CelPart::Str(format!("{sign}{digits}")),
+        )
+      } else {
+        (int.to_token_stream(), CelPart::Str(digits.to_string()))
+      }
+    }
+    // Unsigned literals must have the `u` suffix in CEL
+    (
+      ProtoType::Uint32 | ProtoType::Uint64 | ProtoType::Fixed32 | ProtoType::Fixed64,
+      ConditionValue::Lit {
+        lit: Lit::Int(int),
+        negative: false,
+      },
+    ) => (
+      int.to_token_stream(),
+      CelPart::Str(format!("{}u", int.base10_digits())),
+    ),
+    (
+      ProtoType::Bytes
+      | ProtoType::Message(_)
+      | ProtoType::Duration
+      | ProtoType::Timestamp
+      | ProtoType::Any
+      | ProtoType::FieldMask,
+      _,
+    ) => {
+      bail!(
+        field,
+        "This field cannot be compared with a value. Use the field name to check whether it's set"
+      );
+    }
+    _ => return Err(mismatch()),
+  };
+
+  Ok(output)
+}
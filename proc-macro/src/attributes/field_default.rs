@@ -0,0 +1,227 @@
+use syn::{ExprUnary, LitFloat, UnOp};
+
+use crate::*;
+
+/// The value set with `#[proto(default = ...)]`.
+#[derive(Clone)]
+pub enum FieldDefault {
+  Lit { lit: Lit, negative: bool },
+  EnumVariant(Ident),
+}
+
+impl FieldDefault {
+  pub fn span(&self) -> Span {
+    match self {
+      Self::Lit { lit, .. } => lit.span(),
+      Self::EnumVariant(ident) => ident.span(),
+    }
+  }
+
+  pub fn from_expr(expr: &Expr) -> syn::Result<Self> {
+    let output = match expr {
+      Expr::Lit(expr_lit) => Self::Lit {
+        lit: expr_lit.lit.clone(),
+        negative: false,
+      },
+      Expr::Unary(ExprUnary {
+        op: UnOp::Neg(_),
+        expr: inner,
+        ..
+      }) => match inner.as_ref() {
+        Expr::Lit(expr_lit) if matches!(expr_lit.lit, Lit::Int(_) | Lit::Float(_)) => Self::Lit {
+          lit: expr_lit.lit.clone(),
+          negative: true,
+        },
+        _ => return Err(error!(expr, "Only numeric literals can be negated")),
+      },
+      // Enum variants can be referred to with their name or their full path
+      Expr::Path(expr_path) => {
+        let last_segment = expr_path
+          .path
+          .segments
+          .last()
+          .ok_or_else(|| error!(expr_path, "Expected an enum variant"))?;
+
+        Self::EnumVariant(last_segment.ident.clone())
+      }
+      _ => {
+        return Err(error!(
+          expr,
+          "Default values must be literals or enum variants"
+        ));
+      }
+    };
+
+    if let Self::Lit { lit, .. } = &output {
+      let suffix = match lit {
+        Lit::Int(int) => int.suffix(),
+        Lit::Float(float) => float.suffix(),
+        _ => "",
+      };
+
+      if !suffix.is_empty() {
+        return Err(error!(lit, "Default values cannot have a type suffix"));
+      }
+    }
+
+    Ok(output)
+  }
+
+  /// Checks that the default value can be used with this field, and that its type matches the type of the field.
+  pub fn check(&self, proto_field: &ProtoField, required: bool, span: Span) -> syn::Result<()> {
+    let proto_type = match proto_field {
+      ProtoField::Optional(proto_type) => proto_type,
+      ProtoField::Single(proto_type) if required => proto_type,
+      ProtoField::Single(proto_type) if !proto_type.is_message() => {
+        bail_with_span!(
+          span,
+          "`default` cannot be used on fields with implicit presence, whose default is always the zero value. Use an Option or mark the field as required"
+        );
+      }
+      _ => {
+        bail_with_span!(
+          span,
+          "`default` can only be used with singular scalar, string, bytes or enum fields"
+        );
+      }
+    };
+
+    let is_valid = match (proto_type, self) {
+      (
+        ProtoType::String,
+        Self::Lit {
+          lit: Lit::Str(_), ..
+        },
+      )
+      | (
+        ProtoType::Bytes,
+        Self::Lit {
+          lit: Lit::ByteStr(_),
+          ..
+        },
+      )
+      | (
+        ProtoType::Bool,
+        Self::Lit {
+          lit: Lit::Bool(_), ..
+        },
+      )
+      | (ProtoType::Enum(_), Self::EnumVariant(_))
+      | (
+        ProtoType::Float | ProtoType::Double,
+        Self::Lit {
+          lit: Lit::Int(_) | Lit::Float(_),
+          ..
+        },
+      )
+      | (
+        ProtoType::Int32
+        | ProtoType::Int64
+        | ProtoType::Sint32
+        | ProtoType::Sint64
+        | ProtoType::Sfixed32
+        | ProtoType::Sfixed64,
+        Self::Lit {
+          lit: Lit::Int(_), ..
+        },
+      )
+      | (
+        ProtoType::Uint32 | ProtoType::Uint64 | ProtoType::Fixed32 | ProtoType::Fixed64,
+        Self::Lit {
+          lit: Lit::Int(_),
+          negative: false,
+        },
+      ) => true,
+      (
+        ProtoType::Message(_)
+        | ProtoType::Duration
+        | ProtoType::Timestamp
+        | ProtoType::Any
+        | ProtoType::FieldMask,
+        _,
+      ) => {
+        bail_with_span!(span, "`default` cannot be used with message fields");
+      }
+      _ => false,
+    };
+
+    if !is_valid {
+      bail_with_span!(
+        span,
+        "The default value does not match the type of the field"
+      );
+    }
+
+    Ok(())
+  }
+
+  /// The `default` argument for the prost attribute.
+  pub fn prost_attr_tokens(&self, span: Span) -> TokenStream2 {
+    match self {
+      Self::Lit {
+        lit,
+        negative: false,
+      } => quote_spanned! {span=> default = #lit },
+      // Prost parses negative numbers from strings
+      Self::Lit {
+        lit,
+        negative: true,
+      } => {
+        let value = format!("-{}", lit.to_token_stream());
+
+        quote_spanned! {span=> default = #value }
+      }
+      Self::EnumVariant(ident) => {
+        let variant = ident.to_string();
+
+        quote_spanned! {span=> default = #variant }
+      }
+    }
+  }
+
+  /// The tokens for the `OptionValue` of the `default` option in the schema.
+  pub fn option_value_tokens(&self, proto_type: &ProtoType, span: Span) -> TokenStream2 {
+    let sign = match self {
+      Self::Lit { negative: true, .. } => Some(quote_spanned! {span=> - }),
+      _ => None,
+    };
+
+    match (self, proto_type) {
+      (Self::EnumVariant(variant), ProtoType::Enum(path)) => quote_spanned! {span=>
+        ::prelude::OptionValue::Enum(::prelude::ProtoEnumSchema::as_proto_name(&#path::#variant).into())
+      },
+      (Self::Lit { lit, .. }, ProtoType::Float | ProtoType::Double) => {
+        let float = match lit {
+          Lit::Int(int) => Lit::Float(LitFloat::new(&format!("{}.0", int.base10_digits()), span)),
+          _ => lit.clone(),
+        };
+
+        quote_spanned! {span=> ::prelude::OptionValue::Float(#sign #float) }
+      }
+      (
+        Self::Lit { lit, .. },
+        ProtoType::Uint32 | ProtoType::Uint64 | ProtoType::Fixed32 | ProtoType::Fixed64,
+      ) => quote_spanned! {span=> ::prelude::OptionValue::Uint(#lit) },
+      (
+        Self::Lit {
+          lit: Lit::Int(lit), ..
+        },
+        _,
+      ) => {
+        quote_spanned! {span=> ::prelude::OptionValue::Int(#sign #lit) }
+      }
+      (
+        Self::Lit {
+          lit: Lit::Str(lit), ..
+        },
+        _,
+      ) => {
+        quote_spanned! {span=> ::prelude::OptionValue::String(#lit.into()) }
+      }
+      (Self::Lit { lit, .. }, _) => quote_spanned! {span=> ::prelude::OptionValue::from(#lit) },
+      (Self::EnumVariant(_), _) => {
+        quote_spanned! {span=> compile_error!("Found an enum default for a non-enum field, please report this bug if you see it") }
+      }
+    }
+  }
+}
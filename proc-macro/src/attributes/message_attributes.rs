@@ -6,7 +6,9 @@ use crate::*;
 pub struct MessageAttrs {
   pub reserved_names: Vec<String>,
   pub reserved_numbers: ReservedNumbers,
+  pub tag_lockfile: Option<TagLockfileAttr>,
   pub options: TokensOr<TokenStream2>,
+  pub features: TokensOr<TokenStream2>,
   pub name: ParsedStr,
   pub parent_message: Option<Ident>,
   pub from_proto: Option<PathOrClosure>,
@@ -17,6 +19,9 @@ pub struct MessageAttrs {
   pub auto_tests: AutoTests,
   pub deprecated: bool,
   pub validators: Validators,
+  pub async_validators: Validators,
+  pub groups: Option<ValidationGroups>,
+  pub comments: DocComments,
 }
 
 impl MessageAttrs {
@@ -60,7 +65,9 @@ pub fn process_message_attrs(
 ) -> Result<MessageAttrs, Error> {
   let mut reserved_names: Vec<String> = Vec::new();
   let mut reserved_numbers = ReservedNumbers::default();
+  let mut tag_lockfile: Option<TagLockfileAttr> = None;
   let mut options = TokensOr::<TokenStream2>::vec();
+  let mut features = features_tokens_or_default();
   let mut proto_name: Option<ParsedStr> = None;
   let mut from_proto: Option<PathOrClosure> = None;
   let mut into_proto: Option<PathOrClosure> = None;
@@ -68,6 +75,7 @@ pub fn process_message_attrs(
   let mut parent_message: Option<Ident> = None;
   let mut deprecated = false;
   let mut validators = Validators::default();
+  let mut groups: Option<ValidationGroups> = None;
   let mut auto_tests = AutoTests::default();
   let mut forwarded_attrs: Vec<Meta> = Vec::new();
 
@@ -96,6 +104,9 @@ pub fn process_message_attrs(
             "validate" => {
               validators = meta.parse_value::<Validators>()?;
             }
+            "groups" => {
+              groups = Some(ValidationGroups::from_expr(meta.expr_value()?)?);
+            }
             "deprecated" => {
               let boolean = meta.parse_value::<LitBool>()?;
 
@@ -111,6 +122,9 @@ pub fn process_message_attrs(
 
               reserved_numbers = numbers;
             }
+            "tag_lockfile" => {
+              tag_lockfile = Some(TagLockfileAttr::parse(&meta)?);
+            }
             "derive" => {
               shadow_derives = meta.parse_list::<PathList>()?.list;
             }
@@ -123,6 +137,10 @@ pub fn process_message_attrs(
                   .clone(),
               );
             }
+            "features" => {
+              features.span = meta.input.span();
+              features.set(meta.expr_value()?.into_token_stream());
+            }
             "options" => {
               options.span = meta.input.span();
               options.set(meta.expr_value()?.into_token_stream());
@@ -146,13 +164,7 @@ pub fn process_message_attrs(
     }
   }
 
-  for validator in validators.validators.iter_mut() {
-    if validator.kind.is_closure() {
-      validator.expr = quote_spanned! {validator.span=>
-        ::prelude::apply(::prelude::CelValidator::default(), #validator)
-      }
-    }
-  }
+  let async_validators = validators.take_async()?;
 
   let name = proto_name
     .unwrap_or_else(|| ParsedStr::with_default_span(to_pascal_case(&struct_ident.to_string())));
@@ -160,7 +172,9 @@ pub fn process_message_attrs(
   Ok(MessageAttrs {
     reserved_names,
     reserved_numbers,
+    tag_lockfile,
     options,
+    features,
     name,
     parent_message,
     from_proto,
@@ -170,6 +184,9 @@ pub fn process_message_attrs(
     auto_tests,
     deprecated,
     validators,
+    async_validators,
+    groups,
     forwarded_attrs,
+    comments: DocComments::from_attributes(attrs),
   })
 }
@@ -5,6 +5,7 @@ pub struct MessageInfo {
   pub path: Path,
   pub boxed: bool,
   pub default: bool,
+  pub delimited: bool,
 }
 
 impl MessageInfo {
@@ -12,6 +13,7 @@ impl MessageInfo {
     let mut item_path = ItemPathEntry::default();
     let mut boxed = false;
     let mut default = false;
+    let mut delimited = false;
 
     // Checking first in case we just get `message` without the parentheses
     if meta.is_list() {
@@ -23,6 +25,7 @@ impl MessageInfo {
             }
             "boxed" => boxed = true,
             "default" => default = true,
+            "delimited" => delimited = true,
             _ => item_path = ItemPathEntry::Path(meta.path),
           };
         } else {
@@ -67,10 +70,15 @@ impl MessageInfo {
       }
     };
 
+    if boxed && delimited {
+      return Err(meta.error("Delimited messages cannot be boxed"));
+    }
+
     Ok(Self {
       path,
       boxed,
       default,
+      delimited,
     })
   }
 }
@@ -4,12 +4,14 @@ use crate::*;
 pub struct AutoTests {
   pub skip_consistency_checks: bool,
   pub skip_oneof_tags_check: bool,
+  pub skip_edition_check: bool,
 }
 
 impl AutoTests {
   pub fn parse(nested_meta: &ParseNestedMeta) -> syn::Result<Self> {
     let mut skip_consistency_checks = false;
     let mut skip_oneof_tags_check = false;
+    let mut skip_edition_check = false;
 
     nested_meta.parse_nested_meta(|meta| {
       let ident_str = meta.ident_str()?;
@@ -18,6 +20,7 @@ impl AutoTests {
         "all" => {
           skip_consistency_checks = true;
           skip_oneof_tags_check = true;
+          skip_edition_check = true;
         }
         "validators" => {
           skip_consistency_checks = true;
@@ -25,6 +28,9 @@ impl AutoTests {
         "oneof_tags" => {
           skip_oneof_tags_check = true;
         }
+        "edition" => {
+          skip_edition_check = true;
+        }
         _ => return Err(meta.error("Unknown attribute")),
       };
 
@@ -34,15 +40,19 @@ impl AutoTests {
     Ok(Self {
       skip_consistency_checks,
       skip_oneof_tags_check,
+      skip_edition_check,
     })
   }
 }
 
 mod extension_field_attributes;
 pub use extension_field_attributes::*;
+mod doc_comments;
 mod enum_attributes;
 mod enum_variant_attributes;
 mod field_attributes;
+mod field_condition;
+mod field_default;
 mod message_attributes;
 mod message_info;
 mod oneof_attributes;
@@ -50,10 +60,15 @@ mod oneof_info;
 mod reserved_numbers;
 mod service_attributes;
 mod tag_allocator;
+mod tag_lockfile;
+mod validation_groups;
 
+pub use doc_comments::*;
 pub use enum_attributes::*;
 pub use enum_variant_attributes::*;
 pub use field_attributes::*;
+pub use field_condition::*;
+pub use field_default::*;
 pub use message_attributes::*;
 pub use message_info::*;
 pub use oneof_attributes::*;
@@ -61,3 +76,5 @@ pub use oneof_info::*;
 pub use reserved_numbers::*;
 pub use service_attributes::*;
 pub use tag_allocator::*;
+pub use tag_lockfile::*;
+pub use validation_groups::*;
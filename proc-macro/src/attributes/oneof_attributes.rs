@@ -11,6 +11,7 @@ pub struct OneofAttrs {
   pub is_proxied: bool,
   pub auto_tests: AutoTests,
   pub validators: Validators,
+  pub comments: DocComments,
 }
 
 #[derive(Default)]
@@ -93,6 +94,13 @@ pub fn process_oneof_attrs(
     if validator.kind.is_closure() {
       bail_with_span!(validator.span, "Closures are not supported for oneofs");
     }
+
+    if validator.kind.is_async() {
+      bail_with_span!(
+        validator.span,
+        "Async validators are not supported for oneofs"
+      );
+    }
   }
 
   Ok(OneofAttrs {
@@ -105,5 +113,6 @@ pub fn process_oneof_attrs(
     auto_tests,
     validators,
     forwarded_attrs,
+    comments: DocComments::from_attributes(attrs),
   })
 }
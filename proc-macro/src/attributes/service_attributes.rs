@@ -3,6 +3,7 @@ use crate::*;
 pub struct ServiceOrHandlerAttrs {
   pub options: TokensOr<TokenStream2>,
   pub deprecated: bool,
+  pub comments: DocComments,
 }
 
 pub fn process_service_or_handler_attrs(
@@ -49,5 +50,6 @@ pub fn process_service_or_handler_attrs(
   Ok(ServiceOrHandlerAttrs {
     options,
     deprecated,
+    comments: DocComments::from_attributes(attrs),
   })
 }
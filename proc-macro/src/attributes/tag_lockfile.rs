@@ -0,0 +1,306 @@
+use std::{collections::BTreeMap, fs, io::ErrorKind, path::PathBuf};
+
+use syn::LitStr;
+
+use crate::*;
+
+#[derive(Clone)]
+pub struct TagLockfileAttr {
+  pub path: String,
+  pub strict: bool,
+  pub span: Span,
+}
+
+impl TagLockfileAttr {
+  // Supports both `tag_lockfile = "path"` and `tag_lockfile(path = "path", strict)`
+  pub fn parse(meta: &ParseNestedMeta) -> syn::Result<Self> {
+    let span = meta.path.span();
+
+    if !meta.is_list() {
+      let path = meta.parse_value::<LitStr>()?.value();
+
+      return Ok(Self {
+        path,
+        strict: false,
+        span,
+      });
+    }
+
+    let mut path: Option<String> = None;
+    let mut strict = false;
+
+    meta.parse_nested_meta(|meta| {
+      let ident_str = meta.ident_str()?;
+
+      match ident_str.as_str() {
+        "path" => path = Some(meta.parse_value::<LitStr>()?.value()),
+        "strict" => strict = true,
+        _ => return Err(meta.error("Unknown attribute")),
+      };
+
+      Ok(())
+    })?;
+
+    let path = path.ok_or_else(|| error_with_span!(span, "Missing lockfile path"))?;
+
+    Ok(Self { path, strict, span })
+  }
+}
+
+/// Reads the tags assigned to the fields of messages (or the variants of enums)
+/// so that the tags that were allocated automatically remain stable across changes.
+///
+/// The lockfile is only read by the macros. It is written with `Package::write_tag_lockfile`,
+/// and the fields that are not in the lockfile yet are allocated the next free tags.
+/// The tests generated with [`TagLockfile::test_tokens`] report the entries that are missing or outdated.
+pub struct TagLockfile {
+  path: PathBuf,
+  exists: bool,
+  strict: bool,
+  span: Span,
+  // The proto path of the item, which is unknown for nested items
+  // until the path of their parent is recorded in the lockfile
+  item: Option<String>,
+  entries: BTreeMap<String, i32>,
+}
+
+impl TagLockfile {
+  pub fn load(
+    attr: &TagLockfileAttr,
+    name: &str,
+    parent_message: Option<&Ident>,
+  ) -> syn::Result<Self> {
+    let TagLockfileAttr { path, strict, span } = attr;
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").map_err(|_| {
+      error_with_span!(
+        *span,
+        "Cannot locate the tag lockfile because `CARGO_MANIFEST_DIR` is not set"
+      )
+    })?;
+    let path = PathBuf::from(manifest_dir).join(path);
+
+    // A missing lockfile is treated as empty, so that it can be created with `Package::write_tag_lockfile`
+    let (content, exists) = match fs::read_to_string(&path) {
+      Ok(content) => (content, true),
+      Err(e) if e.kind() == ErrorKind::NotFound => (String::new(), false),
+      Err(e) => bail_with_span!(
+        *span,
+        "Failed to read the tag lockfile at `{}`: {e}",
+        path.display()
+      ),
+    };
+
+    let mut entries = BTreeMap::new();
+    let mut parents = BTreeMap::new();
+
+    for line in content.lines() {
+      let line = line.trim();
+
+      if line.is_empty() || line.starts_with('#') {
+        continue;
+      }
+
+      let Some((key, value)) = line.split_once('=') else {
+        bail_with_span!(
+          *span,
+          "Invalid line in the tag lockfile at `{}`: `{line}`",
+          path.display()
+        );
+      };
+
+      let (key, value) = (key.trim(), value.trim());
+
+      if let Some(ident) = key.strip_prefix('@') {
+        parents.insert(ident.to_string(), value.to_string());
+        continue;
+      }
+
+      let tag = value.parse::<i32>().map_err(|e| {
+        error_with_span!(
+          *span,
+          "Invalid tag in the tag lockfile at `{}`: `{line}` ({e})",
+          path.display()
+        )
+      })?;
+
+      entries.insert(key.to_string(), tag);
+    }
+
+    let item = match parent_message {
+      Some(parent) => parents
+        .get(&parent.to_string())
+        .map(|parent_path| format!("{parent_path}.{name}")),
+      None => Some(name.to_string()),
+    };
+
+    Ok(Self {
+      path,
+      exists,
+      strict: *strict,
+      span: *span,
+      item,
+      entries,
+    })
+  }
+
+  pub fn locked_tag(&self, name: &str) -> Option<i32> {
+    let item = self.item.as_ref()?;
+
+    self
+      .entries
+      .get(&format!("{item}.{name}"))
+      .copied()
+  }
+
+  // Returns the entries that belong to this item (and not to its nested items)
+  fn item_entries(&self) -> impl Iterator<Item = (&str, i32)> {
+    let prefix = self.item.as_ref().map(|item| format!("{item}."));
+
+    self.entries.iter().filter_map(move |(key, tag)| {
+      key
+        .strip_prefix(prefix.as_deref()?)
+        .filter(|name| !name.contains('.'))
+        .map(|name| (name, *tag))
+    })
+  }
+
+  /// Checks the locked entries that are no longer present in the item, and returns their tags,
+  /// so that they are not assigned to other fields.
+  ///
+  /// Entries for items that have been reserved (in both name and number) are ignored,
+  /// whereas in strict mode, an error is returned if they have not been reserved.
+  pub fn removed_tags(
+    &self,
+    current_names: &[&str],
+    reserved_names: &[String],
+    reserved_numbers: &ReservedNumbers,
+    manual_tags: &[ParsedNum],
+  ) -> syn::Result<Vec<ParsedNum>> {
+    let mut tags = Vec::new();
+
+    for (name, tag) in self.item_entries() {
+      if current_names.contains(&name) {
+        continue;
+      }
+
+      let is_reserved = reserved_names.iter().any(|n| n == name) && reserved_numbers.contains(tag);
+
+      if is_reserved {
+        continue;
+      }
+
+      if self.strict {
+        bail_with_span!(
+          self.span,
+          "`{}.{name}` (tag {tag}) is recorded in the tag lockfile but it's no longer present. Add its name and number to the reserved names and numbers, or remove it from the lockfile",
+          self.item.as_deref().unwrap_or_default()
+        );
+      } else if !reserved_numbers.contains(tag) && !manual_tags.iter().any(|t| t.num == tag) {
+        tags.push(ParsedNum {
+          num: tag,
+          span: self.span,
+        });
+      }
+    }
+
+    Ok(tags)
+  }
+
+  /// Assigns the locked tags to the fields without a manually assigned tag.
+  pub fn lock_fields(
+    &self,
+    fields_data: &mut [FieldDataKind],
+    reserved_names: &[String],
+    reserved_numbers: &ReservedNumbers,
+    manual_tags: &mut Vec<ParsedNum>,
+  ) -> syn::Result<()> {
+    for data in fields_data.iter_mut() {
+      if let FieldDataKind::Normal(data) = data
+        && data.tag.is_none()
+        && !data.proto_field.is_oneof()
+        && let Some(num) = self.locked_tag(&data.proto_name)
+      {
+        let tag = ParsedNum {
+          num,
+          span: data.span,
+        };
+
+        data.tag = Some(tag);
+        manual_tags.push(tag);
+      }
+    }
+
+    let current_names: Vec<&str> = fields_data
+      .iter()
+      .filter_map(|data| match data {
+        FieldDataKind::Normal(data) if !data.proto_field.is_oneof() => {
+          Some(data.proto_name.as_str())
+        }
+        _ => None,
+      })
+      .collect();
+
+    let removed_tags = self.removed_tags(
+      &current_names,
+      reserved_names,
+      reserved_numbers,
+      manual_tags,
+    )?;
+
+    manual_tags.extend(removed_tags);
+
+    Ok(())
+  }
+
+  /// Generates a test that checks that the lockfile records the current tag of each field (or variant) of the item.
+  ///
+  /// The macros never write to the lockfile, so the test fails with the entries that must be added or updated,
+  /// which can be done with `Package::write_tag_lockfile`.
+  pub fn test_tokens(
+    &self,
+    item_ident: &Ident,
+    proto_name: TokenStream2,
+    parent_message: Option<&Ident>,
+    assigned: impl IntoIterator<Item = (String, i32)>,
+  ) -> TokenStream2 {
+    let path = self.path.display().to_string();
+    let test_fn_ident = format_ident!("{}_tag_lockfile", to_snake_case(&item_ident.to_string()));
+
+    let parent = match parent_message {
+      Some(parent) => {
+        let parent_str = parent.to_string();
+
+        quote_spanned! {parent.span()=>
+          Some((#parent_str, <#parent as ::prelude::ProtoMessage>::proto_name()))
+        }
+      }
+      None => quote! { None },
+    };
+
+    let assigned = assigned
+      .into_iter()
+      .map(|(name, tag)| quote! { (#name, #tag) });
+
+    quote_spanned! {self.span=>
+      #[cfg(test)]
+      #[test]
+      fn #test_fn_ident() {
+        if let Err(e) = ::prelude::check_tag_lockfile(#path, #proto_name, #parent, &[ #(#assigned),* ]) {
+          panic!("{e}")
+        }
+      }
+    }
+  }
+
+  /// Makes cargo rebuild the item whenever the lockfile changes.
+  pub fn tracking_tokens(&self) -> Option<TokenStream2> {
+    let path = self.path.display().to_string();
+
+    self.exists.then(|| {
+      quote! {
+        const _: &[u8] = include_bytes!(#path);
+      }
+    })
+  }
+}
@@ -0,0 +1,62 @@
+use crate::*;
+
+/// The groups set with `#[proto(groups = [...])]`, which limit the validators of a field
+/// or message to the matching validation profiles.
+#[derive(Clone)]
+pub struct ValidationGroups {
+  pub groups: Vec<Expr>,
+  pub span: Span,
+}
+
+impl ValidationGroups {
+  pub fn from_expr(expr: Expr) -> syn::Result<Self> {
+    let span = expr.span();
+
+    let groups: Vec<Expr> = match expr {
+      Expr::Array(array) => array.elems.into_iter().collect(),
+      other => vec![other],
+    };
+
+    if groups.is_empty() {
+      bail_with_span!(span, "At least one group must be specified");
+    }
+
+    Ok(Self { groups, span })
+  }
+
+  fn names_tokens(&self) -> TokenStream2 {
+    let span = self.span;
+    let groups = &self.groups;
+
+    quote_spanned! {span=>
+      [ #(::prelude::ValidationProfile::profile_name(&#groups)),* ]
+    }
+  }
+
+  /// Wraps the given validation tokens so that they only run in the matching profiles.
+  pub fn wrap_validation(&self, tokens: TokenStream2) -> TokenStream2 {
+    let span = self.span;
+    let names = self.names_tokens();
+
+    quote_spanned! {span=>
+      if ctx.is_in_groups(&#names) {
+        #tokens
+      }
+    }
+  }
+
+  /// Wraps the tokens for an `Option<ValidatorSchema>` so that the schema is only rendered
+  /// if the groups include the default profile.
+  pub fn wrap_schema(&self, tokens: TokenStream2) -> TokenStream2 {
+    let span = self.span;
+    let names = self.names_tokens();
+
+    quote_spanned! {span=>
+      if ::prelude::includes_default_profile(&#names) {
+        #tokens
+      } else {
+        None
+      }
+    }
+  }
+}
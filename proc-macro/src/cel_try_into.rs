@@ -81,6 +81,8 @@ pub(crate) fn derive_cel_value_struct(item: &ItemStruct) -> Result<TokenStream2,
   };
 
   let mut tokens = TokenStream2::new();
+  let mut resolver_arms = TokenStream2::new();
+  let mut oneofs_resolver_tokens = TokenStream2::new();
 
   for field in fields {
     let field_ident = field.ident.as_ref().unwrap();
@@ -106,58 +108,86 @@ pub(crate) fn derive_cel_value_struct(item: &ItemStruct) -> Result<TokenStream2,
           fields.insert(oneof_field_name.into(), cel_val);
         }
       });
+
+      // The name of the active variant is only known after the conversion
+      oneofs_resolver_tokens.extend(quote_spanned! {span=>
+        if let Some(oneof) = &self.#field_ident {
+          let (oneof_field_name, cel_val) = ::prelude::CelOneof::try_into_cel(oneof.clone())?;
+
+          if oneof_field_name == name {
+            return Ok(Some(cel_val));
+          }
+        }
+      });
     } else {
       let outer_type = TypeInfo::from_type(&field.ty)?;
 
       let val_tokens = quote_spanned! {span=> val };
 
-      match outer_type.type_.as_ref() {
+      // Converts the owned value of the field, stored in `field_value`
+      let conversion_tokens = match outer_type.type_.as_ref() {
         RustType::Option(inner) => {
           let conversion_tokens = get_conversion_tokens(inner, &val_tokens);
 
-          tokens.extend(quote_spanned! {span=>
-            if let Some(val) = value.#field_ident {
-              fields.insert(#field_name.into(), #conversion_tokens);
+          quote_spanned! {span=>
+            if let Some(val) = field_value {
+              #conversion_tokens
             } else {
-              fields.insert(#field_name.into(), ::prelude::cel::Value::Null);
+              ::prelude::cel::Value::Null
             }
-          });
+          }
         }
         RustType::Vec(inner) => {
           let conversion_tokens = get_conversion_tokens(inner, &val_tokens);
 
-          tokens.extend(quote_spanned! {span=>
-            let mut converted: Vec<::prelude::cel::Value> = Vec::new();
-            for val in value.#field_ident {
-              converted.push(#conversion_tokens);
-            }
+          quote_spanned! {span=>
+            {
+              let mut converted: Vec<::prelude::cel::Value> = Vec::new();
+              for val in field_value {
+                converted.push(#conversion_tokens);
+              }
 
-            fields.insert(#field_name.into(), ::prelude::cel::Value::List(converted.into()));
-          });
+              ::prelude::cel::Value::List(converted.into())
+            }
+          }
         }
 
         RustType::HashMap((_, v)) | RustType::BTreeMap((_, v)) => {
           let values_conversion_tokens = get_conversion_tokens(v, &val_tokens);
 
-          tokens.extend(quote_spanned! {span=>
-            let mut field_map: ::std::collections::HashMap<::prelude::cel::objects::Key, ::prelude::cel::Value> = ::std::collections::HashMap::new();
+          quote_spanned! {span=>
+            {
+              let mut field_map: ::std::collections::HashMap<::prelude::cel::objects::Key, ::prelude::cel::Value> = ::std::collections::HashMap::new();
 
-            for (key, val) in value.#field_ident {
-              field_map.insert(key.into(), #values_conversion_tokens);
-            }
+              for (key, val) in field_value {
+                field_map.insert(key.into(), #values_conversion_tokens);
+              }
 
-            fields.insert(#field_name.into(), ::prelude::cel::Value::Map(field_map.into()));
-          });
+              ::prelude::cel::Value::Map(field_map.into())
+            }
+          }
         }
         _ => {
-          let val_tokens = quote_spanned! {span=> value.#field_ident };
-          let conversion_tokens = get_conversion_tokens(&outer_type, &val_tokens);
+          let val_tokens = quote_spanned! {span=> field_value };
 
-          tokens.extend(quote_spanned! {span=>
-            fields.insert(#field_name.into(), #conversion_tokens);
-          });
+          get_conversion_tokens(&outer_type, &val_tokens)
         }
       };
+
+      tokens.extend(quote_spanned! {span=>
+        {
+          let field_value = value.#field_ident;
+          fields.insert(#field_name.into(), #conversion_tokens);
+        }
+      });
+
+      // Only the requested field is cloned
+      resolver_arms.extend(quote_spanned! {span=>
+        #field_name => {
+          let field_value = self.#field_ident.clone();
+          Ok(Some(#conversion_tokens))
+        }
+      });
     }
   }
 
@@ -177,5 +207,25 @@ pub(crate) fn derive_cel_value_struct(item: &ItemStruct) -> Result<TokenStream2,
         Ok(::prelude::cel::Value::Map(fields.into()))
       }
     }
+
+    impl ::prelude::CelFields for #struct_name {
+      #[doc(hidden)]
+      fn __cel_field(&self, name: &str) -> Result<Option<::prelude::cel::Value>, ::prelude::CelError> {
+        use ::prelude::{CelOneof as __CelOneof, CelValue as __CelValue};
+
+        let resolve = || -> Result<Option<::prelude::cel::Value>, ::prelude::proto_types::cel::CelConversionError> {
+          match name {
+            #resolver_arms
+            _ => {
+              #oneofs_resolver_tokens
+
+              Ok(None)
+            }
+          }
+        };
+
+        resolve().map_err(|e| ::prelude::CelError::ConversionError(e.to_string()))
+      }
+    }
   })
 }
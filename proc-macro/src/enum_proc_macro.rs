@@ -7,12 +7,14 @@ struct EnumVariantCtx {
   ident: Ident,
   deprecated: bool,
   span: Span,
+  comments: DocComments,
 }
 
 #[derive(Default)]
 struct EnumData {
   variants_data: Vec<EnumVariantCtx>,
   enum_attrs: EnumAttrs,
+  lockfile_tokens: Option<TokenStream2>,
 }
 
 fn extract_enum_data(item: &mut ItemEnum) -> syn::Result<EnumData> {
@@ -28,6 +30,31 @@ fn extract_enum_data(item: &mut ItemEnum) -> syn::Result<EnumData> {
   let mut variants_data: Vec<EnumVariantCtx> = Vec::new();
   let mut manually_set_tags: Vec<ParsedNum> = Vec::new();
 
+  let tag_lockfile = enum_attrs
+    .tag_lockfile
+    .as_ref()
+    .map(|attr| TagLockfile::load(attr, &enum_attrs.name, enum_attrs.parent_message.as_ref()))
+    .transpose()?;
+
+  let mut current_names: Vec<String> = Vec::new();
+
+  if let Some(lockfile) = &tag_lockfile {
+    for (i, variant) in variants.iter_mut().enumerate() {
+      let name =
+        process_derive_enum_variants_attrs(&enum_attrs.name, &variant.ident, &variant.attrs)?.name;
+
+      // The first variant is always 0, so it does not need to be locked
+      if i > 0
+        && variant.discriminant.is_none()
+        && let Some(tag) = lockfile.locked_tag(&name)
+      {
+        variant.discriminant = Some((token::Eq::default(), parse_quote!(#tag)));
+      }
+
+      current_names.push(name);
+    }
+  }
+
   for variant in variants.iter() {
     if let Some((_, expr)) = &variant.discriminant {
       let num = expr.as_int::<i32>()?;
@@ -39,6 +66,19 @@ fn extract_enum_data(item: &mut ItemEnum) -> syn::Result<EnumData> {
     }
   }
 
+  if let Some(lockfile) = &tag_lockfile {
+    let current_names: Vec<&str> = current_names.iter().map(|n| n.as_str()).collect();
+
+    let removed_tags = lockfile.removed_tags(
+      &current_names,
+      &enum_attrs.reserved_names,
+      &enum_attrs.reserved_numbers,
+      &manually_set_tags,
+    )?;
+
+    manually_set_tags.extend(removed_tags);
+  }
+
   let unavailable_ranges =
     build_unavailable_ranges(&enum_attrs.reserved_numbers, &mut manually_set_tags)?;
 
@@ -55,6 +95,7 @@ fn extract_enum_data(item: &mut ItemEnum) -> syn::Result<EnumData> {
       options,
       name,
       deprecated,
+      comments,
     } = process_derive_enum_variants_attrs(&enum_attrs.name, variant_ident, &variant.attrs)?;
 
     if enum_attrs.reserved_names.contains(&name) {
@@ -92,12 +133,31 @@ fn extract_enum_data(item: &mut ItemEnum) -> syn::Result<EnumData> {
       ident: variant_ident.clone(),
       deprecated,
       span: variant_ident.span(),
+      comments,
     });
   }
 
+  let lockfile_tokens = tag_lockfile.map(|lockfile| {
+    let test = lockfile.test_tokens(
+      enum_ident,
+      quote! { <#enum_ident as ::prelude::ProtoEnum>::proto_name() },
+      enum_attrs.parent_message.as_ref(),
+      variants_data
+        .iter()
+        .map(|variant| (variant.name.clone(), variant.tag)),
+    );
+    let tracking = lockfile.tracking_tokens();
+
+    quote! {
+      #test
+      #tracking
+    }
+  });
+
   Ok(EnumData {
     variants_data,
     enum_attrs,
+    lockfile_tokens,
   })
 }
 
@@ -114,8 +174,11 @@ pub fn enum_proc_macro(mut item: ItemEnum) -> TokenStream2 {
         parent_message,
         name: proto_name,
         deprecated,
+        comments,
+        features,
         ..
       },
+    lockfile_tokens,
   } = extract_enum_data(&mut item).unwrap_or_else(|e| {
     error = Some(e.into_compile_error());
     EnumData::default()
@@ -153,13 +216,14 @@ pub fn enum_proc_macro(mut item: ItemEnum) -> TokenStream2 {
       tag,
       deprecated,
       span,
+      comments,
       ..
     } = var;
 
     let options_tokens = options_tokens(*span, options, *deprecated);
 
     quote_spanned! {*span=>
-      ::prelude::EnumVariant { name: #name.into(), options: #options_tokens.into_iter().collect(), tag: #tag, }
+      ::prelude::EnumVariant { name: #name.into(), comments: #comments, options: #options_tokens.into_iter().collect(), tag: #tag, }
     }
   });
 
@@ -232,6 +296,8 @@ pub fn enum_proc_macro(mut item: ItemEnum) -> TokenStream2 {
 
   let options_tokens = options_tokens(Span::call_site(), &enum_options, deprecated);
 
+  let json_impls = (cfg!(feature = "json") && error.is_none()).then(|| enum_json_impls(enum_ident));
+
   quote! {
     #[repr(i32)]
     #[derive(::prelude::macros::Enum, Hash, PartialEq, Eq, Debug, Clone, Copy)]
@@ -321,17 +387,23 @@ pub fn enum_proc_macro(mut item: ItemEnum) -> TokenStream2 {
         ::prelude::Enum {
           short_name: #proto_name.into(),
           name: <Self as ::prelude::ProtoEnum>::proto_name().into(),
+          comments: #comments,
           file: __PROTO_FILE.name.into(),
           package: __PROTO_FILE.package.into(),
           variants: ::prelude::vec! [ #variants_tokens ],
           reserved_names: ::prelude::vec![ #(#reserved_names.into()),* ],
           reserved_numbers: #reserved_numbers,
           options: #options_tokens.into_iter().collect(),
+          features: #features,
           rust_path:  ::prelude::format!("::{}::{}", __PROTO_FILE.extern_path, #rust_ident_str).into()
         }
       }
     }
 
+    #json_impls
+
+    #lockfile_tokens
+
     #error
   }
 }
@@ -32,6 +32,7 @@ pub fn process_extension_derive(
       options,
       proto_name,
       proto_field,
+      comments,
     } = process_extension_field_attrs(field)?;
 
     if tag.is_none() {
@@ -46,8 +47,10 @@ pub fn process_extension_derive(
     fields_tokens.push(quote_spanned! {field.ident.span()=>
       ::prelude::Field {
         name: #proto_name.into(),
+        comments: #comments,
         tag: #tag,
         options: #options.into_iter().collect(),
+        features: ::prelude::FeatureSet::new(),
         type_: #field_type_tokens,
         validators: ::prelude::vec![],
       }
@@ -80,7 +80,23 @@ impl FieldData {
 
         quote_spanned! {self.span=> #p_type, optional }
       }
-      ProtoField::Single(proto_type) => proto_type.as_prost_attr_type(self.span),
+      ProtoField::Single(proto_type) => {
+        let p_type = proto_type.as_prost_attr_type(self.span);
+
+        if self.required {
+          quote_spanned! {self.span=> #p_type, required }
+        } else {
+          p_type
+        }
+      }
+    };
+
+    let inner = if let Some(default) = &self.default {
+      let default_tokens = default.prost_attr_tokens(self.span);
+
+      quote_spanned! {self.span=> #inner, #default_tokens }
+    } else {
+      inner
     };
 
     let tag_as_str = self
@@ -93,6 +109,93 @@ impl FieldData {
     parse_quote_spanned! {self.span=> #[prost(#inner, tag = #tag_as_str)] }
   }
 
+  /// Generates the accessors for the value of this field on the proto struct (`receiver`),
+  /// which return the value as an `Option`, whether it's set, and whether the field tracks presence.
+  ///
+  /// These follow the prost layout rather than the layout of a proxy.
+  pub fn presence_accessors(&self, receiver: &TokenStream2) -> (TokenStream2, TokenStream2, bool) {
+    let Self {
+      ident,
+      proto_field,
+      span,
+      ..
+    } = self;
+
+    match proto_field {
+      ProtoField::Single(proto_type) | ProtoField::Optional(proto_type)
+        if matches!(
+          proto_type,
+          ProtoType::Message(_)
+            | ProtoType::Duration
+            | ProtoType::Timestamp
+            | ProtoType::Any
+            | ProtoType::FieldMask
+        ) =>
+      {
+        let get = if self.message_info().is_some_and(|info| info.boxed) {
+          quote_spanned! {*span=> #receiver.#ident.as_deref() }
+        } else {
+          quote_spanned! {*span=> #receiver.#ident.as_ref() }
+        };
+
+        (
+          get,
+          quote_spanned! {*span=> #receiver.#ident.is_some() },
+          true,
+        )
+      }
+      ProtoField::Optional(_) | ProtoField::Oneof(_) => (
+        quote_spanned! {*span=> #receiver.#ident.as_ref() },
+        quote_spanned! {*span=> #receiver.#ident.is_some() },
+        true,
+      ),
+      ProtoField::Repeated(_)
+      | ProtoField::Map(_)
+      | ProtoField::Single(ProtoType::String | ProtoType::Bytes) => (
+        quote_spanned! {*span=> Some(&#receiver.#ident) },
+        quote_spanned! {*span=> !#receiver.#ident.is_empty() },
+        false,
+      ),
+      ProtoField::Single(_) => (
+        quote_spanned! {*span=> Some(&#receiver.#ident) },
+        quote_spanned! {*span=> #receiver.#ident != ::core::default::Default::default() },
+        false,
+      ),
+    }
+  }
+
+  /// Generates the `FieldRef` binding for this field, which is used by the typed message-level rules.
+  pub fn field_ref_tokens(&self, target_ident: &Ident) -> Option<TokenStream2> {
+    let Self {
+      ident,
+      proto_name,
+      tag,
+      proto_field,
+      span,
+      ..
+    } = self;
+
+    if proto_field.is_oneof() {
+      return None;
+    }
+
+    let (get, is_set, has_presence) = self.presence_accessors(&quote_spanned! {*span=> m });
+
+    let field_type = self.descriptor_type_tokens();
+
+    Some(quote_spanned! {*span=>
+      #[allow(unused_variables)]
+      let #ident = ::prelude::FieldRef::<#target_ident, _> {
+        name: #proto_name,
+        tag: #tag,
+        field_type: #field_type,
+        has_presence: #has_presence,
+        get: |m| #get,
+        is_set: |m| #is_set,
+      };
+    })
+  }
+
   pub fn validator_name(&self) -> TokenStream2 {
     match &self.proto_field {
       ProtoField::Map(map) => {
@@ -92,6 +92,15 @@ impl Parse for MessageExpr {
 custom_keyword!(messages);
 custom_keyword!(enums);
 
+// Allows writing `edition = Proto2` as well as the full path
+fn edition_tokens(path: Path) -> TokenStream2 {
+  if let Some(ident) = path.get_ident() {
+    quote_spanned! {ident.span()=> ::prelude::Edition::#ident }
+  } else {
+    path.into_token_stream()
+  }
+}
+
 fn parse_bracketed<T: Parse>(input: ParseStream) -> syn::Result<T> {
   let content;
   bracketed!(content in input);
@@ -104,6 +113,7 @@ pub fn schema_file_macro(input: TokenStream2) -> syn::Result<TokenStream2> {
   let mut options = TokenStreamOr::new(|_| quote! { ::prelude::vec![] });
   let mut extensions: Vec<Path> = Vec::new();
   let mut edition = TokenStreamOr::new(|_| quote! { ::prelude::Edition::Proto3 });
+  let mut features = TokenStreamOr::new(|_| quote! { ::prelude::FeatureSet::new() });
   let mut messages: Vec<MessageExpr> = Vec::new();
   let mut enums: Vec<Path> = Vec::new();
   let mut services: Vec<Path> = Vec::new();
@@ -141,7 +151,11 @@ pub fn schema_file_macro(input: TokenStream2) -> syn::Result<TokenStream2> {
         extensions = parse_bracketed::<PathList>(meta.value()?)?.list;
       }
       "edition" => {
-        edition.set(meta.parse_value::<Path>()?.into_token_stream());
+        edition.set(edition_tokens(meta.parse_value::<Path>()?));
+      }
+      "features" => {
+        features.span = meta.input.span();
+        features.set(meta.expr_value()?.into_token_stream());
       }
       _ => return Err(meta.error("Unknown attribute")),
     };
@@ -183,6 +197,7 @@ pub fn schema_file_macro(input: TokenStream2) -> syn::Result<TokenStream2> {
 
       file
         .with_edition(#edition)
+        .with_features(#features)
         .with_extensions([ #(#extensions::as_proto_extension()),* ])
         .with_options(#options);
 
@@ -203,6 +218,7 @@ pub fn process_file_macro(input: TokenStream2) -> syn::Result<TokenStream2> {
   let mut imports = TokenStreamOr::new(|_| quote! { [] });
   let mut extensions: Vec<Path> = Vec::new();
   let mut edition = TokenStreamOr::new(|_| quote! { ::prelude::Edition::Proto3 });
+  let mut features = TokenStreamOr::new(|_| quote! { ::prelude::FeatureSet::new() });
 
   let parser = syn::meta::parser(|meta| {
     let ident_str = meta.ident_str()?;
@@ -228,7 +244,11 @@ pub fn process_file_macro(input: TokenStream2) -> syn::Result<TokenStream2> {
         extensions = parse_bracketed::<PathList>(meta.value()?)?.list;
       }
       "edition" => {
-        edition.set(meta.parse_value::<Path>()?.into_token_stream());
+        edition.set(edition_tokens(meta.parse_value::<Path>()?));
+      }
+      "features" => {
+        features.span = meta.input.span();
+        features.set(meta.expr_value()?.into_token_stream());
       }
       _ => {
         const_ident = Some(meta.ident()?.clone());
@@ -252,6 +272,7 @@ pub fn process_file_macro(input: TokenStream2) -> syn::Result<TokenStream2> {
       name: #file,
       package: #package.name,
       extern_path: #extern_path,
+      edition: #edition,
     };
 
     #[doc(hidden)]
@@ -262,7 +283,8 @@ pub fn process_file_macro(input: TokenStream2) -> syn::Result<TokenStream2> {
       ::prelude::RegistryFile {
         name: __PROTO_FILE.name,
         package: __PROTO_FILE.package,
-        edition: #edition,
+        edition: __PROTO_FILE.edition,
+        features: || #features,
         options: || #options.into_iter().collect(),
         imports: || #imports.into_iter().collect(),
         extensions: || ::prelude::vec![ #(<#extensions as ::prelude::ProtoExtension>::as_proto_extension()),* ]
@@ -53,10 +53,28 @@ impl ProtoConversions<'_> {
       }
     };
 
+    // Only generated when it's needed, so that it does not conflict with a derived impl
+    let has_field_defaults = self
+      .fields
+      .iter()
+      .filter_map(|d| d.as_normal())
+      .any(|d| d.default.is_some());
+
+    let default_impl = (kind.is_message() && has_field_defaults).then(|| {
+      quote! {
+        impl Default for #proxy_ident {
+          fn default() -> Self {
+            <#proto_ident as Default>::default().into()
+          }
+        }
+      }
+    });
+
     quote! {
       #from_proto
       #into_proto
       #proxy_trait_impl
+      #default_impl
     }
   }
 
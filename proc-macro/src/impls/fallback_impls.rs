@@ -105,6 +105,8 @@ impl<'a> FallbackImpls<'a> {
         ItemKind::Message => quote! {
           impl ::prelude::CelValue for #target_ident {}
 
+          impl ::prelude::CelFields for #target_ident {}
+
           impl TryFrom<#target_ident> for ::prelude::cel::Value {
             type Error = ::prelude::proto_types::cel::CelConversionError;
 
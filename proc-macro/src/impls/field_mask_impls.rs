@@ -0,0 +1,239 @@
+use crate::*;
+
+fn field_masks_module() -> TokenStream2 {
+  quote! { ::prelude::field_masks }
+}
+
+impl FieldData {
+  /// Returns the message type of this field, if it can be traversed by the paths of a mask.
+  const fn mask_message_info(&self) -> Option<&MessageInfo> {
+    match &self.proto_field {
+      ProtoField::Optional(inner) | ProtoField::Single(inner) => inner.as_message(),
+      _ => None,
+    }
+  }
+
+  fn mask_check_arm(&self, module: &TokenStream2) -> TokenStream2 {
+    let FieldData {
+      span, proto_name, ..
+    } = self;
+
+    if let Some(MessageInfo { path, .. }) = self.mask_message_info() {
+      quote_spanned! {*span=>
+        #proto_name => #module::check_mask_message::<#path>(path, rest)
+      }
+    } else {
+      quote_spanned! {*span=>
+        #proto_name => #module::check_mask_leaf(path, field, rest)
+      }
+    }
+  }
+}
+
+impl MessageCtx<'_> {
+  pub fn generate_field_mask_impls(&self) -> TokenStream2 {
+    let proto_struct = self.proto_struct_ident();
+    let message_name: &str = &self.message_attrs.name;
+    let module = field_masks_module();
+
+    let mut check_arms: Vec<TokenStream2> = Vec::new();
+    let mut prune_tokens: Vec<TokenStream2> = Vec::new();
+    let mut merge_tokens: Vec<TokenStream2> = Vec::new();
+
+    for data in self
+      .fields_data
+      .iter()
+      .filter_map(|d| d.as_normal())
+    {
+      let FieldData {
+        ident,
+        span,
+        proto_name,
+        ..
+      } = data;
+
+      if let ProtoField::Oneof(OneofInfo { path, .. }) = &data.proto_field {
+        check_arms.push(quote_spanned! {*span=>
+          key if <#path as #module::ProtoMaskOneof>::FIELD_NAMES.contains(&key) => {
+            <#path as #module::ProtoMaskOneof>::check_mask_field(path, field, rest)
+          }
+        });
+
+        prune_tokens.push(quote_spanned! {*span=>
+          <#path as #module::ProtoMaskOneof>::prune_oneof(&mut self.#ident, tree);
+        });
+
+        merge_tokens.push(quote_spanned! {*span=>
+          <#path as #module::ProtoMaskOneof>::merge_oneof(&mut self.#ident, source.#ident.as_ref(), tree);
+        });
+      } else if let Some(MessageInfo { path, .. }) = data.mask_message_info() {
+        check_arms.push(data.mask_check_arm(&module));
+
+        prune_tokens.push(quote_spanned! {*span=>
+          #module::prune_message_field::<#path, _>(&mut self.#ident, tree.get(#proto_name));
+        });
+
+        merge_tokens.push(quote_spanned! {*span=>
+          if let Some(subtree) = tree.get(#proto_name) {
+            #module::merge_message_field::<#path, _>(&mut self.#ident, source.#ident.as_ref(), subtree);
+          }
+        });
+      } else {
+        check_arms.push(data.mask_check_arm(&module));
+
+        prune_tokens.push(quote_spanned! {*span=>
+          if tree.get(#proto_name).is_none() {
+            self.#ident = ::core::default::Default::default();
+          }
+        });
+
+        merge_tokens.push(quote_spanned! {*span=>
+          if tree.get(#proto_name).is_some() {
+            self.#ident = ::core::clone::Clone::clone(&source.#ident);
+          }
+        });
+      }
+    }
+
+    quote! {
+      impl #module::ProtoMask for #proto_struct {
+        #[doc(hidden)]
+        const MASK_MESSAGE_NAME: &'static str = #message_name;
+
+        #[doc(hidden)]
+        #[allow(unused_variables, clippy::match_single_binding)]
+        fn check_mask_field(path: &str, field: &str, rest: Option<&str>) -> Result<(), #module::FieldMaskError> {
+          match field {
+            #(#check_arms,)*
+            _ => #module::unknown_mask_field::<Self>(path, field),
+          }
+        }
+
+        #[doc(hidden)]
+        #[allow(unused_variables)]
+        fn prune_to_tree(&mut self, tree: &#module::MaskTree) {
+          #(#prune_tokens)*
+        }
+
+        #[doc(hidden)]
+        #[allow(unused_variables)]
+        fn merge_tree(&mut self, source: &Self, tree: &#module::MaskTree) {
+          #(#merge_tokens)*
+        }
+      }
+    }
+  }
+}
+
+impl OneofCtx<'_> {
+  pub fn generate_field_mask_impls(&self) -> TokenStream2 {
+    let enum_ident = self.proto_enum_ident();
+    let module = field_masks_module();
+
+    let mut field_names: Vec<&str> = Vec::new();
+    let mut check_arms: Vec<TokenStream2> = Vec::new();
+    let mut prune_arms: Vec<TokenStream2> = Vec::new();
+    let mut merge_tokens: Vec<TokenStream2> = Vec::new();
+
+    for data in self.variants.iter().filter_map(|v| v.as_normal()) {
+      let FieldData {
+        ident,
+        span,
+        proto_name,
+        ..
+      } = data;
+
+      check_arms.push(data.mask_check_arm(&module));
+
+      // The current variant of the target is taken out, so that it can be replaced or cleared
+      let take_target = quote_spanned! {*span=>
+        let target_value = match target.take() {
+          Some(Self::#ident(value)) => Some(value),
+          other => {
+            *target = other;
+            None
+          }
+        };
+      };
+
+      if let Some(MessageInfo { path, .. }) = data.mask_message_info() {
+        prune_arms.push(quote_spanned! {*span=>
+          Some(Self::#ident(value)) => match tree.get(#proto_name) {
+            Some(subtree) => {
+              if !subtree.is_leaf() {
+                <#path as #module::ProtoMask>::prune_to_tree(value, subtree);
+              }
+
+              true
+            }
+            None => false,
+          }
+        });
+
+        merge_tokens.push(quote_spanned! {*span=>
+          if let Some(subtree) = tree.get(#proto_name) {
+            let source_value = match source {
+              Some(Self::#ident(value)) => Some(value),
+              _ => None,
+            };
+
+            #take_target
+
+            if let Some(value) = #module::merge_message_variant::<#path, _>(target_value, source_value, subtree) {
+              *target = Some(Self::#ident(value));
+            }
+          }
+        });
+      } else {
+        prune_arms.push(quote_spanned! {*span=>
+          Some(Self::#ident(_)) => tree.get(#proto_name).is_some()
+        });
+
+        merge_tokens.push(quote_spanned! {*span=>
+          if tree.get(#proto_name).is_some() {
+            #take_target
+
+            if let Some(Self::#ident(value)) = source {
+              *target = Some(Self::#ident(::core::clone::Clone::clone(value)));
+            }
+          }
+        });
+      }
+
+      field_names.push(proto_name);
+    }
+
+    quote! {
+      impl #module::ProtoMaskOneof for #enum_ident {
+        #[doc(hidden)]
+        const FIELD_NAMES: &'static [&'static str] = &[ #(#field_names),* ];
+
+        #[doc(hidden)]
+        fn check_mask_field(path: &str, field: &str, rest: Option<&str>) -> Result<(), #module::FieldMaskError> {
+          match field {
+            #(#check_arms,)*
+            _ => #module::check_mask_leaf(path, field, rest),
+          }
+        }
+
+        #[doc(hidden)]
+        fn prune_oneof(target: &mut Option<Self>, tree: &#module::MaskTree) {
+          let keep = match target {
+            #(#prune_arms,)*
+            None => true,
+          };
+
+          if !keep {
+            *target = None;
+          }
+        }
+
+        #[doc(hidden)]
+        #[allow(unused_variables)]
+        fn merge_oneof(target: &mut Option<Self>, source: Option<&Self>, tree: &#module::MaskTree) {
+          #(#merge_tokens)*
+        }
+      }
+    }
+  }
+}
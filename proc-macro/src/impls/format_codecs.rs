@@ -0,0 +1,65 @@
+use crate::*;
+
+// The JSON and text format modules of the prelude use the same names for their codecs,
+// so the codec of a field can be built for either of them by changing the module path.
+
+impl ProtoType {
+  pub fn codec_tokens(&self, module: &TokenStream2, span: Span) -> TokenStream2 {
+    match self {
+      Self::String => quote_spanned! {span=> #module::StringCodec },
+      Self::Bool => quote_spanned! {span=> #module::BoolCodec },
+      Self::Bytes => quote_spanned! {span=> #module::BytesCodec },
+      Self::Enum(path) => quote_spanned! {span=> #module::EnumCodec<#path> },
+      Self::Message(_) | Self::Duration | Self::Timestamp | Self::Any | Self::FieldMask => {
+        quote_spanned! {span=> #module::MessageCodec }
+      }
+      Self::Float => quote_spanned! {span=> #module::FloatCodec },
+      Self::Double => quote_spanned! {span=> #module::DoubleCodec },
+      Self::Int32 | Self::Sint32 | Self::Sfixed32 => {
+        quote_spanned! {span=> #module::Int32Codec }
+      }
+      Self::Int64 | Self::Sint64 | Self::Sfixed64 => {
+        quote_spanned! {span=> #module::Int64Codec }
+      }
+      Self::Uint32 | Self::Fixed32 => quote_spanned! {span=> #module::Uint32Codec },
+      Self::Uint64 | Self::Fixed64 => quote_spanned! {span=> #module::Uint64Codec },
+    }
+  }
+}
+
+impl FieldData {
+  pub fn codec_tokens(&self, module: &TokenStream2, item_kind: ItemKind) -> TokenStream2 {
+    let span = self.span;
+
+    match &self.proto_field {
+      ProtoField::Map(map) => {
+        let keys = map.keys.into_type().codec_tokens(module, span);
+        let values = map.values.codec_tokens(module, span);
+
+        quote_spanned! {span=> #module::MapCodec<#keys, #values> }
+      }
+      ProtoField::Oneof(_) => quote! {
+        compile_error!("Codec tokens should not be called for oneofs, if you see this please report it as a bug")
+      },
+      ProtoField::Repeated(inner) => {
+        let inner = inner.codec_tokens(module, span);
+
+        quote_spanned! {span=> #module::RepeatedCodec<#inner> }
+      }
+      ProtoField::Optional(inner) => {
+        let inner = inner.codec_tokens(module, span);
+
+        quote_spanned! {span=> #module::OptionalCodec<#inner> }
+      }
+      ProtoField::Single(inner) => {
+        let codec = inner.codec_tokens(module, span);
+
+        if inner.is_message() && item_kind.is_message() {
+          quote_spanned! {span=> #module::OptionalCodec<#codec> }
+        } else {
+          codec
+        }
+      }
+    }
+  }
+}
@@ -0,0 +1,245 @@
+use crate::*;
+
+/// Converts a field name to its default json name, following the same logic as `protoc`.
+pub fn default_json_name(name: &str) -> String {
+  let mut output = String::with_capacity(name.len());
+  let mut capitalize_next = false;
+
+  for ch in name.chars() {
+    if ch == '_' {
+      capitalize_next = true;
+    } else if capitalize_next {
+      output.push(ch.to_ascii_uppercase());
+      capitalize_next = false;
+    } else {
+      output.push(ch);
+    }
+  }
+
+  output
+}
+
+impl FieldData {
+  pub fn json_name(&self) -> String {
+    self
+      .json_name
+      .clone()
+      .unwrap_or_else(|| default_json_name(&self.proto_name))
+  }
+
+  /// The names that are accepted for this field when deserializing.
+  fn json_names(&self) -> Vec<String> {
+    let json_name = self.json_name();
+
+    if json_name == self.proto_name {
+      vec![json_name]
+    } else {
+      vec![json_name, self.proto_name.clone()]
+    }
+  }
+}
+
+fn json_module() -> TokenStream2 {
+  quote! { ::prelude::json }
+}
+
+impl MessageCtx<'_> {
+  pub fn generate_json_impls(&self) -> TokenStream2 {
+    let proto_struct = self.proto_struct_ident();
+    let message_name: &str = &self.message_attrs.name;
+    let expecting = format!("a JSON object for the message `{message_name}`");
+
+    let mut serialize_tokens: Vec<TokenStream2> = Vec::new();
+    let mut deserialize_arms: Vec<TokenStream2> = Vec::new();
+
+    for data in self
+      .fields_data
+      .iter()
+      .filter_map(|d| d.as_normal())
+    {
+      let FieldData { ident, span, .. } = data;
+
+      if let ProtoField::Oneof(OneofInfo { path, .. }) = &data.proto_field {
+        serialize_tokens.push(quote_spanned! {*span=>
+          if let Some(oneof) = &self.#ident {
+            <#path as ::prelude::json::ProtoJsonOneof>::serialize_json_field(oneof, &mut map)?;
+          }
+        });
+
+        deserialize_arms.push(quote_spanned! {*span=>
+          key if <#path as ::prelude::json::ProtoJsonOneof>::FIELD_NAMES.contains(&key) => {
+            if let Some(oneof) = <#path as ::prelude::json::ProtoJsonOneof>::deserialize_json_field(key, &mut map)? {
+              msg.#ident = Some(oneof);
+            }
+          }
+        });
+      } else {
+        let codec = data.codec_tokens(&json_module(), ItemKind::Message);
+        let json_name = data.json_name();
+        let json_names = data.json_names();
+
+        let serialize_entry = quote_spanned! {*span=>
+          map.serialize_entry(#json_name, &::prelude::json::JsonRef::<#codec, _>::new(&self.#ident))?;
+        };
+
+        // Required fields are always present in the output
+        serialize_tokens.push(if data.required {
+          serialize_entry
+        } else {
+          quote_spanned! {*span=>
+            if !<#codec as ::prelude::json::JsonCodec<_>>::is_default(&self.#ident) {
+              #serialize_entry
+            }
+          }
+        });
+
+        deserialize_arms.push(quote_spanned! {*span=>
+          #(#json_names)|* => {
+            msg.#ident = map.next_value_seed(::prelude::json::JsonSeed::<#codec, _>::new())?;
+          }
+        });
+      }
+    }
+
+    quote! {
+      impl ::prelude::json::ProtoJson for #proto_struct {
+        #[allow(unused_mut)]
+        fn serialize_json<S: ::prelude::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+          use ::prelude::serde::ser::SerializeMap as _;
+
+          let mut map = serializer.serialize_map(None)?;
+          #(#serialize_tokens)*
+          map.end()
+        }
+
+        fn deserialize_json<'de, D: ::prelude::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+          struct __Visitor;
+
+          impl<'de> ::prelude::serde::de::Visitor<'de> for __Visitor {
+            type Value = #proto_struct;
+
+            fn expecting(&self, formatter: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+              formatter.write_str(#expecting)
+            }
+
+            #[allow(unused_mut)]
+            fn visit_map<A: ::prelude::serde::de::MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+              let mut msg = <#proto_struct as ::core::default::Default>::default();
+
+              while let Some(key) = map.next_key::<::prelude::String>()? {
+                match key.as_str() {
+                  #(#deserialize_arms)*
+                  _ => {
+                    return Err(<A::Error as ::prelude::serde::de::Error>::custom(
+                      ::prelude::format!("unknown field `{key}`")
+                    ));
+                  }
+                }
+              }
+
+              Ok(msg)
+            }
+          }
+
+          deserializer.deserialize_map(__Visitor)
+        }
+      }
+
+      impl ::prelude::serde::Serialize for #proto_struct {
+        #[inline]
+        fn serialize<S: ::prelude::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+          <Self as ::prelude::json::ProtoJson>::serialize_json(self, serializer)
+        }
+      }
+
+      impl<'de> ::prelude::serde::Deserialize<'de> for #proto_struct {
+        #[inline]
+        fn deserialize<D: ::prelude::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+          <Self as ::prelude::json::ProtoJson>::deserialize_json(deserializer)
+        }
+      }
+
+      ::prelude::register_proto_data! {
+        ::prelude::json::RegistryJsonMessage {
+          full_name: || <#proto_struct as ::prelude::ProtoMessage>::full_name(),
+          to_json: ::prelude::json::message_to_json::<#proto_struct>,
+          from_json: ::prelude::json::message_from_json::<#proto_struct>,
+        }
+      }
+    }
+  }
+}
+
+impl OneofCtx<'_> {
+  pub fn generate_json_impls(&self) -> TokenStream2 {
+    let enum_ident = self.proto_enum_ident();
+
+    let mut field_names: Vec<String> = Vec::new();
+    let mut serialize_arms: Vec<TokenStream2> = Vec::new();
+    let mut deserialize_arms: Vec<TokenStream2> = Vec::new();
+
+    for data in self.variants.iter().filter_map(|v| v.as_normal()) {
+      let FieldData { ident, span, .. } = data;
+
+      let codec = data.codec_tokens(&json_module(), ItemKind::Oneof);
+      let json_name = data.json_name();
+      let json_names = data.json_names();
+
+      serialize_arms.push(quote_spanned! {*span=>
+        Self::#ident(value) => map.serialize_entry(#json_name, &::prelude::json::JsonRef::<#codec, _>::new(value))
+      });
+
+      // A null value means that the oneof is not set
+      deserialize_arms.push(quote_spanned! {*span=>
+        #(#json_names)|* => Ok(
+          map
+            .next_value_seed(::prelude::json::JsonSeed::<::prelude::json::OptionalCodec<#codec>, _>::new())?
+            .map(Self::#ident)
+        )
+      });
+
+      field_names.extend(json_names);
+    }
+
+    quote! {
+      impl ::prelude::json::ProtoJsonOneof for #enum_ident {
+        #[doc(hidden)]
+        const FIELD_NAMES: &'static [&'static str] = &[ #(#field_names),* ];
+
+        fn serialize_json_field<M: ::prelude::serde::ser::SerializeMap>(&self, map: &mut M) -> Result<(), M::Error> {
+          match self {
+            #(#serialize_arms),*
+          }
+        }
+
+        fn deserialize_json_field<'de, A: ::prelude::serde::de::MapAccess<'de>>(
+          key: &str,
+          map: &mut A,
+        ) -> Result<Option<Self>, A::Error> {
+          match key {
+            #(#deserialize_arms,)*
+            _ => Ok(None),
+          }
+        }
+      }
+    }
+  }
+}
+
+pub fn enum_json_impls(enum_ident: &Ident) -> TokenStream2 {
+  quote! {
+    impl ::prelude::serde::Serialize for #enum_ident {
+      #[inline]
+      fn serialize<S: ::prelude::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ::prelude::json::serialize_enum(self, serializer)
+      }
+    }
+
+    impl<'de> ::prelude::serde::Deserialize<'de> for #enum_ident {
+      #[inline]
+      fn deserialize<D: ::prelude::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        ::prelude::json::deserialize_enum(deserializer)
+      }
+    }
+  }
+}
@@ -120,6 +120,25 @@ pub fn generate_validators_consistency_checks(
   }
 }
 
+pub fn generate_edition_check(item_ident: &Ident, auto_tests: AutoTests) -> Option<TokenStream2> {
+  (!auto_tests.skip_edition_check).then(|| {
+    let test_fn_ident = format_ident!(
+      "{}_edition_consistency",
+      to_snake_case(&item_ident.to_string())
+    );
+
+    quote! {
+      #[cfg(test)]
+      #[test]
+      fn #test_fn_ident() {
+        if let Err(e) = <#item_ident as ::prelude::ProtoMessage>::proto_schema().check_edition_consistency() {
+          panic!("{e}")
+        }
+      }
+    }
+  })
+}
+
 impl MessageCtx<'_> {
   pub fn generate_consistency_checks(&self) -> TokenStream2 {
     let item_ident = self.proto_struct_ident();
@@ -138,9 +157,15 @@ impl MessageCtx<'_> {
       &self.message_attrs.name,
     );
 
+    // The schema of messages without fields cannot be generated
+    let edition_check = (!self.fields_data.is_empty())
+      .then(|| generate_edition_check(item_ident, self.message_attrs.auto_tests))
+      .flatten();
+
     quote! {
       #validators_checks
       #oneofs_checks
+      #edition_check
     }
   }
 }
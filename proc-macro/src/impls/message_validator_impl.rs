@@ -1,6 +1,8 @@
 use crate::*;
 
 bool_enum!(pub UseFallback);
+bool_enum!(pub UseMask);
+bool_enum!(pub UseAsync);
 
 #[derive(Default)]
 pub struct ValidatorsData {
@@ -8,6 +10,201 @@ pub struct ValidatorsData {
   pub default_check_tokens: Vec<TokenStream2>,
 }
 
+impl FieldData {
+  /// Wraps the validators of a message field so that they only run if the field is in the active mask.
+  pub fn mask_validation_tokens(&self, tokens: TokenStream2) -> TokenStream2 {
+    let span = self.span;
+
+    if let ProtoField::Oneof(OneofInfo { path, .. }) = &self.proto_field {
+      // The fields of a oneof are checked individually by the oneof itself
+      quote_spanned! {span=>
+        if ctx.is_any_in_mask(<#path as ::prelude::field_masks::ProtoMaskOneof>::FIELD_NAMES) {
+          #tokens
+        }
+      }
+    } else {
+      let proto_name = &self.proto_name;
+
+      quote_spanned! {span=>
+        {
+          let mask_scope = ctx.enter_masked_field(#proto_name);
+
+          if !mask_scope.is_skipped() {
+            #tokens
+          }
+
+          ctx.exit_masked_field(mask_scope);
+        }
+      }
+    }
+  }
+}
+
+impl FieldData {
+  /// The value of the field, passed to its validators as an `Option`.
+  pub fn validation_argument_tokens(&self, item_kind: ItemKind, span: Span) -> TokenStream2 {
+    let ident = &self.ident;
+
+    match item_kind {
+      ItemKind::Oneof => quote_spanned! {span=> Some(v) },
+      ItemKind::Message => match self.type_info.type_.as_ref() {
+        RustType::Option(inner) => {
+          if inner.is_box() {
+            quote_spanned! (span=> self.#ident.as_deref())
+          } else {
+            quote_spanned! (span=> self.#ident.as_ref())
+          }
+        }
+        RustType::Box(_) => quote_spanned! (span=> self.#ident.as_deref()),
+        _ => {
+          if matches!(
+            self.proto_field,
+            ProtoField::Single(ProtoType::Message(MessageInfo { .. })) | ProtoField::Oneof(_)
+          ) {
+            quote_spanned! (span=> self.#ident.as_ref())
+          } else {
+            quote_spanned! (span=> Some(&self.#ident))
+          }
+        }
+      },
+    }
+  }
+
+  pub fn field_context_tokens(&self, span: Span) -> TokenStream2 {
+    let Self {
+      proto_name, tag, ..
+    } = self;
+    let field_type = self.descriptor_type_tokens();
+
+    quote_spanned! {span=>
+      ::prelude::FieldContext {
+        name: #proto_name.into(),
+        tag: #tag,
+        field_type: #field_type,
+        map_key_type: None,
+        map_value_type: None,
+        subscript: None,
+        field_kind: Default::default(),
+      }
+    }
+  }
+
+  /// The context and value arguments for the validators of this field.
+  pub fn validate_args_tokens(&self, item_kind: ItemKind, span: Span) -> TokenStream2 {
+    let argument = self.validation_argument_tokens(item_kind, span);
+
+    if self.proto_field.is_oneof() {
+      quote_spanned! {span=>
+        ctx,
+        #argument
+      }
+    } else {
+      let field_context = self.field_context_tokens(span);
+
+      quote_spanned! {span=>
+        ctx.with_field_context(#field_context),
+        #argument
+      }
+    }
+  }
+
+  /// Applies the mask, `when` condition and validation groups of the field to its validation tokens.
+  pub fn wrap_field_validation(
+    &self,
+    mut tokens: TokenStream2,
+    fields: &[FieldDataKind],
+    use_mask: UseMask,
+  ) -> TokenStream2 {
+    if *use_mask {
+      tokens = self.mask_validation_tokens(tokens);
+    }
+
+    if let Some(condition) = &self.when {
+      let check = condition
+        .resolve(fields)
+        .map_or_else(|e| e.to_compile_error(), |c| c.rust);
+
+      tokens = quote_spanned! {self.span=>
+        if #check {
+          #tokens
+        }
+      };
+    }
+
+    if let Some(groups) = &self.groups {
+      tokens = groups.wrap_validation(tokens);
+    }
+
+    tokens
+  }
+
+  /// Generates the calls to the async validators of the field, and to the async validation of its nested messages.
+  pub fn async_validation_tokens(&self) -> TokenStream2 {
+    let span = self.span;
+    let validator_target_type = self.proto_field.validator_target_type(span);
+
+    let mut tokens: TokenStream2 = self
+      .async_validators
+      .iter()
+      .map(|v| {
+        let validate_args = self.validate_args_tokens(ItemKind::Message, v.span);
+
+        quote_spanned! {v.span=>
+          is_valid &= ::prelude::validate_field_async::<#validator_target_type, _, _>(
+            &(#v),
+            #validate_args
+          ).await?;
+        }
+      })
+      .collect();
+
+    let ident = &self.ident;
+    let field_context = self.field_context_tokens(span);
+
+    // Boxed to allow recursive messages
+    let nested_validation = quote_spanned! {span=>
+      let result = ::prelude::Box::pin(
+        ::prelude::ValidatedMessage::validate_async_with_ctx(v, ctx)
+      ).await;
+
+      ctx.parent_elements.pop();
+      is_valid &= result?;
+    };
+
+    match &self.proto_field {
+      ProtoField::Single(ProtoType::Message(_)) | ProtoField::Optional(ProtoType::Message(_)) => {
+        let argument = self.validation_argument_tokens(ItemKind::Message, span);
+
+        tokens.extend(quote_spanned! {span=>
+          if let Some(v) = #argument {
+            ctx.parent_elements.push(#field_context.as_path_element());
+
+            #nested_validation
+          }
+        });
+      }
+      ProtoField::Repeated(ProtoType::Message(_)) => {
+        tokens.extend(quote_spanned! {span=>
+          for (i, v) in self.#ident.iter().enumerate() {
+            let mut field_context = #field_context;
+            field_context.subscript = Some(
+              ::prelude::proto_types::protovalidate::field_path_element::Subscript::Index(i as u64)
+            );
+            field_context.field_kind = ::prelude::FieldKind::RepeatedItem;
+
+            ctx.parent_elements.push(field_context.as_path_element());
+
+            #nested_validation
+          }
+        });
+      }
+      _ => {}
+    };
+
+    tokens
+  }
+}
+
 pub fn field_validator_tokens(
   input_ident: &Ident,
   validators_data: &mut ValidatorsData,
@@ -15,13 +212,9 @@ pub fn field_validator_tokens(
   item_kind: ItemKind,
 ) -> Vec<TokenStream2> {
   let FieldData {
-    ident,
     ident_str,
-    tag,
     validators,
-    proto_name,
     proto_field,
-    type_info,
     ..
   } = field_data;
 
@@ -67,54 +260,8 @@ pub fn field_validator_tokens(
       }
     }
 
-    let argument = match item_kind {
-      ItemKind::Oneof => quote_spanned! {*span=> Some(v) },
-      ItemKind::Message => match type_info.type_.as_ref() {
-        RustType::Option(inner) => {
-          if inner.is_box() {
-            quote_spanned! (*span=> self.#ident.as_deref())
-          } else {
-            quote_spanned! (*span=> self.#ident.as_ref())
-          }
-        }
-        RustType::Box(_) => quote_spanned! (*span=> self.#ident.as_deref()),
-        _ => {
-          if matches!(
-            proto_field,
-            ProtoField::Single(ProtoType::Message(MessageInfo { .. })) | ProtoField::Oneof(_)
-          ) {
-            quote_spanned! (*span=> self.#ident.as_ref())
-          } else {
-            quote_spanned! (*span=> Some(&self.#ident))
-          }
-        }
-      },
-    };
-
-    let field_type = field_data.descriptor_type_tokens();
     let validator_target_type = proto_field.validator_target_type(*span);
-
-    let validate_args = if proto_field.is_oneof() {
-      quote_spanned! {*span=>
-        ctx,
-        #argument
-      }
-    } else {
-      quote_spanned! {*span=>
-        ctx.with_field_context(
-          ::prelude::FieldContext {
-            name: #proto_name.into(),
-            tag: #tag,
-            field_type: #field_type,
-            map_key_type: None,
-            map_value_type: None,
-            subscript: None,
-            field_kind: Default::default(),
-          }
-        ),
-        #argument
-      }
-    };
+    let validate_args = field_data.validate_args_tokens(item_kind, *span);
 
     let validator_call = if kind.should_be_cached() {
       let static_ident = format_ident!("{}_VALIDATOR", to_upper_snake_case(ident_str));
@@ -157,11 +304,85 @@ pub fn field_validator_tokens(
   tokens
 }
 
+/// Generates the `validate_async_with_ctx` method, if the message has async validators or nested messages.
+fn async_validator_method(
+  use_mask: UseMask,
+  target_ident: &Ident,
+  fields: &[FieldDataKind],
+  top_level_validators: &Validators,
+  top_level_groups: Option<&ValidationGroups>,
+) -> Option<TokenStream2> {
+  let field_validators: TokenStream2 = fields
+    .iter()
+    .filter_map(|d| d.as_normal())
+    .filter_map(|d| {
+      let tokens = d.async_validation_tokens();
+
+      (!tokens.is_empty()).then(|| d.wrap_field_validation(tokens, fields, use_mask))
+    })
+    .collect();
+
+  let mut top_level_tokens: TokenStream2 = top_level_validators
+    .iter()
+    .map(|v| {
+      quote_spanned! {v.span=>
+        is_valid &= ::prelude::AsyncValidator::<#target_ident>::validate_async(
+          &(#v),
+          ctx,
+          Some(self)
+        ).await?;
+      }
+    })
+    .collect();
+
+  if !top_level_tokens.is_empty() {
+    if *use_mask {
+      top_level_tokens = quote! {
+        if !ctx.is_masked() {
+          #top_level_tokens
+        }
+      };
+    }
+
+    if let Some(groups) = top_level_groups {
+      top_level_tokens = groups.wrap_validation(top_level_tokens);
+    }
+
+    top_level_tokens = quote! {
+      ctx.field_context = None;
+
+      #top_level_tokens
+    };
+  }
+
+  if field_validators.is_empty() && top_level_tokens.is_empty() {
+    return None;
+  }
+
+  Some(quote! {
+    #[doc(hidden)]
+    async fn validate_async_with_ctx(&self, ctx: &mut ::prelude::ValidationCtx) -> ::prelude::ValidationResult {
+      let mut is_valid = ::prelude::IsValid::Yes;
+
+      #field_validators
+
+      #top_level_tokens
+
+      Ok(is_valid)
+    }
+  })
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn generate_message_validator(
   use_fallback: UseFallback,
+  use_mask: UseMask,
+  use_async: UseAsync,
   target_ident: &Ident,
   fields: &[FieldDataKind],
   top_level_validators: &Validators,
+  top_level_async_validators: &Validators,
+  top_level_groups: Option<&ValidationGroups>,
 ) -> TokenStream2 {
   let mut validators_data = ValidatorsData {
     has_non_default_validators: !top_level_validators.is_empty(),
@@ -173,19 +394,30 @@ pub fn generate_message_validator(
   } else {
     let top_level = top_level_validators.iter().enumerate().map(|(i, v)| {
       if v.kind.is_custom() {
-        quote_spanned! {v.span=>
+        let tokens = quote_spanned! {v.span=>
           is_valid &= ::prelude::Validator::<#target_ident>::validate_core(
             &(#v),
             ctx,
             Some(self)
           )?;
+        };
+
+        // The fields used by custom validators are not known, so they are skipped with a mask
+        if *use_mask {
+          quote_spanned! {v.span=>
+            if !ctx.is_masked() {
+              #tokens
+            }
+          }
+        } else {
+          tokens
         }
       } else {
         let validator_static_ident = format_ident!("__VALIDATOR_{i}");
 
         quote_spanned! {v.span=>
           is_valid &= {
-            static #validator_static_ident: ::prelude::Lazy<::prelude::CelValidator> = ::prelude::Lazy::new(|| {
+            static #validator_static_ident: ::prelude::Lazy<::prelude::MessageRules<#target_ident>> = ::prelude::Lazy::new(|| {
               #v
             });
 
@@ -203,10 +435,23 @@ pub fn generate_message_validator(
       .iter()
       .filter_map(|d| d.as_normal())
       .flat_map(|d| {
-        field_validator_tokens(target_ident, &mut validators_data, d, ItemKind::Message)
+        let tokens =
+          field_validator_tokens(target_ident, &mut validators_data, d, ItemKind::Message);
+
+        if tokens.is_empty() {
+          return tokens;
+        }
+
+        vec![d.wrap_field_validation(quote! { #(#tokens)* }, fields, use_mask)]
       });
 
-    let top_level_tokens = quote! { #(#top_level)* };
+    let mut top_level_tokens = quote! { #(#top_level)* };
+
+    if let Some(groups) = top_level_groups
+      && !top_level_tokens.is_empty()
+    {
+      top_level_tokens = groups.wrap_validation(top_level_tokens);
+    }
     let field_validators_tokens = quote! { #(#field_validators)* };
 
     let has_field_validators = !field_validators_tokens.is_empty();
@@ -248,6 +493,18 @@ pub fn generate_message_validator(
 
   let inline_if_empty = (!has_validators).then(|| quote! { #[inline(always)] });
 
+  let async_method = (*use_async && !*use_fallback)
+    .then(|| {
+      async_validator_method(
+        use_mask,
+        target_ident,
+        fields,
+        top_level_async_validators,
+        top_level_groups,
+      )
+    })
+    .flatten();
+
   let has_default_validator_tokens = if has_non_default_validators {
     quote! { true }
     // Means we only encountered boxed self for defaults, so it's false
@@ -278,6 +535,8 @@ pub fn generate_message_validator(
 
         Ok(is_valid)
       }
+
+      #async_method
     }
 
     impl ::prelude::ProtoValidation for #target_ident {
@@ -310,9 +569,13 @@ impl MessageCtx<'_> {
       // For non-reflection implementations we don't skip fields if they don't have
       // validators, so having empty fields means an error occurred
       UseFallback::from(self.fields_data.is_empty()),
+      UseMask::from(cfg!(feature = "field-mask")),
+      UseAsync::from(cfg!(feature = "async")),
       target_ident,
       &self.fields_data,
       &self.message_attrs.validators,
+      &self.message_attrs.async_validators,
+      self.message_attrs.groups.as_ref(),
     )
   }
 }
@@ -10,7 +10,20 @@ mod message_consistency_checks;
 pub use message_consistency_checks::*;
 mod message_validator_impl;
 pub use message_validator_impl::*;
+mod format_codecs;
+mod json_impls;
 mod oneof_consistency_checks;
+pub use json_impls::*;
+mod text_format_impls;
+pub use text_format_impls::*;
+mod samples_impls;
+pub use samples_impls::*;
+mod field_mask_impls;
+pub use field_mask_impls::*;
+mod normalize_impls;
+pub use normalize_impls::*;
+mod type_registry_impls;
+pub use type_registry_impls::*;
 
 pub fn wrap_with_imports(tokens: &TokenStream2) -> TokenStream2 {
   quote! {
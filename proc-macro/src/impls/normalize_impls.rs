@@ -0,0 +1,144 @@
+use crate::*;
+
+fn normalize_module() -> TokenStream2 {
+  quote! { ::prelude::normalize }
+}
+
+impl FieldData {
+  /// Generates the normalization of this field, where `value` is a mutable reference to it.
+  fn normalize_tokens(&self, value: &TokenStream2, item_kind: ItemKind) -> TokenStream2 {
+    let span = self.span;
+    let module = normalize_module();
+
+    let mut tokens = TokenStream2::new();
+
+    if let Some(normalizer) = &self.normalizer {
+      let apply = match &self.proto_field {
+        ProtoField::Optional(_) => quote_spanned! {span=>
+          if let Some(value) = #value {
+            NORMALIZER.normalize(value);
+          }
+        },
+        ProtoField::Repeated(_) => quote_spanned! {span=> NORMALIZER.normalize_all(#value); },
+        _ => quote_spanned! {span=> NORMALIZER.normalize(#value); },
+      };
+
+      tokens.extend(quote_spanned! {span=>
+        {
+          static NORMALIZER: ::prelude::Lazy<#module::StringNormalizer> =
+            ::prelude::Lazy::new(|| ::core::convert::Into::into(#normalizer));
+
+          #apply
+        }
+      });
+    }
+
+    if let Some(transform) = &self.transform {
+      tokens.extend(match transform {
+        PathOrClosure::Path(path) => quote_spanned! {span=> #path(#value); },
+        PathOrClosure::Closure(closure) => {
+          quote_spanned! {span=> ::prelude::apply(#value, #closure); }
+        }
+      });
+    }
+
+    let normalize_message =
+      quote_spanned! {span=> #module::ProtoNormalize::normalize_fields(value) };
+
+    match &self.proto_field {
+      ProtoField::Oneof(_) => tokens.extend(quote_spanned! {span=>
+        if let Some(value) = #value {
+          #normalize_message;
+        }
+      }),
+      _ if self.message_info().is_none() => {}
+      ProtoField::Map(_) => tokens.extend(quote_spanned! {span=>
+        for value in (#value).values_mut() {
+          #normalize_message;
+        }
+      }),
+      ProtoField::Repeated(_) => tokens.extend(quote_spanned! {span=>
+        for value in #value {
+          #normalize_message;
+        }
+      }),
+      // Oneof variants hold the message directly
+      ProtoField::Single(_) if item_kind.is_oneof() => tokens.extend(quote_spanned! {span=>
+        #module::ProtoNormalize::normalize_fields(#value);
+      }),
+      ProtoField::Single(_) | ProtoField::Optional(_) => tokens.extend(quote_spanned! {span=>
+        if let Some(value) = #value {
+          #normalize_message;
+        }
+      }),
+    }
+
+    tokens
+  }
+}
+
+impl MessageCtx<'_> {
+  pub fn generate_normalize_impls(&self) -> TokenStream2 {
+    let proto_struct = self.proto_struct_ident();
+    let module = normalize_module();
+
+    let fields_tokens = self
+      .fields_data
+      .iter()
+      .filter_map(|d| d.as_normal())
+      .map(|data| {
+        let ident = &data.ident;
+
+        data.normalize_tokens(
+          &quote_spanned! {data.span=> &mut self.#ident },
+          ItemKind::Message,
+        )
+      });
+
+    quote! {
+      impl #module::ProtoNormalize for #proto_struct {
+        #[doc(hidden)]
+        fn normalize_fields(&mut self) {
+          #(#fields_tokens)*
+        }
+      }
+    }
+  }
+}
+
+impl OneofCtx<'_> {
+  pub fn generate_normalize_impls(&self) -> TokenStream2 {
+    let enum_ident = self.proto_enum_ident();
+    let module = normalize_module();
+
+    let arms = self
+      .variants
+      .iter()
+      .filter_map(|v| v.as_normal())
+      .map(|data| {
+        let FieldData { ident, span, .. } = data;
+
+        let tokens = data.normalize_tokens(&quote_spanned! {*span=> value }, ItemKind::Oneof);
+
+        quote_spanned! {*span=>
+          Self::#ident(value) => {
+            #tokens
+          }
+        }
+      });
+
+    quote! {
+      impl #module::ProtoNormalize for #enum_ident {
+        #[doc(hidden)]
+        #[allow(unused_variables, clippy::match_single_binding)]
+        fn normalize_fields(&mut self) {
+          match self {
+            #(#arms,)*
+            #[allow(unreachable_patterns)]
+            _ => {}
+          }
+        }
+      }
+    }
+  }
+}
@@ -2,6 +2,7 @@ use crate::*;
 
 pub fn generate_oneof_validator(
   use_fallback: UseFallback,
+  use_mask: UseMask,
   oneof_ident: &Ident,
   variants: &[FieldDataKind],
   top_level_validators: &Validators,
@@ -34,15 +35,34 @@ pub fn generate_oneof_validator(
       })
       .map(|(data, validators)| {
         let ident = &data.ident;
+        let mut validators = quote! { #(#validators)* };
+
+        if *use_mask {
+          validators = data.mask_validation_tokens(validators);
+        }
 
         quote_spanned! {data.span=>
           Self::#ident(v) => {
-            #(#validators)*
+            #validators
           }
         }
       });
 
-    let top_level_tokens = quote! { #(#top_level)* };
+    let mut top_level_tokens = quote! { #(#top_level)* };
+
+    // The oneof-level validators only run if one of its fields is in the mask
+    if *use_mask && !top_level_tokens.is_empty() {
+      let names = variants
+        .iter()
+        .filter_map(|d| d.as_normal())
+        .map(|d| &d.proto_name);
+
+      top_level_tokens = quote! {
+        if ctx.is_any_in_mask(&[ #(#names),* ]) {
+          #top_level_tokens
+        }
+      };
+    }
     let variants_tokens = quote! { #(#variants_validators,)* };
 
     if top_level_tokens.is_empty() && variants_tokens.is_empty() {
@@ -129,6 +149,7 @@ impl OneofCtx<'_> {
     // validators, so having empty fields means an error occurred
     generate_oneof_validator(
       UseFallback::from(self.variants.is_empty()),
+      UseMask::from(cfg!(feature = "field-mask")),
       oneof_ident,
       &self.variants,
       &self.oneof_attrs.validators,
@@ -0,0 +1,275 @@
+use crate::*;
+
+fn samples_module() -> TokenStream2 {
+  quote! { ::prelude::samples }
+}
+
+impl FieldData {
+  /// Returns the validator that should be used to generate samples for this field, if any.
+  ///
+  /// Custom validators are skipped, since their rules cannot be inspected.
+  fn sample_validator(&self) -> Option<&ValidatorTokens> {
+    let mut validators = self
+      .validators
+      .validators
+      .iter()
+      .filter(|v| v.kind.should_be_cached());
+
+    let first = validators.next()?;
+
+    if first.kind.is_default() {
+      Some(validators.next().unwrap_or(first))
+    } else {
+      Some(first)
+    }
+  }
+
+  /// Converts an optional sample (called `value`) into the type of this field.
+  fn sample_conversion(&self, item_kind: ItemKind) -> TokenStream2 {
+    let span = self.span;
+
+    match &self.proto_field {
+      ProtoField::Map(_) | ProtoField::Repeated(_) | ProtoField::Oneof(_) => {
+        quote_spanned! {span=> value.unwrap_or_default() }
+      }
+      ProtoField::Optional(_) => {
+        quote_spanned! {span=> value.map(::core::convert::Into::into) }
+      }
+      ProtoField::Single(inner) => {
+        if inner.is_message() && item_kind.is_message() {
+          quote_spanned! {span=> value.map(::core::convert::Into::into) }
+        } else {
+          quote_spanned! {span=> ::core::convert::Into::into(value.unwrap_or_default()) }
+        }
+      }
+    }
+  }
+
+  /// Returns the tokens for the validator of this field and the type that it validates.
+  fn sample_validator_tokens(&self) -> Option<(TokenStream2, TokenStream2)> {
+    let validator = self.sample_validator()?;
+
+    let validator_name = self.validator_name();
+    let validator_expr = &validator.expr;
+    let target_type = self
+      .proto_field
+      .validator_target_type(validator.span);
+
+    Some((
+      quote_spanned! {validator.span=>
+        let validator: #validator_name = #validator_expr;
+      },
+      target_type,
+    ))
+  }
+}
+
+impl MessageCtx<'_> {
+  pub fn generate_samples_impls(&self) -> TokenStream2 {
+    let proto_struct = self.proto_struct_ident();
+    let module = samples_module();
+
+    let mut valid_tokens: Vec<TokenStream2> = Vec::new();
+    let mut invalid_tokens: Vec<TokenStream2> = Vec::new();
+
+    for data in self
+      .fields_data
+      .iter()
+      .filter_map(|d| d.as_normal())
+    {
+      let FieldData {
+        ident,
+        span,
+        proto_name,
+        ..
+      } = data;
+
+      if let ProtoField::Oneof(OneofInfo { path, required, .. }) = &data.proto_field {
+        valid_tokens.push(quote_spanned! {*span=>
+          sample.#ident = <#path as #module::ProtoSample>::valid_sample(ctx);
+        });
+
+        invalid_tokens.push(quote_spanned! {*span=>
+          for invalid in <#path as #module::ProtoSample>::invalid_samples(ctx) {
+            candidates.push(invalid.map(|value| {
+              let mut sample = base.clone();
+              sample.#ident = Some(value);
+              sample
+            }));
+          }
+        });
+
+        if *required {
+          invalid_tokens.push(quote_spanned! {*span=>
+            candidates.push(
+              #module::InvalidSample::new(
+                ::prelude::proto_types::protovalidate::ViolationKind::RequiredOneof,
+                {
+                  let mut sample = base.clone();
+                  sample.#ident = None;
+                  sample
+                }
+              )
+              .in_field(#proto_name)
+            );
+          });
+        }
+
+        continue;
+      }
+
+      let Some((validator_tokens, target_type)) = data.sample_validator_tokens() else {
+        continue;
+      };
+
+      let conversion = data.sample_conversion(ItemKind::Message);
+
+      valid_tokens.push(quote_spanned! {*span=>
+        {
+          #validator_tokens
+
+          if let Some(value) = #module::SampleValidator::<#target_type>::valid_sample(&validator, ctx) {
+            let value = Some(value);
+            sample.#ident = #conversion;
+          }
+        }
+      });
+
+      invalid_tokens.push(quote_spanned! {*span=>
+        {
+          #validator_tokens
+
+          for invalid in #module::SampleValidator::<#target_type>::invalid_samples(&validator, ctx) {
+            candidates.push(invalid.in_field(#proto_name).map(|value| {
+              let mut sample = base.clone();
+              sample.#ident = #conversion;
+              sample
+            }));
+          }
+        }
+      });
+    }
+
+    quote! {
+      impl #module::ProtoSample for #proto_struct {
+        #[doc(hidden)]
+        #[allow(unused_variables, unused_mut, clippy::redundant_closure_for_method_calls)]
+        fn sample_candidate(ctx: &mut #module::SampleCtx) -> Option<Self> {
+          let mut sample = <Self as ::core::default::Default>::default();
+
+          #(#valid_tokens)*
+
+          Some(sample)
+        }
+
+        #[doc(hidden)]
+        #[allow(unused_variables, unused_mut, clippy::redundant_closure_for_method_calls)]
+        fn invalid_candidates(base: &Self, ctx: &mut #module::SampleCtx) -> Vec<#module::InvalidSample<Self>> {
+          let mut candidates: Vec<#module::InvalidSample<Self>> = Vec::new();
+
+          #(#invalid_tokens)*
+
+          candidates
+        }
+
+        #[doc(hidden)]
+        #[inline]
+        fn sample_violations(&self) -> ::prelude::ValidationErrors {
+          #module::message_violations(self)
+        }
+      }
+    }
+  }
+}
+
+impl OneofCtx<'_> {
+  pub fn generate_samples_impls(&self) -> TokenStream2 {
+    let enum_ident = self.proto_enum_ident();
+    let module = samples_module();
+
+    let mut valid_arms: Vec<TokenStream2> = Vec::new();
+    let mut invalid_tokens: Vec<TokenStream2> = Vec::new();
+
+    for (i, data) in self
+      .variants
+      .iter()
+      .filter_map(|v| v.as_normal())
+      .enumerate()
+    {
+      let FieldData {
+        ident,
+        span,
+        proto_name,
+        ..
+      } = data;
+
+      let Some((validator_tokens, target_type)) = data.sample_validator_tokens() else {
+        // Variants without rules are always valid
+        valid_arms.push(quote_spanned! {*span=>
+          #i => Some(Self::#ident(::core::default::Default::default()))
+        });
+
+        continue;
+      };
+
+      valid_arms.push(quote_spanned! {*span=>
+        #i => {
+          #validator_tokens
+
+          #module::SampleValidator::<#target_type>::valid_sample(&validator, ctx)
+            .map(|value| Self::#ident(::core::convert::Into::into(value)))
+        }
+      });
+
+      // Missing values cannot be represented by a variant, so they are skipped
+      invalid_tokens.push(quote_spanned! {*span=>
+        {
+          #validator_tokens
+
+          for invalid in #module::SampleValidator::<#target_type>::invalid_samples(&validator, ctx) {
+            let invalid = invalid.in_field(#proto_name);
+
+            if let Some(value) = invalid.value {
+              candidates.push(#module::InvalidSample {
+                violation: invalid.violation,
+                path: invalid.path,
+                value: Self::#ident(::core::convert::Into::into(value)),
+              });
+            }
+          }
+        }
+      });
+    }
+
+    let variants_count = valid_arms.len();
+
+    quote! {
+      impl #module::ProtoSample for #enum_ident {
+        #[doc(hidden)]
+        #[allow(unused_variables, clippy::match_single_binding)]
+        fn sample_candidate(ctx: &mut #module::SampleCtx) -> Option<Self> {
+          match ctx.below(#variants_count) {
+            #(#valid_arms,)*
+            _ => None,
+          }
+        }
+
+        #[doc(hidden)]
+        #[allow(unused_variables, unused_mut)]
+        fn invalid_candidates(base: &Self, ctx: &mut #module::SampleCtx) -> Vec<#module::InvalidSample<Self>> {
+          let mut candidates: Vec<#module::InvalidSample<Self>> = Vec::new();
+
+          #(#invalid_tokens)*
+
+          candidates
+        }
+
+        #[doc(hidden)]
+        #[inline]
+        fn sample_violations(&self) -> ::prelude::ValidationErrors {
+          #module::oneof_violations(self)
+        }
+      }
+    }
+  }
+}
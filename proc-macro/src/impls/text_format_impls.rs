@@ -0,0 +1,151 @@
+use crate::*;
+
+fn text_format_module() -> TokenStream2 {
+  quote! { ::prelude::text_format }
+}
+
+impl MessageCtx<'_> {
+  pub fn generate_text_format_impls(&self) -> TokenStream2 {
+    let proto_struct = self.proto_struct_ident();
+    let module = text_format_module();
+
+    let mut write_tokens: Vec<TokenStream2> = Vec::new();
+    let mut merge_arms: Vec<TokenStream2> = Vec::new();
+
+    for data in self
+      .fields_data
+      .iter()
+      .filter_map(|d| d.as_normal())
+    {
+      let FieldData {
+        ident,
+        span,
+        proto_name,
+        ..
+      } = data;
+
+      if let ProtoField::Oneof(OneofInfo { path, .. }) = &data.proto_field {
+        write_tokens.push(quote_spanned! {*span=>
+          if let Some(oneof) = &self.#ident {
+            <#path as #module::ProtoTextOneof>::write_text_field(oneof, writer);
+          }
+        });
+
+        merge_arms.push(quote_spanned! {*span=>
+          key if <#path as #module::ProtoTextOneof>::FIELD_NAMES.contains(&key) => {
+            <#path as #module::ProtoTextOneof>::merge_text_field(&mut self.#ident, &name, value, position)?;
+          }
+        });
+      } else {
+        let codec = data.codec_tokens(&module, ItemKind::Message);
+
+        // Required fields are always present in the output
+        write_tokens.push(if data.required {
+          quote_spanned! {*span=>
+            <#codec as #module::TextCodec<_>>::write(&self.#ident, #proto_name, writer);
+          }
+        } else {
+          quote_spanned! {*span=>
+            #module::write_field::<#codec, _>(writer, #proto_name, &self.#ident);
+          }
+        });
+
+        merge_arms.push(quote_spanned! {*span=>
+          #proto_name => {
+            #module::merge_field::<#codec, _>(&mut self.#ident, &name, value, position)?;
+          }
+        });
+      }
+    }
+
+    quote! {
+      impl #module::ProtoText for #proto_struct {
+        #[allow(unused_variables)]
+        fn write_text(&self, writer: &mut #module::TextWriter) {
+          #(#write_tokens)*
+        }
+
+        #[allow(unused_variables, clippy::match_single_binding, clippy::never_loop)]
+        fn merge_text(&mut self, message: #module::TextMessage) -> Result<(), #module::TextFormatError> {
+          for #module::TextField { name, value, position } in message.fields {
+            match name.as_str() {
+              #(#merge_arms)*
+              _ => return Err(#module::TextFormatError::unknown_field(&name, position)),
+            }
+          }
+
+          Ok(())
+        }
+      }
+    }
+  }
+}
+
+impl OneofCtx<'_> {
+  pub fn generate_text_format_impls(&self) -> TokenStream2 {
+    let enum_ident = self.proto_enum_ident();
+    let module = text_format_module();
+
+    let mut field_names: Vec<&str> = Vec::new();
+    let mut write_arms: Vec<TokenStream2> = Vec::new();
+    let mut merge_arms: Vec<TokenStream2> = Vec::new();
+
+    for data in self.variants.iter().filter_map(|v| v.as_normal()) {
+      let FieldData {
+        ident,
+        span,
+        proto_name,
+        ..
+      } = data;
+
+      let codec = data.codec_tokens(&module, ItemKind::Oneof);
+
+      write_arms.push(quote_spanned! {*span=>
+        Self::#ident(value) => <#codec as #module::TextCodec<_>>::write(value, #proto_name, writer)
+      });
+
+      // If the same field appears more than once, its values are merged
+      merge_arms.push(quote_spanned! {*span=>
+        #proto_name => {
+          let mut inner = match target.take() {
+            Some(Self::#ident(inner)) => inner,
+            _ => ::core::default::Default::default(),
+          };
+
+          #module::merge_field::<#codec, _>(&mut inner, name, value, position)?;
+
+          *target = Some(Self::#ident(inner));
+        }
+      });
+
+      field_names.push(proto_name);
+    }
+
+    quote! {
+      impl #module::ProtoTextOneof for #enum_ident {
+        #[doc(hidden)]
+        const FIELD_NAMES: &'static [&'static str] = &[ #(#field_names),* ];
+
+        fn write_text_field(&self, writer: &mut #module::TextWriter) {
+          match self {
+            #(#write_arms),*
+          }
+        }
+
+        fn merge_text_field(
+          target: &mut Option<Self>,
+          name: &str,
+          value: #module::TextValue,
+          position: #module::TextPosition,
+        ) -> Result<(), #module::TextFormatError> {
+          match name {
+            #(#merge_arms)*
+            _ => return Err(#module::TextFormatError::unknown_field(name, position)),
+          }
+
+          Ok(())
+        }
+      }
+    }
+  }
+}
@@ -0,0 +1,16 @@
+use crate::*;
+
+impl MessageCtx<'_> {
+  pub fn generate_type_registry_entry(&self) -> TokenStream2 {
+    let proto_struct = self.proto_struct_ident();
+
+    quote! {
+      ::prelude::register_proto_data! {
+        ::prelude::type_registry::RegistryAnyMessage {
+          type_url: || <#proto_struct as ::prelude::ProtoMessage>::type_url(),
+          validate: ::prelude::type_registry::validate_payload::<#proto_struct>,
+        }
+      }
+    }
+  }
+}
@@ -6,7 +6,7 @@ pub fn message_proc_macro(mut item: ItemStruct, macro_attrs: TokenStream2) -> To
   let macro_args =
     MessageMacroArgs::parse(macro_attrs).unwrap_or_default_and_push_error(&mut errors);
 
-  let message_attrs = process_message_attrs(&item.ident, macro_args, &item.attrs)
+  let mut message_attrs = process_message_attrs(&item.ident, macro_args, &item.attrs)
     .unwrap_or_default_and_push_error(&mut errors);
 
   let is_proxied = message_attrs.is_proxied;
@@ -31,6 +31,30 @@ pub fn message_proc_macro(mut item: ItemStruct, macro_attrs: TokenStream2) -> To
   )
   .unwrap_or_default_and_push_error(&mut errors);
 
+  let tag_lockfile = message_attrs
+    .tag_lockfile
+    .as_ref()
+    .map(|attr| {
+      TagLockfile::load(
+        attr,
+        &message_attrs.name,
+        message_attrs.parent_message.as_ref(),
+      )
+    })
+    .transpose()
+    .unwrap_or_default_and_push_error(&mut errors);
+
+  if let Some(lockfile) = &tag_lockfile {
+    lockfile
+      .lock_fields(
+        &mut fields_data,
+        &message_attrs.reserved_names,
+        &message_attrs.reserved_numbers,
+        &mut manually_set_tags,
+      )
+      .unwrap_or_default_and_push_error(&mut errors);
+  }
+
   let used_ranges =
     build_unavailable_ranges(&message_attrs.reserved_numbers, &mut manually_set_tags)
       .unwrap_or_default_and_push_error(&mut errors);
@@ -59,6 +83,37 @@ pub fn message_proc_macro(mut item: ItemStruct, macro_attrs: TokenStream2) -> To
   .process_fields_data()
   .unwrap_or_default_and_push_error(&mut errors);
 
+  check_field_conditions(&fields_data).unwrap_or_default_and_push_error(&mut errors);
+
+  let lockfile_tokens = tag_lockfile
+    .as_ref()
+    .filter(|_| errors.is_empty())
+    .map(|lockfile| {
+      let proto_ident = proto_struct
+        .as_ref()
+        .map_or(&item.ident, |ps| &ps.ident);
+
+      let assigned = fields_data.iter().filter_map(|data| match data {
+        FieldDataKind::Normal(data) if !data.proto_field.is_oneof() => data
+          .tag
+          .map(|tag| (data.proto_name.clone(), tag.num)),
+        _ => None,
+      });
+
+      let test = lockfile.test_tokens(
+        proto_ident,
+        quote! { <#proto_ident as ::prelude::ProtoMessage>::proto_name() },
+        message_attrs.parent_message.as_ref(),
+        assigned,
+      );
+      let tracking = lockfile.tracking_tokens();
+
+      quote! {
+        #test
+        #tracking
+      }
+    });
+
   let proto_derives = if !errors.is_empty() {
     FallbackImpls {
       orig_ident: &item.ident,
@@ -139,6 +194,13 @@ pub fn message_proc_macro(mut item: ItemStruct, macro_attrs: TokenStream2) -> To
     }
   };
 
+  message_attrs.validators.adjust_message_closures(
+    proto_struct
+      .as_ref()
+      .map_or(&item.ident, |ps| &ps.ident),
+    &fields_data,
+  );
+
   let message_ctx = MessageCtx {
     orig_struct_ident: &item.ident,
     shadow_struct_ident: proto_struct.as_ref().map(|ps| &ps.ident),
@@ -151,8 +213,36 @@ pub fn message_proc_macro(mut item: ItemStruct, macro_attrs: TokenStream2) -> To
     .then(|| message_ctx.generate_consistency_checks());
   let validator_impl = message_ctx.generate_validator();
   let schema_impls = message_ctx.generate_schema_impls();
-
-  let wrapped_items = wrap_multiple_with_imports(&[schema_impls, validator_impl]);
+  let json_impls = (cfg!(feature = "json") && errors.is_empty())
+    .then(|| message_ctx.generate_json_impls())
+    .unwrap_or_default();
+  let text_format_impls = (cfg!(feature = "text-format") && errors.is_empty())
+    .then(|| message_ctx.generate_text_format_impls())
+    .unwrap_or_default();
+  let samples_impls = (cfg!(feature = "samples") && errors.is_empty())
+    .then(|| message_ctx.generate_samples_impls())
+    .unwrap_or_default();
+  let field_mask_impls = (cfg!(feature = "field-mask") && errors.is_empty())
+    .then(|| message_ctx.generate_field_mask_impls())
+    .unwrap_or_default();
+  let normalize_impls = (cfg!(feature = "normalize") && errors.is_empty())
+    .then(|| message_ctx.generate_normalize_impls())
+    .unwrap_or_default();
+  let type_registry_entry = errors
+    .is_empty()
+    .then(|| message_ctx.generate_type_registry_entry())
+    .unwrap_or_default();
+
+  let wrapped_items = wrap_multiple_with_imports(&[
+    schema_impls,
+    validator_impl,
+    json_impls,
+    text_format_impls,
+    samples_impls,
+    field_mask_impls,
+    normalize_impls,
+    type_registry_entry,
+  ]);
 
   let errors = errors.iter().map(|e| e.to_compile_error());
 
@@ -160,6 +250,7 @@ pub fn message_proc_macro(mut item: ItemStruct, macro_attrs: TokenStream2) -> To
     #main_struct_tokens
     #wrapped_items
     #consistency_checks
+    #lockfile_tokens
     #(#errors)*
   }
 }
@@ -9,6 +9,11 @@ pub fn field_schema_tokens(data: &FieldData) -> TokenStream2 {
     proto_field,
     deprecated,
     span,
+    comments,
+    features,
+    required,
+    json_name,
+    default,
     ..
   } = data;
 
@@ -16,33 +21,96 @@ pub fn field_schema_tokens(data: &FieldData) -> TokenStream2 {
     .iter()
     // For default validators (messages only) we skip the schema generation
     .filter(|v| !v.kind.is_default())
+    // The rules of conditional fields are rendered at the message level
+    .filter(|_| data.when.is_none())
     .map(|e| {
       let validator_target_type = proto_field.validator_target_type(*span);
 
-      quote_spanned! {*span=>
+      let schema = quote_spanned! {*span=>
         ::prelude::Validator::<#validator_target_type>::schema(&#e)
+      };
+
+      match &data.groups {
+        Some(groups) => groups.wrap_schema(schema),
+        None => schema,
       }
     });
 
   if let ProtoField::Oneof(OneofInfo { path, .. }) = proto_field {
+    // Doc comments on the field take priority over those of the oneof enum
+    let comments_tokens = (!comments.is_empty()).then(|| {
+      quote_spanned! {*span=> .with_comments(#comments) }
+    });
+
     quote_spanned! {*span=>
       ::prelude::MessageEntry::Oneof(
         <#path as ::prelude::ProtoOneof>::proto_schema()
           .with_name(#proto_name)
           .with_options(#options)
           .with_validators(::prelude::filter_validators([ #(#validator_schema_tokens),* ]))
+          #comments_tokens
       )
     }
   } else {
-    let field_type_tokens = proto_field.proto_field_target_type(*span);
+    let mut field_type_tokens = proto_field.proto_field_target_type(*span);
+
+    if *required {
+      field_type_tokens = quote_spanned! {*span=> #field_type_tokens.into_required() };
+    }
+
+    let mut options_tokens = options_tokens(*span, options, *deprecated);
+
+    if let Some(json_name) = json_name {
+      options_tokens = quote_spanned! {*span=>
+        {
+          let mut options: Vec<::prelude::ProtoOption> = #options_tokens.into_iter().collect();
+          options.push(::prelude::proto_json_name(#json_name));
+          options
+        }
+      };
+    }
+
+    if let Some(default) = default
+      && let ProtoField::Single(proto_type) | ProtoField::Optional(proto_type) = proto_field
+    {
+      let value = default.option_value_tokens(proto_type, *span);
+      let default_span = default.span();
+
+      // Checked at compile time, so that it cannot be disabled with `skip_checks`
+      let edition_check = quote_spanned! {default_span=>
+        const _: () = ::core::assert!(
+          !::core::matches!(__PROTO_FILE.edition, ::prelude::Edition::Proto3),
+          "Default values are not supported by proto3"
+        );
+      };
+
+      options_tokens = quote_spanned! {*span=>
+        {
+          #edition_check
+
+          let mut options: Vec<::prelude::ProtoOption> = #options_tokens.into_iter().collect();
+          options.push(::prelude::proto_default(#value));
+          options
+        }
+      };
+    }
 
-    let options_tokens = options_tokens(*span, options, *deprecated);
+    let features_tokens = if data
+      .message_info()
+      .is_some_and(|info| info.delimited)
+    {
+      quote_spanned! {*span=> (#features).with_message_encoding(::prelude::MessageEncoding::Delimited) }
+    } else {
+      features.to_token_stream()
+    };
 
     quote_spanned! {*span=>
       ::prelude::Field {
         name: #proto_name.into(),
+        comments: #comments,
         tag: #tag,
         options: #options_tokens.into_iter().collect(),
+        features: #features_tokens,
         type_: #field_type_tokens,
         validators: ::prelude::collect_validators([ #(#validator_schema_tokens),* ]),
       }
@@ -50,6 +118,44 @@ pub fn field_schema_tokens(data: &FieldData) -> TokenStream2 {
   }
 }
 
+/// Generates the message-level rules for a field with a `when` condition.
+fn conditional_rules_tokens(data: &FieldData, fields: &[FieldDataKind]) -> Option<TokenStream2> {
+  let condition = data.when.as_ref()?;
+  let span = data.span;
+
+  let validator_target_type = data.proto_field.validator_target_type(span);
+
+  let schemas: Vec<TokenStream2> = data
+    .validators
+    .iter()
+    .filter(|v| !v.kind.is_default())
+    .map(|e| {
+      quote_spanned! {span=>
+        ::prelude::Validator::<#validator_target_type>::schema(&#e)
+      }
+    })
+    .collect();
+
+  if schemas.is_empty() {
+    return None;
+  }
+
+  let cel = condition
+    .resolve(fields)
+    .map_or_else(|e| e.to_compile_error(), |c| c.cel);
+  let (_, _, has_presence) = data.presence_accessors(&quote_spanned! {span=> self });
+  let proto_name = &data.proto_name;
+
+  let tokens = quote_spanned! {span=>
+    ::prelude::conditional_field_rules(#proto_name, #has_presence, &#cel, [ #(#schemas),* ])
+  };
+
+  Some(match &data.groups {
+    Some(groups) => groups.wrap_schema(tokens),
+    None => tokens,
+  })
+}
+
 impl MessageCtx<'_> {
   pub fn generate_schema_impls(&self) -> TokenStream2 {
     let MessageAttrs {
@@ -60,9 +166,18 @@ impl MessageCtx<'_> {
       parent_message,
       deprecated,
       validators,
+      groups,
+      comments,
+      features,
       ..
     } = &self.message_attrs;
 
+    let conditional_rules = self
+      .fields_data
+      .iter()
+      .filter_map(|d| d.as_normal())
+      .filter_map(|data| conditional_rules_tokens(data, &self.fields_data));
+
     let entries_tokens = if self.fields_data.is_empty() {
       quote! { unimplemented!() }
     } else {
@@ -89,6 +204,15 @@ impl MessageCtx<'_> {
 
     let proto_struct = self.proto_struct_ident();
 
+    let validator_schemas = validators.iter().map(|v| {
+      let schema = quote_spanned! {v.span=> ::prelude::Validator::<#proto_struct>::schema(&#v) };
+
+      match groups {
+        Some(groups) => groups.wrap_schema(schema),
+        None => schema,
+      }
+    });
+
     let name_method = if let Some(parent) = parent_message {
       quote_spanned! {parent.span()=>
         static __NAME: ::prelude::Lazy<String> = ::prelude::Lazy::new(|| {
@@ -125,6 +249,19 @@ impl MessageCtx<'_> {
         }
       });
 
+    let reflect_message_impl = cfg!(feature = "runtime-descriptors").then(|| {
+      quote! {
+        impl ::prelude::prost_reflect::ReflectMessage for #proto_struct {
+          fn descriptor(&self) -> ::prelude::prost_reflect::MessageDescriptor {
+            static DESCRIPTOR: ::prelude::Lazy<::prelude::prost_reflect::MessageDescriptor> =
+              ::prelude::Lazy::new(|| <#proto_struct as ::prelude::ProtoMessage>::message_descriptor());
+
+            DESCRIPTOR.clone()
+          }
+        }
+      }
+    });
+
     quote! {
       ::prelude::register_proto_data! {
         ::prelude::RegistryMessage {
@@ -197,21 +334,25 @@ impl MessageCtx<'_> {
           ::prelude::Message {
             short_name: #proto_name.into(),
             name: <Self as ::prelude::ProtoMessage>::proto_name().into(),
+            comments: #comments,
             file: __PROTO_FILE.name.into(),
             package: __PROTO_FILE.package.into(),
             reserved_names: vec![ #(#reserved_names.into()),* ],
             reserved_numbers: #reserved_numbers,
             options: #options_tokens.into_iter().collect(),
+            features: #features,
+            edition: __PROTO_FILE.edition,
             messages: vec![],
             enums: vec![],
             entries: vec![ #entries_tokens ],
-            validators: ::prelude::collect_validators([ #(::prelude::Validator::<#proto_struct>::schema(&#validators)),* ]),
+            validators: ::prelude::collect_validators([ #(#validator_schemas,)* #(#conditional_rules),* ]),
             rust_path:  format!("::{}::{}", __PROTO_FILE.extern_path, #rust_ident_str).into()
           }
         }
       }
 
       #proxy_struct_impl
+      #reflect_message_impl
     }
   }
 }
@@ -165,8 +165,31 @@ pub fn process_oneof_proc_macro(mut item: ItemEnum, macro_attrs: TokenStream2) -
     .then(|| oneof_ctx.generate_consistency_checks());
   let validator_impl = oneof_ctx.generate_validator();
   let schema_impls = oneof_ctx.generate_schema_impl();
-
-  let wrapped_items = wrap_multiple_with_imports(&[schema_impls, validator_impl]);
+  let json_impls = (cfg!(feature = "json") && errors.is_empty())
+    .then(|| oneof_ctx.generate_json_impls())
+    .unwrap_or_default();
+  let text_format_impls = (cfg!(feature = "text-format") && errors.is_empty())
+    .then(|| oneof_ctx.generate_text_format_impls())
+    .unwrap_or_default();
+  let samples_impls = (cfg!(feature = "samples") && errors.is_empty())
+    .then(|| oneof_ctx.generate_samples_impls())
+    .unwrap_or_default();
+  let field_mask_impls = (cfg!(feature = "field-mask") && errors.is_empty())
+    .then(|| oneof_ctx.generate_field_mask_impls())
+    .unwrap_or_default();
+  let normalize_impls = (cfg!(feature = "normalize") && errors.is_empty())
+    .then(|| oneof_ctx.generate_normalize_impls())
+    .unwrap_or_default();
+
+  let wrapped_items = wrap_multiple_with_imports(&[
+    schema_impls,
+    validator_impl,
+    json_impls,
+    text_format_impls,
+    samples_impls,
+    field_mask_impls,
+    normalize_impls,
+  ]);
 
   let errors = errors.iter().map(|e| e.to_compile_error());
 
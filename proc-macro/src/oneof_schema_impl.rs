@@ -20,6 +20,7 @@ impl OneofCtx<'_> {
       options: options_tokens,
       name: proto_name,
       validators,
+      comments,
       ..
     } = &self.oneof_attrs;
     let tags = &self.tags;
@@ -34,6 +35,7 @@ impl OneofCtx<'_> {
         fn proto_schema() -> ::prelude::Oneof {
           ::prelude::Oneof {
             name: #proto_name.into(),
+            comments: #comments,
             fields: vec![ #variants_tokens ],
             options: #options_tokens.into_iter().collect(),
             validators: ::prelude::collect_validators([ #(::prelude::Validator::<#enum_ident>::schema(&#validators)),* ]),
@@ -307,8 +307,12 @@ impl ProtoType {
 
         quote_spanned! {span=> enumeration = #path_as_str }
       }
-      Self::Message(MessageInfo { boxed, .. }) => {
-        if *boxed {
+      Self::Message(MessageInfo {
+        boxed, delimited, ..
+      }) => {
+        if *delimited {
+          quote_spanned! {span=> group }
+        } else if *boxed {
           quote_spanned! {span=> message, boxed }
         } else {
           quote_spanned! {span=> message }
@@ -264,6 +264,7 @@ impl ProtoType {
               .ok_or_else(|| error!(type_info, "Failed to infer message path"))?,
             boxed,
             default: false,
+            delimited: false,
           })
         }
       },
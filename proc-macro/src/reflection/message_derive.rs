@@ -120,11 +120,21 @@ fn extract_fields_data(item: &mut ItemStruct) -> Result<ReflectionMsgData, Error
               .expect("Failed to get the default oneof validator, this shouldn't have happened"),
           ),
           options: TokensOr::<TokenStream2>::vec(),
+          features: features_tokens_or_default(),
+          json_name: None,
           proto_field,
           from_proto: None,
           into_proto: None,
+          normalizer: None,
+          transform: None,
+          default: None,
+          async_validators: Validators::default(),
+          when: None,
+          groups: None,
           deprecated: false,
           forwarded_attrs: vec![],
+          comments: DocComments::default(),
+          required: false,
         }));
 
         continue;
@@ -169,11 +179,21 @@ fn extract_fields_data(item: &mut ItemStruct) -> Result<ReflectionMsgData, Error
         }),
         validators: Validators::from_single(validator),
         options: TokensOr::<TokenStream2>::vec(),
+        features: features_tokens_or_default(),
+        json_name: None,
         proto_field,
         from_proto: None,
         into_proto: None,
+        normalizer: None,
+        transform: None,
+        default: None,
+        async_validators: Validators::default(),
+        when: None,
+        groups: None,
         deprecated: false,
         forwarded_attrs: vec![],
+        comments: DocComments::default(),
+        required: false,
       }));
     }
   }
@@ -184,7 +204,7 @@ fn extract_fields_data(item: &mut ItemStruct) -> Result<ReflectionMsgData, Error
   if let Some(message_rules) = get_message_rules(&message_desc) {
     if !message_rules.cel.is_empty() {
       let mut builder_tokens = quote! {
-        ::prelude::CelValidator::default()
+        ::prelude::MessageRules::default()
       };
 
       for rule in message_rules.cel {
@@ -296,9 +316,13 @@ pub fn reflection_message_derive(item: &mut ItemStruct) -> TokenStream2 {
 
   let validator_impl = wrap_multiple_with_imports(&[generate_message_validator(
     use_fallback,
+    UseMask::No,
+    UseAsync::No,
     &item.ident,
     &fields_data,
     &top_level_validator,
+    &Validators::default(),
+    None,
   )]);
 
   let consistency_checks = errors.is_empty().then(|| {
@@ -112,11 +112,21 @@ fn extract_oneof_data(item: &mut ItemEnum) -> Result<OneofDataReflection, Error>
       )),
       validators: Validators::from_single(validator),
       options: TokenStreamOr::vec(),
+      features: features_tokens_or_default(),
+      json_name: None,
       proto_field,
       from_proto: None,
       into_proto: None,
+      normalizer: None,
+      transform: None,
+      default: None,
+      async_validators: Validators::default(),
+      when: None,
+      groups: None,
       deprecated: false,
       forwarded_attrs: vec![],
+      comments: DocComments::default(),
+      required: false,
     }));
   }
 
@@ -142,6 +152,7 @@ pub fn reflection_oneof_derive(item: &mut ItemEnum) -> TokenStream2 {
 
   let validator_impl = wrap_multiple_with_imports(&[generate_oneof_validator(
     use_fallback,
+    UseMask::No,
     &item.ident,
     &fields_data,
     &Validators::default(),
@@ -5,8 +5,45 @@ struct HandlerCtx {
   span: Span,
   request: TokenStream2,
   response: TokenStream2,
+  client_streaming: bool,
+  server_streaming: bool,
   options: TokensOr<TokenStream2>,
   deprecated: bool,
+  comments: DocComments,
+}
+
+// Extracts the message path from a handler's type, and whether
+// it's been marked as a stream with `Stream<T>`
+fn extract_handler_type(ty: &Type) -> Result<(&Path, bool), Error> {
+  let path = match ty {
+    Type::Path(type_path) => &type_path.path,
+    _ => bail!(ty, "Expected a type path"),
+  };
+
+  let last_segment = path
+    .segments
+    .last()
+    .ok_or_else(|| error!(path, "Expected a type path"))?;
+
+  if last_segment.ident != "Stream" {
+    return Ok((path, false));
+  }
+
+  let syn::PathArguments::AngleBracketed(args) = &last_segment.arguments else {
+    bail!(
+      last_segment,
+      "Expected a message type inside `Stream`, like `Stream<MyMessage>`"
+    );
+  };
+
+  if args.args.len() != 1 {
+    bail!(args, "`Stream` accepts exactly one message type");
+  }
+
+  match args.args.first() {
+    Some(syn::GenericArgument::Type(Type::Path(inner))) => Ok((&inner.path, true)),
+    _ => bail!(args, "Expected a type path inside `Stream`"),
+  }
 }
 
 pub fn process_service_derive(item: &ItemEnum) -> Result<TokenStream2, Error> {
@@ -23,6 +60,7 @@ pub fn process_service_derive(item: &ItemEnum) -> Result<TokenStream2, Error> {
   let ServiceOrHandlerAttrs {
     options: service_options,
     deprecated,
+    comments: service_comments,
   } = process_service_or_handler_attrs(attrs)?;
 
   let service_name = to_pascal_case(&ident.to_string());
@@ -31,12 +69,13 @@ pub fn process_service_derive(item: &ItemEnum) -> Result<TokenStream2, Error> {
     let ServiceOrHandlerAttrs {
       options: handler_options,
       deprecated,
+      comments,
     } = process_service_or_handler_attrs(&variant.attrs)?;
 
     let handler_name = variant.ident.to_string();
 
-    let mut request: Option<&Path> = None;
-    let mut response: Option<&Path> = None;
+    let mut request: Option<(&Path, bool)> = None;
+    let mut response: Option<(&Path, bool)> = None;
 
     let fields = if let Fields::Named(named) = &variant.fields {
       &named.named
@@ -50,10 +89,7 @@ pub fn process_service_derive(item: &ItemEnum) -> Result<TokenStream2, Error> {
     for field in fields {
       let field_ident = field.require_ident()?.to_string();
 
-      let field_type = match &field.ty {
-        Type::Path(type_path) => &type_path.path,
-        _ => bail!(&field.ty, "Expected a type path"),
-      };
+      let field_type = extract_handler_type(&field.ty)?;
 
       match field_ident.as_str() {
         "request" => request = Some(field_type),
@@ -65,19 +101,20 @@ pub fn process_service_derive(item: &ItemEnum) -> Result<TokenStream2, Error> {
       };
     }
 
-    let request = request
-      .ok_or_else(|| error!(&variant, "Missing request type"))?
-      .to_token_stream();
-    let response = response
-      .ok_or_else(|| error!(&variant, "Missing response type"))?
-      .to_token_stream();
+    let (request, client_streaming) =
+      request.ok_or_else(|| error!(&variant, "Missing request type"))?;
+    let (response, server_streaming) =
+      response.ok_or_else(|| error!(&variant, "Missing response type"))?;
 
     handlers_data.push(HandlerCtx {
       name: handler_name,
-      request,
-      response,
+      request: request.to_token_stream(),
+      response: response.to_token_stream(),
+      client_streaming,
+      server_streaming,
       options: handler_options,
       deprecated,
+      comments,
       span: variant.ident.span(),
     });
   }
@@ -87,8 +124,11 @@ pub fn process_service_derive(item: &ItemEnum) -> Result<TokenStream2, Error> {
       name,
       request,
       response,
+      client_streaming,
+      server_streaming,
       options,
       deprecated,
+      comments,
       span,
     } = data;
 
@@ -97,8 +137,11 @@ pub fn process_service_derive(item: &ItemEnum) -> Result<TokenStream2, Error> {
     quote_spanned! {*span=>
       ::prelude::ServiceHandler {
         name: #name.into(),
+        comments: #comments,
         request: <#request as ::prelude::MessagePath>::proto_path(),
         response: <#response as ::prelude::MessagePath>::proto_path(),
+        client_streaming: #client_streaming,
+        server_streaming: #server_streaming,
         options: #options_tokens.into_iter().collect()
       }
     }
@@ -121,6 +164,7 @@ pub fn process_service_derive(item: &ItemEnum) -> Result<TokenStream2, Error> {
       fn as_proto_service() -> ::prelude::Service {
         ::prelude::Service {
           name: #service_name.into(),
+          comments: #service_comments,
           file: __PROTO_FILE.name.into(),
           package: __PROTO_FILE.package.into(),
           handlers: ::prelude::vec![ #(#handlers_tokens),* ],
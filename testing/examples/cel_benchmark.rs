@@ -0,0 +1,118 @@
+// Compares the CEL evaluation of programs that only read some fields of a message,
+// which only convert those fields, with programs that use `this` as a whole,
+// which need to clone and convert the entire message.
+//
+// Run with `cargo run --release -p testing --example cel_benchmark`
+use std::{
+  hint::black_box,
+  time::{Duration, Instant},
+};
+
+use prelude::*;
+
+proto_package!(BENCH_PKG, name = "bench", no_cel_test);
+
+define_proto_file!(BENCH, name = "bench.proto", package = BENCH_PKG);
+
+#[proto_message]
+#[proto(skip_checks(all))]
+struct BenchAddress {
+  street: String,
+  city: String,
+  zip: String,
+}
+
+#[proto_message]
+#[proto(skip_checks(all))]
+struct BenchCustomer {
+  name: String,
+  #[proto(repeated(message))]
+  addresses: Vec<BenchAddress>,
+}
+
+#[proto_message]
+#[proto(skip_checks(all))]
+struct BenchLine {
+  sku: String,
+  quantity: u32,
+  notes: Vec<String>,
+}
+
+#[proto_message]
+#[proto(skip_checks(all))]
+struct BenchOrder {
+  id: i32,
+  #[proto(message)]
+  customer: Option<BenchCustomer>,
+  #[proto(repeated(message))]
+  lines: Vec<BenchLine>,
+}
+
+#[proto_message]
+#[proto(skip_checks(all))]
+struct LazyBench {
+  #[proto(message, validate = |v| v.cel(cel_program!(id = "lazy", msg = "abc", expr = "this.id > 0")))]
+  order: Option<BenchOrder>,
+}
+
+#[proto_message]
+#[proto(skip_checks(all))]
+struct FullBench {
+  // Comparing `this` as a whole requires the full conversion
+  #[proto(message, validate = |v| v.cel(cel_program!(id = "full", msg = "abc", expr = "this != null && this.id > 0")))]
+  order: Option<BenchOrder>,
+}
+
+fn order(lines: usize) -> BenchOrder {
+  BenchOrder {
+    id: 1,
+    customer: Some(BenchCustomer {
+      name: "Frodo".to_string(),
+      addresses: (0..lines / 10)
+        .map(|i| BenchAddress {
+          street: format!("Bagshot Row {i}"),
+          city: "Hobbiton".to_string(),
+          zip: "00001".to_string(),
+        })
+        .collect(),
+    }),
+    lines: (0..lines)
+      .map(|i| BenchLine {
+        sku: format!("SKU-{i}"),
+        quantity: 1,
+        notes: vec!["fragile".to_string(); 4],
+      })
+      .collect(),
+  }
+}
+
+fn measure(iterations: u32, f: impl Fn() -> bool) -> Duration {
+  let start = Instant::now();
+
+  for _ in 0..iterations {
+    assert!(black_box(f()));
+  }
+
+  start.elapsed() / iterations
+}
+
+fn main() {
+  const ITERATIONS: u32 = 200;
+
+  for lines in [10, 100, 1_000, 10_000] {
+    let lazy = LazyBench {
+      order: Some(order(lines)),
+    };
+    let full = FullBench {
+      order: Some(order(lines)),
+    };
+
+    let lazy_time = measure(ITERATIONS, || lazy.validate().is_ok());
+    let full_time = measure(ITERATIONS, || full.validate().is_ok());
+
+    println!(
+      "{lines:>6} lines: full conversion {full_time:>12?}, field resolution {lazy_time:>12?} ({:.1}x)",
+      full_time.as_secs_f64() / lazy_time.as_secs_f64()
+    );
+  }
+}
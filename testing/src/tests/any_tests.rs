@@ -0,0 +1,152 @@
+use super::*;
+use prelude::{
+  proto_types::Any,
+  type_registry::{AnyError, AnyExt, find_type},
+};
+
+proto_package!(ANY_PKG, name = "any_tests", no_cel_test);
+
+define_proto_file!(ANY_FILE, name = "any_tests.proto", package = ANY_PKG);
+
+#[proto_message]
+#[proto(skip_checks(all))]
+pub struct AnyPayload {
+  #[proto(validate = |v| v.min_len(3))]
+  pub name: String,
+}
+
+#[proto_message]
+#[proto(skip_checks(all))]
+pub struct OtherPayload {
+  pub id: i32,
+}
+
+#[proto_message]
+#[proto(skip_checks(all))]
+pub struct AnyHolder {
+  #[proto(any, validate = |v| v.validate_payload())]
+  pub payload: Option<Any>,
+}
+
+fn violation_path(msg: &impl ValidatedMessage) -> (String, String) {
+  let violations = msg.validate().unwrap_err().into_violations();
+
+  assert_eq!(violations.len(), 1, "Expected a single violation");
+
+  let violation = violations.first().unwrap();
+
+  let path = violation
+    .field
+    .as_ref()
+    .map(|path| {
+      path
+        .elements
+        .iter()
+        .filter_map(|el| el.field_name.clone())
+        .collect::<Vec<_>>()
+        .join(".")
+    })
+    .unwrap_or_default();
+
+  (path, violation.rule_id().to_string())
+}
+
+#[test]
+fn pack_and_unpack() {
+  let payload = AnyPayload {
+    name: "abcde".to_string(),
+  };
+
+  let any = Any::pack(&payload);
+
+  assert_eq_pretty!(any.type_url, AnyPayload::type_url());
+  assert!(any.is::<AnyPayload>());
+  assert!(!any.is::<OtherPayload>());
+
+  assert_eq_pretty!(any.unpack::<AnyPayload>().unwrap(), payload);
+
+  assert!(matches!(
+    any.unpack::<OtherPayload>(),
+    Err(AnyError::TypeMismatch { .. })
+  ));
+
+  // The domain of the type url is ignored
+  let with_domain = Any {
+    type_url: format!("type.googleapis.com/{}", AnyPayload::full_name()),
+    value: any.value.clone(),
+  };
+
+  assert_eq_pretty!(with_domain.unpack::<AnyPayload>().unwrap(), payload);
+}
+
+#[test]
+fn type_registry_lookup() {
+  let entry = find_type(AnyPayload::type_url()).unwrap();
+
+  assert_eq_pretty!(entry.full_name(), AnyPayload::full_name());
+
+  assert!(
+    find_type(&format!(
+      "type.googleapis.com/{}",
+      OtherPayload::full_name()
+    ))
+    .is_some()
+  );
+  assert!(find_type("type.googleapis.com/google.protobuf.Duration").is_some());
+  assert!(find_type("/any_tests.Unknown").is_none());
+}
+
+#[test]
+fn payload_validation() {
+  let valid = AnyHolder {
+    payload: Some(Any::pack(&AnyPayload {
+      name: "abcde".to_string(),
+    })),
+  };
+
+  assert!(valid.validate().is_ok());
+
+  let well_known = AnyHolder {
+    payload: Some(Any {
+      type_url: "type.googleapis.com/google.protobuf.Duration".to_string(),
+      value: Default::default(),
+    }),
+  };
+
+  assert!(well_known.validate().is_ok());
+
+  let invalid = AnyHolder {
+    payload: Some(Any::pack(&AnyPayload {
+      name: "a".to_string(),
+    })),
+  };
+
+  assert_eq_pretty!(
+    violation_path(&invalid),
+    ("payload.name".to_string(), "string.min_len".to_string())
+  );
+}
+
+#[test]
+fn unresolvable_payloads() {
+  let unregistered = AnyHolder {
+    payload: Some(Any {
+      type_url: "/any_tests.Unknown".to_string(),
+      value: Default::default(),
+    }),
+  };
+
+  assert_violation_id(&unregistered, "any.registered", "unregistered type url");
+
+  let undecodable = AnyHolder {
+    payload: Some(Any {
+      type_url: AnyPayload::type_url().to_string(),
+      value: vec![0xff].into(),
+    }),
+  };
+
+  assert_eq_pretty!(
+    violation_path(&undecodable),
+    ("payload".to_string(), "any.decodable".to_string())
+  );
+}
@@ -0,0 +1,239 @@
+use super::*;
+use prelude::proto_types::protovalidate::ViolationKind;
+use std::{
+  pin::pin,
+  task::{Context, Poll, Waker},
+};
+
+proto_package!(ASYNC_VALIDATORS_PKG, name = "async_validators", no_cel_test);
+
+define_proto_file!(
+  ASYNC_VALIDATORS_FILE,
+  name = "async_validators.proto",
+  package = ASYNC_VALIDATORS_PKG
+);
+
+// The validators below never suspend, so polling once is enough
+fn block_on<F: Future>(fut: F) -> F::Output {
+  let mut fut = pin!(fut);
+  let mut cx = Context::from_waker(Waker::noop());
+
+  loop {
+    if let Poll::Ready(output) = fut.as_mut().poll(&mut cx) {
+      return output;
+    }
+  }
+}
+
+async fn username_exists(username: &str) -> bool {
+  username == "admin"
+}
+
+struct UsernameNotTaken;
+
+impl AsyncValidator<String> for UsernameNotTaken {
+  async fn validate_async(&self, ctx: &mut ValidationCtx, val: Option<&str>) -> ValidationResult {
+    if let Some(username) = val
+      && username_exists(username).await
+    {
+      ctx.add_violation_with_custom_id("username.taken", ViolationKind::Cel, "is already taken")
+    } else {
+      Ok(IsValid::Yes)
+    }
+  }
+}
+
+struct OrderExists;
+
+impl AsyncValidator<AsyncOrderRef> for OrderExists {
+  async fn validate_async(
+    &self,
+    ctx: &mut ValidationCtx,
+    val: Option<&AsyncOrderRef>,
+  ) -> ValidationResult {
+    if val.is_some_and(|order| order.id > 100) {
+      ctx.add_violation_with_custom_id("order.exists", ViolationKind::Cel, "order not found")
+    } else {
+      Ok(IsValid::Yes)
+    }
+  }
+}
+
+#[proto_message]
+#[proto(skip_checks(all))]
+#[proto(validate = async OrderExists)]
+pub struct AsyncOrderRef {
+  #[proto(validate = |v| v.gt(0))]
+  pub id: i32,
+}
+
+#[proto_message]
+#[proto(skip_checks(all))]
+pub struct AsyncUser {
+  #[proto(validate = [ |v| v.min_len(3), async UsernameNotTaken ])]
+  pub username: String,
+  #[proto(message)]
+  pub order: Option<AsyncOrderRef>,
+  #[proto(repeated(message))]
+  pub past_orders: Vec<AsyncOrderRef>,
+}
+
+fn violation_paths(errors: ValidationErrors) -> Vec<(String, String)> {
+  errors
+    .into_violations()
+    .iter()
+    .map(|v| {
+      let path = v
+        .field
+        .as_ref()
+        .map(|path| {
+          path
+            .elements
+            .iter()
+            .map(|el| {
+              let name = el.field_name.clone().unwrap_or_default();
+
+              match &el.subscript {
+                Some(
+                  prelude::proto_types::protovalidate::field_path_element::Subscript::Index(i),
+                ) => format!("{name}[{i}]"),
+                _ => name,
+              }
+            })
+            .collect::<Vec<_>>()
+            .join(".")
+        })
+        .unwrap_or_default();
+
+      (path, v.rule_id().to_string())
+    })
+    .collect()
+}
+
+#[test]
+fn async_validators() {
+  let user = AsyncUser {
+    username: "admin".to_string(),
+    order: Some(AsyncOrderRef { id: 200 }),
+    past_orders: vec![AsyncOrderRef { id: 1 }, AsyncOrderRef { id: 101 }],
+  };
+
+  // Async validators are not executed by the sync methods
+  assert!(user.validate_all().is_ok());
+
+  assert_eq_pretty!(
+    violation_paths(block_on(user.validate_all_async()).unwrap_err()),
+    vec![
+      ("username".to_string(), "username.taken".to_string()),
+      ("order".to_string(), "order.exists".to_string()),
+      ("past_orders[1]".to_string(), "order.exists".to_string()),
+    ]
+  );
+
+  assert_eq_pretty!(
+    block_on(user.validate_async())
+      .unwrap_err()
+      .into_violations()
+      .len(),
+    1
+  );
+
+  let valid = AsyncUser {
+    username: "new_user".to_string(),
+    order: Some(AsyncOrderRef { id: 5 }),
+    past_orders: vec![],
+  };
+
+  assert!(block_on(valid.validate_async()).is_ok());
+}
+
+#[test]
+fn sync_validators_run_first() {
+  let user = AsyncUser {
+    username: "ab".to_string(),
+    order: Some(AsyncOrderRef { id: 200 }),
+    past_orders: vec![],
+  };
+
+  // The async validators are not reached if a sync validator fails fast
+  assert_eq_pretty!(
+    violation_paths(block_on(user.validate_async()).unwrap_err()),
+    vec![("username".to_string(), "string.min_len".to_string())]
+  );
+
+  assert_eq_pretty!(
+    violation_paths(block_on(user.validate_all_async()).unwrap_err()),
+    vec![
+      ("username".to_string(), "string.min_len".to_string()),
+      ("order".to_string(), "order.exists".to_string()),
+    ]
+  );
+}
+
+struct BannedUsernames(Vec<&'static str>);
+
+struct NotBanned;
+
+impl AsyncValidator<String> for NotBanned {
+  async fn validate_async(&self, ctx: &mut ValidationCtx, val: Option<&str>) -> ValidationResult {
+    let is_banned = ctx
+      .data::<BannedUsernames>()
+      .is_some_and(|banned| val.is_some_and(|username| banned.0.contains(&username)));
+
+    if is_banned {
+      ctx.add_violation_with_custom_id("username.banned", ViolationKind::Cel, "is banned")
+    } else {
+      Ok(IsValid::Yes)
+    }
+  }
+}
+
+#[proto_message]
+#[proto(skip_checks(all))]
+pub struct AsyncDataUser {
+  #[proto(validate = [ |v| v.min_len(3), async NotBanned ])]
+  pub username: String,
+  #[proto(message)]
+  pub order: Option<AsyncOrderRef>,
+}
+
+#[test]
+fn async_validators_with_data() {
+  let user = AsyncDataUser {
+    username: "spammer".to_string(),
+    order: Some(AsyncOrderRef { id: 200 }),
+  };
+
+  // Without the data, only the order is invalid
+  assert_eq_pretty!(
+    violation_paths(block_on(user.validate_all_async()).unwrap_err()),
+    vec![("order".to_string(), "order.exists".to_string())]
+  );
+
+  let data = Arc::new(ValidationData::new().with(BannedUsernames(vec!["spammer"])));
+
+  assert_eq_pretty!(
+    violation_paths(block_on(user.validate_all_async_with(data.clone())).unwrap_err()),
+    vec![
+      ("username".to_string(), "username.banned".to_string()),
+      ("order".to_string(), "order.exists".to_string()),
+    ]
+  );
+
+  assert_eq_pretty!(
+    violation_paths(block_on(user.validate_async_with(data)).unwrap_err()),
+    vec![("username".to_string(), "username.banned".to_string())]
+  );
+
+  let valid = AsyncDataUser {
+    username: "new_user".to_string(),
+    order: None,
+  };
+
+  assert!(
+    block_on(
+      valid.validate_async_with(ValidationData::new().with(BannedUsernames(vec!["spammer"])))
+    )
+    .is_ok()
+  );
+}
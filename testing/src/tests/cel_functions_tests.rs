@@ -0,0 +1,50 @@
+use super::*;
+use prelude::cel::Context;
+
+proto_package!(CEL_FUNCTIONS_PKG, name = "cel_functions", no_cel_test);
+
+define_proto_file!(
+  CEL_FUNCTIONS_FILE,
+  name = "cel_functions.proto",
+  package = CEL_FUNCTIONS_PKG
+);
+
+fn sku_functions(ctx: &mut Context) {
+  ctx.add_function("isValidSku", |sku: Arc<String>| {
+    sku.starts_with("SKU-") && sku.len() == 8
+  });
+}
+
+register_cel_functions!(sku_functions);
+
+#[proto_message]
+#[proto(skip_checks(all))]
+pub struct CelFunctionsProduct {
+  #[proto(validate = |v| v.cel(cel_program!(id = "valid_sku", msg = "must be a valid sku", expr = "isValidSku(this)")))]
+  pub sku: String,
+}
+
+#[test]
+fn custom_cel_functions() {
+  let mut product = CelFunctionsProduct {
+    sku: "SKU-1234".to_string(),
+  };
+
+  assert!(product.validate().is_ok());
+
+  product.sku = "1234".to_string();
+
+  assert_eq_pretty!(
+    product
+      .validate()
+      .unwrap_err()
+      .into_violations()
+      .first()
+      .unwrap()
+      .rule_id(),
+    "valid_sku"
+  );
+
+  // The functions are also available in the consistency checks
+  assert!(CelFunctionsProduct::check_validators_consistency().is_ok());
+}
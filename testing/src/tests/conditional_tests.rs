@@ -0,0 +1,111 @@
+use super::*;
+
+proto_package!(CONDITIONAL_PKG, name = "conditional", no_cel_test);
+
+define_proto_file!(
+  CONDITIONAL_FILE,
+  name = "conditional.proto",
+  package = CONDITIONAL_PKG
+);
+
+#[proto_enum]
+pub enum PaymentMethod {
+  Unspecified,
+  Card,
+  Bank,
+}
+
+#[proto_message]
+#[proto(skip_checks(all))]
+pub struct Payment {
+  #[proto(enum_(PaymentMethod))]
+  pub payment_method: i32,
+  #[proto(
+    validate = |v| v.required().min_len(5).cel(cel_program!(id = "iban_prefix", msg = "must start with IT", expr = "this.startsWith('IT')")),
+    when = payment_method == PaymentMethod::Bank
+  )]
+  pub iban: String,
+  #[proto(validate = |v| v.gt(0), when = payment_method != PaymentMethod::Bank && !express)]
+  pub days: i32,
+  pub express: bool,
+}
+
+fn rule_ids(msg: &impl ValidatedMessage) -> Vec<String> {
+  msg
+    .validate_all()
+    .err()
+    .map(|errs| {
+      errs
+        .into_violations()
+        .iter()
+        .map(|v| v.rule_id().to_string())
+        .collect()
+    })
+    .unwrap_or_default()
+}
+
+#[test]
+fn conditional_validation() {
+  let mut msg = Payment {
+    payment_method: PaymentMethod::Card as i32,
+    iban: String::new(),
+    days: 0,
+    express: true,
+  };
+
+  // Neither condition is met
+  assert!(msg.validate().is_ok());
+
+  msg.express = false;
+
+  assert_eq_pretty!(rule_ids(&msg), vec!["int32.gt".to_string()]);
+
+  msg.days = 2;
+  msg.payment_method = PaymentMethod::Bank as i32;
+
+  assert_eq_pretty!(rule_ids(&msg), vec!["required".to_string()]);
+
+  msg.iban = "DE123".to_string();
+
+  assert_eq_pretty!(rule_ids(&msg), vec!["iban_prefix".to_string()]);
+
+  msg.iban = "IT123".to_string();
+
+  assert!(msg.validate().is_ok());
+}
+
+#[test]
+fn conditional_rules_in_schema() {
+  let schema = Payment::proto_schema();
+
+  let rules = schema
+    .validators
+    .iter()
+    .flat_map(|v| v.cel_rules.iter())
+    .map(|rule| (rule.id.to_string(), rule.expression.to_string()))
+    .collect::<Vec<_>>();
+
+  let bank = PaymentMethod::Bank as i32;
+
+  assert_eq_pretty!(
+    rules,
+    vec![
+      (
+        "iban.required".to_string(),
+        format!("!(this.payment_method == {bank}) || has(this.iban)")
+      ),
+      (
+        "iban_prefix".to_string(),
+        format!("!(this.payment_method == {bank}) || (this.iban.startsWith('IT'))")
+      ),
+    ]
+  );
+
+  // The rules of conditional fields are not rendered on the fields themselves
+  assert!(schema.fields().all(|f| f.validators.is_empty()));
+
+  let rendered = schema.to_string();
+
+  assert!(rendered.contains("(buf.validate.message).cel"));
+  assert!(!rendered.contains("(buf.validate.field)"));
+}
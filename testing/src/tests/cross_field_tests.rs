@@ -0,0 +1,141 @@
+use super::*;
+
+proto_package!(CROSS_FIELD_PKG, name = "cross_field", no_cel_test);
+
+define_proto_file!(
+  CROSS_FIELD_FILE,
+  name = "cross_field.proto",
+  package = CROSS_FIELD_PKG
+);
+
+#[proto_message]
+#[proto(skip_checks(all))]
+#[proto(validate = |v| v
+  .field_lt(start, end)
+  .field_eq(password, confirm_password)
+  .at_least_one_of((email, phone))
+  .custom(move |msg, ctx| {
+    if msg.start < 0 {
+      start.add_violation(ctx, "start.non_negative", "must not be negative")
+    } else {
+      Ok(IsValid::Yes)
+    }
+  })
+)]
+pub struct CrossFieldMsg {
+  pub start: i64,
+  pub end: i64,
+  pub password: String,
+  pub confirm_password: String,
+  pub email: Option<String>,
+  pub phone: Option<String>,
+}
+
+fn valid_msg() -> CrossFieldMsg {
+  CrossFieldMsg {
+    start: 1,
+    end: 2,
+    password: "abc".to_string(),
+    confirm_password: "abc".to_string(),
+    email: Some("me@example.com".to_string()),
+    phone: None,
+  }
+}
+
+fn violation_path(msg: &impl ValidatedMessage) -> (String, String) {
+  let violations = msg.validate().unwrap_err().into_violations();
+
+  assert_eq!(violations.len(), 1, "Expected a single violation");
+
+  let violation = violations.first().unwrap();
+
+  let path = violation
+    .field
+    .as_ref()
+    .map(|path| {
+      path
+        .elements
+        .iter()
+        .filter_map(|el| el.field_name.clone())
+        .collect::<Vec<_>>()
+        .join(".")
+    })
+    .unwrap_or_default();
+
+  (path, violation.rule_id().to_string())
+}
+
+#[test]
+fn cross_field_rules() {
+  assert!(valid_msg().validate().is_ok());
+
+  let mut msg = valid_msg();
+  msg.end = 1;
+
+  assert_eq_pretty!(
+    violation_path(&msg),
+    ("start".to_string(), "start.lt.end".to_string())
+  );
+
+  let mut msg = valid_msg();
+  msg.confirm_password = "abcd".to_string();
+
+  assert_eq_pretty!(
+    violation_path(&msg),
+    (
+      "password".to_string(),
+      "password.eq.confirm_password".to_string()
+    )
+  );
+
+  let mut msg = valid_msg();
+  msg.email = None;
+
+  assert_eq_pretty!(
+    violation_path(&msg),
+    (String::new(), "at_least_one_of.email.phone".to_string())
+  );
+
+  let mut msg = valid_msg();
+  msg.start = -2;
+
+  assert_eq_pretty!(
+    violation_path(&msg),
+    ("start".to_string(), "start.non_negative".to_string())
+  );
+}
+
+#[test]
+fn cross_field_rules_in_schema() {
+  let schema = CrossFieldMsg::proto_schema();
+
+  let rules = schema
+    .validators
+    .iter()
+    .flat_map(|v| v.cel_rules.iter())
+    .map(|rule| (rule.id.to_string(), rule.expression.to_string()))
+    .collect::<Vec<_>>();
+
+  assert_eq_pretty!(
+    rules,
+    vec![
+      (
+        "start.lt.end".to_string(),
+        "this.start < this.end".to_string()
+      ),
+      (
+        "password.eq.confirm_password".to_string(),
+        "this.password == this.confirm_password".to_string()
+      ),
+      (
+        "at_least_one_of.email.phone".to_string(),
+        "has(this.email) || has(this.phone)".to_string()
+      ),
+    ]
+  );
+
+  let rendered = schema.to_string();
+
+  assert!(rendered.contains("(buf.validate.message).cel"));
+  assert!(rendered.contains("this.start < this.end"));
+}
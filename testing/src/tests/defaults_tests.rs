@@ -0,0 +1,128 @@
+use super::*;
+
+proto_package!(DEFAULTS_PKG, name = "defaults", no_cel_test);
+
+define_proto_file!(
+  DEFAULTS_FILE,
+  name = "defaults.proto",
+  package = DEFAULTS_PKG,
+  edition = Proto2
+);
+
+#[proto_enum]
+pub enum DefaultsEnum {
+  Unspecified,
+  First,
+  Second,
+}
+
+#[proto_message]
+#[proto(skip_checks(validators))]
+pub struct DefaultsMsg {
+  #[proto(required, default = 8080)]
+  pub port: i32,
+  #[proto(required, default = -1.5)]
+  pub ratio: f64,
+  #[proto(required, default = "localhost")]
+  pub host: String,
+  #[proto(required, enum_(DefaultsEnum), default = DefaultsEnum::Second)]
+  pub mode: i32,
+  #[proto(default = true)]
+  pub enabled: Option<bool>,
+  #[proto(default = b"a\x01")]
+  pub token: Option<Bytes>,
+  #[proto(default = 3)]
+  pub retries: Option<u32>,
+  pub name: Option<String>,
+}
+
+#[proto_message(proxied)]
+#[proto(skip_checks(validators))]
+pub struct ProxiedDefaults {
+  #[proto(required, default = 10)]
+  pub limit: i64,
+  pub label: Option<String>,
+}
+
+#[test]
+fn default_impls() {
+  let msg = DefaultsMsg::default();
+
+  assert_eq_pretty!(msg.port, 8080);
+  assert_eq_pretty!(msg.ratio, -1.5);
+  assert_eq_pretty!(msg.host, "localhost");
+  assert_eq_pretty!(msg.mode, DefaultsEnum::Second as i32);
+
+  // Optional fields remain unset, and the default is returned by their getters
+  assert_eq_pretty!(msg.enabled, None);
+  assert!(msg.enabled());
+  assert_eq_pretty!(msg.token(), b"a\x01");
+  assert_eq_pretty!(msg.retries(), 3);
+
+  let proxy = ProxiedDefaults::default();
+
+  assert_eq_pretty!(proxy.limit, 10);
+  assert_eq_pretty!(proxy.label, None);
+}
+
+#[test]
+fn default_rendering() {
+  let schema = DefaultsMsg::proto_schema();
+
+  assert!(schema.check_edition_consistency().is_ok());
+
+  let port = schema
+    .fields()
+    .find(|f| f.name == "port")
+    .unwrap();
+
+  assert_eq_pretty!(port.default_value(), Some(&OptionValue::Int(8080)));
+
+  let rendered = schema.to_string();
+
+  for expected in [
+    "default = 8080",
+    "default = -1.5",
+    "default = \"localhost\"",
+    "default = DEFAULTS_ENUM_SECOND",
+    "default = true",
+    "default = 3",
+  ] {
+    assert!(
+      rendered.contains(expected),
+      "expected `{expected}` in the rendered message"
+    );
+  }
+
+  let descriptor = DefaultsMsg::message_descriptor();
+
+  let default_of = |name: &str| {
+    descriptor
+      .get_field_by_name(name)
+      .unwrap()
+      .field_descriptor_proto()
+      .default_value
+      .clone()
+  };
+
+  assert_eq_pretty!(default_of("host").as_deref(), Some("localhost"));
+  assert_eq_pretty!(default_of("token").as_deref(), Some("a\\001"));
+  assert_eq_pretty!(default_of("mode").as_deref(), Some("DEFAULTS_ENUM_SECOND"));
+  assert_eq_pretty!(default_of("name"), None);
+}
+
+// A `default` in a proto3 file is rejected at compile time by the macros,
+// so this covers the schemas that are built or edited manually
+#[test]
+fn defaults_are_rejected_in_proto3() {
+  let mut schema = DefaultsMsg::proto_schema();
+
+  schema.edition = Edition::Proto3;
+
+  let err = schema.check_edition_consistency().unwrap_err();
+
+  assert!(
+    err.contains("Field `retries` has a `default` value, which is not supported by proto3"),
+    "{err}"
+  );
+}
@@ -0,0 +1,213 @@
+use super::*;
+use prelude::{
+  field_masks::{FieldMaskError, MaskTree, ProtoMask},
+  proto_types::FieldMask,
+};
+
+proto_package!(FIELD_MASK_PKG, name = "field_mask", no_cel_test);
+
+define_proto_file!(
+  FIELD_MASK_FILE,
+  name = "field_mask.proto",
+  package = FIELD_MASK_PKG
+);
+
+#[proto_oneof]
+#[proto(skip_checks(all))]
+pub enum MaskOneof {
+  #[proto(tag = 20)]
+  Label(String),
+  #[proto(tag = 21, message)]
+  Inner(Box<MaskNested>),
+}
+
+#[proto_message]
+#[proto(skip_checks(all))]
+pub struct MaskNested {
+  pub id: i32,
+  pub name: String,
+}
+
+#[proto_message]
+#[proto(skip_checks(all))]
+pub struct MaskMsg {
+  pub id: i32,
+  pub title: String,
+  pub tags: Vec<String>,
+  #[proto(map(string, int32))]
+  pub counts: HashMap<String, i32>,
+  #[proto(message)]
+  pub nested: Option<MaskNested>,
+  #[proto(oneof(tags(20, 21)))]
+  pub choice: Option<MaskOneof>,
+}
+
+#[proto_message]
+#[proto(skip_checks(all))]
+pub struct UpdateRequest {
+  #[proto(field_mask, validate = |v| v.paths_of::<MaskMsg>())]
+  pub update_mask: Option<FieldMask>,
+}
+
+fn mask(paths: &[&str]) -> FieldMask {
+  FieldMask {
+    paths: paths.iter().map(|p| (*p).to_string()).collect(),
+  }
+}
+
+fn populated_msg() -> MaskMsg {
+  MaskMsg {
+    id: 1,
+    title: "title".to_string(),
+    tags: vec!["a".to_string()],
+    counts: HashMap::from_iter([("a".to_string(), 1)]),
+    nested: Some(MaskNested {
+      id: 2,
+      name: "nested".to_string(),
+    }),
+    choice: Some(MaskOneof::Inner(Box::new(MaskNested {
+      id: 3,
+      name: "inner".to_string(),
+    }))),
+  }
+}
+
+#[test]
+fn mask_paths_resolution() {
+  assert!(
+    MaskMsg::check_mask(&mask(&["id", "nested.name", "label", "inner.id", "counts"])).is_ok()
+  );
+
+  assert_eq_pretty!(
+    MaskMsg::check_mask_path("nested.unknown"),
+    Err(FieldMaskError::UnknownField {
+      path: "nested.unknown".to_string(),
+      field: "unknown".to_string(),
+      message: MaskNested::MASK_MESSAGE_NAME,
+    })
+  );
+
+  assert_eq_pretty!(
+    MaskMsg::check_mask_path("tags.name"),
+    Err(FieldMaskError::NotAMessage {
+      path: "tags.name".to_string(),
+      field: "tags".to_string(),
+    })
+  );
+
+  assert_eq_pretty!(
+    MaskMsg::check_mask_path("nested..id"),
+    Err(FieldMaskError::InvalidPath("nested..id".to_string()))
+  );
+}
+
+#[test]
+fn mask_tree_normalization() {
+  let tree = MaskTree::from_paths(["nested.id", "nested", "title", "inner.name", "inner.id"]);
+
+  assert_eq_pretty!(
+    tree.to_field_mask(),
+    mask(&["inner.id", "inner.name", "nested", "title"])
+  );
+}
+
+#[test]
+fn prune_to_mask() {
+  let msg = populated_msg();
+
+  let pruned = msg
+    .masked(&mask(&["title", "nested.id", "inner.name"]))
+    .unwrap();
+
+  assert_eq_pretty!(
+    pruned,
+    MaskMsg {
+      title: "title".to_string(),
+      nested: Some(MaskNested {
+        id: 2,
+        name: String::new(),
+      }),
+      choice: Some(MaskOneof::Inner(Box::new(MaskNested {
+        id: 0,
+        name: "inner".to_string(),
+      }))),
+      ..Default::default()
+    }
+  );
+
+  // The oneof is cleared if its current field is not in the mask
+  let pruned = msg.masked(&mask(&["label"])).unwrap();
+
+  assert_eq_pretty!(pruned, MaskMsg::default());
+
+  assert!(msg.masked(&mask(&["unknown"])).is_err());
+}
+
+#[test]
+fn merge_masked() {
+  let source = populated_msg();
+
+  let mut target = MaskMsg {
+    id: 10,
+    title: "old".to_string(),
+    tags: vec!["b".to_string(), "c".to_string()],
+    counts: HashMap::from_iter([("b".to_string(), 2)]),
+    nested: Some(MaskNested {
+      id: 20,
+      name: "old".to_string(),
+    }),
+    choice: Some(MaskOneof::Label("old".to_string())),
+  };
+
+  target
+    .merge_masked(
+      &source,
+      &mask(&["tags", "counts", "nested.name", "inner.id"]),
+    )
+    .unwrap();
+
+  assert_eq_pretty!(
+    target,
+    MaskMsg {
+      id: 10,
+      title: "old".to_string(),
+      // Repeated fields and maps are replaced as a whole
+      tags: vec!["a".to_string()],
+      counts: HashMap::from_iter([("a".to_string(), 1)]),
+      nested: Some(MaskNested {
+        id: 20,
+        name: "nested".to_string(),
+      }),
+      // Merging a field of a different variant replaces the current one
+      choice: Some(MaskOneof::Inner(Box::new(MaskNested {
+        id: 3,
+        name: String::new(),
+      }))),
+    }
+  );
+
+  // Masked fields that are not set in the source are cleared
+  target
+    .merge_masked(&MaskMsg::default(), &mask(&["nested", "inner", "title"]))
+    .unwrap();
+
+  assert_eq_pretty!(target.nested, None);
+  assert_eq_pretty!(target.choice, None);
+  assert_eq_pretty!(target.title, "");
+  assert_eq_pretty!(target.id, 10);
+}
+
+#[test]
+fn message_paths_rule() {
+  let valid = UpdateRequest {
+    update_mask: Some(mask(&["title", "nested.id"])),
+  };
+
+  assert!(valid.validate().is_ok());
+
+  let invalid = UpdateRequest {
+    update_mask: Some(mask(&["title", "nested.other"])),
+  };
+
+  assert_violation_id(&invalid, "field_mask.message_paths", "unknown mask path");
+}
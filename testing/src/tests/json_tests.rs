@@ -0,0 +1,235 @@
+use super::*;
+use prelude::{
+  json::ProtoJson,
+  proto_types::{Any, Duration, FieldMask, Timestamp},
+};
+use serde_json::json;
+
+proto_package!(JSON_PKG, name = "json", no_cel_test);
+
+define_proto_file!(JSON_FILE, name = "json.proto", package = JSON_PKG);
+
+#[proto_enum]
+pub enum JsonEnum {
+  Unspecified,
+  First,
+  Second,
+}
+
+#[proto_oneof]
+#[proto(skip_checks(all))]
+pub enum JsonOneof {
+  #[proto(tag = 20)]
+  OneofString(String),
+  #[proto(tag = 21, message)]
+  OneofMsg(JsonNested),
+}
+
+#[proto_message]
+#[proto(skip_checks(all))]
+pub struct JsonNested {
+  pub inner_id: i32,
+}
+
+#[proto_message]
+#[proto(skip_checks(all))]
+pub struct JsonMsg {
+  pub int32_field: i32,
+  pub int64_field: i64,
+  pub uint64_field: u64,
+  pub double_field: f64,
+  pub bool_field: bool,
+  pub bytes_field: Bytes,
+  #[proto(json_name = "customName")]
+  pub renamed: String,
+  #[proto(enum_(JsonEnum))]
+  pub enum_field: i32,
+  #[proto(repeated(enum_(JsonEnum)))]
+  pub enum_list: Vec<i32>,
+  pub optional_int: Option<i32>,
+  pub repeated_int64: Vec<i64>,
+  #[proto(map(int64, string))]
+  pub int64_map: HashMap<i64, String>,
+  #[proto(message)]
+  pub nested: Option<JsonNested>,
+  #[proto(timestamp)]
+  pub timestamp: Option<Timestamp>,
+  #[proto(duration)]
+  pub duration: Option<Duration>,
+  #[proto(field_mask)]
+  pub field_mask: Option<FieldMask>,
+  #[proto(oneof(tags(20, 21)))]
+  pub choice: Option<JsonOneof>,
+}
+
+fn populated_msg() -> JsonMsg {
+  JsonMsg {
+    int32_field: -5,
+    int64_field: -9_007_199_254_740_993,
+    uint64_field: 18_446_744_073_709_551_615,
+    double_field: f64::INFINITY,
+    bool_field: true,
+    bytes_field: Bytes::from_static(b"hello"),
+    renamed: "renamed".to_string(),
+    enum_field: JsonEnum::First as i32,
+    enum_list: vec![JsonEnum::Second as i32, 7],
+    optional_int: Some(0),
+    repeated_int64: vec![1, -2],
+    int64_map: HashMap::from_iter([(3, "three".to_string())]),
+    nested: Some(JsonNested { inner_id: 1 }),
+    timestamp: Some(Timestamp {
+      seconds: 1_700_000_000,
+      nanos: 10_000_000,
+    }),
+    duration: Some(Duration {
+      seconds: -1,
+      nanos: -500_000_000,
+    }),
+    field_mask: Some(FieldMask {
+      paths: vec!["int32_field".to_string(), "nested.inner_id".to_string()],
+    }),
+    choice: Some(JsonOneof::OneofMsg(JsonNested { inner_id: 2 })),
+  }
+}
+
+#[test]
+fn canonical_json_output() {
+  let value = serde_json::to_value(populated_msg()).unwrap();
+
+  assert_eq_pretty!(
+    value,
+    json!({
+      "int32Field": -5,
+      "int64Field": "-9007199254740993",
+      "uint64Field": "18446744073709551615",
+      "doubleField": "Infinity",
+      "boolField": true,
+      "bytesField": "aGVsbG8=",
+      "customName": "renamed",
+      "enumField": "JSON_ENUM_FIRST",
+      "enumList": ["JSON_ENUM_SECOND", 7],
+      "optionalInt": 0,
+      "repeatedInt64": ["1", "-2"],
+      "int64Map": { "3": "three" },
+      "nested": { "innerId": 1 },
+      "timestamp": "2023-11-14T22:13:20.010Z",
+      "duration": "-1.500s",
+      "fieldMask": "int32Field,nested.innerId",
+      "oneofMsg": { "innerId": 2 }
+    })
+  );
+}
+
+#[test]
+fn default_values_are_omitted() {
+  let value = serde_json::to_value(JsonMsg::default()).unwrap();
+
+  assert_eq_pretty!(value, json!({}));
+}
+
+#[test]
+fn json_round_trip() {
+  let msg = populated_msg();
+
+  let encoded = serde_json::to_string(&msg).unwrap();
+  let decoded: JsonMsg = serde_json::from_str(&encoded).unwrap();
+
+  assert_eq_pretty!(decoded, msg);
+}
+
+#[test]
+fn lenient_parsing() {
+  let msg: JsonMsg = serde_json::from_value(json!({
+    "int32_field": "12",
+    "int64Field": 42,
+    "double_field": "NaN",
+    "renamed": "proto name",
+    "enumField": 2,
+    "bytesField": "aGVsbG8",
+    "timestamp": "1970-01-01T01:00:00+01:00",
+    "nested": null,
+    "oneof_string": "abc"
+  }))
+  .unwrap();
+
+  assert_eq_pretty!(msg.int32_field, 12);
+  assert_eq_pretty!(msg.int64_field, 42);
+  assert!(msg.double_field.is_nan());
+  assert_eq_pretty!(msg.renamed, "proto name");
+  assert_eq_pretty!(msg.enum_field, JsonEnum::Second as i32);
+  assert_eq_pretty!(msg.bytes_field, Bytes::from_static(b"hello"));
+  assert_eq_pretty!(msg.timestamp, Some(Timestamp::default()));
+  assert_eq_pretty!(msg.nested, None);
+  assert_eq_pretty!(msg.choice, Some(JsonOneof::OneofString("abc".to_string())));
+}
+
+#[test]
+fn invalid_json() {
+  assert!(serde_json::from_value::<JsonMsg>(json!({ "unknownField": 1 })).is_err());
+  assert!(serde_json::from_value::<JsonMsg>(json!({ "int32Field": 1.5 })).is_err());
+  assert!(serde_json::from_value::<JsonMsg>(json!({ "enumField": "JSON_ENUM_THIRD" })).is_err());
+  assert!(serde_json::from_value::<JsonMsg>(json!({ "duration": "1.5" })).is_err());
+}
+
+#[test]
+fn enum_json() {
+  assert_eq_pretty!(
+    serde_json::to_value(JsonEnum::Second).unwrap(),
+    json!("JSON_ENUM_SECOND")
+  );
+  assert_eq_pretty!(
+    serde_json::from_value::<JsonEnum>(json!("JSON_ENUM_FIRST")).unwrap(),
+    JsonEnum::First
+  );
+}
+
+#[test]
+fn any_json() {
+  let any = Any {
+    type_url: "type.googleapis.com/json.JsonNested".to_string(),
+    value: prost::Message::encode_to_vec(&JsonNested { inner_id: 3 }).into(),
+  };
+
+  let mut output = Vec::new();
+  any
+    .serialize_json(&mut serde_json::Serializer::new(&mut output))
+    .unwrap();
+  let value: serde_json::Value = serde_json::from_slice(&output).unwrap();
+
+  assert_eq_pretty!(
+    value,
+    json!({ "@type": "type.googleapis.com/json.JsonNested", "innerId": 3 })
+  );
+
+  assert_eq_pretty!(Any::deserialize_json(value).unwrap(), any);
+}
+
+#[test]
+fn json_name_schema() {
+  let schema = JsonMsg::proto_schema();
+
+  let renamed = schema
+    .fields()
+    .find(|f| f.name == "renamed")
+    .unwrap();
+
+  assert_eq_pretty!(renamed.options, vec![proto_json_name("customName")]);
+  assert_eq_pretty!(renamed.json_name(), "customName");
+
+  let int32_field = schema
+    .fields()
+    .find(|f| f.name == "int32_field")
+    .unwrap();
+
+  assert_eq_pretty!(int32_field.json_name(), "int32Field");
+
+  let descriptor = JsonMsg::message_descriptor();
+
+  assert_eq_pretty!(
+    descriptor
+      .get_field_by_name("renamed")
+      .unwrap()
+      .json_name(),
+    "customName"
+  );
+}
@@ -0,0 +1,129 @@
+use super::*;
+use prelude::cel::{Value, objects::Key};
+
+proto_package!(LAZY_CEL_PKG, name = "lazy_cel", no_cel_test);
+
+define_proto_file!(
+  LAZY_CEL_FILE,
+  name = "lazy_cel.proto",
+  package = LAZY_CEL_PKG
+);
+
+#[proto_message]
+#[proto(skip_checks(all))]
+pub struct LazyItem {
+  pub sku: String,
+  pub quantity: u32,
+}
+
+#[proto_oneof]
+#[proto(skip_checks(all))]
+pub enum LazyPayment {
+  #[proto(tag = 10)]
+  Card(String),
+  #[proto(tag = 11)]
+  Cash(bool),
+}
+
+#[proto_message]
+#[proto(skip_checks(all))]
+#[proto(validate = |v| v
+  .cel(cel_program!(id = "has_items", msg = "must have items", expr = "size(this.items) > 0"))
+  .cel(cel_program!(id = "card_number", msg = "invalid card", expr = "!has(this.card) || this.card.startsWith('4')"))
+  .cel(cel_program!(id = "note", msg = "invalid note", expr = "this.note == null || this.note != ''"))
+)]
+pub struct LazyOrder {
+  pub id: i32,
+  pub note: Option<String>,
+  #[proto(repeated(message))]
+  pub items: Vec<LazyItem>,
+  #[proto(map(string, int32))]
+  pub tags: HashMap<String, i32>,
+  #[proto(oneof(tags(10, 11)))]
+  pub payment: Option<LazyPayment>,
+}
+
+#[proto_message]
+#[proto(skip_checks(all))]
+pub struct LazyCart {
+  #[proto(message, validate = |v| v.cel(cel_program!(id = "whole_order", msg = "must be set", expr = "this != null && this.id > 0")))]
+  pub order: Option<LazyOrder>,
+}
+
+fn order() -> LazyOrder {
+  LazyOrder {
+    id: 1,
+    note: None,
+    items: vec![LazyItem {
+      sku: "abc".to_string(),
+      quantity: 2,
+    }],
+    tags: HashMap::from([("urgent".to_string(), 1)]),
+    payment: Some(LazyPayment::Card("4111".to_string())),
+  }
+}
+
+fn rule_ids(result: Result<(), ValidationErrors>) -> Vec<String> {
+  result
+    .err()
+    .map(|errs| {
+      errs
+        .into_violations()
+        .iter()
+        .map(|v| v.rule_id().to_string())
+        .collect()
+    })
+    .unwrap_or_default()
+}
+
+#[test]
+fn lazy_fields_match_full_conversion() {
+  let order = order();
+
+  let Value::Map(full) = Value::try_from(order.clone()).unwrap() else {
+    panic!("Expected a CEL map");
+  };
+
+  for name in ["id", "note", "items", "tags", "card"] {
+    assert_eq!(
+      order.__cel_field(name).unwrap().as_ref(),
+      full.map.get(&Key::from(name.to_string())),
+      "field `{name}`"
+    );
+  }
+
+  // Unknown fields and inactive oneof variants are not present in the map either
+  assert!(order.__cel_field("cash").unwrap().is_none());
+  assert!(order.__cel_field("unknown").unwrap().is_none());
+}
+
+#[test]
+fn lazy_cel_validation() {
+  let mut order = order();
+
+  assert!(order.validate().is_ok());
+
+  order.items.clear();
+  order.note = Some(String::new());
+  order.payment = Some(LazyPayment::Card("5111".to_string()));
+
+  assert_eq_pretty!(
+    rule_ids(order.validate_all()),
+    vec![
+      "has_items".to_string(),
+      "card_number".to_string(),
+      "note".to_string()
+    ]
+  );
+
+  // Programs that use `this` as a whole still receive the full message
+  let mut cart = LazyCart {
+    order: Some(self::order()),
+  };
+
+  assert!(cart.validate().is_ok());
+
+  cart.order.as_mut().unwrap().id = 0;
+
+  assert_eq_pretty!(rule_ids(cart.validate()), vec!["whole_order".to_string()]);
+}
@@ -0,0 +1,143 @@
+use super::*;
+use prelude::proto_types::FieldMask;
+
+proto_package!(
+  MASKED_VALIDATION_PKG,
+  name = "masked_validation",
+  no_cel_test
+);
+
+define_proto_file!(
+  MASKED_VALIDATION_FILE,
+  name = "masked_validation.proto",
+  package = MASKED_VALIDATION_PKG
+);
+
+#[proto_message]
+#[proto(skip_checks(all))]
+pub struct MaskedAddress {
+  #[proto(validate = |v| v.min_len(2))]
+  pub city: String,
+  #[proto(validate = |v| v.len(5))]
+  pub zip: String,
+}
+
+#[proto_oneof]
+#[proto(skip_checks(all))]
+pub enum MaskedContact {
+  #[proto(tag = 10, validate = |v| v.email())]
+  Email(String),
+  #[proto(tag = 11, validate = |v| v.min_len(6))]
+  Phone(String),
+}
+
+#[proto_message]
+#[proto(skip_checks(all))]
+#[proto(validate = |v| v.field_lt(min, max))]
+pub struct MaskedUpdate {
+  #[proto(validate = |v| v.required())]
+  pub name: String,
+  #[proto(message)]
+  pub address: Option<MaskedAddress>,
+  pub min: i32,
+  pub max: i32,
+  #[proto(oneof(tags(10, 11)))]
+  pub contact: Option<MaskedContact>,
+}
+
+fn mask(paths: &[&str]) -> FieldMask {
+  FieldMask {
+    paths: paths.iter().map(|p| p.to_string()).collect(),
+  }
+}
+
+fn masked_rule_ids(msg: &MaskedUpdate, paths: &[&str]) -> Vec<String> {
+  let mut ctx = ValidationCtx {
+    fail_fast: false,
+    ..Default::default()
+  };
+
+  ctx.with_mask(&mask(paths));
+
+  let _ = msg.validate_with_ctx(&mut ctx);
+
+  ctx
+    .violations
+    .into_violations()
+    .iter()
+    .map(|v| v.rule_id().to_string())
+    .collect()
+}
+
+fn invalid_msg() -> MaskedUpdate {
+  MaskedUpdate {
+    name: String::new(),
+    address: Some(MaskedAddress {
+      city: "a".to_string(),
+      zip: "123".to_string(),
+    }),
+    min: 5,
+    max: 1,
+    contact: Some(MaskedContact::Phone("123".to_string())),
+  }
+}
+
+#[test]
+fn unmasked_fields_are_skipped() {
+  let msg = invalid_msg();
+
+  assert!(msg.validate().is_err());
+
+  // Unset required fields are not flagged if they are not in the mask
+  assert!(
+    msg
+      .validate_masked(&mask(&["address.city"]))
+      .is_err()
+  );
+  assert_eq_pretty!(
+    masked_rule_ids(&msg, &["address.city"]),
+    vec!["string.min_len".to_string()]
+  );
+
+  assert_eq_pretty!(
+    masked_rule_ids(&msg, &["address"]),
+    vec!["string.min_len".to_string(), "string.len".to_string()]
+  );
+
+  assert_eq_pretty!(
+    masked_rule_ids(&msg, &["name"]),
+    vec!["required".to_string()]
+  );
+
+  assert!(msg.validate_masked(&mask(&["email"])).is_ok());
+  assert_eq_pretty!(
+    masked_rule_ids(&msg, &["phone"]),
+    vec!["string.min_len".to_string()]
+  );
+}
+
+#[test]
+fn masked_message_rules() {
+  let msg = invalid_msg();
+
+  // Typed rules only run if all of their fields are in the mask
+  assert!(masked_rule_ids(&msg, &["min"]).is_empty());
+  assert_eq_pretty!(
+    masked_rule_ids(&msg, &["min", "max"]),
+    vec!["min.lt.max".to_string()]
+  );
+}
+
+#[test]
+fn empty_mask_validates_everything() {
+  let msg = invalid_msg();
+
+  assert_eq_pretty!(
+    masked_rule_ids(&msg, &[]).len(),
+    msg
+      .validate_all()
+      .unwrap_err()
+      .into_violations()
+      .len()
+  );
+}
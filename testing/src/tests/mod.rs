@@ -6,6 +6,7 @@
   clippy::enum_variant_names
 )]
 
+mod cel_functions_tests;
 mod cel_tests;
 mod custom_validators_tests;
 mod enum_methods_tests;
@@ -13,11 +14,27 @@ mod extern_paths_tests;
 mod message_methods_tests;
 mod oneof_tags_tests;
 mod recursion_tests;
+mod tag_lockfile_tests;
 mod validation_tests;
 
+mod any_tests;
+mod async_validators_tests;
+mod conditional_tests;
+mod cross_field_tests;
 mod custom_errors_tests;
+mod defaults_tests;
+mod field_mask_tests;
+mod json_tests;
+mod lazy_cel_tests;
+mod masked_validation_tests;
+mod normalize_tests;
+mod profiles_tests;
+mod rust_schemas_tests;
+mod samples_tests;
+mod text_format_tests;
 mod schema_tests;
 mod tolerances_tests;
+mod validation_data_tests;
 
 use ::bytes::Bytes;
 use paste::paste;
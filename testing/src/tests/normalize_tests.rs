@@ -0,0 +1,145 @@
+use super::*;
+use prelude::normalize::StringNormalizer;
+
+proto_package!(NORMALIZE_PKG, name = "normalize", no_cel_test);
+
+define_proto_file!(
+  NORMALIZE_FILE,
+  name = "normalize.proto",
+  package = NORMALIZE_PKG
+);
+
+fn dedup_tags(tags: &mut Vec<String>) {
+  tags.sort_unstable();
+  tags.dedup();
+}
+
+#[proto_oneof]
+#[proto(skip_checks(all))]
+pub enum NormalizeOneof {
+  #[proto(tag = 20, normalize = |n| n.trim().uppercase())]
+  Code(String),
+  #[proto(tag = 21, message)]
+  Nested(Box<NormalizeNested>),
+}
+
+#[proto_message]
+#[proto(skip_checks(all))]
+pub struct NormalizeNested {
+  #[proto(normalize = |n| n.collapse_whitespace(), validate = |v| v.max_len(5))]
+  pub label: String,
+}
+
+#[proto_message]
+#[proto(skip_checks(all))]
+pub struct NormalizeMsg {
+  #[proto(normalize = |n| n.trim().lowercase(), validate = |v| v.email())]
+  pub email: String,
+  #[proto(normalize = |n| n.nfc())]
+  pub name: Option<String>,
+  #[proto(normalize = |n| n.trim(), transform = dedup_tags)]
+  pub tags: Vec<String>,
+  #[proto(transform = |v: &mut i32| *v = (*v).max(0))]
+  pub count: i32,
+  #[proto(message)]
+  pub nested: Option<NormalizeNested>,
+  #[proto(repeated(message))]
+  pub items: Vec<NormalizeNested>,
+  #[proto(oneof(tags(20, 21)))]
+  pub choice: Option<NormalizeOneof>,
+}
+
+#[test]
+fn field_normalization() {
+  let mut msg = NormalizeMsg {
+    email: "  John@Example.COM ".to_string(),
+    // "e" followed by a combining acute accent
+    name: Some("Jose\u{0301}".to_string()),
+    tags: vec![" b".to_string(), "a ".to_string(), "b".to_string()],
+    count: -3,
+    ..Default::default()
+  };
+
+  msg.normalize();
+
+  assert_eq_pretty!(msg.email, "john@example.com");
+  assert_eq_pretty!(msg.name.as_deref(), Some("Jos\u{00e9}"));
+  assert_eq_pretty!(msg.tags, vec!["a".to_string(), "b".to_string()]);
+  assert_eq_pretty!(msg.count, 0);
+}
+
+#[test]
+fn nested_normalization() {
+  let mut msg = NormalizeMsg {
+    nested: Some(NormalizeNested {
+      label: " a   b ".to_string(),
+    }),
+    items: vec![NormalizeNested {
+      label: "c \t d".to_string(),
+    }],
+    choice: Some(NormalizeOneof::Code(" xy ".to_string())),
+    ..Default::default()
+  };
+
+  msg.normalize();
+
+  assert_eq_pretty!(msg.nested.unwrap().label, "a b");
+  assert_eq_pretty!(msg.items[0].label, "c d");
+  assert_eq_pretty!(msg.choice, Some(NormalizeOneof::Code("XY".to_string())));
+
+  let mut boxed = NormalizeMsg {
+    choice: Some(NormalizeOneof::Nested(Box::new(NormalizeNested {
+      label: "e   f".to_string(),
+    }))),
+    ..Default::default()
+  };
+
+  boxed.normalize();
+
+  assert_eq_pretty!(
+    boxed.choice,
+    Some(NormalizeOneof::Nested(Box::new(NormalizeNested {
+      label: "e f".to_string(),
+    })))
+  );
+}
+
+#[test]
+fn normalized_and_validated() {
+  let msg = NormalizeMsg {
+    email: " Someone@Example.com ".to_string(),
+    nested: Some(NormalizeNested {
+      // Would fail the max_len rule without normalization
+      label: "a    b".to_string(),
+    }),
+    ..Default::default()
+  };
+
+  assert!(msg.validate().is_err());
+
+  let normalized = msg.normalized_and_validated().unwrap();
+
+  assert_eq_pretty!(normalized.email, "someone@example.com");
+
+  let invalid = NormalizeMsg {
+    email: " not an email ".to_string(),
+    ..Default::default()
+  };
+
+  assert!(invalid.normalized_and_validated().is_err());
+}
+
+#[test]
+fn normalizer_builder() {
+  let normalizer = StringNormalizer::builder()
+    .collapse_whitespace()
+    .lowercase()
+    .custom(|v| v.push('!'))
+    .build();
+
+  let mut value = "  Hello   WORLD ".to_string();
+
+  normalizer.normalize(&mut value);
+
+  assert_eq_pretty!(value, "hello world!");
+}
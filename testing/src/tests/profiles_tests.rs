@@ -0,0 +1,110 @@
+use super::*;
+
+proto_package!(PROFILES_PKG, name = "profiles", no_cel_test);
+
+define_proto_file!(
+  PROFILES_FILE,
+  name = "profiles.proto",
+  package = PROFILES_PKG
+);
+
+pub struct Import;
+
+impl ValidationProfile for Import {
+  fn profile_name(&self) -> &'static str {
+    "import"
+  }
+}
+
+#[proto_message]
+#[proto(skip_checks(all))]
+#[proto(validate = |v| v.field_lt(created_at, updated_at), groups = [DEFAULT_PROFILE, "update"])]
+pub struct ProfileUser {
+  #[proto(validate = |v| v.const_(0), groups = ["create"])]
+  pub id: i64,
+  #[proto(validate = |v| v.gt(0), groups = ["update", Import])]
+  pub version: i64,
+  #[proto(validate = |v| v.min_len(3))]
+  pub name: String,
+  pub created_at: i64,
+  pub updated_at: i64,
+}
+
+fn rule_ids(result: Result<(), ValidationErrors>) -> Vec<String> {
+  result
+    .err()
+    .map(|errs| {
+      errs
+        .into_violations()
+        .iter()
+        .map(|v| v.rule_id().to_string())
+        .collect()
+    })
+    .unwrap_or_default()
+}
+
+#[test]
+fn validation_profiles() {
+  let user = ProfileUser {
+    id: 5,
+    version: 0,
+    name: "ab".to_string(),
+    created_at: 2,
+    updated_at: 1,
+  };
+
+  // Ungrouped validators always run
+  assert_eq_pretty!(
+    rule_ids(user.validate_all()),
+    vec![
+      "string.min_len".to_string(),
+      "created_at.lt.updated_at".to_string()
+    ]
+  );
+
+  assert_eq_pretty!(
+    rule_ids(user.validate_all_with_profile("create")),
+    vec!["int64.const".to_string(), "string.min_len".to_string()]
+  );
+
+  assert_eq_pretty!(
+    rule_ids(user.validate_all_with_profile("update")),
+    vec![
+      "int64.gt".to_string(),
+      "string.min_len".to_string(),
+      "created_at.lt.updated_at".to_string()
+    ]
+  );
+
+  assert_eq_pretty!(
+    rule_ids(user.validate_all_with_profile(Import)),
+    vec!["int64.gt".to_string(), "string.min_len".to_string()]
+  );
+
+  assert!(
+    user
+      .validate_with_profile("create")
+      .is_err_and(|errs| errs.into_violations().len() == 1)
+  );
+}
+
+#[test]
+fn only_default_profile_is_rendered() {
+  let schema = ProfileUser::proto_schema();
+
+  let field_validators = |name: &str| {
+    schema
+      .fields()
+      .find(|f| f.name == name)
+      .unwrap()
+      .validators
+      .len()
+  };
+
+  assert_eq_pretty!(field_validators("id"), 0);
+  assert_eq_pretty!(field_validators("version"), 0);
+  assert_eq_pretty!(field_validators("name"), 1);
+
+  // The message rules are part of the default profile
+  assert_eq_pretty!(schema.validators.len(), 1);
+}
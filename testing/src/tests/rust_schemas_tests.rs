@@ -0,0 +1,139 @@
+use std::{fs, path::PathBuf};
+
+use builder::generate_rust_schemas;
+use prelude::prost_reflect::DescriptorPool;
+
+use super::*;
+
+// The output of `generate_rust_schemas` for the `rust_schemas` package, which is defined
+// by the generated code itself
+#[rustfmt::skip]
+mod generated;
+
+use generated::RUST_SCHEMAS_PKG;
+
+fn descriptor_set() -> Vec<u8> {
+  RUST_SCHEMAS_PKG
+    .get_package()
+    .encode_file_descriptor_set_with_imports()
+    .unwrap()
+}
+
+// The items are collected from the registry in no particular order,
+// so the code is compared one item at a time
+fn code_items(code: &str) -> Vec<String> {
+  let mut items: Vec<String> = code
+    // Separates the closing brace of the module from the last item
+    .replace("\n}\n", "\n\n}\n")
+    .split("\n\n")
+    .map(|item| item.trim().to_string())
+    .filter(|item| !item.is_empty())
+    .collect();
+
+  items.sort_unstable();
+  items
+}
+
+#[test]
+fn generated_rust_schemas_round_trip() {
+  let code = generate_rust_schemas(&descriptor_set(), "rust_schemas").unwrap();
+
+  assert_eq_pretty!(
+    code_items(&code),
+    code_items(include_str!("rust_schemas_tests/generated.rs"))
+  );
+}
+
+#[test]
+fn generated_rust_schemas_render_the_original_files() {
+  let pool = DescriptorPool::decode(descriptor_set().as_slice()).unwrap();
+
+  let original_output = PathBuf::from(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/proto_test_rust_schemas"
+  ));
+  let generated_output = PathBuf::from(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/proto_test_rust_schemas2"
+  ));
+
+  Package::from_descriptor_pool(&pool, "rust_schemas")
+    .unwrap()
+    .render_files(&original_output)
+    .unwrap();
+  RUST_SCHEMAS_PKG
+    .get_package()
+    .render_files(&generated_output)
+    .unwrap();
+
+  let original = fs::read_to_string(original_output.join("rust_schemas.proto")).unwrap();
+  let generated = fs::read_to_string(generated_output.join("rust_schemas.proto")).unwrap();
+
+  assert_eq_pretty!(original, generated);
+
+  for expected in [
+    "map<string, int32> tags = 5",
+    "Order.Line lines = 6",
+    "oneof payment {",
+    "enum Priority {",
+    "rpc Get (Order) returns (Order.Line)",
+  ] {
+    assert!(
+      generated.contains(expected),
+      "expected `{expected}` in the rendered file"
+    );
+  }
+}
+
+proto_package!(
+  UNSUPPORTED_RULES_PKG,
+  name = "unsupported_rules",
+  no_cel_test
+);
+
+define_proto_file!(
+  UNSUPPORTED_RULES_FILE,
+  name = "unsupported_rules.proto",
+  package = UNSUPPORTED_RULES_PKG,
+  imports = ["buf/validate/validate.proto"]
+);
+
+// `example` has no equivalent in the validator builders
+fn example_rule() -> Vec<ProtoOption> {
+  vec![ProtoOption {
+    name: "(buf.validate.field)".into(),
+    value: OptionValue::new_message(vec![ProtoOption {
+      name: "string".into(),
+      value: OptionValue::new_message(vec![ProtoOption {
+        name: "example".into(),
+        value: OptionValue::new_list(vec![OptionValue::String("abc".into())]),
+      }]),
+    }]),
+  }]
+}
+
+#[proto_message]
+#[proto(skip_checks(all))]
+pub struct UnsupportedRuleMsg {
+  #[proto(options = example_rule())]
+  pub name: String,
+}
+
+#[test]
+fn unconvertible_rules_are_rejected() {
+  let descriptor_set = UNSUPPORTED_RULES_PKG
+    .get_package()
+    .encode_file_descriptor_set_with_imports()
+    .unwrap();
+
+  let error = generate_rust_schemas(&descriptor_set, "unsupported_rules")
+    .unwrap_err()
+    .to_string();
+
+  assert!(
+    error.contains(
+      "- `unsupported_rules.UnsupportedRuleMsg.name`: (buf.validate.field).string.example"
+    ),
+    "{error}"
+  );
+}
@@ -0,0 +1,82 @@
+// Generated from the descriptors of the `rust_schemas` package
+use prelude::*;
+use std::collections::HashMap;
+
+proto_package!(RUST_SCHEMAS_PKG, name = "rust_schemas");
+
+pub use rust_schemas::*;
+
+pub mod rust_schemas {
+  use super::*;
+
+  define_proto_file!(
+    RUST_SCHEMAS_FILE,
+    name = "rust_schemas.proto",
+    package = RUST_SCHEMAS_PKG
+  );
+
+  #[proto_message]
+  #[proto(validate = |v| v.cel(cel_program!(id = "note_differs_from_name", msg = "the note must be different from the name", expr = "this.note != this.name")))]
+  pub struct Order {
+    #[proto(tag = 1, validate = |v| v.min_len(1).max_len(10))]
+    pub name: String,
+    #[proto(tag = 2)]
+    pub note: String,
+    #[proto(tag = 3, validate = |v| v.gt(0))]
+    pub quantity: i32,
+    #[proto(tag = 4)]
+    pub discount: Option<i32>,
+    #[proto(tag = 5, map(string, int32))]
+    pub tags: HashMap<String, i32>,
+    #[proto(tag = 6, message, validate = |v| v.min_items(1))]
+    pub lines: Vec<Line>,
+    #[proto(tag = 7, enum_(Status))]
+    pub status: i32,
+    #[proto(tag = 8, enum_(Priority))]
+    pub priority: i32,
+    #[proto(oneof(tags(9, 10)))]
+    pub payment: Option<OrderPayment>,
+  }
+
+  #[proto_oneof]
+  pub enum OrderPayment {
+    #[proto(tag = 9, validate = |v| v.min_len(4))]
+    Card(String),
+    #[proto(tag = 10)]
+    Cash(bool),
+  }
+
+  #[proto_message]
+  #[proto(parent_message = Order)]
+  pub struct Line {
+    #[proto(tag = 1)]
+    pub sku: String,
+    #[proto(tag = 2)]
+    pub quantity: u32,
+  }
+
+  #[proto_enum]
+  #[proto(parent_message = Order)]
+  pub enum Priority {
+    #[proto(tag = 0)]
+    Unspecified,
+    #[proto(tag = 1)]
+    High,
+  }
+
+  #[proto_enum]
+  pub enum Status {
+    #[proto(tag = 0)]
+    Unspecified,
+    #[proto(tag = 1)]
+    Active,
+  }
+
+  #[proto_service]
+  pub enum OrderService {
+    Get {
+      request: Order,
+      response: Line,
+    },
+  }
+}
@@ -0,0 +1,186 @@
+use super::*;
+use prelude::proto_types::protovalidate::{StringViolation, ViolationKind};
+use prelude::samples::{InvalidSample, ProtoSample, SampleCtx, SampleValidator};
+
+proto_package!(SAMPLES_PKG, name = "samples", no_cel_test);
+
+define_proto_file!(SAMPLES_FILE, name = "samples.proto", package = SAMPLES_PKG);
+
+#[proto_enum]
+pub enum SampleEnum {
+  Unspecified,
+  First,
+  Second,
+}
+
+#[proto_oneof]
+#[proto(skip_checks(all))]
+pub enum SampleOneof {
+  #[proto(tag = 20, validate = |v| v.prefix("id_").max_len(10))]
+  Id(String),
+  #[proto(tag = 21, validate = |v| v.gte(100))]
+  Code(i64),
+}
+
+#[proto_message]
+#[proto(skip_checks(all))]
+pub struct SampleNested {
+  #[proto(validate = |v| v.required().contains("x"))]
+  pub label: String,
+}
+
+#[proto_message]
+#[proto(skip_checks(all))]
+pub struct SampleMsg {
+  #[proto(validate = |v| v.min_len(3).max_len(8).suffix("z"))]
+  pub name: String,
+  #[proto(validate = |v| v.gt(0).lte(10).not_in([1, 2]))]
+  pub count: i32,
+  #[proto(validate = |v| v.gte(1.5).lt(2.0))]
+  pub ratio: f64,
+  #[proto(enum_(SampleEnum), validate = |v| v.defined_only().not_in([0]))]
+  pub kind: i32,
+  #[proto(repeated(string), validate = |v| v.min_items(1).max_items(3).unique().items(|it| it.min_len(2)))]
+  pub tags: Vec<String>,
+  #[proto(map(string, int32), validate = |v| v.max_pairs(2).keys(|k| k.len(3)).values(|vals| vals.lt(0)))]
+  pub scores: HashMap<String, i32>,
+  #[proto(message)]
+  pub nested: Option<SampleNested>,
+  #[proto(oneof(required, tags(20, 21)))]
+  pub choice: Option<SampleOneof>,
+}
+
+#[track_caller]
+fn assert_triggers<T: ValidatedMessage>(sample: &InvalidSample<T>) {
+  let errors = sample.value.validate_all().unwrap_err();
+
+  assert!(
+    errors
+      .iter()
+      .any(|v| v.meta.kind == sample.violation),
+    "Expected {} to be violated at `{}`",
+    sample.rule_id(),
+    sample.path
+  );
+}
+
+#[test]
+fn valid_message_samples() {
+  for seed in 0..20 {
+    let msg = SampleMsg::valid_sample(&mut SampleCtx::new(seed)).unwrap();
+
+    assert!(msg.validate_all().is_ok(), "Invalid sample: {msg:#?}");
+    assert!(msg.nested.is_some());
+    assert!(msg.choice.is_some());
+  }
+}
+
+#[test]
+fn samples_are_deterministic() {
+  let first = SampleMsg::valid_sample(&mut SampleCtx::new(7));
+  let second = SampleMsg::valid_sample(&mut SampleCtx::new(7));
+
+  assert_eq_pretty!(first, second);
+}
+
+#[test]
+fn invalid_message_samples() {
+  let samples = SampleMsg::invalid_samples(&mut SampleCtx::new(3));
+
+  for sample in &samples {
+    assert_triggers(sample);
+  }
+
+  let mut rules: Vec<(&str, &str)> = samples
+    .iter()
+    .map(|s| (s.path.as_str(), s.rule_id()))
+    .collect();
+  rules.sort_unstable();
+
+  assert!(
+    samples
+      .iter()
+      .any(|s| s.path == "choice" && s.violation == ViolationKind::RequiredOneof)
+  );
+  assert!(
+    samples.iter().any(|s| s.path == "nested.label"
+      && s.violation == ViolationKind::String(StringViolation::Required))
+  );
+
+  let expected = [
+    ("code", "int64.gte"),
+    ("count", "int32.gt"),
+    ("count", "int32.lte"),
+    ("count", "int32.not_in"),
+    ("id", "string.max_len"),
+    ("id", "string.prefix"),
+    ("kind", "enum.defined_only"),
+    ("kind", "enum.not_in"),
+    ("name", "string.max_len"),
+    ("name", "string.min_len"),
+    ("name", "string.suffix"),
+    ("nested.label", "string.contains"),
+    ("ratio", "double.gte"),
+    ("ratio", "double.lt"),
+    ("scores", "map.max_pairs"),
+    ("scores", "int32.lt"),
+    ("scores", "string.len"),
+    ("tags", "repeated.max_items"),
+    ("tags", "repeated.min_items"),
+    ("tags", "repeated.unique"),
+    ("tags", "string.min_len"),
+  ];
+
+  for rule in expected {
+    assert!(
+      rules.contains(&rule),
+      "Missing sample for {rule:?} in {rules:#?}"
+    );
+  }
+}
+
+#[test]
+fn invalid_sample_for_rule() {
+  let mut ctx = SampleCtx::new(1);
+
+  let sample =
+    SampleMsg::invalid_sample_for(&mut ctx, ViolationKind::String(StringViolation::Suffix))
+      .unwrap();
+
+  assert_eq_pretty!(sample.path, "name");
+  assert!(!sample.value.name.ends_with('z'));
+  assert_triggers(&sample);
+}
+
+#[test]
+fn validator_samples() {
+  let validator = StringValidator::builder()
+    .min_len(4)
+    .prefix("ab")
+    .build();
+
+  let mut ctx = SampleCtx::default();
+
+  let valid = validator.valid_sample(&mut ctx).unwrap();
+
+  assert!(valid.starts_with("ab") && valid.chars().count() >= 4);
+
+  let invalid = validator.invalid_samples(&mut ctx);
+
+  assert_eq_pretty!(invalid.len(), 2);
+  assert_eq_pretty!(invalid[0].rule_id(), "string.min_len");
+  assert_eq_pretty!(invalid[0].value.as_deref(), Some("aaa"));
+  assert_eq_pretty!(invalid[1].rule_id(), "string.prefix");
+  assert!(
+    invalid[1]
+      .value
+      .as_ref()
+      .unwrap()
+      .starts_with('_')
+  );
+
+  // Rules that cannot be satisfied result in no samples
+  let impossible = IntValidator::<i32>::builder().gt(5).lt(6).build();
+
+  assert_eq_pretty!(impossible.valid_sample(&mut ctx), None);
+}
@@ -2,9 +2,15 @@ use rendering_tests::TestEnum;
 use std::{collections::HashMap, sync::Arc};
 
 use super::*;
+mod comments_tests;
+mod compatibility_tests;
 mod conversions_tests;
 mod deprecated_tests;
+mod descriptor_pool_tests;
+mod descriptor_tests;
+mod editions_tests;
 mod inference_tests;
 mod nested_items_tests;
 mod rendering_tests;
+mod runtime_descriptors_tests;
 mod type_resolution_tests;
@@ -0,0 +1,178 @@
+use super::*;
+use std::{fs, path::PathBuf};
+
+/// A message with comments.
+///
+/// They can span multiple lines.
+#[proto_message]
+#[proto(skip_checks(all))]
+pub struct CommentedMsg {
+  /// A plain field
+  pub id: i32,
+
+  pub no_comments: i32,
+
+  /// Doc comments on the field override
+  /// those of the oneof
+  #[proto(oneof(tags(1, 2)))]
+  pub overridden_oneof: Option<CommentedOneof>,
+
+  #[proto(oneof(tags(3, 4)))]
+  pub inherited_oneof: Option<OtherCommentedOneof>,
+}
+
+/// A oneof with comments
+#[proto_oneof]
+#[proto(skip_checks(all))]
+pub enum CommentedOneof {
+  /// Variant A
+  #[proto(tag = 1)]
+  A(i32),
+  #[proto(tag = 2)]
+  B(i32),
+}
+
+/// Another oneof with comments
+#[proto_oneof]
+#[proto(skip_checks(all))]
+pub enum OtherCommentedOneof {
+  #[proto(tag = 3)]
+  A(i32),
+  #[proto(tag = 4)]
+  B(i32),
+}
+
+/// An enum with comments
+#[proto_enum]
+pub enum CommentedEnum {
+  /// The default value
+  Unspecified,
+  A,
+}
+
+/// A service with comments
+#[proto_service]
+pub enum CommentedService {
+  /// Returns a message
+  GetMsg {
+    request: CommentedMsg,
+    response: CommentedMsg,
+  },
+  Other {
+    request: CommentedMsg,
+    response: CommentedMsg,
+  },
+}
+
+#[test]
+fn message_comments() {
+  let schema = CommentedMsg::proto_schema();
+
+  assert_eq_pretty!(
+    schema.comments.as_deref(),
+    Some("A message with comments.\n\nThey can span multiple lines.")
+  );
+
+  let fields: Vec<&Field> = schema
+    .entries
+    .iter()
+    .filter_map(|e| e.as_field())
+    .collect();
+
+  assert_eq_pretty!(fields[0].comments.as_deref(), Some("A plain field"));
+  assert_eq_pretty!(fields[1].comments, None);
+
+  let oneofs: Vec<&Oneof> = schema
+    .entries
+    .iter()
+    .filter_map(|e| e.as_oneof())
+    .collect();
+
+  assert_eq_pretty!(
+    oneofs[0].comments.as_deref(),
+    Some("Doc comments on the field override\nthose of the oneof")
+  );
+  assert_eq_pretty!(
+    oneofs[1].comments.as_deref(),
+    Some("Another oneof with comments")
+  );
+}
+
+#[test]
+fn oneof_comments() {
+  let schema = CommentedOneof::proto_schema();
+
+  assert_eq_pretty!(schema.comments.as_deref(), Some("A oneof with comments"));
+  assert_eq_pretty!(schema.fields[0].comments.as_deref(), Some("Variant A"));
+  assert_eq_pretty!(schema.fields[1].comments, None);
+}
+
+#[test]
+fn enum_comments() {
+  let schema = CommentedEnum::proto_schema();
+
+  assert_eq_pretty!(schema.comments.as_deref(), Some("An enum with comments"));
+  assert_eq_pretty!(
+    schema.variants[0].comments.as_deref(),
+    Some("The default value")
+  );
+  assert_eq_pretty!(schema.variants[1].comments, None);
+}
+
+#[test]
+fn service_comments() {
+  let schema = CommentedService::as_proto_service();
+
+  assert_eq_pretty!(schema.comments.as_deref(), Some("A service with comments"));
+  assert_eq_pretty!(
+    schema.handlers[0].comments.as_deref(),
+    Some("Returns a message")
+  );
+  assert_eq_pretty!(schema.handlers[1].comments, None);
+}
+
+#[test]
+fn rendered_comments() {
+  let file = file_schema!(
+    name = "comments.proto",
+    messages = [CommentedMsg],
+    enums = [CommentedEnum],
+    services = [CommentedService]
+  );
+
+  let pkg = package_schema!("comments", files = [file]);
+  let output = PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/proto_test_comments"));
+
+  pkg.render_files(&output).unwrap();
+
+  let content = fs::read_to_string(output.join("comments.proto")).unwrap();
+
+  // Only the order of the lines matters here, not the indentation
+  let content = content
+    .lines()
+    .map(str::trim)
+    .filter(|l| !l.is_empty())
+    .collect::<Vec<_>>()
+    .join("\n");
+
+  for expected in [
+    "// A message with comments.\n//\n// They can span multiple lines.\nmessage CommentedMsg {",
+    "// A plain field\nint32 id = ",
+    "// Doc comments on the field override\n// those of the oneof\noneof overridden_oneof {",
+    "// Variant A\nint32 a = 1;",
+    "// Another oneof with comments\noneof inherited_oneof {",
+    "// An enum with comments\nenum CommentedEnum {",
+    "// The default value\nCOMMENTED_ENUM_UNSPECIFIED = 0;",
+    "// A service with comments\nservice CommentedService {",
+    "// Returns a message\nrpc GetMsg (CommentedMsg) returns (CommentedMsg);",
+    // Items without comments are rendered without them
+    "COMMENTED_ENUM_UNSPECIFIED = 0;\nCOMMENTED_ENUM_A = 1;",
+    "int32 a = 1;\nint32 b = 2;",
+    "rpc GetMsg (CommentedMsg) returns (CommentedMsg);\nrpc Other (CommentedMsg) returns (CommentedMsg);",
+  ] {
+    assert!(
+      content.contains(expected),
+      "expected `{expected}` in the rendered file:\n{content}"
+    );
+  }
+}
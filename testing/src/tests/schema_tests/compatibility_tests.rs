@@ -0,0 +1,372 @@
+use super::*;
+
+#[proto_message]
+#[proto(skip_checks(all))]
+pub struct CompatMsg {
+  pub id: i32,
+  pub name: String,
+  pub tags: Vec<String>,
+}
+
+#[proto_enum]
+pub enum CompatEnum {
+  Unspecified,
+  A,
+  B,
+}
+
+#[proto_service]
+pub enum CompatService {
+  Get {
+    request: CompatMsg,
+    response: CompatMsg,
+  },
+}
+
+fn package(message: Message, enum_: Enum, service: Service) -> Package {
+  let mut file = ProtoFile::new("compat.proto", "compat");
+
+  file
+    .with_messages([message])
+    .with_enums([enum_])
+    .with_services([service]);
+
+  Package::new("compat").with_files([file])
+}
+
+fn previous_package() -> Package {
+  package(
+    CompatMsg::proto_schema(),
+    CompatEnum::proto_schema(),
+    CompatService::as_proto_service(),
+  )
+}
+
+fn field_mut<'a>(message: &'a mut Message, name: &str) -> &'a mut Field {
+  message
+    .entries
+    .iter_mut()
+    .find_map(|e| match e {
+      MessageEntry::Field(f) if f.name == name => Some(f),
+      _ => None,
+    })
+    .unwrap()
+}
+
+fn remove_field(message: &mut Message, name: &str) {
+  message
+    .entries
+    .retain(|e| e.as_field().is_none_or(|f| f.name != name));
+}
+
+#[test]
+fn unchanged_package() {
+  let current = previous_package();
+
+  assert!(
+    current
+      .check_wire_compatibility(&previous_package())
+      .is_ok()
+  );
+}
+
+#[test]
+fn changed_tags_and_types() {
+  let mut message = CompatMsg::proto_schema();
+
+  field_mut(&mut message, "id").tag = 10;
+  field_mut(&mut message, "name").type_ = FieldType::Normal(ProtoType::Scalar(ProtoScalar::Bytes));
+  field_mut(&mut message, "tags").type_ = FieldType::Normal(ProtoType::Scalar(ProtoScalar::String));
+
+  let current = package(
+    message,
+    CompatEnum::proto_schema(),
+    CompatService::as_proto_service(),
+  );
+
+  let changes = current.breaking_changes(&previous_package());
+
+  assert_eq_pretty!(
+    changes,
+    [
+      BreakingChange::FieldTagChanged {
+        message: "CompatMsg".into(),
+        field: "id".into(),
+        old_tag: 1,
+        new_tag: 10
+      },
+      BreakingChange::FieldTypeChanged {
+        message: "CompatMsg".into(),
+        field: "name".into(),
+        old_type: "string".into(),
+        new_type: "bytes".into()
+      },
+      BreakingChange::FieldTypeChanged {
+        message: "CompatMsg".into(),
+        field: "tags".into(),
+        old_type: "repeated string".into(),
+        new_type: "string".into()
+      },
+    ]
+  );
+}
+
+#[test]
+fn removed_fields_must_be_reserved() {
+  let mut message = CompatMsg::proto_schema();
+
+  remove_field(&mut message, "name");
+
+  let current = package(
+    message.clone(),
+    CompatEnum::proto_schema(),
+    CompatService::as_proto_service(),
+  );
+
+  assert_eq_pretty!(
+    current.breaking_changes(&previous_package()),
+    [BreakingChange::FieldNotReserved {
+      message: "CompatMsg".into(),
+      field: "name".into(),
+      tag: 2
+    }]
+  );
+
+  message.reserved_numbers.push(2..3);
+  message.reserved_names.push("name".into());
+
+  let current = package(
+    message,
+    CompatEnum::proto_schema(),
+    CompatService::as_proto_service(),
+  );
+
+  assert!(
+    current
+      .check_wire_compatibility(&previous_package())
+      .is_ok()
+  );
+}
+
+#[test]
+fn reused_tags() {
+  let mut message = CompatMsg::proto_schema();
+
+  remove_field(&mut message, "name");
+  message.reserved_names.push("name".into());
+  message.reserved_numbers.push(2..3);
+
+  let mut new_field = CompatMsg::proto_schema()
+    .fields()
+    .nth(1)
+    .unwrap()
+    .clone();
+  new_field.name = "title".into();
+  message
+    .entries
+    .push(MessageEntry::Field(new_field));
+
+  let current = package(
+    message,
+    CompatEnum::proto_schema(),
+    CompatService::as_proto_service(),
+  );
+
+  assert_eq_pretty!(
+    current.breaking_changes(&previous_package()),
+    [BreakingChange::FieldTagReused {
+      message: "CompatMsg".into(),
+      tag: 2,
+      old_field: "name".into(),
+      new_field: "title".into()
+    }]
+  );
+}
+
+#[test]
+fn enum_changes() {
+  let mut enum_ = CompatEnum::proto_schema();
+
+  let removed = enum_.variants.pop().unwrap();
+  enum_.variants[1].tag = 5;
+
+  let current = package(
+    CompatMsg::proto_schema(),
+    enum_,
+    CompatService::as_proto_service(),
+  );
+
+  assert_eq_pretty!(
+    current.breaking_changes(&previous_package()),
+    [
+      BreakingChange::VariantTagChanged {
+        enum_: "CompatEnum".into(),
+        variant: CompatEnum::proto_schema().variants[1]
+          .name
+          .clone(),
+        old_tag: 1,
+        new_tag: 5
+      },
+      BreakingChange::VariantNotReserved {
+        enum_: "CompatEnum".into(),
+        variant: removed.name,
+        tag: 2
+      },
+    ]
+  );
+}
+
+#[test]
+fn package_and_service_changes() {
+  let mut service = CompatService::as_proto_service();
+
+  service.handlers[0].server_streaming = true;
+
+  let mut current = package(
+    CompatMsg::proto_schema(),
+    CompatEnum::proto_schema(),
+    service,
+  );
+  current.name = "compat.v2".into();
+
+  let changes = current.breaking_changes(&previous_package());
+  let msg_path = format!("{}.CompatMsg", CompatMsg::proto_schema().package);
+
+  assert_eq_pretty!(
+    changes,
+    [
+      BreakingChange::PackageRenamed {
+        old: "compat".into(),
+        new: "compat.v2".into()
+      },
+      BreakingChange::MethodSignatureChanged {
+        service: "CompatService".into(),
+        method: "Get".into(),
+        old: format!("({msg_path}) returns ({msg_path})"),
+        new: format!("({msg_path}) returns (stream {msg_path})")
+      },
+    ]
+  );
+
+  current.files[0].services.clear();
+
+  assert!(
+    current
+      .breaking_changes(&previous_package())
+      .contains(&BreakingChange::ServiceRemoved {
+        service: "CompatService".into()
+      })
+  );
+
+  let error = current
+    .check_wire_compatibility(&previous_package())
+    .unwrap_err();
+
+  assert!(error.contains("The service `CompatService` has been removed"));
+}
+
+#[test]
+fn swapped_tags() {
+  let mut message = CompatMsg::proto_schema();
+
+  field_mut(&mut message, "id").tag = 2;
+  field_mut(&mut message, "name").tag = 1;
+
+  let current = package(
+    message,
+    CompatEnum::proto_schema(),
+    CompatService::as_proto_service(),
+  );
+
+  assert_eq_pretty!(
+    current.breaking_changes(&previous_package()),
+    [
+      BreakingChange::FieldTagChanged {
+        message: "CompatMsg".into(),
+        field: "id".into(),
+        old_tag: 1,
+        new_tag: 2
+      },
+      BreakingChange::FieldTagChanged {
+        message: "CompatMsg".into(),
+        field: "name".into(),
+        old_tag: 2,
+        new_tag: 1
+      },
+    ]
+  );
+}
+
+#[test]
+fn reserving_the_name_or_the_number_is_enough() {
+  let mut message = CompatMsg::proto_schema();
+
+  remove_field(&mut message, "name");
+  remove_field(&mut message, "tags");
+  message.reserved_names.push("name".into());
+  message.reserved_numbers.push(3..4);
+
+  let mut enum_ = CompatEnum::proto_schema();
+  let removed = enum_.variants.pop().unwrap();
+  enum_.reserved_names.push(removed.name);
+
+  let current = package(message, enum_, CompatService::as_proto_service());
+
+  assert!(
+    current
+      .breaking_changes(&previous_package())
+      .is_empty()
+  );
+}
+
+#[test]
+fn removed_messages_and_enums() {
+  let mut nested = CompatMsg::proto_schema();
+  nested.name = "CompatMsg.Nested".into();
+
+  let mut nested_enum = CompatEnum::proto_schema();
+  nested_enum.name = "CompatMsg.NestedEnum".into();
+
+  let mut message = CompatMsg::proto_schema();
+  message.messages.push(nested);
+  message.enums.push(nested_enum);
+
+  let previous = package(
+    message,
+    CompatEnum::proto_schema(),
+    CompatService::as_proto_service(),
+  );
+
+  // Removing the nested items only
+  let current = previous_package();
+
+  assert_eq_pretty!(
+    current.breaking_changes(&previous),
+    [
+      BreakingChange::MessageRemoved {
+        message: "CompatMsg.Nested".into()
+      },
+      BreakingChange::EnumRemoved {
+        enum_: "CompatMsg.NestedEnum".into()
+      },
+    ]
+  );
+
+  // The nested items are not reported again when their parent is removed
+  let mut file = ProtoFile::new("compat.proto", "compat");
+  file.with_services([CompatService::as_proto_service()]);
+
+  let current = Package::new("compat").with_files([file]);
+
+  assert_eq_pretty!(
+    current.breaking_changes(&previous),
+    [
+      BreakingChange::MessageRemoved {
+        message: "CompatMsg".into()
+      },
+      BreakingChange::EnumRemoved {
+        enum_: "CompatEnum".into()
+      },
+    ]
+  );
+}
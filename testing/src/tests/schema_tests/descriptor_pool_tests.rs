@@ -0,0 +1,329 @@
+use super::*;
+use prelude::prost_reflect::DescriptorPool;
+use prelude::proto_types::{
+  DescriptorProto, Edition as DescriptorEdition, FieldDescriptorProto, FileDescriptorProto,
+  descriptor_proto::ExtensionRange,
+  field_descriptor_proto::{Label, Type},
+};
+use prost::Message as _;
+
+proto_package!(POOL_PKG, name = "pool", no_cel_test);
+
+define_proto_file!(POOL_FILE, name = "pool.proto", package = POOL_PKG);
+
+#[proto_message]
+#[proto(skip_checks(all))]
+pub struct PoolMsg {
+  pub name: String,
+  pub labels: HashMap<String, i32>,
+  pub maybe: Option<i32>,
+  pub ids: Vec<u64>,
+  #[proto(message)]
+  pub nested: Option<PoolNested>,
+  #[proto(enum_(PoolEnum))]
+  pub status: i32,
+  #[proto(oneof(tags(10, 11)))]
+  pub choice: Option<PoolOneof>,
+}
+
+#[proto_message]
+#[proto(parent_message = PoolMsg)]
+#[proto(skip_checks(all))]
+pub struct PoolNested {
+  pub id: i32,
+}
+
+#[proto_message]
+#[proto(skip_checks(all))]
+#[proto(validate = |v| v.cel(cel_program!(id = "different_names", msg = "the alias must be different from the name", expr = "this.alias != this.name")))]
+pub struct PoolValidated {
+  #[proto(validate = |v| v.min_len(1).max_len(10))]
+  pub name: String,
+  #[proto(validate = |v| v.gt(0))]
+  pub count: i32,
+  pub alias: String,
+}
+
+#[proto_oneof]
+pub enum PoolOneof {
+  #[proto(tag = 10)]
+  A(i32),
+  #[proto(tag = 11)]
+  B(String),
+}
+
+#[proto_enum]
+pub enum PoolEnum {
+  Unspecified,
+  A,
+  B,
+}
+
+#[proto_service]
+pub enum PoolService {
+  Get {
+    request: PoolMsg,
+    response: PoolNested,
+  },
+}
+
+fn round_trip() -> Package {
+  // The set must include `buf/validate/validate.proto` for the rules to be decoded
+  let bytes = POOL_PKG
+    .get_package()
+    .encode_file_descriptor_set_with_imports()
+    .unwrap();
+  let pool = DescriptorPool::decode(bytes.as_slice()).unwrap();
+
+  Package::from_descriptor_pool(&pool, "pool").unwrap()
+}
+
+#[test]
+fn descriptor_pool_round_trip() {
+  let pkg = round_trip();
+
+  assert_eq_pretty!(pkg.name, "pool");
+  assert_eq_pretty!(pkg.files.len(), 1);
+
+  let file = &pkg.files[0];
+
+  assert_eq_pretty!(file.name, "pool.proto");
+  assert_eq_pretty!(file.edition, Edition::Proto3);
+
+  let msg = file
+    .messages
+    .iter()
+    .find(|m| m.name == "PoolMsg")
+    .unwrap();
+
+  // The synthetic map entry is not included
+  let nested_names: Vec<&str> = msg.messages.iter().map(|m| &*m.name).collect();
+  assert_eq_pretty!(nested_names, ["PoolMsg.PoolNested"]);
+
+  let field = |name: &str| msg.fields().find(|f| f.name == name).unwrap();
+
+  assert_eq_pretty!(
+    field("labels").type_,
+    FieldType::Map {
+      keys: ProtoMapKey::String,
+      values: ProtoType::Scalar(ProtoScalar::Int32)
+    }
+  );
+  assert_eq_pretty!(
+    field("maybe").type_,
+    FieldType::Optional(ProtoType::Scalar(ProtoScalar::Int32))
+  );
+  assert_eq_pretty!(
+    field("ids").type_,
+    FieldType::Repeated(ProtoType::Scalar(ProtoScalar::Uint64))
+  );
+  assert!(matches!(
+    &field("nested").type_,
+    FieldType::Normal(ProtoType::Message(path)) if path.name == "PoolMsg.PoolNested" && path.package == "pool"
+  ));
+  assert!(matches!(
+    &field("status").type_,
+    FieldType::Normal(ProtoType::Enum(path)) if path.name == "PoolEnum"
+  ));
+
+  let oneof = msg
+    .entries
+    .iter()
+    .find_map(|e| e.as_oneof())
+    .unwrap();
+
+  assert_eq_pretty!(oneof.name, "choice");
+  assert_eq_pretty!(
+    oneof
+      .fields
+      .iter()
+      .map(|f| f.tag)
+      .collect::<Vec<_>>(),
+    [10, 11]
+  );
+
+  let enum_ = &file.enums[0];
+
+  assert_eq_pretty!(enum_.variants.len(), 3);
+  assert_eq_pretty!(enum_.variants[2].tag, 2);
+
+  let handler = &file.services[0].handlers[0];
+
+  assert_eq_pretty!(handler.name, "Get");
+  assert_eq_pretty!(handler.request.name, "PoolMsg");
+  assert_eq_pretty!(handler.response.name, "PoolMsg.PoolNested");
+}
+
+fn option_at<'a>(value: &'a OptionValue, path: &[&str]) -> Option<&'a OptionValue> {
+  path
+    .iter()
+    .try_fold(value, |value, name| match value {
+      OptionValue::Message(message) => message.get(name),
+      _ => None,
+    })
+}
+
+#[test]
+fn descriptor_pool_validators() {
+  let pkg = round_trip();
+
+  let msg = pkg.files[0]
+    .messages
+    .iter()
+    .find(|m| m.name == "PoolValidated")
+    .unwrap();
+
+  let [message_validator] = msg.validators.as_slice() else {
+    panic!(
+      "Expected one message validator, found {:#?}",
+      msg.validators
+    );
+  };
+
+  assert_eq_pretty!(message_validator.schema.name, "(buf.validate.message)");
+  assert_eq_pretty!(
+    message_validator.cel_rules,
+    [CelRule {
+      id: "different_names".into(),
+      message: "the alias must be different from the name".into(),
+      expression: "this.alias != this.name".into(),
+    }]
+  );
+  assert_eq_pretty!(message_validator.imports, ["buf/validate/validate.proto"]);
+
+  let field_validator = |name: &str| {
+    let field = msg.fields().find(|f| f.name == name).unwrap();
+
+    let [validator] = field.validators.as_slice() else {
+      panic!(
+        "Expected one validator for `{name}`, found {:#?}",
+        field.validators
+      );
+    };
+
+    assert_eq_pretty!(validator.schema.name, "(buf.validate.field)");
+
+    validator.schema.value.clone()
+  };
+
+  let name_rules = field_validator("name");
+
+  assert_eq_pretty!(
+    option_at(&name_rules, &["string", "min_len"]),
+    Some(&OptionValue::Uint(1))
+  );
+  assert_eq_pretty!(
+    option_at(&name_rules, &["string", "max_len"]),
+    Some(&OptionValue::Uint(10))
+  );
+
+  assert_eq_pretty!(
+    option_at(&field_validator("count"), &["int32", "gt"]),
+    Some(&OptionValue::Int(0))
+  );
+
+  assert!(
+    msg
+      .fields()
+      .find(|f| f.name == "alias")
+      .unwrap()
+      .validators
+      .is_empty()
+  );
+
+  // The validators match the ones of the original schema
+  let original = PoolValidated::proto_schema();
+
+  assert_eq_pretty!(
+    msg
+      .validators
+      .iter()
+      .flat_map(|v| &v.cel_rules)
+      .collect::<Vec<_>>(),
+    original
+      .validators
+      .iter()
+      .flat_map(|v| &v.cel_rules)
+      .collect::<Vec<_>>()
+  );
+}
+
+#[test]
+fn descriptor_pool_is_wire_compatible() {
+  let original = POOL_PKG.get_package();
+
+  assert!(
+    round_trip()
+      .check_wire_compatibility(&original)
+      .is_ok()
+  );
+}
+
+fn pool_with_file(file: &FileDescriptorProto) -> DescriptorPool {
+  let mut pool = DescriptorPool::new();
+
+  pool
+    .decode_file_descriptor_proto(file.encode_to_vec().as_slice())
+    .unwrap();
+
+  pool
+}
+
+#[test]
+fn message_extensions_are_rejected() {
+  let file = FileDescriptorProto {
+    name: Some("message_extension.proto".into()),
+    package: Some("message_extension".into()),
+    syntax: Some("proto2".into()),
+    message_type: vec![DescriptorProto {
+      name: Some("Extendable".into()),
+      extension_range: vec![ExtensionRange {
+        start: Some(100),
+        end: Some(200),
+        ..Default::default()
+      }],
+      ..Default::default()
+    }],
+    extension: vec![FieldDescriptorProto {
+      name: Some("extra".into()),
+      number: Some(100),
+      label: Some(Label::Optional as i32),
+      r#type: Some(Type::String as i32),
+      extendee: Some(".message_extension.Extendable".into()),
+      ..Default::default()
+    }],
+    ..Default::default()
+  };
+
+  let err = Package::from_descriptor_pool(&pool_with_file(&file), "message_extension").unwrap_err();
+
+  assert_eq_pretty!(
+    err,
+    DescriptorConversionError::UnsupportedExtendee {
+      file: "message_extension.proto".into(),
+      extension: "message_extension.extra".into(),
+      extendee: "message_extension.Extendable".into(),
+    }
+  );
+}
+
+#[test]
+fn unsupported_editions_are_rejected() {
+  let file = FileDescriptorProto {
+    name: Some("edition_2024.proto".into()),
+    package: Some("edition_2024".into()),
+    syntax: Some("editions".into()),
+    edition: Some(DescriptorEdition::Edition2024 as i32),
+    ..Default::default()
+  };
+
+  let err = Package::from_descriptor_pool(&pool_with_file(&file), "edition_2024").unwrap_err();
+
+  assert_eq_pretty!(
+    err,
+    DescriptorConversionError::UnsupportedSyntax {
+      file: "edition_2024.proto".into(),
+      syntax: "edition `EDITION_2024`".into(),
+    }
+  );
+}
@@ -0,0 +1,334 @@
+use super::*;
+use prelude::proto_types::{
+  FileDescriptorSet, field_descriptor_proto::Label, protovalidate::field_rules,
+};
+use prost::Message as _;
+
+proto_package!(DESCRIPTORS_PKG, name = "descriptors", no_cel_test);
+
+define_proto_file!(
+  DESCRIPTORS_FILE,
+  name = "descriptors.proto",
+  package = DESCRIPTORS_PKG
+);
+
+#[proto_message]
+pub struct DescMsg {
+  #[proto(validate = |v| v.min_len(3))]
+  pub name: String,
+  pub labels: HashMap<String, i32>,
+  pub maybe: Option<i32>,
+  #[proto(message)]
+  pub nested: Option<DescNested>,
+  #[proto(oneof(tags(10, 11)))]
+  pub choice: Option<DescOneof>,
+}
+
+#[proto_message]
+#[proto(parent_message = DescMsg)]
+pub struct DescNested {
+  pub id: i32,
+}
+
+#[proto_oneof]
+pub enum DescOneof {
+  #[proto(tag = 10)]
+  A(i32),
+  #[proto(tag = 11)]
+  B(String),
+}
+
+#[proto_enum]
+pub enum DescEnum {
+  Unspecified,
+  A,
+}
+
+#[proto_service]
+pub enum DescService {
+  Unary {
+    request: DescMsg,
+    response: DescMsg,
+  },
+  Streaming {
+    request: Stream<DescMsg>,
+    response: DescMsg,
+  },
+}
+
+// Minimal mirrors of the descriptor types, used to check
+// that the `buf.validate` rules are encoded as an extension
+#[derive(Clone, PartialEq, prost::Message)]
+struct SetMirror {
+  #[prost(message, repeated, tag = "1")]
+  file: Vec<FileMirror>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct FileMirror {
+  #[prost(message, repeated, tag = "4")]
+  message_type: Vec<MessageMirror>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct MessageMirror {
+  #[prost(string, tag = "1")]
+  name: String,
+  #[prost(message, repeated, tag = "2")]
+  field: Vec<FieldMirror>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct FieldMirror {
+  #[prost(string, tag = "1")]
+  name: String,
+  #[prost(message, optional, tag = "8")]
+  options: Option<FieldOptionsMirror>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct FieldOptionsMirror {
+  #[prost(message, optional, tag = "1159")]
+  rules: Option<prelude::proto_types::protovalidate::FieldRules>,
+}
+
+#[test]
+fn file_descriptor() {
+  let pkg = DESCRIPTORS_PKG.get_package();
+  let set = pkg.to_file_descriptor_set();
+
+  assert_eq_pretty!(set.file.len(), 1);
+
+  let file = &set.file[0];
+
+  assert_eq_pretty!(file.name(), "descriptors.proto");
+  assert_eq_pretty!(file.package(), "descriptors");
+  assert_eq_pretty!(file.syntax(), "proto3");
+  assert_eq_pretty!(file.edition, None);
+  assert_eq_pretty!(file.service.len(), 1);
+  assert_eq_pretty!(file.enum_type.len(), 1);
+  assert_eq_pretty!(file.enum_type[0].value.len(), 2);
+
+  let msg = file
+    .message_type
+    .iter()
+    .find(|m| m.name() == "DescMsg")
+    .unwrap();
+
+  let nested_names: Vec<&str> = msg.nested_type.iter().map(|m| m.name()).collect();
+
+  assert_eq_pretty!(nested_names, ["DescNested", "LabelsEntry"]);
+  assert!(
+    msg.nested_type[1]
+      .options
+      .as_ref()
+      .unwrap()
+      .map_entry()
+  );
+
+  let labels = msg
+    .field
+    .iter()
+    .find(|f| f.name() == "labels")
+    .unwrap();
+
+  assert_eq_pretty!(labels.label(), Label::Repeated);
+  assert_eq_pretty!(labels.type_name(), ".descriptors.DescMsg.LabelsEntry");
+
+  let nested = msg
+    .field
+    .iter()
+    .find(|f| f.name() == "nested")
+    .unwrap();
+
+  assert_eq_pretty!(nested.type_name(), ".descriptors.DescMsg.DescNested");
+
+  let oneof_names: Vec<&str> = msg.oneof_decl.iter().map(|o| o.name()).collect();
+
+  assert_eq_pretty!(oneof_names, ["choice", "_maybe"]);
+
+  let maybe = msg
+    .field
+    .iter()
+    .find(|f| f.name() == "maybe")
+    .unwrap();
+
+  assert!(maybe.proto3_optional());
+  assert_eq_pretty!(maybe.oneof_index, Some(1));
+
+  let a = msg
+    .field
+    .iter()
+    .find(|f| f.name() == "a")
+    .unwrap();
+
+  assert_eq_pretty!(a.number(), 10);
+  assert_eq_pretty!(a.oneof_index, Some(0));
+
+  let name = msg
+    .field
+    .iter()
+    .find(|f| f.name() == "name")
+    .unwrap();
+  let uninterpreted = &name
+    .options
+    .as_ref()
+    .unwrap()
+    .uninterpreted_option;
+
+  assert_eq_pretty!(uninterpreted.len(), 1);
+  assert_eq_pretty!(uninterpreted[0].name[0].name_part, "buf.validate.field");
+  assert!(uninterpreted[0].name[0].is_extension);
+
+  // The typed descriptor is the same one that is encoded, minus the options encoded as extensions
+  let encoded = FileDescriptorSet::decode(
+    pkg
+      .encode_file_descriptor_set()
+      .unwrap()
+      .as_slice(),
+  )
+  .unwrap();
+
+  assert_eq_pretty!(encoded.file[0].message_type.len(), file.message_type.len());
+  assert_eq_pretty!(encoded.file[0].edition, file.edition);
+
+  let streaming = file.service[0]
+    .method
+    .iter()
+    .find(|m| m.name() == "Streaming")
+    .unwrap();
+
+  assert!(streaming.client_streaming());
+  assert!(!streaming.server_streaming());
+  assert_eq_pretty!(streaming.input_type(), ".descriptors.DescMsg");
+}
+
+#[test]
+fn encoded_file_descriptor_set() {
+  let pkg = DESCRIPTORS_PKG.get_package();
+  let bytes = pkg.encode_file_descriptor_set().unwrap();
+
+  let set = FileDescriptorSet::decode(bytes.as_slice()).unwrap();
+  let msg = set.file[0]
+    .message_type
+    .iter()
+    .find(|m| m.name() == "DescMsg")
+    .unwrap();
+  let name = msg
+    .field
+    .iter()
+    .find(|f| f.name() == "name")
+    .unwrap();
+
+  assert!(
+    name
+      .options
+      .as_ref()
+      .is_none_or(|o| o.uninterpreted_option.is_empty())
+  );
+
+  let mirror = SetMirror::decode(bytes.as_slice()).unwrap();
+  let name = mirror.file[0]
+    .message_type
+    .iter()
+    .find(|m| m.name == "DescMsg")
+    .unwrap()
+    .field
+    .iter()
+    .find(|f| f.name == "name")
+    .unwrap();
+
+  let rules = name
+    .options
+    .as_ref()
+    .and_then(|o| o.rules.as_ref())
+    .unwrap();
+
+  assert!(
+    matches!(&rules.r#type, Some(field_rules::Type::String(s)) if s.min_len == Some(3)),
+    "{rules:?}"
+  );
+}
+
+mod invalid_options {
+  use super::*;
+
+  proto_package!(INVALID_OPTIONS_PKG, name = "invalid_options", no_cel_test);
+
+  define_proto_file!(
+    INVALID_OPTIONS_FILE,
+    name = "invalid_options.proto",
+    package = INVALID_OPTIONS_PKG,
+    imports = ["buf/validate/validate.proto"]
+  );
+
+  fn string_rule(name: &str, value: OptionValue) -> Vec<ProtoOption> {
+    vec![ProtoOption {
+      name: "(buf.validate.field)".into(),
+      value: OptionValue::new_message(vec![ProtoOption {
+        name: "string".into(),
+        value: OptionValue::new_message(vec![ProtoOption {
+          name: name.to_string().into(),
+          value,
+        }]),
+      }]),
+    }]
+  }
+
+  #[proto_message]
+  #[proto(skip_checks(all))]
+  pub struct MistypedRule {
+    #[proto(options = string_rule("min_len", OptionValue::String("3".into())))]
+    pub name: String,
+  }
+
+  #[proto_message]
+  #[proto(skip_checks(all))]
+  pub struct UnknownRule {
+    #[proto(options = string_rule("not_a_rule", OptionValue::Uint(3)))]
+    pub name: String,
+  }
+
+  #[test]
+  fn invalid_known_options_are_reported() {
+    let file = file_schema!(
+      name = "mistyped.proto",
+      package = "invalid_options",
+      messages = [MistypedRule]
+    );
+
+    let error = file.encode_file_descriptor_proto().unwrap_err();
+
+    assert_eq_pretty!(error.item, "invalid_options.MistypedRule.name");
+    assert_eq_pretty!(error.option, "(buf.validate.field).string.min_len");
+    assert_eq_pretty!(
+      error.kind,
+      OptionEncodingErrorKind::TypeMismatch {
+        expected: "uint64",
+        found: "string"
+      }
+    );
+
+    let file = file_schema!(
+      name = "unknown.proto",
+      package = "invalid_options",
+      messages = [UnknownRule]
+    );
+
+    let error = file.encode_file_descriptor_proto().unwrap_err();
+
+    assert_eq_pretty!(error.option, "(buf.validate.field).string");
+    assert_eq_pretty!(
+      error.kind,
+      OptionEncodingErrorKind::UnknownField("not_a_rule".to_string())
+    );
+
+    // The typed descriptors keep them as uninterpreted options
+    assert!(
+      file.to_file_descriptor_proto().message_type[0].field[0]
+        .options
+        .as_ref()
+        .is_some_and(|o| !o.uninterpreted_option.is_empty())
+    );
+  }
+}
@@ -0,0 +1,265 @@
+use super::*;
+
+mod e2023 {
+  use super::*;
+
+  proto_package!(EDITIONS_PKG, name = "editions", no_cel_test);
+
+  define_proto_file!(
+    EDITIONS_FILE,
+    name = "editions.proto",
+    package = EDITIONS_PKG,
+    edition = E2023,
+    features = FeatureSet::new().with_enum_type(EnumType::Closed)
+  );
+
+  #[proto_message]
+  pub struct EditionsMsg {
+    pub implicit: i32,
+    pub explicit: Option<i32>,
+    #[proto(required)]
+    pub required: i32,
+    pub list: Vec<i32>,
+    #[proto(message(delimited))]
+    pub delimited: Option<EditionsNested>,
+    #[proto(features = FeatureSet::new().with_utf8_validation(Utf8Validation::None))]
+    pub unchecked: String,
+    #[proto(oneof(tags(20, 21)))]
+    pub choice: Option<EditionsOneof>,
+  }
+
+  #[proto_message]
+  pub struct EditionsNested {
+    pub id: i32,
+  }
+
+  #[proto_oneof]
+  pub enum EditionsOneof {
+    #[proto(tag = 20)]
+    A(i32),
+    #[proto(tag = 21)]
+    B(String),
+  }
+
+  #[proto_message]
+  #[proto(skip_checks(edition))]
+  pub struct MismatchedPresence {
+    #[proto(features = FeatureSet::new().with_field_presence(FieldPresence::Explicit))]
+    pub implicit: i32,
+  }
+
+  #[test]
+  fn file_reference_edition() {
+    assert_eq_pretty!(EDITIONS_FILE.edition, Edition::E2023);
+    assert_eq_pretty!(EditionsMsg::proto_schema().edition, Edition::E2023);
+  }
+
+  #[test]
+  fn editions_presence() {
+    let schema = EditionsMsg::proto_schema();
+
+    let presence: Vec<Option<FieldPresence>> = schema
+      .entries
+      .iter()
+      .filter_map(|e| e.as_field())
+      .map(|f| {
+        f.resolved_features(Edition::E2023, FieldScope::Message)
+          .field_presence
+      })
+      .collect();
+
+    assert_eq_pretty!(
+      presence,
+      [
+        Some(FieldPresence::Implicit),
+        None,
+        Some(FieldPresence::LegacyRequired),
+        None,
+        None,
+        Some(FieldPresence::Implicit),
+      ]
+    );
+
+    let required = schema.entries[2].as_field().unwrap();
+
+    assert_eq_pretty!(
+      required.type_,
+      FieldType::Required(ProtoType::Scalar(ProtoScalar::Int32))
+    );
+
+    let delimited = schema.entries[4].as_field().unwrap();
+
+    assert_eq_pretty!(
+      delimited.features.message_encoding,
+      Some(MessageEncoding::Delimited)
+    );
+
+    assert!(schema.check_edition_consistency().is_ok());
+  }
+
+  #[test]
+  fn editions_rendering() {
+    let rendered = EditionsMsg::proto_schema().to_string();
+
+    assert!(!rendered.contains("optional"));
+    assert!(!rendered.contains("required int32"));
+
+    for expected in [
+      "int32 explicit = ",
+      "repeated int32 list = ",
+      "features.field_presence = LEGACY_REQUIRED",
+      "features.message_encoding = DELIMITED",
+      "features.utf8_validation = NONE",
+      "int32 a = 20;",
+    ] {
+      assert!(
+        rendered.contains(expected),
+        "expected `{expected}` in the rendered message"
+      );
+    }
+
+    let pkg = EDITIONS_PKG.get_package();
+    let file = pkg.get_file("editions.proto").unwrap();
+
+    assert_eq_pretty!(file.edition, Edition::E2023);
+    assert_eq_pretty!(file.features.enum_type, Some(EnumType::Closed));
+
+    let rendered_file = file.to_string();
+
+    assert!(rendered_file.contains("edition = \"2023\";"));
+    assert!(rendered_file.contains("option features.enum_type = CLOSED;"));
+
+    assert!(pkg.check_edition_consistency().is_ok());
+  }
+
+  #[test]
+  fn editions_descriptor() {
+    use prelude::proto_types::{Edition as DescriptorEdition, FileDescriptorSet};
+    use prost::Message as _;
+
+    let set = FileDescriptorSet::decode(
+      EDITIONS_PKG
+        .get_package()
+        .encode_file_descriptor_set()
+        .unwrap()
+        .as_slice(),
+    )
+    .unwrap();
+
+    let file = &set.file[0];
+
+    assert_eq_pretty!(file.syntax(), "editions");
+    assert_eq_pretty!(file.edition(), DescriptorEdition::Edition2023);
+  }
+
+  #[test]
+  fn mismatched_presence() {
+    let err = MismatchedPresence::proto_schema()
+      .check_edition_consistency()
+      .unwrap_err();
+
+    assert!(err.contains("features.field_presence = EXPLICIT"), "{err}");
+  }
+}
+
+mod proto2 {
+  use super::*;
+
+  proto_package!(PROTO2_PKG, name = "proto2_pkg", no_cel_test);
+
+  define_proto_file!(
+    PROTO2_FILE,
+    name = "proto2.proto",
+    package = PROTO2_PKG,
+    edition = Proto2
+  );
+
+  #[proto_message]
+  pub struct Proto2Msg {
+    pub optional: Option<String>,
+    #[proto(required)]
+    pub required: i32,
+    pub list: Vec<i32>,
+    #[proto(oneof(tags(10, 11)))]
+    pub choice: Option<Proto2Oneof>,
+  }
+
+  #[proto_oneof]
+  pub enum Proto2Oneof {
+    #[proto(tag = 10)]
+    A(i32),
+    #[proto(tag = 11)]
+    B(String),
+  }
+
+  #[proto_message]
+  #[proto(skip_checks(edition))]
+  pub struct ImplicitInProto2 {
+    pub implicit: i32,
+  }
+
+  #[test]
+  fn proto2_rendering() {
+    let schema = Proto2Msg::proto_schema();
+
+    assert_eq_pretty!(schema.edition, Edition::Proto2);
+    assert!(schema.check_edition_consistency().is_ok());
+
+    let rendered = schema.to_string();
+
+    for expected in [
+      "optional string optional = ",
+      "required int32 required = ",
+      "repeated int32 list = ",
+      "int32 a = 10;",
+    ] {
+      assert!(
+        rendered.contains(expected),
+        "expected `{expected}` in the rendered message"
+      );
+    }
+  }
+
+  #[test]
+  fn implicit_presence_in_proto2() {
+    let err = ImplicitInProto2::proto_schema()
+      .check_edition_consistency()
+      .unwrap_err();
+
+    assert!(err.contains("implicit presence"), "{err}");
+  }
+}
+
+#[proto_message]
+#[proto(skip_checks(edition))]
+pub struct RequiredInProto3 {
+  #[proto(required)]
+  pub required: i32,
+}
+
+#[proto_message]
+#[proto(skip_checks(edition))]
+pub struct FeaturesInProto3 {
+  #[proto(features = FeatureSet::new().with_utf8_validation(Utf8Validation::None))]
+  pub name: String,
+}
+
+#[test]
+fn required_in_proto3() {
+  let schema = RequiredInProto3::proto_schema();
+
+  assert_eq_pretty!(schema.edition, Edition::Proto3);
+
+  let err = schema.check_edition_consistency().unwrap_err();
+
+  assert!(err.contains("not supported by proto3"), "{err}");
+}
+
+#[test]
+fn features_in_proto3() {
+  let err = FeaturesInProto3::proto_schema()
+    .check_edition_consistency()
+    .unwrap_err();
+
+  assert!(err.contains("only supported by editions files"), "{err}");
+}
@@ -147,6 +147,20 @@ fn rendering_test() {
   let second_content = fs::read_to_string(output2.join("rendering.proto")).unwrap();
 
   assert_eq_pretty!(first_content, second_content);
+
+  for streaming_rpc in [
+    "rpc ClientStream (stream TestMessage) returns (TestMessage.Nested1)",
+    "rpc ServerStream (TestMessage) returns (stream TestMessage.Nested1)",
+    "rpc BidiStream (stream TestMessage) returns (stream TestMessage.Nested1)",
+  ] {
+    assert!(
+      first_content.contains(streaming_rpc),
+      "expected `{streaming_rpc}` in the rendered file"
+    );
+  }
+
+  assert!(first_content.contains("// Service used for rendering tests\nservice TestService {"));
+  assert!(first_content.contains("  // Manually assigned tag\n  int32 manual_tag_field = 9;"));
 }
 
 #[test]
@@ -231,6 +245,7 @@ fn extension_schema_output() {
   );
 }
 
+/// Service used for rendering tests
 #[proto_service]
 #[proto(options = test_options())]
 pub enum TestService {
@@ -243,6 +258,18 @@ pub enum TestService {
     request: TestMessage,
     response: Nested1,
   },
+  ClientStream {
+    request: Stream<TestMessage>,
+    response: Nested1,
+  },
+  ServerStream {
+    request: TestMessage,
+    response: Stream<Nested1>,
+  },
+  BidiStream {
+    request: Stream<TestMessage>,
+    response: Stream<Nested1>,
+  },
 }
 
 #[test]
@@ -259,6 +286,28 @@ fn service_schema_output() {
   assert_eq_pretty!(service1.request.package, RENDERING.package);
   assert_eq_pretty!(service1.response.name, "TestMessage.Nested1");
   assert_eq_pretty!(service1.options, test_options());
+  assert!(!service1.client_streaming && !service1.server_streaming);
+
+  let streaming_flags: Vec<(&str, bool, bool)> = schema
+    .handlers
+    .iter()
+    .skip(2)
+    .map(|h| (h.name.as_ref(), h.client_streaming, h.server_streaming))
+    .collect();
+
+  assert_eq_pretty!(
+    streaming_flags,
+    [
+      ("ClientStream", true, false),
+      ("ServerStream", false, true),
+      ("BidiStream", true, true),
+    ]
+  );
+
+  let bidi = schema.handlers.last().unwrap();
+
+  assert_eq_pretty!(bidi.request.name, "TestMessage");
+  assert_eq_pretty!(bidi.response.name, "TestMessage.Nested1");
 }
 
 #[proto_enum]
@@ -366,6 +415,7 @@ fn msg_rule() -> CelProgram {
 #[proto(options = test_options())]
 #[proto(validate = |v| v.cel(msg_rule()).cel(msg_rule()))]
 pub struct TestMessage {
+  /// Manually assigned tag
   #[proto(tag = 9)]
   pub manual_tag_field: i32,
 
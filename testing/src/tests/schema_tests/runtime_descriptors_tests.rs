@@ -0,0 +1,226 @@
+use super::*;
+use prelude::prost_reflect::{DescriptorPool, ReflectMessage, Value};
+use proto_types::Timestamp;
+
+proto_package!(RUNTIME_PKG, name = "runtime", no_cel_test);
+
+define_proto_file!(RUNTIME_FILE, name = "runtime.proto", package = RUNTIME_PKG);
+
+#[proto_message]
+#[proto(skip_checks(all))]
+pub struct RuntimeMsg {
+  #[proto(validate = |v| v.min_len(1))]
+  pub name: String,
+  pub ids: Vec<u64>,
+  #[proto(timestamp)]
+  pub created_at: Option<Timestamp>,
+  #[proto(message)]
+  pub nested: Option<RuntimeNested>,
+}
+
+#[proto_message]
+#[proto(parent_message = RuntimeMsg)]
+#[proto(skip_checks(all))]
+pub struct RuntimeNested {
+  pub id: i32,
+}
+
+#[proto_service]
+pub enum RuntimeService {
+  Get {
+    request: RuntimeMsg,
+    response: RuntimeMsg,
+  },
+}
+
+#[test]
+fn message_descriptors() {
+  let descriptor = RuntimeMsg::default().descriptor();
+
+  assert_eq_pretty!(descriptor.full_name(), "runtime.RuntimeMsg");
+  assert_eq_pretty!(
+    descriptor
+      .fields()
+      .map(|f| f.name().to_string())
+      .collect::<Vec<_>>(),
+    ["name", "ids", "created_at", "nested"]
+  );
+
+  let nested = RuntimeNested::message_descriptor();
+
+  assert_eq_pretty!(nested.full_name(), "runtime.RuntimeMsg.RuntimeNested");
+  assert_eq_pretty!(
+    nested.parent_message().unwrap().full_name(),
+    "runtime.RuntimeMsg"
+  );
+
+  let created_at = descriptor
+    .get_field_by_name("created_at")
+    .unwrap();
+
+  assert_eq_pretty!(
+    created_at
+      .kind()
+      .as_message()
+      .unwrap()
+      .full_name(),
+    "google.protobuf.Timestamp"
+  );
+
+  // The pool is cached
+  assert!(RUNTIME_PKG.descriptor_pool() == *descriptor.parent_pool());
+  assert!(RuntimeMsg::message_descriptor() == descriptor);
+}
+
+#[test]
+fn fallible_message_descriptors() {
+  assert_eq_pretty!(
+    RuntimeNested::try_message_descriptor()
+      .unwrap()
+      .full_name(),
+    "runtime.RuntimeMsg.RuntimeNested"
+  );
+
+  let error = try_message_descriptor("runtime", "runtime.Missing").unwrap_err();
+
+  assert!(matches!(error, MessageDescriptorError::NotFound { .. }));
+  assert_eq_pretty!(
+    error.to_string(),
+    "could not find message `runtime.Missing` in the descriptor pool of package `runtime`"
+  );
+}
+
+#[test]
+fn dynamic_message_transcoding() {
+  let msg = RuntimeMsg {
+    name: "abc".into(),
+    ids: vec![1, 2],
+    created_at: None,
+    nested: Some(RuntimeNested { id: 5 }),
+  };
+
+  let dynamic = msg.transcode_to_dynamic();
+
+  assert_eq_pretty!(
+    dynamic
+      .get_field_by_name("name")
+      .unwrap()
+      .as_str(),
+    Some("abc")
+  );
+  assert_eq_pretty!(
+    *dynamic.get_field_by_name("ids").unwrap(),
+    Value::List(vec![Value::U64(1), Value::U64(2)])
+  );
+
+  let nested = dynamic.get_field_by_name("nested").unwrap();
+
+  assert_eq_pretty!(
+    nested
+      .as_message()
+      .unwrap()
+      .get_field_by_name("id")
+      .unwrap()
+      .as_i32(),
+    Some(5)
+  );
+
+  let decoded: RuntimeMsg = dynamic.transcode_to().unwrap();
+
+  assert_eq_pretty!(decoded, msg);
+}
+
+#[test]
+fn encoded_file_descriptor_set_with_imports() {
+  let bytes = RUNTIME_PKG.encoded_file_descriptor_set();
+
+  // The set is cached
+  assert!(Arc::ptr_eq(
+    &bytes,
+    &RUNTIME_PKG.encoded_file_descriptor_set()
+  ));
+
+  let pool = DescriptorPool::decode(&*bytes).unwrap();
+
+  let file_names: Vec<&str> = pool.files().map(|f| f.name()).collect();
+
+  assert!(file_names.contains(&"runtime.proto"));
+  assert!(file_names.contains(&"google/protobuf/timestamp.proto"));
+
+  let service = pool
+    .get_service_by_name("runtime.RuntimeService")
+    .unwrap();
+  let method = service.methods().next().unwrap();
+
+  assert_eq_pretty!(method.name(), "Get");
+  assert_eq_pretty!(method.input().full_name(), "runtime.RuntimeMsg");
+
+  // Dependencies come before the files that import them
+  let timestamp_idx = file_names
+    .iter()
+    .position(|name| *name == "google/protobuf/timestamp.proto")
+    .unwrap();
+  let runtime_idx = file_names
+    .iter()
+    .position(|name| *name == "runtime.proto")
+    .unwrap();
+
+  assert!(timestamp_idx < runtime_idx);
+}
+
+#[test]
+fn partial_validate_proto_is_embedded() {
+  let bytes = RUNTIME_PKG
+    .get_package()
+    .encode_file_descriptor_set_with_imports()
+    .unwrap();
+
+  // A new pool does not contain `buf/validate/validate.proto`, so the partial version is used
+  let pool = DescriptorPool::decode(bytes.as_slice()).unwrap();
+
+  assert!(
+    pool
+      .get_file_by_name("buf/validate/validate.proto")
+      .is_none()
+  );
+  assert!(
+    pool
+      .get_file_by_name("buf/validate/validate_known_rules.proto")
+      .is_some()
+  );
+
+  let file = pool.get_file_by_name("runtime.proto").unwrap();
+
+  assert!(
+    file
+      .dependencies()
+      .any(|dep| dep.name() == "buf/validate/validate_known_rules.proto")
+  );
+
+  let extension = pool
+    .get_extension_by_name("buf.validate.field")
+    .unwrap();
+
+  let options = pool
+    .get_message_by_name("runtime.RuntimeMsg")
+    .unwrap()
+    .get_field_by_name("name")
+    .unwrap()
+    .options();
+
+  assert!(options.has_extension(&extension));
+
+  let rules = options.get_extension(&extension);
+
+  let min_len = rules
+    .as_message()
+    .and_then(|rules| rules.get_field_by_name("string"))
+    .and_then(|string_rules| {
+      string_rules
+        .as_message()?
+        .get_field_by_name("min_len")?
+        .as_u64()
+    });
+
+  assert_eq_pretty!(min_len, Some(1));
+}
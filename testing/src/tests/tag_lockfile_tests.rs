@@ -0,0 +1,169 @@
+use super::*;
+
+// The lockfile was generated with the fields in this order:
+// `first`, `second`, `removed`, `inserted` (the latter added after `removed` was deleted)
+#[proto_message]
+#[proto(tag_lockfile = "locks/tags.lock")]
+pub struct LockedMsg {
+  pub inserted: i32,
+  pub second: i32,
+  pub first: i32,
+  pub new_field: i32,
+}
+
+#[proto_enum]
+#[proto(tag_lockfile = "locks/tags.lock")]
+pub enum LockedEnum {
+  Unspecified,
+  Inserted,
+  B,
+}
+
+// The parent is renamed, and the lockfile maps its Rust ident to its proto name
+#[proto_message]
+#[proto(tag_lockfile = "locks/tags.lock", name = "RenamedOuter")]
+pub struct LockedOuter {
+  pub id: i32,
+}
+
+#[proto_message]
+#[proto(tag_lockfile = "locks/tags.lock", parent_message = LockedOuter)]
+pub struct LockedMiddle {
+  pub id: i32,
+}
+
+// The lockfile was generated with `first` declared before `inserted`
+#[proto_message]
+#[proto(tag_lockfile = "locks/tags.lock", parent_message = LockedMiddle)]
+pub struct LockedInner {
+  pub inserted: i32,
+  pub first: i32,
+}
+
+#[proto_enum]
+#[proto(tag_lockfile = "locks/tags.lock", parent_message = LockedMiddle)]
+pub enum LockedInnerEnum {
+  Unspecified,
+  Inserted,
+  First,
+}
+
+#[test]
+fn locked_message_tags() {
+  let schema = LockedMsg::proto_schema();
+
+  let tags: Vec<(&str, i32)> = schema
+    .fields()
+    .map(|f| (f.name.as_ref(), f.tag))
+    .collect();
+
+  // `new_field` skips the tag of the removed field
+  assert_eq_pretty!(
+    tags,
+    [
+      ("inserted", 4),
+      ("second", 2),
+      ("first", 1),
+      ("new_field", 5)
+    ]
+  );
+}
+
+#[test]
+fn locked_enum_tags() {
+  assert_eq_pretty!(LockedEnum::Inserted as i32, 3);
+  assert_eq_pretty!(LockedEnum::B as i32, 1);
+}
+
+#[test]
+fn nested_locked_tags() {
+  assert_eq_pretty!(
+    LockedInner::proto_name(),
+    "RenamedOuter.LockedMiddle.LockedInner"
+  );
+
+  let tags: Vec<(String, i32)> = LockedInner::proto_schema()
+    .fields()
+    .map(|f| (f.name.to_string(), f.tag))
+    .collect();
+
+  assert_eq_pretty!(
+    tags,
+    [("inserted".to_string(), 2), ("first".to_string(), 1)]
+  );
+
+  assert_eq_pretty!(LockedInnerEnum::Inserted as i32, 2);
+  assert_eq_pretty!(LockedInnerEnum::First as i32, 1);
+}
+
+fn locked_package() -> Package {
+  let file = file_schema!(
+    name = "tag_lockfile.proto",
+    messages = [
+      LockedMsg,
+      LockedOuter = {
+        messages = [
+          LockedMiddle = { messages = [LockedInner], enums = [LockedInnerEnum] }
+        ]
+      }
+    ],
+    enums = [LockedEnum]
+  );
+
+  package_schema!("tag_lockfile", files = [file])
+}
+
+#[test]
+fn lockfile_is_up_to_date() {
+  let pkg = locked_package();
+
+  let existing = include_str!("../../locks/tags.lock");
+
+  // The entries of the removed items are kept
+  assert_eq_pretty!(pkg.render_tag_lockfile(existing), existing);
+
+  let fresh = pkg.render_tag_lockfile("");
+
+  assert!(fresh.contains("LockedMsg.new_field = 5\n"));
+  assert!(fresh.contains("LockedEnum.LOCKED_ENUM_INSERTED = 3\n"));
+  assert!(!fresh.contains("removed"));
+  assert!(fresh.contains("@LockedMiddle = RenamedOuter.LockedMiddle\n"));
+  assert!(fresh.contains("RenamedOuter.LockedMiddle.LockedInner.inserted = 2\n"));
+}
+
+#[test]
+fn missing_lockfile_entries_are_reported() {
+  let path = concat!(env!("CARGO_MANIFEST_DIR"), "/locks/tags.lock");
+
+  assert!(
+    check_tag_lockfile(
+      path,
+      LockedInner::proto_name(),
+      Some(("LockedMiddle", LockedMiddle::proto_name())),
+      &[("first", 1), ("inserted", 2)]
+    )
+    .is_ok()
+  );
+
+  let error = check_tag_lockfile(path, "LockedMsg", None, &[("not_locked", 6)]).unwrap_err();
+
+  assert!(error.contains("LockedMsg.not_locked = 6"), "{error}");
+
+  // The lockfile does not need to exist yet, so that it can be created with `Package::write_tag_lockfile`
+  let error = check_tag_lockfile(
+    concat!(env!("CARGO_MANIFEST_DIR"), "/locks/missing.lock"),
+    LockedInner::proto_name(),
+    Some(("LockedMiddle", LockedMiddle::proto_name())),
+    &[("first", 1)],
+  )
+  .unwrap_err();
+
+  assert!(
+    error.contains("@LockedMiddle = RenamedOuter.LockedMiddle"),
+    "{error}"
+  );
+  assert!(
+    error.contains("RenamedOuter.LockedMiddle.LockedInner.first = 1"),
+    "{error}"
+  );
+}
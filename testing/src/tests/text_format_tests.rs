@@ -0,0 +1,233 @@
+use super::*;
+use prelude::{
+  proto_types::{Duration, Timestamp},
+  text_format::{TextFormatError, TextPosition},
+};
+
+proto_package!(TEXT_FORMAT_PKG, name = "text_format", no_cel_test);
+
+define_proto_file!(
+  TEXT_FORMAT_FILE,
+  name = "text_format.proto",
+  package = TEXT_FORMAT_PKG
+);
+
+#[proto_enum]
+pub enum TextEnum {
+  Unspecified,
+  First,
+  Second,
+}
+
+#[proto_oneof]
+#[proto(skip_checks(all))]
+pub enum TextOneof {
+  #[proto(tag = 20)]
+  OneofString(String),
+  #[proto(tag = 21, message)]
+  OneofMsg(TextNested),
+}
+
+#[proto_message]
+#[proto(skip_checks(all))]
+pub struct TextNested {
+  pub id: i32,
+  pub labels: Vec<String>,
+}
+
+#[proto_message]
+#[proto(skip_checks(all))]
+pub struct TextMsg {
+  pub int32_field: i32,
+  pub uint64_field: u64,
+  pub double_field: f64,
+  pub bool_field: bool,
+  pub string_field: String,
+  pub bytes_field: Bytes,
+  #[proto(enum_(TextEnum))]
+  pub enum_field: i32,
+  #[proto(repeated(enum_(TextEnum)))]
+  pub enum_list: Vec<i32>,
+  pub optional_int: Option<i32>,
+  pub repeated_int: Vec<i32>,
+  #[proto(map(string, int32))]
+  pub string_map: HashMap<String, i32>,
+  #[proto(message)]
+  pub nested: Option<TextNested>,
+  #[proto(repeated(message))]
+  pub nested_list: Vec<TextNested>,
+  #[proto(timestamp)]
+  pub timestamp: Option<Timestamp>,
+  #[proto(duration)]
+  pub duration: Option<Duration>,
+  #[proto(oneof(tags(20, 21)))]
+  pub choice: Option<TextOneof>,
+}
+
+fn populated_msg() -> TextMsg {
+  TextMsg {
+    int32_field: -5,
+    uint64_field: 18_446_744_073_709_551_615,
+    double_field: f64::NEG_INFINITY,
+    bool_field: true,
+    string_field: "quote \" and ü".to_string(),
+    bytes_field: Bytes::from_static(b"\x00\xffab"),
+    enum_field: TextEnum::First as i32,
+    enum_list: vec![TextEnum::Second as i32, 7],
+    optional_int: Some(0),
+    repeated_int: vec![1, 2],
+    string_map: HashMap::from_iter([("b".to_string(), 2), ("a".to_string(), 1)]),
+    nested: Some(TextNested {
+      id: 1,
+      labels: vec!["x".to_string()],
+    }),
+    nested_list: vec![
+      TextNested::default(),
+      TextNested {
+        id: 2,
+        labels: vec![],
+      },
+    ],
+    timestamp: Some(Timestamp {
+      seconds: 10,
+      nanos: 0,
+    }),
+    duration: None,
+    choice: Some(TextOneof::OneofMsg(TextNested {
+      id: 3,
+      labels: vec![],
+    })),
+  }
+}
+
+#[test]
+fn text_format_output() {
+  let expected = r#"int32_field: -5
+uint64_field: 18446744073709551615
+double_field: -inf
+bool_field: true
+string_field: "quote \" and ü"
+bytes_field: "\000\377ab"
+enum_field: TEXT_ENUM_FIRST
+enum_list: TEXT_ENUM_SECOND
+enum_list: 7
+optional_int: 0
+repeated_int: 1
+repeated_int: 2
+string_map {
+  key: "a"
+  value: 1
+}
+string_map {
+  key: "b"
+  value: 2
+}
+nested {
+  id: 1
+  labels: "x"
+}
+nested_list {
+}
+nested_list {
+  id: 2
+}
+timestamp {
+  seconds: 10
+}
+oneof_msg {
+  id: 3
+}
+"#;
+
+  assert_eq_pretty!(populated_msg().to_text_format(), expected);
+}
+
+#[test]
+fn default_values_are_omitted() {
+  assert_eq_pretty!(TextMsg::default().to_text_format(), "");
+}
+
+#[test]
+fn text_format_round_trip() {
+  let msg = populated_msg();
+
+  let decoded = TextMsg::from_text_format(&msg.to_text_format()).unwrap();
+
+  assert_eq_pretty!(decoded, msg);
+}
+
+#[test]
+fn text_format_parsing() {
+  let input = r#"
+    # Comments are ignored
+    int32_field: 0x10
+    double_field: 1.5e3f,
+    string_field: 'single' " and double"
+    enum_field: 2
+    enum_list: [TEXT_ENUM_FIRST, 2]
+    repeated_int: [1, 2]; repeated_int: 3
+    string_map: [{ key: "a" value: 1 }, < key: "b", value: 2 >]
+    nested { id: 1 }
+    nested { labels: "merged" }
+    oneof_string: "first"
+    oneof_string: "second"
+  "#;
+
+  let msg = TextMsg::from_text_format(input).unwrap();
+
+  assert_eq_pretty!(msg.int32_field, 16);
+  assert_eq_pretty!(msg.double_field, 1500.0);
+  assert_eq_pretty!(msg.string_field, "single and double");
+  assert_eq_pretty!(msg.enum_field, TextEnum::Second as i32);
+  assert_eq_pretty!(
+    msg.enum_list,
+    vec![TextEnum::First as i32, TextEnum::Second as i32]
+  );
+  assert_eq_pretty!(msg.repeated_int, vec![1, 2, 3]);
+  assert_eq_pretty!(
+    msg.string_map,
+    HashMap::from_iter([("a".to_string(), 1), ("b".to_string(), 2)])
+  );
+  assert_eq_pretty!(
+    msg.nested,
+    Some(TextNested {
+      id: 1,
+      labels: vec!["merged".to_string()]
+    })
+  );
+  assert_eq_pretty!(
+    msg.choice,
+    Some(TextOneof::OneofString("second".to_string()))
+  );
+}
+
+#[test]
+fn text_format_errors() {
+  let err = TextMsg::from_text_format("nested {\n  labels: [\"a\", 1]\n}").unwrap_err();
+
+  assert_eq_pretty!(err.path, "nested.labels[1]");
+  assert_eq_pretty!(err.position, Some(TextPosition { line: 2, column: 3 }));
+
+  let err = TextMsg::from_text_format("nested_list {}\nnested_list { unknown: 1 }").unwrap_err();
+
+  assert_eq_pretty!(err.path, "nested_list[1]");
+  assert_eq_pretty!(err.message, "unknown field `unknown`");
+  assert_eq_pretty!(
+    err.to_string(),
+    "line 2, column 15: at `nested_list[1]`: unknown field `unknown`"
+  );
+
+  let err = TextMsg::from_text_format("enum_field: TEXT_ENUM_THIRD").unwrap_err();
+
+  assert_eq_pretty!(err.path, "enum_field");
+  assert!(err.message.contains("TEXT_ENUM_THIRD"));
+
+  let err = TextMsg::from_text_format("int32_field: 3000000000").unwrap_err();
+
+  assert_eq_pretty!(err.path, "int32_field");
+
+  let err: TextFormatError = TextMsg::from_text_format("nested { id: 1").unwrap_err();
+
+  assert_eq_pretty!(err.path, "");
+  assert!(err.position.is_some());
+}
@@ -0,0 +1,109 @@
+use super::*;
+use prelude::proto_types::protovalidate::ViolationKind;
+
+proto_package!(VALIDATION_DATA_PKG, name = "validation_data", no_cel_test);
+
+define_proto_file!(
+  VALIDATION_DATA_FILE,
+  name = "validation_data.proto",
+  package = VALIDATION_DATA_PKG
+);
+
+register_cel_variables!("tenant");
+
+#[derive(Debug, PartialEq)]
+enum CallerRole {
+  Admin,
+  User,
+}
+
+fn admin_only(ctx: &mut ValidationCtx, val: Option<&bool>) -> ValidationResult {
+  if val.is_some_and(|is_set| *is_set) && ctx.data::<CallerRole>() != Some(&CallerRole::Admin) {
+    ctx.add_violation_with_custom_id(
+      "admin_only",
+      ViolationKind::Cel,
+      "can only be set by admins",
+    )
+  } else {
+    Ok(IsValid::Yes)
+  }
+}
+
+#[proto_message]
+#[proto(skip_checks(all))]
+pub struct DataUser {
+  #[proto(validate = |v| v.cel(cel_program!(id = "same_tenant", msg = "must belong to the current tenant", expr = "this == tenant")))]
+  pub tenant: String,
+  #[proto(validate = from_fn(admin_only))]
+  pub is_admin: bool,
+}
+
+fn rule_ids(result: Result<(), ValidationErrors>) -> Vec<String> {
+  result
+    .err()
+    .map(|errs| {
+      errs
+        .into_violations()
+        .iter()
+        .map(|v| v.rule_id().to_string())
+        .collect()
+    })
+    .unwrap_or_default()
+}
+
+#[test]
+fn validation_data() {
+  let user = DataUser {
+    tenant: "acme".to_string(),
+    is_admin: true,
+  };
+
+  let admin_data = ValidationData::new()
+    .with(CallerRole::Admin)
+    .with_cel_variable("tenant", "acme");
+
+  assert!(user.validate_with(admin_data).is_ok());
+
+  // Shared data can be reused across validations without being cloned
+  let user_data = Arc::new(
+    ValidationData::new()
+      .with(CallerRole::User)
+      .with_cel_variable("tenant", "other"),
+  );
+
+  assert_eq_pretty!(
+    rule_ids(user.validate_all_with(user_data.clone())),
+    vec!["same_tenant".to_string(), "admin_only".to_string()]
+  );
+  assert!(rule_ids(user.validate_with(user_data)).contains(&"same_tenant".to_string()));
+
+  let mut data = ValidationData::new();
+
+  assert!(data.insert(CallerRole::User).is_none());
+  assert_eq_pretty!(data.insert(CallerRole::Admin), Some(CallerRole::User));
+  assert_eq_pretty!(data.get::<CallerRole>(), Some(&CallerRole::Admin));
+  assert!(data.get::<String>().is_none());
+}
+
+#[proto_message]
+#[proto(skip_checks(all))]
+#[proto(validate = |v| v.cel(cel_program!(id = "misspelled", msg = "must have a positive id", expr = "thsi.id > 0")))]
+pub struct MisspelledVariable {
+  pub id: i32,
+}
+
+#[test]
+fn registered_variables_are_not_reported_by_checks() {
+  assert!(DataUser::check_validators_consistency().is_ok());
+}
+
+#[test]
+fn misspelled_variables_are_reported_by_checks() {
+  assert!(MisspelledVariable::check_validators_consistency().is_err());
+}
+
+#[test]
+#[should_panic(expected = "reserved")]
+fn reserved_cel_variables_are_rejected() {
+  let _ = ValidationData::new().with_cel_variable("this", 1);
+}